@@ -4,6 +4,7 @@ pub mod bot;
 pub mod card_library;
 pub mod cards_positions;
 pub mod ocr;
+pub mod ocr_engine;
 pub mod ui;
 pub mod state;
 pub mod creature_positions;
@@ -13,19 +14,28 @@ pub mod gre;
 pub mod error;
 pub mod game_state_updater;
 pub mod combat_engine;
+pub mod mulligan;
+pub mod opponent_model;
+pub mod screen_source;
+pub mod input_driver;
+pub mod name_resolver;
+pub mod card_hash;
+pub mod battlefield_reader;
+pub mod layout;
+pub mod calibration;
+pub mod screen_state;
+pub mod session;
 
 use crate::app::error::AppError;
 use crate::app::game_state::Player;
 use crate::app::game_state::GameEvent;
-use tracing::{info, error};
+use tracing::info;
 use bot::Bot;
-use std::error::Error;
-use std::thread::sleep;
-use std::time::Duration;
 use state::start_state::StartState;
 use state::State;
 use crate::app::game_state_updater::GameStateUpdater;
-use crate::app::card_library::build_card_library;
+use crate::app::session::{leave_results_screen, recover_after_error, tally};
+use std::time::Duration;
 
 
 #[derive(Debug, Copy, Clone, PartialEq)]
@@ -40,20 +50,81 @@ pub struct App {
     bot: Bot,
 }
 
+impl Default for App {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Giving up after this many games in a row ended in an error instead of a result.
+const MAX_CONSECUTIVE_FAILURES: u32 = 3;
+/// After an error, this long to reach the results or home screen.
+const RECOVERY_TIMEOUT: Duration = Duration::from_secs(300);
+
 impl App {
+    /// Plays games until the session limit: queue, play, record the result, click
+    /// through the results screens, back to `StartState` and queue again.
     pub fn start(&mut self) {
+        let mut failures = 0;
+        loop {
+            let outcome = match self.play_game() {
+                AppError::GameOver(outcome) => {
+                    failures = 0;
+                    Some(outcome)
+                }
+                e => {
+                    tracing::error!("App error during the game: {:?}", e);
+                    failures += 1;
+                    if failures >= MAX_CONSECUTIVE_FAILURES {
+                        tracing::error!("App: {} failed games in a row, stopping.", failures);
+                        break;
+                    }
+                    match recover_after_error(RECOVERY_TIMEOUT) {
+                        Ok(outcome) => outcome,
+                        Err(e) => {
+                            tracing::error!("App: cannot recover: {:?}", e);
+                            break;
+                        }
+                    }
+                }
+            };
+            match outcome {
+                Some(outcome) => {
+                    self.bot.record_game_result(outcome);
+                }
+                None => self.bot.record_unfinished_game(),
+            }
+            let (wins, losses) = tally(&self.bot.game_results);
+            info!("App: session so far: {} won, {} lost.", wins, losses);
 
+            let center = (self.bot.screen_width / 2, self.bot.screen_height / 2);
+            if let Err(e) = leave_results_screen(center) {
+                tracing::error!("App: stuck after the game: {:?}", e);
+                break;
+            }
+            if self.bot.session_limit_reached() {
+                info!("App: session limit reached, not queueing again.");
+                break;
+            }
+            info!("App: queueing for the next game.");
+            self.bot.reset_for_new_game();
+            self.state = Box::new(StartState::new());
+        }
+    }
+
+    /// Plays one game from `StartState` on; returns what ended it
+    /// (`AppError::GameOver` when the victory/defeat screen showed up).
+    fn play_game(&mut self) -> AppError {
         info!("App: Running StartState...");
         if let Err(e) = self.state.update(&mut self.bot) {
-            tracing::error!("Error in StartState: {:?}", e);
-            return;
+            return e;
         }
         info!("App: Transitioning from StartState to MulliganState...");
         self.state = self.state.next();
 
 
         let mut current_phase = self.state.phase();
-        let mut updater = GameStateUpdater::new();
+        let updater = GameStateUpdater::new();
 
         loop {
 
@@ -61,8 +132,7 @@ impl App {
 
 
             if let Err(e) = self.state.update(&mut self.bot) {
-                tracing::error!("App error during state update: {:?}", e);
-                break;
+                return e;
             }
 
 
@@ -99,6 +169,7 @@ impl App {
         info!("App: Transitioning to new state.");
         let new_phase = next.phase();
         self.state = next;
+        self.bot.gre.set_phase(new_phase);
         // Notify the GRE of the phase change
         self.bot.gre.trigger_event(
             GameEvent::PhaseChange(new_phase),
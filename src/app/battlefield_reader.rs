@@ -0,0 +1,286 @@
+// app/battlefield_reader.rs
+
+//! Full battlefield read per creature slot: name, P/T box, counter badge and tapped
+//! rotation. The readings are reconciled with what the GRE expects, and mismatches
+//! (missed buffs, counters, damage) are reported instead of silently ignored.
+
+use std::collections::HashMap;
+
+use image::DynamicImage;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use tracing::{info, warn};
+
+use crate::app::card_attribute::CounterType;
+use crate::app::card_hash::identify_creature;
+use crate::app::card_library::{Card, CardType};
+use crate::app::creature_positions::{
+    get_opponent_creature_positions, get_own_creature_positions, CreaturePosition,
+};
+use crate::app::game_state::Player;
+use crate::app::game_state_updater::detect_creature_count_for_side;
+use crate::app::gre::Gre;
+use crate::app::name_resolver::{resolve_card, NameResolver};
+use crate::app::ocr::{read_region_text, read_rotated_region_text};
+use crate::app::ocr_engine::OcrRegion;
+use crate::app::screen_source::screen_source;
+
+/// Everything read from one creature slot.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CreatureReading {
+    /// 1-based slot index
+    pub index: usize,
+    pub name: String,
+    /// As printed in the P/T box (includes buffs, counters and marked damage)
+    pub power_toughness: Option<(i32, i32)>,
+    /// Number on the counter badge, if a badge is shown
+    pub counter_badge: Option<u32>,
+    pub tapped: bool,
+}
+
+/// What did not match between the screen and the GRE.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MismatchKind {
+    Power { expected: i32, seen: i32 },
+    /// Seen toughness above the expected one (a buff the GRE does not know about)
+    Toughness { expected: i32, seen: i32 },
+    /// Seen toughness below the expected one: marked damage or a missed debuff
+    Damaged { expected: i32, seen: i32 },
+    Counters { expected: u32, seen: u32 },
+    /// On screen, but the GRE has no such permanent
+    Unknown,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BattlefieldMismatch {
+    /// GRE card id; 0 if the permanent is not tracked
+    pub card_id: u64,
+    pub name: String,
+    pub controller: Player,
+    pub kind: MismatchKind,
+}
+
+static PT_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(-?\d+)\s*/\s*(-?\d+)").unwrap());
+static NUMBER_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"\d+").unwrap());
+
+/// "3/4", " 3 / 4", "+1/+1 3/4" → the last P/T pair.
+pub fn parse_power_toughness(text: &str) -> Option<(i32, i32)> {
+    let caps = PT_RE.captures_iter(text).last()?;
+    Some((caps[1].parse().ok()?, caps[2].parse().ok()?))
+}
+
+/// "2", "x2", "+2" → 2. The badge only shows the count.
+pub fn parse_counter_badge(text: &str) -> Option<u32> {
+    NUMBER_RE.find_iter(text).last()?.as_str().parse().ok()
+}
+
+/// P/T box: bottom-right corner of the card.
+pub fn pt_box_region(pos: &CreaturePosition) -> (u32, u32, u32, u32) {
+    let w = pos.click_x2.saturating_sub(pos.click_x1) as f64;
+    let h = pos.click_y2.saturating_sub(pos.click_y1) as f64;
+    (
+        pos.click_x1 + (w * 0.62) as u32,
+        pos.click_y2.saturating_sub((h * 0.14) as u32),
+        pos.click_x2,
+        pos.click_y2,
+    )
+}
+
+/// Counter badge: lower-left part of the art.
+pub fn counter_badge_region(pos: &CreaturePosition) -> (u32, u32, u32, u32) {
+    let w = pos.click_x2.saturating_sub(pos.click_x1) as f64;
+    let h = pos.click_y2.saturating_sub(pos.click_y1) as f64;
+    (
+        pos.click_x1,
+        pos.click_y1 + (h * 0.55) as u32,
+        pos.click_x1 + (w * 0.35) as u32,
+        pos.click_y1 + (h * 0.75) as u32,
+    )
+}
+
+/// The slot widened by a quarter card on both sides, so a rotated card still fits.
+pub fn tapped_probe_region(pos: &CreaturePosition, screen_width: u32) -> (u32, u32, u32, u32) {
+    let margin = pos.click_x2.saturating_sub(pos.click_x1) / 4;
+    (
+        pos.click_x1.saturating_sub(margin),
+        pos.click_y1,
+        (pos.click_x2 + margin).min(screen_width),
+        pos.click_y2,
+    )
+}
+
+/// Where `region` of the untapped card ends up once the card is tapped, i.e. turned
+/// by 90° clockwise around its centre. The text inside is turned the same way.
+pub fn tapped_region(region: (u32, u32, u32, u32), pos: &CreaturePosition) -> (u32, u32, u32, u32) {
+    let cx = (pos.click_x1 as i64 + pos.click_x2 as i64) / 2;
+    let cy = (pos.click_y1 as i64 + pos.click_y2 as i64) / 2;
+    let turn = |x: u32, y: u32| (cx - (y as i64 - cy), cy + (x as i64 - cx));
+    let (ax, ay) = turn(region.0, region.1);
+    let (bx, by) = turn(region.2, region.3);
+    let clamp = |v: i64| v.max(0) as u32;
+    (
+        clamp(ax.min(bx)),
+        clamp(ay.min(by)),
+        clamp(ax.max(bx)),
+        clamp(ay.max(by)),
+    )
+}
+
+/// A tapped card is rotated by 90°, so its outline in the probe is wider than tall.
+/// The background is taken from the two top corners of the probe.
+pub fn looks_tapped(probe: &DynamicImage, tolerance: u8) -> bool {
+    let img = probe.to_rgb8();
+    let (w, h) = img.dimensions();
+    if w < 2 || h < 2 {
+        return false;
+    }
+    let (a, b) = (img.get_pixel(0, 0), img.get_pixel(w - 1, 0));
+    let bg: Vec<i32> = (0..3).map(|i| (a[i] as i32 + b[i] as i32) / 2).collect();
+
+    let (mut min_x, mut min_y, mut max_x, mut max_y) = (u32::MAX, u32::MAX, 0, 0);
+    for (x, y, p) in img.enumerate_pixels() {
+        let differs = (0..3).any(|i| (p[i] as i32 - bg[i]).abs() > tolerance as i32);
+        if differs {
+            min_x = min_x.min(x);
+            min_y = min_y.min(y);
+            max_x = max_x.max(x);
+            max_y = max_y.max(y);
+        }
+    }
+    if min_x == u32::MAX {
+        return false;
+    }
+    max_x - min_x > max_y - min_y
+}
+
+fn read_tapped(pos: &CreaturePosition, screen_width: u32) -> bool {
+    let (x1, y1, x2, y2) = tapped_probe_region(pos, screen_width);
+    match screen_source().capture_area(x1 as i32, y1 as i32, x2 - x1, y2 - y1) {
+        Ok(img) => looks_tapped(&img, 40),
+        Err(e) => {
+            warn!("Tapped probe capture failed: {}", e);
+            false
+        }
+    }
+}
+
+/// Reads every creature slot on one side.
+pub fn read_battlefield_side(screen_width: u32, screen_height: u32, is_opponent: bool) -> Vec<CreatureReading> {
+    let count = detect_creature_count_for_side(screen_width, screen_height, is_opponent);
+    let positions = if is_opponent {
+        get_opponent_creature_positions(count, screen_width, screen_height)
+    } else {
+        get_own_creature_positions(count, screen_width, screen_height)
+    };
+    let side = if is_opponent { "opp" } else { "own" };
+
+    let mut readings = Vec::new();
+    for (i, pos) in positions.into_iter().enumerate() {
+        let index = i + 1;
+        let tapped = read_tapped(&pos, screen_width);
+        // A tapped card's P/T box and badge are turned along with the card
+        let read = |rect, name: String| {
+            if tapped {
+                read_rotated_region_text(tapped_region(rect, &pos), OcrRegion::PowerToughness, &name)
+            } else {
+                read_region_text(rect, OcrRegion::PowerToughness, &name)
+            }
+        };
+        let pt_text = read(pt_box_region(&pos), format!("{}_pt_{}.png", side, index));
+        let badge_text = read(counter_badge_region(&pos), format!("{}_counters_{}.png", side, index));
+        let name = identify_creature(pos, index, is_opponent, screen_width, screen_height);
+        let reading = CreatureReading {
+            index,
+            name,
+            power_toughness: parse_power_toughness(&pt_text),
+            counter_badge: parse_counter_badge(&badge_text),
+            tapped,
+        };
+        info!("Battlefield {} slot #{}: {:?}", side, index, reading);
+        readings.push(reading);
+    }
+    readings
+}
+
+/// Library cards for the readings, keyed by card name, with the tapped state applied.
+/// Names are matched by `resolver`, built over `library`.
+pub fn cards_from_readings(
+    readings: &[CreatureReading],
+    library: &HashMap<String, Card>,
+    resolver: &NameResolver,
+    is_opponent: bool,
+) -> HashMap<String, Card> {
+    let mut map = HashMap::new();
+    for r in readings {
+        if let Some(card) = resolver.resolve_card(library, &r.name) {
+            let mut card = card.clone();
+            card.tapped = r.tapped;
+            map.insert(card.name.clone(), card);
+        } else if !r.name.is_empty() {
+            warn!("Unknown OCR creature `{}` on {}", r.name, if is_opponent { "opponent" } else { "own" });
+        }
+    }
+    map
+}
+
+/// Compares the readings with the GRE's view of `controller`'s creatures.
+/// `cards` is the bot's name → card map for that side, used when the GRE does not track the card.
+pub fn reconcile(
+    readings: &[CreatureReading],
+    cards: &HashMap<String, Card>,
+    gre: &Gre,
+    controller: Player,
+) -> Vec<BattlefieldMismatch> {
+    let mut mismatches = Vec::new();
+    for r in readings {
+        let Some(tracked) = resolve_card(cards, &r.name) else {
+            if !r.name.is_empty() {
+                mismatches.push(BattlefieldMismatch {
+                    card_id: 0,
+                    name: r.name.clone(),
+                    controller,
+                    kind: MismatchKind::Unknown,
+                });
+            }
+            continue;
+        };
+        // The GRE's copy carries counters and attachments; fall back to the bot's card
+        let card = gre
+            .battlefield_creatures
+            .get(&tracked.card_id)
+            .unwrap_or(tracked);
+        if !matches!(card.card_type, CardType::Creature(_)) {
+            continue;
+        }
+        let mut flag = |kind| {
+            mismatches.push(BattlefieldMismatch {
+                card_id: card.card_id,
+                name: card.name.clone(),
+                controller,
+                kind,
+            })
+        };
+
+        if let Some((seen_p, seen_t)) = r.power_toughness {
+            let (exp_p, exp_t) = card.current_power_toughness(gre);
+            if seen_p != exp_p {
+                flag(MismatchKind::Power { expected: exp_p, seen: seen_p });
+            }
+            if seen_t > exp_t {
+                flag(MismatchKind::Toughness { expected: exp_t, seen: seen_t });
+            } else if seen_t < exp_t {
+                flag(MismatchKind::Damaged { expected: exp_t, seen: seen_t });
+            }
+        }
+        let expected_counters = card.counter_count(&CounterType::PlusOnePlusOne)
+            + card.counter_count(&CounterType::MinusOneMinusOne);
+        let seen_counters = r.counter_badge.unwrap_or(0);
+        if seen_counters != expected_counters {
+            flag(MismatchKind::Counters { expected: expected_counters, seen: seen_counters });
+        }
+    }
+    for m in &mismatches {
+        warn!("Battlefield mismatch ({:?}) `{}`: {:?}", m.controller, m.name, m.kind);
+    }
+    mismatches
+}
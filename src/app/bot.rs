@@ -1,17 +1,12 @@
 // app/bot.rs
 
 use crate::multiplatform::screen_size;
-use crate::app::gre::Gre;
+use crate::app::gre::{Gre, ManaPool};
 use crate::app::card_attribute::{Amount, Effect};
 use crate::app::game_state::Player;
-use crate::app::game_state::Player as OtherPlayer;
 use crate::app::game_state::GameEvent;
-use crate::app::gre;
 use crate::app::error::AppError;
-use crate::app::game_state::StackEntry;
-use crate::app::game_state::StackEntry as GameStateStackEntry;
-use crate::app::gre::StackEntry as GreStackEntry;
-use crate::app::game_state_updater::{GameStateUpdater, load_side_creatures};
+use crate::app::game_state_updater::GameStateUpdater;
 use std::{
     collections::HashMap,
     thread::sleep,
@@ -19,14 +14,29 @@ use std::{
 };
 use tracing::{error, info, warn};
 
-use crate::app::{card_library::{build_card_library, Card, CardType}, cards_positions::get_card_positions, creature_positions::{get_own_creature_positions, get_opponent_creature_positions}, ui::{Cords, set_cursor_pos, left_click, press_key, get_average_color, is_color_within_tolerance}, ocr::{read_creature_text, get_card_text}, game_state};
+use crate::app::{card_library::{build_card_library, Card, CardType}, cards_positions::{get_card_positions, CardPosition}, creature_positions::{get_ability_button_position, get_own_creature_positions}, ui::{Cords, set_cursor_pos, left_click, click_at, press_key}, card_hash::identify_hand_card};
 use crate::app::card_library::CardTypeFlags;
-use crate::app::game_state::{Strategy, SimpleHeuristic};
+use crate::app::opponent_model::OpponentModel;
+use crate::app::combat_engine::AttackTarget;
+use crate::app::game_state::{GameAction, GameState, Strategy, SimpleHeuristic};
+use crate::app::name_resolver::NameResolver;
+use crate::app::layout::layout;
+use crate::app::battlefield_reader::{cards_from_readings, read_battlefield_side, reconcile, MismatchKind};
+use crate::app::screen_state::GameOutcome;
+use crate::app::session::{GameResult, SessionLimits};
+use chrono::Local;
+
+/// Ennyi stratégiai akció fázisonként; véd a körbeforgó döntések ellen
+const MAX_STRATEGY_ACTIONS: usize = 10;
 
 pub struct Bot {
+    /// Befejezett játszmák száma ebben a sessionben
     pub end_game_counter: u32,
+    /// Ennyi játszma után nem sorolunk be újra
     pub end_game_threshold: u32,
+    /// A session kezdete
     pub time_game_started: Instant,
+    /// Ennyi futásidő után nem sorolunk be újra
     pub time_game_threshold: Duration,
     pub time_waiting_started: Instant,
     pub time_waiting_threshold: Duration,
@@ -42,6 +52,7 @@ pub struct Bot {
     pub land_played_this_turn: bool,
     pub battlefield_creatures: HashMap<String, Card>,
     pub battlefield_opponent_creatures: HashMap<String, Card>,
+    pub opponent_model: OpponentModel,
     pub next_state_override: Option<StateOverride>,
     pub last_cast_card_name: String,
     pub first_main_phase_done: bool,
@@ -51,6 +62,16 @@ pub struct Bot {
     pub attacking: Vec<String>,
     pub combat_attackers: Vec<usize>,
     pub combat_blocks: HashMap<usize, Vec<usize>>,
+    /// Támadónként a célpont (játékos vagy planeswalker); hiányzó kulcs = játékos
+    pub combat_attack_targets: HashMap<usize, AttackTarget>,
+    /// Az aktuális játszma kezdete
+    pub current_game_started: Instant,
+    /// A session lejátszott játszmái
+    pub game_results: Vec<GameResult>,
+    /// A kártyakönyvtár, amelyhez a `name_resolver` az OCR-olvasatokat egyezteti
+    pub card_library: HashMap<String, Card>,
+    /// OCR-olvasatok egyeztetése a library neveivel
+    pub name_resolver: NameResolver,
 }
 
 pub enum StateOverride {
@@ -67,27 +88,16 @@ impl Bot {
         });
         let cords = Cords::new(screen_width, screen_height);
 
-        // Build and configure the GRE
-        let mut gre = Gre::new(Player::Us);
-
-        // Példa: minden damage effektust +1-gyel növelünk
-        gre.add_continuous_effect(|eff| {
-            // klónozzuk, hogy könnyen mintázhassuk
-            if let Effect::Damage { amount: Amount::Fixed(n), target } = eff.clone() {
-                // építsük újra az Effect-et a megváltoztatott mennyiséggel
-                *eff = Effect::Damage {
-                    amount: Amount::Fixed(n + 1),
-                    target,
-                };
-            }
-        });
+        let gre = Self::new_gre();
+        let limits = SessionLimits::from_env();
+        let card_library = build_card_library();
+        let name_resolver = NameResolver::from_library(&card_library);
 
-        // Instantiate Bot
-        let bot = Self {
+        Self {
             end_game_counter: 0,
-            end_game_threshold: 3,
+            end_game_threshold: limits.max_games,
             time_game_started: Instant::now(),
-            time_game_threshold: Duration::from_secs(1200),
+            time_game_threshold: limits.max_duration,
             time_waiting_started: Instant::now(),
             time_waiting_threshold: Duration::from_secs(120),
             cords,
@@ -102,6 +112,7 @@ impl Bot {
             land_played_this_turn: false,
             battlefield_creatures: HashMap::new(),
             battlefield_opponent_creatures: HashMap::new(),
+            opponent_model: OpponentModel::new(),
             next_state_override: None,
             last_cast_card_name: String::new(),
             first_main_phase_done: false,
@@ -111,25 +122,83 @@ impl Bot {
             attacking: Vec::new(),
             combat_attackers: Vec::new(),
             combat_blocks: HashMap::new(),
+            combat_attack_targets: HashMap::new(),
+            current_game_started: Instant::now(),
+            game_results: Vec::new(),
+            card_library,
+            name_resolver,
+        }
+    }
+
+    /// Build and configure the GRE
+    fn new_gre() -> Gre {
+        let mut gre = Gre::new(Player::Us);
+
+        // Példa: minden damage effektust +1-gyel növelünk
+        gre.add_continuous_effect(|eff| {
+            // klónozzuk, hogy könnyen mintázhassuk
+            if let Effect::Damage { amount: Amount::Fixed(n), target } = eff.clone() {
+                // építsük újra az Effect-et a megváltoztatott mennyiséggel
+                *eff = Effect::Damage {
+                    amount: Amount::Fixed(n + 1),
+                    target,
+                };
+            }
+        });
+        gre
+    }
+
+    /// Records the finished game and counts it towards the session limit.
+    pub fn record_game_result(&mut self, outcome: GameOutcome) -> &GameResult {
+        let result = GameResult {
+            outcome,
+            turns: self.opponent_turn_counter,
+            duration: self.current_game_started.elapsed(),
+            finished_at: Local::now(),
+        };
+        info!("Game #{} finished: {:?}", self.end_game_counter + 1, result);
+        self.end_game_counter += 1;
+        self.game_results.push(result);
+        self.game_results.last().unwrap()
+    }
+
+    /// Played enough games or ran long enough; don't queue again.
+    pub fn session_limit_reached(&self) -> bool {
+        let limits = SessionLimits {
+            max_games: self.end_game_threshold,
+            max_duration: self.time_game_threshold,
         };
+        limits.reached(self.end_game_counter, self.time_game_started.elapsed())
+    }
 
-        bot
+    /// A game that ended in an error without a result screen; it still counts
+    /// towards the session limit, so a failing game cannot requeue forever.
+    pub fn record_unfinished_game(&mut self) {
+        warn!("Game #{} finished without a result", self.end_game_counter + 1);
+        self.end_game_counter += 1;
     }
 
+    /// Forgets everything about the last game: a fresh `Bot::new()` that keeps the
+    /// session (counters, limits, results), the strategy and the name resolver.
+    pub fn reset_for_new_game(&mut self) {
+        let session = std::mem::replace(self, Self::new());
+        self.end_game_counter = session.end_game_counter;
+        self.end_game_threshold = session.end_game_threshold;
+        self.time_game_started = session.time_game_started;
+        self.time_game_threshold = session.time_game_threshold;
+        self.game_results = session.game_results;
+        self.strategy = session.strategy;
+        self.name_resolver = session.name_resolver;
+    }
 
     /// Draw exactly one card, OCR it, update hand and GameState.
     pub fn draw_card(&mut self) {
         let new_index = self.cards_texts.len();
         let new_count = new_index + 1;
+        info!("Drawing → reading new slot at index {}", new_index);
 
-        let positions = get_card_positions(new_count, self.screen_width as u32);
-        let pos = positions[new_index];
-        let card_y = ((self.screen_height as f64) * 0.97).floor() as i32;
-        info!("Drawing → hovering new slot at index {} @ {:?}", new_index, pos);
-        set_cursor_pos(pos.hover_x as i32, card_y);
-        sleep(Duration::from_secs(2));
-
-        let text = get_card_text(
+        // Hash match without hover; the OCR fallback hovers by itself
+        let text = identify_hand_card(
             new_index,
             new_count,
             self.screen_width as u32,
@@ -139,6 +208,7 @@ impl Bot {
         // most frissítjük az állapotot
         self.cards_texts.push(text.clone());
         self.card_count = self.cards_texts.len();
+        self.sync_hand_to_gre();
 
         info!("Drew card '{}' → Updated hand: {:?}", text, self.cards_texts);
     }
@@ -146,20 +216,20 @@ impl Bot {
     pub fn play_land(&mut self) {
         if !self.land_played_this_turn {
             // find a land in hand via library
-            let library = build_card_library();
-            if let Some((idx, _text)) = self.cards_texts.iter().enumerate()
-                .find(|(_, txt)| library.values().any(|c| matches!(c.card_type, CardType::Land) && txt.contains(&c.name)))
-            {
+            if let Some((idx, _)) = self.find_hand_card(|t| matches!(t, CardType::Land)) {
                 info!("Playing land at hand idx {}", idx);
-                Self::play_card(self, idx);
+                if Self::play_card(self, idx).is_err() {
+                    return;
+                }
                 self.land_played_this_turn = true;
+                self.gre.note_land_played(Player::Us);
                 self.land_count += 1;
                 self.land_number += 1;
             }
         }
         sleep(Duration::from_secs(1));
     }
-    /// Refresh battlefield OCR and merge tracked tokens
+    // Refresh battlefield OCR and merge tracked tokens
     /*pub fn refresh_battlefield(&mut self) {
         let ours_ocr = load_side_creatures(
             self.screen_width as u32,
@@ -190,51 +260,156 @@ impl Bot {
     }*/
 
     pub fn refresh_battlefield(&mut self) {
-        let ours_ocr = load_side_creatures(
-            self.screen_width as u32,
-            self.screen_height as u32,
-            false,
+        let (library, resolver) = (&self.card_library, &self.name_resolver);
+        let (w, h) = (self.screen_width as u32, self.screen_height as u32);
+
+        // 1) Saját oldal: az OCR-rel felismert lapok összefésülése az eddigiekkel
+        let ours_readings = read_battlefield_side(w, h, false);
+        let ours_ocr = cards_from_readings(&ours_readings, library, resolver, false);
+        self.battlefield_creatures = Self::merge_battlefield_side(
+            &mut self.gre,
+            ours_ocr,
+            &self.battlefield_creatures,
+            Player::Us,
         );
 
-        // 1) Létrehozunk egy üres mapet, ebbe fog menni a "végleges" merged
-        let mut merged = HashMap::new();
+        // 2) Ugyanez a logika az ellenfél lényeire is:
+        let opp_readings = read_battlefield_side(w, h, true);
+        let opp_ocr = cards_from_readings(&opp_readings, library, resolver, true);
+        self.battlefield_opponent_creatures = Self::merge_battlefield_side(
+            &mut self.gre,
+            opp_ocr,
+            &self.battlefield_opponent_creatures,
+            Player::Opponent,
+        );
+        self.opponent_model
+            .observe_battlefield(&self.battlefield_opponent_creatures);
 
-        // 2) Végigmegyünk az OCR-rel felismert kártyákon
-        for (name, card) in ours_ocr {
-            let mut c = card;
-            // Itt hívod a GRE-t, hogy adjon neki card_id-t
-            self.gre.enter_battlefield(&mut c);
+        // 3) Képernyőn látott P/T, counterek vs. GRE elvárás; a counter-jelvény a mérvadó
+        let mut mismatches =
+            reconcile(&ours_readings, &self.battlefield_creatures, &self.gre, Player::Us);
+        mismatches.extend(reconcile(
+            &opp_readings,
+            &self.battlefield_opponent_creatures,
+            &self.gre,
+            Player::Opponent,
+        ));
+        for m in mismatches {
+            if let MismatchKind::Counters { seen, .. } = m.kind
+                && m.card_id != 0
+            {
+                self.gre.sync_counter_badge(m.card_id, seen);
+            }
+        }
+    }
 
-            // Ezután betesszük a merged mapbe
+    /// Az egyik oldal OCR-rel látott lapjainak összefésülése az előző olvasással.
+    /// A GRE által már nyilvántartott permanens nem lép be újra (nincs új id, nincs
+    /// újabb ETB), csak a tapped állapota frissül; az új lapok belépnek a GRE-be.
+    /// A tokeneket az OCR nem ismeri fel, ezeket megtartjuk.
+    fn merge_battlefield_side(
+        gre: &mut Gre,
+        seen: HashMap<String, Card>,
+        previous: &HashMap<String, Card>,
+        controller: Player,
+    ) -> HashMap<String, Card> {
+        let mut merged = HashMap::new();
+        for (name, card) in seen {
+            let tracked = previous
+                .get(&name)
+                .filter(|c| c.card_id != 0 && gre.battlefield_creatures.contains_key(&c.card_id));
+            let c = match tracked {
+                Some(prev) => {
+                    let mut c = prev.clone();
+                    c.tapped = card.tapped;
+                    if let Some(permanent) = gre.battlefield_creatures.get_mut(&c.card_id) {
+                        permanent.tapped = card.tapped;
+                    }
+                    c
+                }
+                None => {
+                    let mut c = card.with_controller(controller);
+                    gre.enter_battlefield(&mut c);
+                    c
+                }
+            };
             merged.insert(name, c);
         }
-
-        // 3) Megőrizzük a régi self.battlefield_creatures-ből a tokeneket
-        for (name, card) in self.battlefield_creatures.iter() {
+        for (name, card) in previous {
             if card.type_flags.contains(CardTypeFlags::TOKEN) {
                 merged.insert(name.clone(), card.clone());
             }
         }
-        self.battlefield_creatures = merged;
+        merged
+    }
 
-        // 4) Ugyanez a logika az ellenfél lényeire is:
-        let opp_ocr = load_side_creatures(
-            self.screen_width as u32,
-            self.screen_height as u32,
-            true,);
-        let mut opp_merged = HashMap::new();
-        for (name, card) in opp_ocr {
-            let mut c = card;
-            self.gre.enter_battlefield(&mut c);
-            opp_merged.insert(name, c);
+    /// A `self.attacking` támadók rögzítése a harci feloldáshoz. Az index a
+    /// `battlefield_creatures` lényeinek bejárási sorrendje (a nem-lény permanensek
+    /// kimaradnak), ahogy a CombatEngine támadó oldala is épül.
+    /// A bot csak az "All Attack" gombbal támad, ami minden támadót a védekező játékosra
+    /// küld: planeswalker megtámadása csak a CombatEngine szintjén támogatott
+    /// (`combat_attack_targets` kézi kitöltésével), a kliensen a bot nem választ célpontot.
+    pub fn record_attack_declaration(&mut self) {
+        self.combat_attackers.clear();
+        self.combat_attack_targets.clear();
+        let creatures = self
+            .battlefield_creatures
+            .iter()
+            .filter(|(_, card)| matches!(card.card_type, CardType::Creature(_)))
+            .map(|(name, _)| name);
+        for (idx, name) in creatures.enumerate() {
+            if self.attacking.contains(name) {
+                self.combat_attackers.push(idx);
+                self.combat_attack_targets.insert(idx, AttackTarget::Player);
+            }
         }
-        for (name, card) in self.battlefield_opponent_creatures.iter() {
-            if card.type_flags.contains(CardTypeFlags::TOKEN) {
-                opp_merged.insert(name.clone(), card.clone());
+    }
+
+    /// A `combat_blocks` blokkolások bejelentése a GRE-nek: blokkolónként egy `BlockDeclared`.
+    /// Az indexek a CombatEngine két oldalát követik: a `battlefield_creatures`, illetve a
+    /// `battlefield_opponent_creatures` lényeinek bejárási sorrendje.
+    pub fn record_block_declaration(&mut self) {
+        let attacker_ids: Vec<u64> = self
+            .battlefield_creatures
+            .values()
+            .filter(|c| matches!(c.card_type, CardType::Creature(_)))
+            .map(|c| c.card_id)
+            .collect();
+        let blocker_ids: Vec<u64> = self
+            .battlefield_opponent_creatures
+            .values()
+            .filter(|c| matches!(c.card_type, CardType::Creature(_)))
+            .map(|c| c.card_id)
+            .collect();
+        let mut blocks: Vec<(usize, usize)> = self
+            .combat_blocks
+            .iter()
+            .flat_map(|(&attacker, blockers)| blockers.iter().map(move |&b| (attacker, b)))
+            .collect();
+        blocks.sort_unstable();
+        let mut blockers: Vec<Card> = self.battlefield_opponent_creatures.values().cloned().collect();
+        for (attacker, blocker) in blocks {
+            if let (Some(&attacker), Some(&blocker)) =
+                (attacker_ids.get(attacker), blocker_ids.get(blocker))
+            {
+                self.gre.trigger_event(
+                    GameEvent::BlockDeclared { blocker, attacker },
+                    &mut blockers,
+                    Player::Opponent,
+                );
             }
         }
-        self.battlefield_opponent_creatures = opp_merged;
+        self.gre.resolve_stack();
+    }
+
+    /// Az ellenfél stacken látott varázslata (OCR-szöveg): a lapnevet a bot resolverével
+    /// oldjuk fel és rögzítjük az ellenfél-modellben. A felismert nevet adja vissza.
+    pub fn observe_opponent_spell(&mut self, ocr_text: &str) -> Option<String> {
+        let name = self.name_resolver.resolve(ocr_text)?.name;
+        self.opponent_model.observe_spell(&name);
+        Some(name)
     }
+
     pub fn on_spell_resolved(&mut self) {
         let name = self.last_cast_card_name.clone();
         let mut targets: Vec<Card> = self.battlefield_creatures.values().cloned().collect();
@@ -246,6 +421,7 @@ impl Bot {
         let mut all_creatures: Vec<Card> = self.battlefield_creatures.values().cloned().collect();
         self.gre.trigger_event(GameEvent::TurnEnded, &mut all_creatures, Player::Us);
         self.gre.resolve_stack();
+        self.gre.on_turn_end();
         self.land_played_this_turn = false;
     }
 
@@ -257,80 +433,240 @@ impl Bot {
     }
     /// Cast the first affordable instant, then click on one of our creatures as target.
     pub fn cast_instants_targeting_creature(&mut self, creature_index: usize) {
-        // 1) Keresünk instantot a kezünkben, elég manával
-        if let Some((i, _)) = self.cards_texts.iter().enumerate()
-            .find(|(_i, txt)| self.can_cast_instant() && {
-                let library = build_card_library();
-                library.values().any(|c| {
-                    matches!(c.card_type, CardType::Instant)
-                        && Bot::text_contains(&c.name, txt)
-                    // mana-check is done in can_cast_instant()
-                })
-            })
-        {
-            // 2) Kinyerjük a Card szerkezetet
-            let card_library = build_card_library();
-            if let Some(card) = card_library.values().find(|c| {
-                matches!(c.card_type, CardType::Instant)
-                    && Bot::text_contains(&c.name, &self.cards_texts[i])
-            }) {
-                // 3) UI-s kijátszás (kattintás, cost-check, stb.)
-                if let Ok(cost) = self.try_cast_card(i, card) {
-                    self.land_number = self.land_number.saturating_sub(cost);
-
-                    // 4) Rákattintunk a battlefielden az index=creature_index
-                    //    lénypozícióra
-                    let positions = get_own_creature_positions(
-                        self.battlefield_creatures.len(),
-                        self.screen_width as u32,
-                        self.screen_height as u32,
-                    );
-                    if creature_index < positions.len() {
-                        let p = &positions[creature_index];
-                        let click_x = ((p.click_x1 + p.click_x2) / 2) as i32;
-                        let click_y = ((p.click_y1 + p.click_y2) / 2) as i32;
-                        set_cursor_pos(click_x, click_y);
-                        left_click();
-                        info!("Targeted instant at creature #{}", creature_index);
+        // 1-2) Keresünk instantot a kezünkben, elég manával, és a resolverrel kinyerjük
+        //      a Card szerkezetet (a mana-ellenőrzést a can_cast_instant() végzi)
+        let instant = self
+            .can_cast_instant()
+            .then(|| self.find_hand_card(|t| matches!(t, CardType::Instant)))
+            .flatten();
+        if let Some((i, ref card)) = instant {
+            // 3) UI-s kijátszás (kattintás, cost-check, stb.)
+            if let Ok(cost) = self.try_cast_card(i, card) {
+                self.land_number = self.land_number.saturating_sub(cost);
+
+                // 4) Rákattintunk a battlefielden az index=creature_index
+                //    lénypozícióra
+                let positions = get_own_creature_positions(
+                    self.battlefield_creatures.len(),
+                    self.screen_width as u32,
+                    self.screen_height as u32,
+                );
+                if creature_index < positions.len() {
+                    let p = &positions[creature_index];
+                    let click_x = ((p.click_x1 + p.click_x2) / 2) as i32;
+                    let click_y = ((p.click_y1 + p.click_y2) / 2) as i32;
+                    if click_at(click_x, click_y).is_err() {
+                        return;
                     }
+                    info!("Targeted instant at creature #{}", creature_index);
+                }
 
-                    // 5) **GRE stackre is feltesszük** a Spell
-                    //    Ehhez ki kell keresnünk, melyik
-                    //    creature a 'target' a GRE szemszögéből
-                    if let Some((_, tcard)) = self.battlefield_creatures.iter().nth(creature_index) {
-                        // Betesszük a stackre
-                        self.gre.cast_spell_with_target(
-                            card.clone(),  // ez a Felonious Rage
-                            Player::Us,
-                            tcard.clone(), // a Heartfire Hero pl.
-                        );
-
-                        // 6) A stack feloldása: effectek végrehajtása
-                        self.gre.resolve_stack();
-                        self.sync_battlefield_from_gre();
-                        // 7) A GRE a belső `battlefield_creatures` map-et módosítja,
-                        //    de utána össze akarjuk szinkronizálni a bot állapotával is.
-                        //    Például frissítsük a bot.battlefield_creatures-t is:
-                        //    (Ha a GRE is ebben a mapben tárolja, nincs plusz teendő,
-                        //     de ha külön van, frissítsük.)
-                        //
-                        // Mindenesetre nézzük meg, mi történt:
-                        info!("   - {} -> {:?}", card.name, card);
-                        info!("=== After resolve_stack() - battlefield ===");
-                        for (id, card) in &self.gre.battlefield_creatures {
-                            if let CardType::Creature(_) = &card.card_type {
-                                let cp = card.get_current_power(&self.gre);
-                                let ct = card.get_current_toughness(&self.gre);
-                                info!("   - '{}'(id={}) => current: {}/{}", card.name, id, cp, ct);
-                            } else {
-                                info!("   - '{}'(id={}) => {:?}", card.name, id, card.card_type);
-                            }
+                // 5) **GRE stackre is feltesszük** a Spell
+                //    Ehhez ki kell keresnünk, melyik
+                //    creature a 'target' a GRE szemszögéből
+                if let Some(tcard) = self.battlefield_creatures.values().nth(creature_index).cloned() {
+                    // Betesszük a stackre (pl. Felonious Rage a Heartfire Hero-ra)
+                    self.cast_in_gre(card, Some(tcard));
+
+                    // 6) A stack feloldása: effectek végrehajtása
+                    self.gre.resolve_stack();
+                    self.sync_battlefield_from_gre();
+                    // 7) A GRE a belső `battlefield_creatures` map-et módosítja,
+                    //    de utána össze akarjuk szinkronizálni a bot állapotával is.
+                    //    Például frissítsük a bot.battlefield_creatures-t is:
+                    //    (Ha a GRE is ebben a mapben tárolja, nincs plusz teendő,
+                    //     de ha külön van, frissítsük.)
+                    //
+                    // Mindenesetre nézzük meg, mi történt:
+                    info!("   - {} -> {:?}", card.name, card);
+                    info!("=== After resolve_stack() - battlefield ===");
+                    for (id, card) in &self.gre.battlefield_creatures {
+                        if let CardType::Creature(_) = &card.card_type {
+                            let cp = card.get_current_power(&self.gre);
+                            let ct = card.get_current_toughness(&self.gre);
+                            info!("   - '{}'(id={}) => current: {}/{}", card.name, id, cp, ct);
+                        } else {
+                            info!("   - '{}'(id={}) => {:?}", card.name, id, card.card_type);
                         }
                     }
                 }
             }
         }
     }
+    /// A célzott varázslat a GRE stackjére, a `Gre::cast_spell` egyetlen kijátszási útján.
+    /// A manát a kliens fizeti (a földeket a játék csapolja), ezért a varázslat költségét
+    /// a GRE mana poolba tükrözzük, hogy a költség-ellenőrzés átmenjen; ha a GRE
+    /// elutasítja, a pool visszaáll.
+    fn cast_in_gre(&mut self, card: &Card, target: Option<Card>) {
+        let cost = self.gre.effective_cost(card, Player::Us);
+        let pool = self.gre.player(Player::Us).mana_pool;
+        self.gre.add_mana(Player::Us, ManaPool::from(&cost));
+        if let Err(e) = self.gre.cast_spell(card.clone(), Player::Us, target) {
+            warn!("GRE rejected '{}': {}", card.name, e);
+            self.gre.player_mut(Player::Us).mana_pool = pool;
+        }
+    }
+
+    /// A `strategy` döntéseinek végrehajtása, amíg passzolni nem akar. Minden lépés előtt
+    /// friss `GameState` készül; a kézből kijátszást és a földet a fázisok saját lépései
+    /// intézik, ezért ezeknél (és sikertelen akciónál) megállunk.
+    pub fn act_on_strategy(&mut self) {
+        self.identify_zone_cards();
+        for _ in 0..MAX_STRATEGY_ACTIONS {
+            let mut state = GameState::new();
+            state.update_from_bot(self);
+            let action = self.strategy.decide(&state);
+            info!("Strategy -> {:?}", action);
+            let done = match action {
+                GameAction::ActivateAbility { card_idx, ability_idx } => {
+                    self.activate_own_ability(&state, card_idx, ability_idx)
+                }
+                GameAction::CastFromZone(i) | GameAction::PlayLandFromZone(i) => {
+                    self.play_from_zone(&state, i)
+                }
+                _ => false,
+            };
+            if !done {
+                break;
+            }
+        }
+    }
+
+    /// A `state.battlefield[card_idx]` képességének aktiválása: rákattintunk a permanensre,
+    /// és ha a kliens képesség-választót nyit (planeswalker, több képesség), az `ability_idx`.
+    /// gombjára is. Kattintás előtt a GRE-vel ellenőrizzük, hogy aktiválható-e; a mana
+    /// költséget a kliens fizeti, ezért a GRE poolba tükrözzük (mint a `cast_in_gre`).
+    fn activate_own_ability(&mut self, state: &GameState, card_idx: usize, ability_idx: usize) -> bool {
+        let Some(card) = state.battlefield.get(card_idx) else {
+            return false;
+        };
+        let Some(source) = self.gre.battlefield_creatures.get(&card.card_id).cloned() else {
+            return false;
+        };
+        let Some(ability) = source.activated_abilities.get(ability_idx).cloned() else {
+            return false;
+        };
+        if !self
+            .gre
+            .can_activate_client_paid(&source, &ability, Player::Us, self.land_number)
+        {
+            warn!("Ability #{} of '{}' cannot be activated now", ability_idx, card.name);
+            return false;
+        }
+        let positions = get_own_creature_positions(
+            state.battlefield.len(),
+            self.screen_width as u32,
+            self.screen_height as u32,
+        );
+        let Some(p) = positions.get(card_idx) else {
+            warn!("No screen position for '{}' (#{})", card.name, card_idx);
+            return false;
+        };
+        if click_at(((p.click_x1 + p.click_x2) / 2) as i32, ((p.click_y1 + p.click_y2) / 2) as i32).is_err() {
+            return false;
+        }
+        let ability_count = source.activated_abilities.len();
+        if ability_count > 1 || matches!(source.card_type, CardType::Planeswalker(_)) {
+            let (x, y) = get_ability_button_position(
+                p,
+                ability_idx,
+                ability_count,
+                self.screen_width as u32,
+                self.screen_height as u32,
+            );
+            if click_at(x, y).is_err() {
+                return false;
+            }
+        }
+
+        let pool = self.gre.player(Player::Us).mana_pool;
+        self.gre.add_mana(Player::Us, ManaPool::from(&ability.cost));
+        if !self.gre.activate_ability_at(card.card_id, ability_idx, Player::Us) {
+            warn!("GRE rejected ability #{} of '{}'", ability_idx, card.name);
+            self.gre.player_mut(Player::Us).mana_pool = pool;
+            return false;
+        }
+        info!("Activated ability #{} of '{}'", ability_idx, card.name);
+        self.land_number = self.land_number.saturating_sub(ability.cost.total());
+        self.gre.resolve_stack();
+        true
+    }
+
+    /// A kézen kívülről kijátszható, de fel nem ismert lapok (pl. az ismeretlen
+    /// könyvtártetőről száműzöttek) azonosítása a képernyőről: a kliens a kéz jobb
+    /// szélén, az engedélyek sorrendjében mutatja őket. A felismert lap a GRE-ben a
+    /// helyőrző helyére kerül, így az engedéllyel kijátszható lesz.
+    pub fn identify_zone_cards(&mut self) {
+        let slots = self.gre.permission_slots(Player::Us);
+        if !slots.iter().any(|&(_, hidden)| hidden) {
+            return;
+        }
+        let total = self.card_count + slots.len();
+        for (i, (card_id, hidden)) in slots.into_iter().enumerate() {
+            if !hidden {
+                continue;
+            }
+            let text = identify_hand_card(
+                self.card_count + i,
+                total,
+                self.screen_width as u32,
+                self.screen_height as u32,
+            );
+            match self.resolve_text(&text).cloned() {
+                Some(card) => {
+                    self.gre.reveal_zone_card(card_id, &card);
+                }
+                None => warn!("Card from another zone not recognised: '{}'", text),
+            }
+        }
+    }
+
+    /// A `state.castable_from_zones[idx]` kijátszása, ha a legális akciók (`zone_actions`)
+    /// között van. A kliens a más zónából kijátszható lapokat a kéz jobb szélén mutatja;
+    /// a mana költséget a kliens fizeti, ezért a GRE poolba tükrözzük.
+    fn play_from_zone(&mut self, state: &GameState, idx: usize) -> bool {
+        let legal = state.zone_actions(&self.gre).into_iter().any(|a| {
+            matches!(a, GameAction::CastFromZone(i) | GameAction::PlayLandFromZone(i) if i == idx)
+        });
+        let Some(card) = state.castable_from_zones.get(idx).filter(|_| legal) else {
+            return false;
+        };
+        let positions = get_card_positions(
+            self.card_count + state.castable_from_zones.len(),
+            self.screen_width as u32,
+        );
+        let Some(&pos) = positions.get(self.card_count + idx) else {
+            warn!("No hand slot for '{}' from another zone", card.name);
+            return false;
+        };
+        if let Err(e) = self.click_hand_slot(pos) {
+            warn!("Cannot click '{}': {}", card.name, e);
+            return false;
+        }
+
+        let is_land = matches!(card.card_type, CardType::Land);
+        let cost = self.gre.effective_cost(card, Player::Us);
+        let pool = self.gre.player(Player::Us).mana_pool;
+        if !is_land {
+            self.gre.add_mana(Player::Us, ManaPool::from(&cost));
+        }
+        if !self.gre.play_from_zone(card.card_id, Player::Us) {
+            self.gre.player_mut(Player::Us).mana_pool = pool;
+            return false;
+        }
+        info!("Played '{}' from another zone", card.name);
+        if is_land {
+            self.land_played_this_turn = true;
+            self.land_count += 1;
+            self.land_number += 1;
+        } else {
+            self.last_cast_card_name = card.name.clone();
+            self.land_number = self.land_number.saturating_sub(cost.total());
+            self.gre.resolve_stack();
+        }
+        true
+    }
+
     /// Attempt to cast a card at `pos`, update mana in GameState on success.
     fn try_cast_card(&mut self, pos: usize, card: &Card) -> Result<u32, AppError> {
         let cost = &card.mana_cost;
@@ -364,7 +700,7 @@ impl Bot {
         );
 
         let cost_total = cost.total();
-        Self::play_card(self, pos);
+        Self::play_card(self, pos)?;
         self.last_cast_card_name = card.name.clone();
         self.land_number = self.land_number.saturating_sub(cost_total);
         Ok(cost_total)
@@ -376,7 +712,6 @@ impl Bot {
         F: Fn(&CardType) -> bool,
     {
         let mut mana_available = self.land_number;
-        let card_library = build_card_library();
 
         // Iterálunk visszafelé az aktuális self.cards_texts tömbön.
         let mut i = self.cards_texts.len();
@@ -384,11 +719,12 @@ impl Bot {
             i -= 1; // for ciklusban visszafelé: i = len - 1, len - 2, ... , 0
             // Biztosan lekérjük a kártya szöveget a kézből
             if let Some(text) = self.cards_texts.get(i) {
-                // Megkeressük a card_library-ben azt a kártyát, amelynek neve megtalálható a text-ben
-                if let Some(card) = card_library.values().find(|card| Bot::text_contains(&card.name, text)) {
+                // A resolver legjobb egyezése a szövegre a card_library-ből
+                if let Some(card) = &self.resolve_text(text).cloned() {
                     // Csak azokat a kártyákat próbáljuk meg kijátszani, amelyek megfelelnek a predicate-nek
                     if predicate(&card.card_type) {
                         if let Ok(cost_used) = self.try_cast_card(i, card) {
+                            self.gre.note_spell_cast(Player::Us);
                             mana_available = mana_available.saturating_sub(cost_used);
                             if let CardType::Creature(mut cr) = card.card_type.clone() {
                                 cr.summoning_sickness = true;
@@ -413,58 +749,49 @@ impl Bot {
         mana_available
     }
     pub fn cast_instants(&mut self) -> u32 {
-        self.cast_cards_by_filter(|card_type| match card_type {
-            CardType::Instant => true,
-            _ => false,
-        })
+        self.cast_cards_by_filter(|card_type| matches!(card_type, CardType::Instant))
     }
 
     pub fn cast_creatures(&mut self) -> u32 {
-        self.cast_cards_by_filter(|card_type| match card_type {
-            CardType::Creature(_) => true,
-            _ => false,
-        })
+        self.cast_cards_by_filter(|card_type| matches!(card_type, CardType::Creature(_)))
     }
     pub fn can_cast_card<F>(&self, predicate: F) -> bool
     where
         F: Fn(&CardType) -> bool,
     {
-        let library = build_card_library();
-        self.cards_texts.iter().any(|ocr_text| {
-            library.values().any(|card| {
+        self.cards_texts
+            .iter()
+            .filter_map(|ocr_text| self.resolve_text(ocr_text))
+            .any(|card| {
                 // Match card type
-                if predicate(&card.card_type) && Bot::text_contains(&card.name, ocr_text) {
+                if predicate(&card.card_type) {
                     // Mana cost check
-                    let colored = card.mana_cost.colored() as u32;
+                    let colored = card.mana_cost.colored();
                     let leftover = self.land_number.saturating_sub(colored);
                     self.land_number >= colored && leftover >= card.mana_cost.colorless
                 } else {
                     false
                 }
             })
-        })
     }
 
     //// Attempts to cast one creature, returning its name and mana spent if successful.
     pub fn cast_one_creature(&mut self) -> Option<(String, u32)> {
-        let library = crate::app::card_library::build_card_library();
-
         // Use index-based loop to avoid borrow conflicts
         for i in 0..self.cards_texts.len() {
             // Clone OCR text to avoid borrowing self
             let ocr_text = self.cards_texts[i].clone();
 
             // Filter only Creature cards matching the text
-            if let Some(card) = library
-                .values()
-                .find(|card| {
-                    matches!(card.card_type, CardType::Creature(_))
-                        && Bot::text_contains(&card.name, &ocr_text)
-                })
+            if let Some(card) = &self
+                .resolve_text(&ocr_text)
+                .filter(|card| matches!(card.card_type, CardType::Creature(_)))
+                .cloned()
             {
                 // Safe to mutably borrow self now
                 match self.try_cast_card(i, card) {
                     Ok(cost_used) => {
+                        self.gre.note_spell_cast(Player::Us);
                         // Successfully cast: return name and mana
                         return Some((card.name.clone(), cost_used));
                     }
@@ -492,16 +819,9 @@ impl Bot {
     /// Central helper for casting creatures in SecondMainPhaseState.
     pub fn process_creature_casting(&mut self) {
         if self.land_number > 0 {
-            let card_library = build_card_library();
-            let creature_exists = self.cards_texts.iter().any(|text| {
-                card_library.values().any(|card| {
-                    if let CardType::Creature(_) = card.card_type {
-                        Bot::text_contains(&card.name, text)
-                    } else {
-                        false
-                    }
-                })
-            });
+            let creature_exists = self
+                .find_hand_card(|t| matches!(t, CardType::Creature(_)))
+                .is_some();
             if creature_exists {
                 info!("Creature card detected in hand. Attempting to cast creature.");
                 self.cast_creatures();
@@ -515,36 +835,41 @@ impl Bot {
         }
     }
 
-    pub fn play_card(bot: &mut Bot, card_index: usize) {
+    pub fn play_card(bot: &mut Bot, card_index: usize) -> Result<(), AppError> {
         let positions = get_card_positions(bot.card_count, bot.screen_width as u32);
         if card_index >= positions.len() {
             error!("Error: Card index {} is out of range. Only {} cards available.", card_index, positions.len());
-            return;
+            return Err(AppError::HandIndexOutOfRange {
+                index: card_index,
+                hand_size: positions.len(),
+            });
         }
-        let pos = positions[card_index];
-        let card_y = ((bot.screen_height as f64) * 0.97).floor() as i32;
-        set_cursor_pos(pos.hover_x as i32, card_y);
-        left_click();
-        left_click();
-        set_cursor_pos(bot.screen_width - 1, bot.screen_height - 1);
-        press_key(0x5A); // 'Z' billentyű
-        left_click();
-        sleep(Duration::from_millis(150));
+        bot.click_hand_slot(positions[card_index])?;
         Bot::remove_card_from_hand(bot, card_index);
+        Ok(())
+    }
+
+    /// Kijátszás a kéz egy helyéről: kattintás a lapra, majd a 'Z' megerősítés.
+    fn click_hand_slot(&self, pos: CardPosition) -> Result<(), AppError> {
+        let layout = layout();
+        let card_y = layout.sy(layout.value("hand.hover_y"), self.screen_height as u32).floor() as i32;
+        click_at(pos.hover_x as i32, card_y)?;
+        left_click()?;
+        set_cursor_pos(self.screen_width - 1, self.screen_height - 1)?;
+        press_key(0x5A)?; // 'Z' billentyű
+        left_click()?;
+        sleep(Duration::from_millis(150));
+        Ok(())
     }
     pub fn cast_felonious_rage_on_creature(&mut self, rage_card_index: usize, creature_name: &str) {
         // 1) Kikeressük a kezünkből a Felonious Rage card-ot
-        let card_library = build_card_library();
-        let rage_text = &self.cards_texts[rage_card_index];
-        if let Some(rage_card) = card_library.values().find(|c| c.name == *rage_text) {
+        let rage = self.resolve_text(&self.cards_texts[rage_card_index]).cloned();
+        if let Some(rage_card) = rage {
             // 2) Megkeressük a battlefield-en a kiválasztott lénye(ke)t
             if let Some(tcard) = self.battlefield_creatures.get(creature_name) {
                 // 3) Kijátszunk a GRE-be egy Spell { card: Felonious Rage, target_creature: Some(tcard.clone()) }
-                self.gre.cast_spell_with_target(
-                    rage_card.clone(),
-                    Player::Us,
-                    tcard.clone(),  // Ezt klónozzuk, mert a stack-en is tároljuk
-                );
+                let (rage_card, tcard) = (rage_card.clone(), tcard.clone());
+                self.cast_in_gre(&rage_card, Some(tcard));
                 // 4) Töröljük a kijátszott lapot a kezünkből
                 self.remove_card_from_hand(rage_card_index);
             }
@@ -556,22 +881,38 @@ impl Bot {
             info!("Removed card '{}' from hand at index {}.", removed, card_index);
             info!("Updated hand: {:?}", self.cards_texts);
             self.card_count = self.cards_texts.len();
+            self.sync_hand_to_gre();
         } else {
             warn!("Attempted to remove card at invalid index {}.", card_index);
         }
     }
 
-    pub fn text_contains(name: &str, ocr_text: &str) -> bool {
+    pub fn text_contains(&self, name: &str, ocr_text: &str) -> bool {
         //info!("text_contains() called with name = {:?} and ocr_text = {:?}", name, ocr_text);
-        let result = ocr_text.contains(name);
+        let result = ocr_text.contains(name) || self.name_resolver.text_matches(name, ocr_text);
         // info!("text_contains() returning: {}", result);
         result
     }
 
+    /// Egy OCR-szöveg lapja a bot resolverével: a legjobb egyezés a `card_library`-ből,
+    /// nem az első, amelyik elég jól illeszkedik.
+    pub fn resolve_text(&self, ocr_text: &str) -> Option<&Card> {
+        self.name_resolver.resolve_card(&self.card_library, ocr_text)
+    }
+
+    /// Az első kézbeli lap (index, lap), amelynek típusára `predicate` igaz.
+    fn find_hand_card(&self, predicate: impl Fn(&CardType) -> bool) -> Option<(usize, Card)> {
+        self.cards_texts.iter().enumerate().find_map(|(i, text)| {
+            self.resolve_text(text)
+                .filter(|card| predicate(&card.card_type))
+                .map(|card| (i, card.clone()))
+        })
+    }
+
     pub fn examine_cards(&mut self) {
         self.cards_texts.clear(); // Töröljük a korábbi eredményeket.
         for i in 0..self.card_count {
-            let text = get_card_text(
+            let text = identify_hand_card(
                 i,
                 self.card_count,
                 self.screen_width as u32,
@@ -581,6 +922,19 @@ impl Bot {
             self.cards_texts.push(text);
         }
         info!("OCR results for cards: {:?}", self.cards_texts);
+        self.sync_hand_to_gre();
+    }
+
+    /// A kéz a GRE-be: a kézméret a beolvasott lapok száma, a felismert lapok a library-ből
+    /// jönnek; amit nem ismertünk fel, az ismeretlen marad.
+    fn sync_hand_to_gre(&mut self) {
+        let known = self
+            .cards_texts
+            .iter()
+            .filter_map(|text| self.resolve_text(text))
+            .map(|card| card.clone().with_controller(Player::Us))
+            .collect();
+        self.gre.set_hand(Player::Us, self.cards_texts.len() as u32, known);
     }
     /// Megnézi, hogy a battlefielden (OCR + merge) van-e legalább 1 saját creature.
     /// Visszaadja a lények darabszámát.
@@ -613,50 +967,38 @@ impl Bot {
         }
 
         // 1) Keressük meg a kezünkből a legelső instant kártyát, amit ki tudunk játszani.
-        let library = build_card_library();
-        if let Some((hand_idx, card_name)) = self.cards_texts.iter().enumerate()
-            .find(|(_, txt)| {
-                library.values().any(|c| {
-                    matches!(c.card_type, CardType::Instant)
-                        && Self::text_contains(&c.name, txt)
-                })
-            })
-        {
-            // Megvan a kijátszandó kártya neve, most kikeressük magát a Card-ot a library-ben
-            if let Some(card) = library.values().find(|c| {
-                matches!(c.card_type, CardType::Instant) && Self::text_contains(&c.name, &card_name)
-            }) {
-                // 2) Try cast
-                match self.try_cast_card(hand_idx, card) {
-                    Ok(cost_used) => {
-                        self.land_number = self.land_number.saturating_sub(cost_used);
-                        info!("Sikerült kijátszani az instantot: '{}', targeting creature_index={}",
-                              card.name, creature_index);
-
-                        // 3) Megcélozzuk a battlefielden lévő creature-t
-                        let positions = get_own_creature_positions(
-                            self.battlefield_creatures.len(),
-                            self.screen_width as u32,
-                            self.screen_height as u32,
-                        );
-                        if creature_index < positions.len() {
-                            let p = &positions[creature_index];
-                            let click_x = ((p.click_x1 + p.click_x2) / 2) as i32;
-                            let click_y = ((p.click_y1 + p.click_y2) / 2) as i32;
-
-                            // kattintás a creature közepére
-                            set_cursor_pos(click_x, click_y);
-                            left_click();
-                            info!("Instant sikeresen targetelte a creature-t a pozíción ({}, {})", click_x, click_y);
-                            return true;
-                        } else {
-                            warn!("Nem létezik creature_index={} a battlefielden.", creature_index);
+        if let Some((hand_idx, ref card)) = self.find_hand_card(|t| matches!(t, CardType::Instant)) {
+            // 2) Try cast
+            match self.try_cast_card(hand_idx, card) {
+                Ok(cost_used) => {
+                    self.land_number = self.land_number.saturating_sub(cost_used);
+                    info!("Sikerült kijátszani az instantot: '{}', targeting creature_index={}",
+                          card.name, creature_index);
+
+                    // 3) Megcélozzuk a battlefielden lévő creature-t
+                    let positions = get_own_creature_positions(
+                        self.battlefield_creatures.len(),
+                        self.screen_width as u32,
+                        self.screen_height as u32,
+                    );
+                    if creature_index < positions.len() {
+                        let p = &positions[creature_index];
+                        let click_x = ((p.click_x1 + p.click_x2) / 2) as i32;
+                        let click_y = ((p.click_y1 + p.click_y2) / 2) as i32;
+
+                        // kattintás a creature közepére
+                        if click_at(click_x, click_y).is_err() {
+                            return false;
                         }
+                        info!("Instant sikeresen targetelte a creature-t a pozíción ({}, {})", click_x, click_y);
+                        return true;
+                    } else {
+                        warn!("Nem létezik creature_index={} a battlefielden.", creature_index);
                     }
-                    Err(e) => {
-                        warn!("Nem sikerült kijátszani az instantot: {:?}", e);
-                        return false;
-                    }
+                }
+                Err(e) => {
+                    warn!("Nem sikerült kijátszani az instantot: {:?}", e);
+                    return false;
                 }
             }
         }
@@ -0,0 +1,338 @@
+// app/calibration.rs
+
+//! Console-driven layout calibration. The user points the mouse at a handful of
+//! reference points and presses Enter; the base profile is fitted to those points
+//! and written out as a new layout profile for this screen. The interactive
+//! coordinate mode reports single points and rectangles and saves named ones: a right
+//! click captures a point, a right drag OCRs a rectangle and a left click exits. The
+//! buttons are polled from X11; elsewhere (or without a display) Enter stands in for them.
+
+use std::io::{BufRead, Write};
+use std::path::Path;
+use std::sync::mpsc::{channel, TryRecvError};
+use std::thread;
+use std::time::Duration;
+
+use tracing::{info, warn};
+
+use crate::app::layout::{layout, LayoutError, LayoutProfile, CALIBRATION_POINTS};
+use crate::app::ocr::read_region_text;
+use crate::app::ocr_engine::OcrRegion;
+use crate::app::ui::{get_average_color, get_color};
+use crate::multiplatform::{cursor_position, screen_size, PointerPoller, PointerState};
+
+/// Asks for every `CALIBRATION_POINTS` entry on `input`/`output` and returns the fitted profile.
+/// `locate` returns the current cursor position (the real cursor in the binary).
+pub fn calibrate_layout_with(
+    base: &LayoutProfile,
+    name: &str,
+    screen: (u32, u32),
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+    mut locate: impl FnMut() -> Result<(i32, i32), String>,
+) -> Result<LayoutProfile, LayoutError> {
+    let mut references = Vec::new();
+    for (key, description) in CALIBRATION_POINTS {
+        write!(output, "Point at {} and press Enter (s = skip): ", description)?;
+        output.flush()?;
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            break;
+        }
+        if line.trim().eq_ignore_ascii_case("s") {
+            continue;
+        }
+        let pos = locate().map_err(LayoutError::Calibration)?;
+        writeln!(output, "  {} → {:?}", key, pos)?;
+        references.push((key.to_string(), pos));
+    }
+    base.fit(name, &references, screen.0, screen.1)
+}
+
+/// `calibrate-layout <out> [base]`: fits `base` (or the built-in profile closest to the
+/// screen) to the real screen and saves it to `out`.
+pub fn calibrate_layout(out: &Path, base: Option<&Path>) -> Result<(), LayoutError> {
+    let (w, h) = screen_size().map_err(LayoutError::Calibration)?;
+    let (w, h) = (w as u32, h as u32);
+    let base = match base {
+        Some(path) => LayoutProfile::load(path)?,
+        None => LayoutProfile::for_screen(w, h),
+    };
+    let name = format!("calibrated {}x{}", w, h);
+    let stdin = std::io::stdin();
+    let profile = calibrate_layout_with(
+        &base,
+        &name,
+        (w, h),
+        &mut stdin.lock(),
+        &mut std::io::stdout(),
+        || cursor_position().map_err(|e| e.to_string()),
+    )?;
+    profile.save(out)?;
+    info!("Layout profile `{}` written to {}", name, out.display());
+    Ok(())
+}
+
+/// One line of the interactive coordinate mode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SessionCommand {
+    /// Empty line: capture the point under the cursor
+    Capture,
+    /// `r`: mark a rectangle corner; the second one OCRs the rectangle
+    Corner,
+    /// `save <name>`: store the last point (`point.<name>`) or rectangle (`region.<name>`)
+    Save(String),
+    /// `q`: write the profile and exit
+    Quit,
+    Help,
+}
+
+impl SessionCommand {
+    pub fn parse(line: &str) -> Option<Self> {
+        let line = line.trim();
+        match line {
+            "" => Some(Self::Capture),
+            "r" => Some(Self::Corner),
+            "q" => Some(Self::Quit),
+            "h" | "?" => Some(Self::Help),
+            _ => line
+                .strip_prefix("save ")
+                .map(str::trim)
+                .filter(|n| !n.is_empty() && !n.contains(char::is_whitespace))
+                .map(|n| Self::Save(n.to_string())),
+        }
+    }
+}
+
+pub const SESSION_HELP: &str = "RightClick or Enter = capture point under cursor | right drag or r twice = \
+rectangle (OCR) | save <name> = store last point/rectangle | LeftClick or q = write profile and exit";
+
+/// A right press and release closer than this (in pixels) is a click, not a drag.
+pub const DRAG_THRESHOLD: i32 = 4;
+
+/// A finished mouse gesture.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Click {
+    /// Right click: capture the point
+    Point(i32, i32),
+    /// Right drag from press to release: capture the rectangle
+    Drag((i32, i32), (i32, i32)),
+    /// Left click: end the session
+    Exit,
+}
+
+/// Turns polled pointer states into clicks; a gesture ends when its button is released.
+#[derive(Debug, Default)]
+pub struct ClickTracker {
+    right_from: Option<(i32, i32)>,
+    left_down: bool,
+}
+
+impl ClickTracker {
+    pub fn update(&mut self, state: &PointerState) -> Option<Click> {
+        let pos = (state.x, state.y);
+        let left_released = std::mem::replace(&mut self.left_down, state.left) && !state.left;
+        if state.right {
+            self.right_from.get_or_insert(pos);
+        } else if let Some(from) = self.right_from.take() {
+            let moved = (from.0 - pos.0).abs().max((from.1 - pos.1).abs());
+            return Some(if moved <= DRAG_THRESHOLD {
+                Click::Point(pos.0, pos.1)
+            } else {
+                Click::Drag(from, pos)
+            });
+        }
+        left_released.then_some(Click::Exit)
+    }
+}
+
+/// What was captured last, in screen pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Capture {
+    Point(i32, i32),
+    Rect(i32, i32, i32, i32),
+}
+
+/// State of the interactive coordinate mode. Points and rectangles are reported in
+/// screen pixels, screen fractions and the profile's canvas units, with colour samples.
+pub struct CoordinateSession {
+    profile: LayoutProfile,
+    screen: (u32, u32),
+    last: Option<Capture>,
+    corner: Option<(i32, i32)>,
+    saved: Vec<String>,
+}
+
+impl CoordinateSession {
+    pub fn new(profile: LayoutProfile, screen: (u32, u32)) -> Self {
+        Self {
+            profile,
+            screen,
+            last: None,
+            corner: None,
+            saved: Vec::new(),
+        }
+    }
+
+    pub fn profile(&self) -> &LayoutProfile {
+        &self.profile
+    }
+
+    /// Keys stored with `save` so far.
+    pub fn saved(&self) -> &[String] {
+        &self.saved
+    }
+
+    fn canvas(&self, x: i32, y: i32) -> (f64, f64) {
+        (
+            x as f64 / self.screen.0 as f64 * self.profile.canvas.0,
+            y as f64 / self.screen.1 as f64 * self.profile.canvas.1,
+        )
+    }
+
+    fn describe(&self, x: i32, y: i32) -> String {
+        let (cx, cy) = self.canvas(x, y);
+        let c = get_color(x, y);
+        // The 5×5 box is kept on the screen, so edge points still average 25 pixels
+        let (w, h) = (self.screen.0 as i32, self.screen.1 as i32);
+        let avg = get_average_color(
+            (x - 2).clamp(0, (w - 5).max(0)),
+            (y - 2).clamp(0, (h - 5).max(0)),
+            5.min(w),
+            5.min(h),
+        );
+        format!(
+            "abs ({}, {}) | norm ({:.5}, {:.5}) | canvas ({:.3}, {:.3}) | pixel ({}, {}, {}) | avg 5×5 {:?}",
+            x,
+            y,
+            x as f64 / self.screen.0 as f64,
+            y as f64 / self.screen.1 as f64,
+            cx,
+            cy,
+            c.r,
+            c.g,
+            c.b,
+            avg
+        )
+    }
+
+    /// The rectangle spanned by two opposite corners, in either order, with its OCR text.
+    pub fn rectangle(&mut self, (x0, y0): (i32, i32), (x, y): (i32, i32)) -> String {
+        self.corner = None;
+        let (x1, x2) = (x0.min(x), x0.max(x));
+        let (y1, y2) = (y0.min(y), y0.max(y));
+        self.last = Some(Capture::Rect(x1, y1, x2, y2));
+        let text = if x2 > x1 && y2 > y1 {
+            read_region_text(
+                (x1 as u32, y1 as u32, x2 as u32, y2 as u32),
+                OcrRegion::Generic,
+                "calibration_rect.png",
+            )
+        } else {
+            String::new()
+        };
+        let (nx1, ny1) = self.canvas(x1, y1);
+        let (nx2, ny2) = self.canvas(x2, y2);
+        format!(
+            "rect ({}, {})–({}, {}) | canvas {:.3} {:.3} {:.3} {:.3} | OCR {:?}",
+            x1, y1, x2, y2, nx1, ny1, nx2, ny2, text
+        )
+    }
+
+    /// Handles one command with the cursor at `cursor`; returns the text to print.
+    pub fn handle(&mut self, cmd: &SessionCommand, cursor: (i32, i32)) -> String {
+        let (x, y) = cursor;
+        match cmd {
+            SessionCommand::Capture => {
+                self.last = Some(Capture::Point(x, y));
+                self.describe(x, y)
+            }
+            SessionCommand::Corner => match self.corner.take() {
+                None => {
+                    self.corner = Some((x, y));
+                    format!("corner 1: {}", self.describe(x, y))
+                }
+                Some(corner) => self.rectangle(corner, (x, y)),
+            },
+            SessionCommand::Save(name) => {
+                let (key, values) = match self.last {
+                    Some(Capture::Point(px, py)) => {
+                        let (cx, cy) = self.canvas(px, py);
+                        (format!("point.{}", name), vec![cx, cy])
+                    }
+                    Some(Capture::Rect(x1, y1, x2, y2)) => {
+                        let (a, b) = self.canvas(x1, y1);
+                        let (c, d) = self.canvas(x2, y2);
+                        (format!("region.{}", name), vec![a, b, c, d])
+                    }
+                    None => return "nothing captured yet".into(),
+                };
+                self.profile.set(&key, values);
+                self.saved.push(key.clone());
+                format!("saved {}", key)
+            }
+            SessionCommand::Help | SessionCommand::Quit => SESSION_HELP.to_string(),
+        }
+    }
+}
+
+/// The coordinate mode of the binary: clicks from the X11 pointer, commands from stdin
+/// (read on a helper thread so the buttons keep being polled). Saved points go into
+/// `profile_path` (loaded first if it exists, otherwise the current layout profile is the base).
+pub fn run_coordinate_mode(profile_path: &Path) -> Result<(), LayoutError> {
+    let (w, h) = screen_size().map_err(LayoutError::Calibration)?;
+    let base = if profile_path.exists() {
+        LayoutProfile::load(profile_path)?
+    } else {
+        (*layout()).clone()
+    };
+    let mut session = CoordinateSession::new(base, (w as u32, h as u32));
+    let pointer = PointerPoller::connect()
+        .inspect_err(|e| warn!("No click capture, use Enter instead: {}", e))
+        .ok();
+    println!("{}", SESSION_HELP);
+
+    let (tx, lines) = channel();
+    thread::spawn(move || {
+        for line in std::io::stdin().lock().lines() {
+            if tx.send(line).is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut tracker = ClickTracker::default();
+    loop {
+        if let Some(pointer) = &pointer {
+            match pointer.poll().map(|state| tracker.update(&state)) {
+                Ok(Some(Click::Point(x, y))) => println!("{}", session.handle(&SessionCommand::Capture, (x, y))),
+                Ok(Some(Click::Drag(from, to))) => println!("{}", session.rectangle(from, to)),
+                Ok(Some(Click::Exit)) => break,
+                Ok(None) => {}
+                Err(e) => warn!("Pointer poll failed: {}", e),
+            }
+        }
+        let line = match lines.try_recv() {
+            Ok(line) => line?,
+            Err(TryRecvError::Empty) => {
+                thread::sleep(Duration::from_millis(20));
+                continue;
+            }
+            Err(TryRecvError::Disconnected) => break,
+        };
+        let Some(cmd) = SessionCommand::parse(&line) else {
+            println!("unknown command `{}` ({})", line.trim(), SESSION_HELP);
+            continue;
+        };
+        if cmd == SessionCommand::Quit {
+            break;
+        }
+        let cursor = cursor_position().map_err(|e| LayoutError::Calibration(e.to_string()))?;
+        println!("{}", session.handle(&cmd, cursor));
+    }
+    if !session.saved().is_empty() {
+        session.profile().save(profile_path)?;
+        info!("{} point(s) written to {}", session.saved().len(), profile_path.display());
+    }
+    Ok(())
+}
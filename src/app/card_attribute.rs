@@ -2,8 +2,8 @@ use std::any::Any;
 use std::fmt::Debug;
 use tracing::{debug, info};
 
-use crate::app::card_library::{Card, ManaCost};
-use crate::app::game_state::{GameEvent, GamePhase};
+use crate::app::card_library::{Card, CardType, CardTypeFlags, SuperTypeFlags};
+use crate::app::game_state::{GamePhase, Player};
 
 // -- UGYANAZ AZ ENUM, kiegészítve a Offspring { cost: u32 } mezővel:
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -36,8 +36,9 @@ pub enum Effect {
         amount: u32,
         target: TargetFilter,
     },
+    /// Bármennyi, countert hordozó permanens/játékos kiválasztható;
+    /// mindegyik minden ott lévő counter-fajtából kap még egyet.
     Proliferate {
-        counter_type: CounterType,
         player: PlayerSelector,
     },
 
@@ -78,6 +79,11 @@ pub enum Effect {
         count: u32,
         player: PlayerSelector,
     },
+    /// A könyvtár felső `count` lapjából a `DecisionProvider` választja ki, mi kerül alulra
+    Scry {
+        count: u32,
+        player: PlayerSelector,
+    },
     ExileThenPlayFromExile {
         count: u32,
         player: PlayerSelector,
@@ -134,6 +140,152 @@ pub enum Effect {
     DrawCardsCounted,
 }
 
+impl Effect {
+    /// Az effekt célpont-szűrői (a beágyazott effektekével együtt), előfordulási sorrendben.
+    pub fn target_filters(&self) -> Vec<&TargetFilter> {
+        match self {
+            Effect::Damage { target, .. }
+            | Effect::ModifyStats { target, .. }
+            | Effect::GrantAbility { target, .. }
+            | Effect::AddCounter { target, .. }
+            | Effect::RemoveAbility { target, .. }
+            | Effect::TapTarget { target }
+            | Effect::Destroy { target }
+            | Effect::Exile { target } => vec![target],
+            Effect::DamageByTargetPower { source, target } => vec![source, target],
+            Effect::TargetedEffects { sub_effects } => {
+                sub_effects.iter().flat_map(|e| e.target_filters()).collect()
+            }
+            Effect::Conditional {
+                effect_if_true,
+                effect_if_false,
+                ..
+            } => {
+                let mut out = effect_if_true.target_filters();
+                if let Some(e) = effect_if_false {
+                    out.extend(e.target_filters());
+                }
+                out
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Minden célpont-szűrőt `f` szerint átír (a beágyazott effektekben is).
+    pub fn map_targets(self, f: &impl Fn(TargetFilter) -> TargetFilter) -> Effect {
+        match self {
+            Effect::Damage { amount, target } => Effect::Damage {
+                amount,
+                target: f(target),
+            },
+            Effect::ModifyStats {
+                power_delta,
+                toughness_delta,
+                duration,
+                target,
+            } => Effect::ModifyStats {
+                power_delta,
+                toughness_delta,
+                duration,
+                target: f(target),
+            },
+            Effect::GrantAbility {
+                ability,
+                duration,
+                target,
+            } => Effect::GrantAbility {
+                ability,
+                duration,
+                target: f(target),
+            },
+            Effect::AddCounter {
+                counter,
+                amount,
+                target,
+            } => Effect::AddCounter {
+                counter,
+                amount,
+                target: f(target),
+            },
+            Effect::RemoveAbility { ability, target } => Effect::RemoveAbility {
+                ability,
+                target: f(target),
+            },
+            Effect::TapTarget { target } => Effect::TapTarget { target: f(target) },
+            Effect::Destroy { target } => Effect::Destroy { target: f(target) },
+            Effect::Exile { target } => Effect::Exile { target: f(target) },
+            Effect::DamageByTargetPower { source, target } => Effect::DamageByTargetPower {
+                source: f(source),
+                target: f(target),
+            },
+            Effect::TargetedEffects { sub_effects } => Effect::TargetedEffects {
+                sub_effects: sub_effects.into_iter().map(|e| e.map_targets(f)).collect(),
+            },
+            Effect::Conditional {
+                condition,
+                effect_if_true,
+                effect_if_false,
+            } => Effect::Conditional {
+                condition,
+                effect_if_true: Box::new(effect_if_true.map_targets(f)),
+                effect_if_false: effect_if_false.map(|e| Box::new(e.map_targets(f))),
+            },
+            other => other,
+        }
+    }
+}
+
+/// Modális varázslat egy módja. A módon belül az azonos célpont-szűrők
+/// ugyanarra a célpontra vonatkoznak ("target creature you control ... it deals damage").
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SpellMode {
+    pub label: String,
+    pub effects: Vec<Effect>,
+}
+
+impl SpellMode {
+    pub fn new(label: &str, effects: Vec<Effect>) -> Self {
+        Self {
+            label: label.into(),
+            effects,
+        }
+    }
+}
+
+/// Modális varázslat: kijátszáskor `min..=max` különböző módot választunk,
+/// módonként a saját célpontjaikkal.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModalSpell {
+    pub min: usize,
+    pub max: usize,
+    pub modes: Vec<SpellMode>,
+}
+
+impl ModalSpell {
+    /// "Choose one —"
+    pub fn choose_one(modes: Vec<SpellMode>) -> Self {
+        Self::choose(1, modes)
+    }
+
+    /// "Choose two —"
+    pub fn choose(count: usize, modes: Vec<SpellMode>) -> Self {
+        Self {
+            min: count,
+            max: count,
+            modes,
+        }
+    }
+
+    /// "Choose one or more —"
+    pub fn one_or_more(modes: Vec<SpellMode>) -> Self {
+        Self {
+            min: 1,
+            max: modes.len(),
+            modes,
+        }
+    }
+}
+
 /// A mennyiségek
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Amount {
@@ -142,11 +294,37 @@ pub enum Amount {
     SourceToughness,
 }
 
-/// Counter-típusok
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// Counter-típusok (permanenseken és játékosokon)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum CounterType {
     PlusOnePlusOne,
+    /// -1/-1: a +1/+1 counterekkel párban kioltják egymást
+    MinusOneMinusOne,
     Loyalty,
+    /// Sebzés vagy destroy helyett ez kerül le
+    Shield,
+    /// Untap helyett ez kerül le
+    Stun,
+    /// Ha meghalna, exile-ba kerül helyette
+    Finality,
+    /// Az oil countereket a lap saját képességei használják fel
+    Oil,
+    /// Saga fejezetszámláló
+    Lore,
+    /// Játékos counterek
+    Poison,
+    Energy,
+    Experience,
+}
+
+impl CounterType {
+    /// Játékosra kerülő counter-e (poison, energy, experience).
+    pub fn is_player_counter(&self) -> bool {
+        matches!(
+            self,
+            CounterType::Poison | CounterType::Energy | CounterType::Experience
+        )
+    }
 }
 
 /// Keyword-ek
@@ -198,6 +376,45 @@ pub enum CreatureType {
     Raccoon,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum LandType {
+    Plains,
+    Island,
+    Swamp,
+    Mountain,
+    Forest,
+    Town,
+    Cave,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum EnchantmentType {
+    Aura,
+    Role,
+    Class,
+    Saga,
+    Background,
+    Shrine,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ArtifactType {
+    Equipment,
+    Vehicle,
+    Treasure,
+    Food,
+    Clue,
+}
+
+/// Bármely altípus; a lény altípusok a `Creature::types`-ban élnek.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Subtype {
+    Creature(CreatureType),
+    Land(LandType),
+    Enchantment(EnchantmentType),
+    Artifact(ArtifactType),
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Trigger {
     OnEnterBattlefield {
@@ -251,6 +468,29 @@ pub enum Trigger {
 pub enum SpellFilter {
     InstantOrSorcery,
     Any,
+    Creature,
+    Noncreature,
+    /// Minden megadott típus-bit kell (pl. `ARTIFACT | CREATURE`).
+    HasType(CardTypeFlags),
+    Supertype(SuperTypeFlags),
+    Subtype(Subtype),
+}
+
+impl SpellFilter {
+    /// Illeszkedik-e a kijátszott varázslat a szűrőre.
+    pub fn matches(&self, spell: &Card) -> bool {
+        match self {
+            SpellFilter::InstantOrSorcery => {
+                spell.has_type(CardTypeFlags::INSTANT) || spell.has_type(CardTypeFlags::SORCERY)
+            }
+            SpellFilter::Any => true,
+            SpellFilter::Creature => spell.has_type(CardTypeFlags::CREATURE),
+            SpellFilter::Noncreature => !spell.has_type(CardTypeFlags::CREATURE),
+            SpellFilter::HasType(flags) => spell.has_type(flags.clone()),
+            SpellFilter::Supertype(flags) => spell.has_supertype(flags.clone()),
+            SpellFilter::Subtype(st) => spell.has_subtype(st),
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -260,17 +500,102 @@ pub enum PlayerSelector {
     AnyPlayer,
 }
 
+/// Feltétel a játékállapoton és a kiváltó eseményen; a `Gre::evaluate_condition` értékeli ki.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Condition {
     OpponentLostLifeThisTurn,
+    /// Aktiválásnál: még nem aktiválták ebben a körben; eseménynél: ilyen fajta esemény
+    /// ebben a körben először történik az esemény lapjával (pl. először célozzák)
     FirstTimeThisTurn,
+    /// "Do this only once each turn": a forrás ebben a körben még nem tett a stackre
+    /// triggert erre a fajta eseményre
+    OnceEachTurn,
+    /// A kiváltó esemény lapja nem lény
     SpellWasNonCreature,
+    /// {T} költség; aktiváláskor a költség-ellenőrzés nézi
     Tap,
     SacrificeSelf,
     Always,
     SpellWasKicked,
     HasCreaturePower4OrMore,
     ExiledCardWasCreature,
+    /// A szűrőnek megfelelő permanensek száma (a feltétel irányítójához képest)
+    Count {
+        filter: TargetFilter,
+        cmp: Comparison,
+        count: u32,
+    },
+    /// "if you've cast two or more spells this turn"
+    SpellsCastThisTurn {
+        player: PlayerSelector,
+        cmp: Comparison,
+        count: u32,
+    },
+    LifeTotal {
+        player: PlayerSelector,
+        cmp: Comparison,
+        amount: i32,
+    },
+    /// A kiváltó esemény lapja (meghalt lény, célzott permanens) megfelel a szűrőnek
+    EventCard(TargetFilter),
+    And(Vec<Condition>),
+    Or(Vec<Condition>),
+    Not(Box<Condition>),
+}
+
+impl Condition {
+    /// "if you control a Mouse": legalább egy saját permanens illeszkedik
+    pub fn you_control(filter: TargetFilter) -> Condition {
+        Condition::Count {
+            filter: filter.and(TargetFilter::ControlledBy(PlayerSelector::Controller)),
+            cmp: Comparison::AtLeast,
+            count: 1,
+        }
+    }
+
+    pub fn and(self, other: Condition) -> Condition {
+        match self {
+            Condition::And(mut conditions) => {
+                conditions.push(other);
+                Condition::And(conditions)
+            }
+            first => Condition::And(vec![first, other]),
+        }
+    }
+
+    pub fn or(self, other: Condition) -> Condition {
+        match self {
+            Condition::Or(mut conditions) => {
+                conditions.push(other);
+                Condition::Or(conditions)
+            }
+            first => Condition::Or(vec![first, other]),
+        }
+    }
+
+    pub fn negate(self) -> Condition {
+        Condition::Not(Box::new(self))
+    }
+
+    /// Konstans feltétel (a kiváltáskor rögzített eseményfüggő részekhez)
+    pub fn constant(value: bool) -> Condition {
+        if value {
+            Condition::Always
+        } else {
+            Condition::Always.negate()
+        }
+    }
+
+    /// Csak a kiváltó eseményen múlik-e (ezeket a kiváltáskor értékeljük ki)
+    pub fn depends_on_event(&self) -> bool {
+        matches!(
+            self,
+            Condition::FirstTimeThisTurn
+                | Condition::OnceEachTurn
+                | Condition::SpellWasNonCreature
+                | Condition::EventCard(_)
+        )
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -288,6 +613,167 @@ pub enum TargetFilter {
     Land,
     ControllerLand,
     CardInGraveyard,
+    /// Minden megadott típus-bit kell (pl. `ARTIFACT | CREATURE`).
+    HasType(CardTypeFlags),
+    Supertype(SuperTypeFlags),
+    Subtype(Subtype),
+    /// Ki irányítja: `Controller` = "you control", `Opponent` = "an opponent controls"
+    ControlledBy(PlayerSelector),
+    /// Lény aktuális ereje (a kör végéig tartó módosításokkal együtt)
+    Power(Comparison, i32),
+    Toughness(Comparison, i32),
+    HasKeyword(KeywordAbility),
+    Tapped,
+    Token,
+    InZone(Zone),
+    /// Minden részszűrő illeszkedik
+    And(Vec<TargetFilter>),
+    /// Legalább egy részszűrő illeszkedik
+    Or(Vec<TargetFilter>),
+    Not(Box<TargetFilter>),
+}
+
+/// Összehasonlítás a számszerű szűrőkhöz ("power 4 or greater")
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Comparison {
+    LessThan,
+    AtMost,
+    Equal,
+    AtLeast,
+    GreaterThan,
+}
+
+impl Comparison {
+    pub fn holds(self, value: i32, threshold: i32) -> bool {
+        match self {
+            Comparison::LessThan => value < threshold,
+            Comparison::AtMost => value <= threshold,
+            Comparison::Equal => value == threshold,
+            Comparison::AtLeast => value >= threshold,
+            Comparison::GreaterThan => value > threshold,
+        }
+    }
+}
+
+/// Zónák, amelyekben a szűrő lapot keres
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Zone {
+    Battlefield,
+    Graveyard,
+    Exile,
+    Hand,
+    Library,
+    Stack,
+}
+
+/// A szűrő kiértékelésének környezete. A `None` mezőket a kiértékelő nem vizsgálja
+/// (így működik a környezet nélküli `matches_card`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FilterContext {
+    /// Akihez képest a "you control" / "an opponent controls" értendő
+    pub controller: Option<Player>,
+    /// A képesség forrása (`SelfCard`)
+    pub source: Option<u64>,
+    /// A vizsgált lap zónája
+    pub zone: Option<Zone>,
+}
+
+impl FilterContext {
+    pub fn new(controller: Player, source: Option<u64>, zone: Zone) -> Self {
+        Self {
+            controller: Some(controller),
+            source,
+            zone: Some(zone),
+        }
+    }
+}
+
+impl TargetFilter {
+    /// Csak a kártya saját tulajdonságait (típus, szupertípus, altípus, stat) vizsgálja.
+    /// A kontroller- és zónafüggő szűrők (`SelfCard`, `ControllerCreature`, ...)
+    /// itt csak a típusukra szűrnek, a többit a hívónak kell ellenőriznie.
+    pub fn matches_card(&self, card: &Card) -> bool {
+        self.matches(card, &FilterContext::default())
+    }
+
+    /// Az egyetlen szűrő-kiértékelő: minden effekt, trigger és célpont-ellenőrzés ezt hívja.
+    pub fn matches(&self, card: &Card, ctx: &FilterContext) -> bool {
+        let controlled_by = |selector: &PlayerSelector| match (selector, ctx.controller) {
+            (_, None) | (PlayerSelector::AnyPlayer, _) => true,
+            (PlayerSelector::Controller, Some(p)) => card.controller == p,
+            (PlayerSelector::Opponent, Some(p)) => card.controller != p,
+        };
+        let creature = match &card.card_type {
+            CardType::Creature(cr) => Some(cr),
+            _ => None,
+        };
+        match self {
+            TargetFilter::AnyTarget => true,
+            TargetFilter::SelfCard => ctx.source.is_none_or(|id| card.card_id == id),
+            TargetFilter::CardInGraveyard => ctx.zone.is_none_or(|z| z == Zone::Graveyard),
+            TargetFilter::Player => false,
+            TargetFilter::Creature => card.has_type(CardTypeFlags::CREATURE),
+            TargetFilter::ControllerCreature => {
+                card.has_type(CardTypeFlags::CREATURE) && controlled_by(&PlayerSelector::Controller)
+            }
+            TargetFilter::OpponentCreature => {
+                card.has_type(CardTypeFlags::CREATURE) && controlled_by(&PlayerSelector::Opponent)
+            }
+            TargetFilter::CreatureType(ct) => card.has_subtype(&Subtype::Creature(ct.clone())),
+            TargetFilter::ExactCardID(id) => card.card_id == *id,
+            TargetFilter::Artifact => card.has_type(CardTypeFlags::ARTIFACT),
+            TargetFilter::Enchantment => card.has_type(CardTypeFlags::ENCHANTMENT),
+            TargetFilter::Land => card.has_type(CardTypeFlags::LAND),
+            TargetFilter::ControllerLand => {
+                card.has_type(CardTypeFlags::LAND) && controlled_by(&PlayerSelector::Controller)
+            }
+            TargetFilter::HasType(flags) => card.has_type(flags.clone()),
+            TargetFilter::Supertype(flags) => card.has_supertype(flags.clone()),
+            TargetFilter::Subtype(st) => card.has_subtype(st),
+            TargetFilter::ControlledBy(selector) => controlled_by(selector),
+            TargetFilter::Power(cmp, n) => {
+                creature.is_some_and(|cr| cmp.holds(cr.power + cr.ephemeral_power, *n))
+            }
+            TargetFilter::Toughness(cmp, n) => {
+                creature.is_some_and(|cr| cmp.holds(cr.toughness + cr.ephemeral_toughness, *n))
+            }
+            TargetFilter::HasKeyword(ability) => {
+                creature.is_some_and(|cr| cr.abilities.contains(ability))
+            }
+            TargetFilter::Tapped => card.tapped,
+            TargetFilter::Token => card.has_type(CardTypeFlags::TOKEN),
+            TargetFilter::InZone(zone) => ctx.zone.is_none_or(|z| z == *zone),
+            TargetFilter::And(filters) => filters.iter().all(|f| f.matches(card, ctx)),
+            TargetFilter::Or(filters) => filters.iter().any(|f| f.matches(card, ctx)),
+            TargetFilter::Not(filter) => !filter.matches(card, ctx),
+        }
+    }
+
+    /// Szűrők összefűzése: `Creature.and(Power(AtLeast, 4))`
+    pub fn and(self, other: TargetFilter) -> TargetFilter {
+        match self {
+            TargetFilter::And(mut filters) => {
+                filters.push(other);
+                TargetFilter::And(filters)
+            }
+            first => TargetFilter::And(vec![first, other]),
+        }
+    }
+
+    pub fn or(self, other: TargetFilter) -> TargetFilter {
+        match self {
+            TargetFilter::Or(mut filters) => {
+                filters.push(other);
+                TargetFilter::Or(filters)
+            }
+            first => TargetFilter::Or(vec![first, other]),
+        }
+    }
+
+    /// Tagadás: `Land.negate()` = "nonland"
+    pub fn negate(self) -> TargetFilter {
+        TargetFilter::Not(Box::new(self))
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -366,7 +852,7 @@ impl CardAttribute for CreateEnchantmentAttribute {
                     name: self.name.clone(),
                     power_buff: self.power_buff,
                     toughness_buff: self.toughness_buff,
-                    ability: self.ability.clone(),
+                    ability: self.ability,
                 }],
             })
         } else {
@@ -425,7 +911,7 @@ impl CardAttribute for GrantAbilityAttribute {
             || matches!(trigger, Trigger::OnEnterBattlefield { .. })
         {
             Some(Effect::GrantAbility {
-                ability: self.ability.clone(),
+                ability: self.ability,
                 duration: self.duration.clone(),
                 target: self.target.clone(),
             })
@@ -498,8 +984,8 @@ pub struct ProwessAttribute {
 impl CardAttribute for ProwessAttribute {
     fn on_trigger(&mut self, trigger: &Trigger) -> Option<Effect> {
         debug!("ProwessAttribute: trigger = {:?}", trigger);
-        if let Trigger::OnSpellCast { filter } = trigger {
-            if *filter == self.filter {
+        if let Trigger::OnSpellCast { filter } = trigger
+            && *filter == self.filter {
                 let res = Some(Effect::ModifyStats {
                     power_delta: self.power,
                     toughness_delta: self.toughness,
@@ -512,7 +998,6 @@ impl CardAttribute for ProwessAttribute {
                 );
                 return res;
             }
-        }
         None
     }
     fn as_any(&self) -> &dyn Any {
@@ -526,8 +1011,8 @@ pub struct LifelinkAttribute;
 impl CardAttribute for LifelinkAttribute {
     fn on_trigger(&mut self, trigger: &Trigger) -> Option<Effect> {
         debug!("LifelinkAttribute: trigger = {:?}", trigger);
-        if let Trigger::OnCombatDamage { filter } = trigger {
-            if *filter == TargetFilter::SelfCard {
+        if let Trigger::OnCombatDamage { filter } = trigger
+            && *filter == TargetFilter::SelfCard {
                 info!("LifelinkAttribute: granting lifelink conditional");
                 return Some(Effect::Conditional {
                     condition: Condition::Always,
@@ -538,7 +1023,6 @@ impl CardAttribute for LifelinkAttribute {
                     effect_if_false: None,
                 });
             }
-        }
         None
     }
     fn as_any(&self) -> &dyn Any {
@@ -552,15 +1036,14 @@ pub struct DeathtouchAttribute;
 impl CardAttribute for DeathtouchAttribute {
     fn on_trigger(&mut self, trigger: &Trigger) -> Option<Effect> {
         debug!("DeathtouchAttribute: trigger = {:?}", trigger);
-        if let Trigger::OnCombatDamage { filter } = trigger {
-            if *filter == TargetFilter::Creature {
+        if let Trigger::OnCombatDamage { filter } = trigger
+            && *filter == TargetFilter::Creature {
                 info!("DeathtouchAttribute: returning fixed damage = 1");
                 return Some(Effect::Damage {
                     amount: Amount::Fixed(1),
                     target: TargetFilter::SelfCard,
                 });
             }
-        }
         None
     }
     fn as_any(&self) -> &dyn Any {
@@ -574,8 +1057,8 @@ pub struct TrampleAttribute;
 impl CardAttribute for TrampleAttribute {
     fn on_trigger(&mut self, trigger: &Trigger) -> Option<Effect> {
         debug!("TrampleAttribute: trigger = {:?}", trigger);
-        if let Trigger::OnCombatDamage { filter } = trigger {
-            if *filter == TargetFilter::SelfCard {
+        if let Trigger::OnCombatDamage { filter } = trigger
+            && *filter == TargetFilter::SelfCard {
                 info!("TrampleAttribute: returning trample conditional");
                 return Some(Effect::Conditional {
                     condition: Condition::Always,
@@ -586,7 +1069,6 @@ impl CardAttribute for TrampleAttribute {
                     effect_if_false: None,
                 });
             }
-        }
         None
     }
     fn as_any(&self) -> &dyn Any {
@@ -600,8 +1082,8 @@ pub struct DoubleStrikeAttribute;
 impl CardAttribute for DoubleStrikeAttribute {
     fn on_trigger(&mut self, trigger: &Trigger) -> Option<Effect> {
         debug!("DoubleStrikeAttribute: trigger = {:?}", trigger);
-        if let Trigger::OnCombatDamage { filter } = trigger {
-            if *filter == TargetFilter::SelfCard {
+        if let Trigger::OnCombatDamage { filter } = trigger
+            && *filter == TargetFilter::SelfCard {
                 info!("DoubleStrikeAttribute: scheduling delayed damage");
                 return Some(Effect::Delayed {
                     effect: Box::new(Effect::Damage {
@@ -612,7 +1094,6 @@ impl CardAttribute for DoubleStrikeAttribute {
                     deps: Vec::new(),
                 });
             }
-        }
         None
     }
     fn as_any(&self) -> &dyn Any {
@@ -677,7 +1158,6 @@ impl CardAttribute for AddCounterAttribute {
 
 #[derive(Debug, Clone)]
 pub struct ProliferateAttribute {
-    pub counter: CounterType,
     pub player: PlayerSelector,
 }
 
@@ -686,7 +1166,6 @@ impl CardAttribute for ProliferateAttribute {
         info!("ProliferateAttribute: trigger = {:?}", trigger);
         if matches!(trigger, Trigger::OnCombatDamage { .. }) {
             let res = Some(Effect::Proliferate {
-                counter_type: self.counter.clone(),
                 player: self.player.clone(),
             });
             debug!("ProliferateAttribute: result = {:?}", res);
@@ -755,40 +1234,6 @@ impl CardAttribute for ConditionalAttribute {
     }
 }
 
-#[derive(Debug, Clone)]
-pub struct FirstTimePerTurnAttribute {
-    pub base_trigger: Trigger,
-    pub reset_phase: GamePhase,
-    pub action: Effect,
-    pub used: bool,
-}
-
-impl CardAttribute for FirstTimePerTurnAttribute {
-    fn on_trigger(&mut self, trigger: &Trigger) -> Option<Effect> {
-        info!(
-            "FirstTimePerTurnAttribute: trigger = {:?}, used = {}",
-            trigger, self.used
-        );
-        match trigger {
-            t if *t == self.base_trigger && !self.used => {
-                self.used = true;
-                let res = Some(self.action.clone());
-                info!("FirstTimePerTurnAttribute: firing action = {:?}", res);
-                res
-            }
-            Trigger::AtPhase { phase, player: _ } if *phase == self.reset_phase => {
-                self.used = false;
-                info!("FirstTimePerTurnAttribute: reset used flag");
-                None
-            }
-            _ => None,
-        }
-    }
-    fn as_any(&self) -> &dyn Any {
-        self
-    }
-}
-
 #[derive(Debug, Clone)]
 pub struct DelayedAttribute {
     pub effect: Effect,
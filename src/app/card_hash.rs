@@ -0,0 +1,290 @@
+// app/card_hash.rs
+
+//! Card recognition by perceptual hashing. An offline-built index maps art-crop
+//! hashes to card names; hand cards and battlefield creatures are identified from
+//! the screenshot crops without hovering. Low-confidence matches fall back to OCR.
+
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+
+use image::imageops::FilterType;
+use image::DynamicImage;
+use once_cell::sync::Lazy;
+use thiserror::Error;
+use tracing::{debug, info, warn};
+
+use crate::app::cards_positions::get_card_positions;
+use crate::app::creature_positions::CreaturePosition;
+use crate::app::layout::layout;
+use crate::app::ocr::{get_card_text, read_creature_text};
+use crate::app::screen_source::screen_source;
+
+/// Errors of building, loading or saving a hash index.
+#[derive(Debug, Error)]
+pub enum CardHashError {
+    #[error("Failed to load card image {path}: {reason}")]
+    Image { path: String, reason: String },
+    #[error("Malformed index line {line}: {content}")]
+    Parse { line: usize, content: String },
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// 64-bit difference hash (dHash): 9×8 grayscale, one bit per horizontal gradient.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PerceptualHash(pub u64);
+
+impl PerceptualHash {
+    pub fn of(img: &DynamicImage) -> Self {
+        let small = img.resize_exact(9, 8, FilterType::Triangle).to_luma8();
+        let mut bits = 0u64;
+        for y in 0..8 {
+            for x in 0..8 {
+                let left = small.get_pixel(x, y)[0];
+                let right = small.get_pixel(x + 1, y)[0];
+                bits = (bits << 1) | u64::from(left > right);
+            }
+        }
+        Self(bits)
+    }
+
+    pub fn distance(&self, other: &PerceptualHash) -> u32 {
+        (self.0 ^ other.0).count_ones()
+    }
+}
+
+/// Result of an index lookup.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HashMatch {
+    pub name: String,
+    /// Hamming distance, 0–64.
+    pub distance: u32,
+    /// 1.0 for identical hashes, lowered by the distance and by a close runner-up.
+    pub confidence: f32,
+}
+
+/// Card-name → art hash index. Several hashes per card are allowed (variants, foils).
+#[derive(Debug, Clone)]
+pub struct CardHashIndex {
+    entries: Vec<(String, PerceptualHash)>,
+    min_confidence: f32,
+}
+
+impl Default for CardHashIndex {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CardHashIndex {
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            min_confidence: 0.8,
+        }
+    }
+
+    /// Matches below this confidence are rejected (and OCR is used instead).
+    pub fn with_min_confidence(mut self, min_confidence: f32) -> Self {
+        self.min_confidence = min_confidence;
+        self
+    }
+
+    pub fn insert(&mut self, name: &str, hash: PerceptualHash) {
+        self.entries.push((name.to_string(), hash));
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Builds the index from a directory of art crops. The file stem is the card name;
+    /// a `#…` suffix marks extra variants (`Shock#2.png`).
+    pub fn build_from_dir(dir: impl AsRef<Path>) -> Result<Self, CardHashError> {
+        let mut files: Vec<_> = fs::read_dir(dir.as_ref())?
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| {
+                p.extension()
+                    .and_then(|e| e.to_str())
+                    .is_some_and(|e| matches!(e.to_ascii_lowercase().as_str(), "png" | "jpg" | "jpeg"))
+            })
+            .collect();
+        files.sort();
+
+        let mut index = Self::new();
+        for path in files {
+            let img = image::open(&path).map_err(|e| CardHashError::Image {
+                path: path.display().to_string(),
+                reason: e.to_string(),
+            })?;
+            let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or_default();
+            let name = stem.split('#').next().unwrap_or(stem).trim();
+            index.insert(name, PerceptualHash::of(&img));
+        }
+        info!("Built card hash index: {} entries from {}", index.len(), dir.as_ref().display());
+        Ok(index)
+    }
+
+    /// Loads an index saved by `save` (`<16 hex digits>\t<name>` per line).
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, CardHashError> {
+        let mut index = Self::new();
+        for (i, line) in fs::read_to_string(path)?.lines().enumerate() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let parsed = line
+                .split_once('\t')
+                .and_then(|(hash, name)| u64::from_str_radix(hash, 16).ok().map(|h| (h, name)));
+            match parsed {
+                Some((hash, name)) => index.insert(name, PerceptualHash(hash)),
+                None => {
+                    return Err(CardHashError::Parse {
+                        line: i + 1,
+                        content: line.to_string(),
+                    });
+                }
+            }
+        }
+        Ok(index)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), CardHashError> {
+        let mut out = String::new();
+        for (name, hash) in &self.entries {
+            out.push_str(&format!("{:016x}\t{}\n", hash.0, name));
+        }
+        fs::write(path, out)?;
+        Ok(())
+    }
+
+    /// Nearest card, regardless of the threshold.
+    pub fn best_match(&self, hash: PerceptualHash) -> Option<HashMatch> {
+        let mut best: Option<(&str, u32)> = None;
+        let mut runner_up: Option<u32> = None;
+        for (name, h) in &self.entries {
+            let d = hash.distance(h);
+            match best {
+                Some((best_name, best_d)) if d < best_d => {
+                    if best_name != name {
+                        runner_up = Some(best_d);
+                    }
+                    best = Some((name, d));
+                }
+                Some((best_name, _)) if best_name != name => {
+                    runner_up = Some(runner_up.map_or(d, |r| r.min(d)));
+                }
+                None => best = Some((name, d)),
+                _ => {}
+            }
+        }
+        let (name, distance) = best?;
+        let mut confidence = 1.0 - distance as f32 / 32.0;
+        // A different card almost as close makes the match unreliable
+        if runner_up.is_some_and(|r| r <= distance + 2) {
+            confidence -= 0.2;
+        }
+        Some(HashMatch {
+            name: name.to_string(),
+            distance,
+            confidence: confidence.clamp(0.0, 1.0),
+        })
+    }
+
+    /// Nearest card if it passes the threshold.
+    pub fn identify(&self, img: &DynamicImage) -> Option<HashMatch> {
+        let m = self.best_match(PerceptualHash::of(img))?;
+        if m.confidence < self.min_confidence {
+            debug!("Hash match `{}` rejected: confidence {:.2}", m.name, m.confidence);
+            return None;
+        }
+        Some(m)
+    }
+}
+
+static CARD_INDEX: Lazy<RwLock<Option<Arc<CardHashIndex>>>> = Lazy::new(|| RwLock::new(None));
+
+/// Replaces the globally used card index.
+pub fn set_card_index(index: Arc<CardHashIndex>) {
+    *CARD_INDEX.write().unwrap() = Some(index);
+}
+
+/// The currently loaded card index, if any.
+pub fn card_index() -> Option<Arc<CardHashIndex>> {
+    CARD_INDEX.read().unwrap().clone()
+}
+
+/// `MTGA_CARD_INDEX=<file>` loads a saved index; without it everything goes through OCR.
+pub fn init_card_index_from_env() -> Result<(), CardHashError> {
+    if let Ok(path) = std::env::var("MTGA_CARD_INDEX") {
+        let index = CardHashIndex::load(&path)?;
+        info!("Loaded card hash index {} ({} entries)", path, index.len());
+        set_card_index(Arc::new(index));
+    }
+    Ok(())
+}
+
+/// Art crop of an un-hovered hand card: the visible top of the card at the bottom edge.
+pub fn hand_card_art_region(
+    index: usize,
+    card_count: usize,
+    screen_width: u32,
+    screen_height: u32,
+) -> Option<(u32, u32, u32, u32)> {
+    let pos = get_card_positions(card_count, screen_width).get(index).copied()?;
+    let layout = layout();
+    let (art_y1, art_y2) = layout.pair("hand.art_y");
+    let y1 = layout.sy(art_y1, screen_height).floor() as u32;
+    let y2 = layout.sy(art_y2, screen_height).floor() as u32;
+    Some((pos.ocr_x1, y1, pos.ocr_x2, y2))
+}
+
+/// Crops `(x1, y1, x2, y2)` from the current screen and looks it up in the index.
+fn identify_region(index: &CardHashIndex, (x1, y1, x2, y2): (u32, u32, u32, u32)) -> Option<HashMatch> {
+    if x2 <= x1 || y2 <= y1 {
+        return None;
+    }
+    match screen_source().capture_area(x1 as i32, y1 as i32, x2 - x1, y2 - y1) {
+        Ok(img) => index.identify(&img),
+        Err(e) => {
+            warn!("Card art capture failed: {}", e);
+            None
+        }
+    }
+}
+
+/// Name of the hand card at `index`: hash match if confident, otherwise hover + OCR.
+pub fn identify_hand_card(index: usize, card_count: usize, screen_width: u32, screen_height: u32) -> String {
+    if let Some(idx) = card_index() {
+        let m = hand_card_art_region(index, card_count, screen_width, screen_height)
+            .and_then(|region| identify_region(&idx, region));
+        if let Some(m) = m {
+            info!("Hand card {} identified by hash: {} ({:.2})", index, m.name, m.confidence);
+            return m.name;
+        }
+        info!("Hand card {}: no confident hash match, falling back to OCR", index);
+    }
+    get_card_text(index, card_count, screen_width, screen_height)
+}
+
+/// Name of a battlefield creature: hash match on its click area, otherwise OCR.
+pub fn identify_creature(
+    pos: CreaturePosition,
+    index: usize,
+    is_opponent: bool,
+    screen_width: u32,
+    screen_height: u32,
+) -> String {
+    if let Some(idx) = card_index() {
+        if let Some(m) = identify_region(&idx, (pos.click_x1, pos.click_y1, pos.click_x2, pos.click_y2)) {
+            info!("Creature #{} identified by hash: {} ({:.2})", index, m.name, m.confidence);
+            return m.name;
+        }
+        info!("Creature #{}: no confident hash match, falling back to OCR", index);
+    }
+    read_creature_text(pos, index, is_opponent, screen_width, screen_height)
+}
@@ -1,21 +1,23 @@
 use crate::app::card_attribute::CardAttribute;
 use crate::app::card_attribute::CreatureType::Detective;
 use crate::app::card_attribute::*;
-use crate::app::game_state::GamePhase;
+use crate::app::game_state::{GamePhase, Player};
 use crate::app::gre::Gre;
-use crate::app::gre::gre_structs::ActivatedAbility;
+use crate::app::gre::gre_structs::{ActivatedAbility, ActivationTiming};
+use crate::app::gre::{Cost, OptionalCost};
 use bitflags::bitflags;
 use std::collections::HashMap;
 use std::hash::{Hash, Hasher};
 use tracing::{debug, info};
 
+/// Rejtett zónából (könyvtár, ellenfél keze) érkezett, még fel nem ismert lap neve
+pub const HIDDEN_CARD: &str = "Hidden card";
 const CACOPHONY_SCAMP: &str = "Cacophony Scamp";
 const MONASTERY_SWIFTSPEAR: &str = "Monastery Swiftspear";
 const ELECTROSTATIC_INFANTRY: &str = "Electrostatic Infantry";
 const HEARTFIRE_HERO: &str = "Heartfire Hero";
 const FELONIOUS_RAGE: &str = "Felonious Rage";
 const MONSTROUS_RAGE: &str = "Monstrous Rage";
-const MONSTER_ROLE: &str = "Monster Role";
 const BLAZING_CRESCENDO: &str = "Blazing Crescendo";
 const DEMONIC_RUCKUS: &str = "Demonic Ruckus";
 const BURST_LIGHTNING: &str = "Burst Lightning";
@@ -54,15 +56,36 @@ pub struct Creature {
     pub ephemeral_toughness: i32,
 }
 
+/// Planeswalker: a lapra nyomtatott kezdő loyalty és az aktuális loyalty counterek száma.
+/// A counterek a battlefieldre lépéskor kerülnek rá (`Gre::enter_battlefield`).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Planeswalker {
+    pub starting_loyalty: i32,
+    pub loyalty: i32,
+}
+
+impl Planeswalker {
+    pub fn new(starting_loyalty: i32) -> Self {
+        Self {
+            starting_loyalty,
+            loyalty: 0,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum CardType {
     Creature(Creature),
     Instant,
+    Sorcery,
     Land,
     Enchantment,
     Token,
-    Planeswalker,
+    Planeswalker(Planeswalker),
     Artifact,
+    Battle,
+    /// Törzsi (Kindred) lap; a többi típust `with_added_type`-pal kapja meg.
+    Kindred,
 }
 
 /// ManaCost
@@ -110,6 +133,18 @@ bitflags! {
         const PLANESWALKER = 1 << 6;
         const BATTLE       = 1 << 7;
         const TOKEN        = 1 << 8;
+        const KINDRED      = 1 << 9;
+    }
+}
+bitflags! {
+    /// Szupertípusok (Legendary, Basic, Snow, World).
+    #[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
+    pub struct SuperTypeFlags: u32 {
+        const NONE      = 0;
+        const LEGENDARY = 1 << 0;
+        const BASIC     = 1 << 1;
+        const SNOW      = 1 << 2;
+        const WORLD     = 1 << 3;
     }
 }
 /// A kártya fő struktúrája.
@@ -120,11 +155,29 @@ pub struct Card {
     pub name: String,
     pub card_type: CardType,
     pub type_flags: CardTypeFlags,
+    pub supertypes: SuperTypeFlags,
+    /// Nem-lény altípusok (Forest, Aura, Role, Class, ...); a lény altípusok a `Creature::types`-ban vannak.
+    pub subtypes: Vec<Subtype>,
     pub mana_cost: ManaCost,
     pub attributes: Vec<Box<dyn CardAttribute>>,
     pub triggers: Vec<Trigger>,
     pub activated_abilities: Vec<ActivatedAbility>,
     pub attached_to: Option<u64>,
+    /// Ki irányítja a permanenst (alapból mi)
+    pub controller: Player,
+    /// A permanensen lévő counterek. A loyalty a `Planeswalker`-ben van,
+    /// a +1/+1 és -1/-1 counterek a lény base statjaiba is beleszámolódnak.
+    pub counters: HashMap<CounterType, u32>,
+    /// Tappolva van-e (a battlefield-olvasó a képernyőről állítja)
+    pub tapped: bool,
+    /// Modális varázslat módjai (Choose one / two / one or more)
+    pub modal: Option<ModalSpell>,
+    /// Kötelező plusz költségek ("As an additional cost ...")
+    pub additional_costs: Vec<Cost>,
+    /// Választható plusz költségek (kicker, offspring)
+    pub optional_costs: Vec<OptionalCost>,
+    /// Flashback költség: a temetőből is kijátszható ennyiért
+    pub flashback: Option<ManaCost>,
 }
 impl Hash for Card {
     fn hash<H: Hasher>(&self, state: &mut H) {
@@ -138,6 +191,8 @@ impl Hash for Card {
 
         // type_flags bitjei
         self.type_flags.bits().hash(state);
+        self.supertypes.bits().hash(state);
+        self.subtypes.hash(state);
 
         // mana_cost -> #derive(Hash)
         self.mana_cost.hash(state);
@@ -157,6 +212,8 @@ impl Hash for Card {
 
         // attached_to
         self.attached_to.hash(state);
+        self.controller.hash(state);
+        // counters: HashMap nem hashelhető, kimarad
     }
 }
 
@@ -166,9 +223,11 @@ impl PartialEq for Card {
         self.name == other.name
             && self.card_type == other.card_type
             && self.type_flags == other.type_flags
+            && self.supertypes == other.supertypes
+            && self.subtypes == other.subtypes
             && self.mana_cost == other.mana_cost
             && self.triggers == other.triggers
-        // attributes és activated_abilities kimarad
+        // attributes, activated_abilities, controller és counters kimarad
     }
 }
 
@@ -186,6 +245,9 @@ impl Card {
             CardType::Instant => {
                 flags |= CardTypeFlags::INSTANT;
             }
+            CardType::Sorcery => {
+                flags |= CardTypeFlags::SORCERY;
+            }
             CardType::Enchantment => {
                 flags |= CardTypeFlags::ENCHANTMENT;
             }
@@ -195,31 +257,86 @@ impl Card {
             CardType::Token => {
                 flags |= CardTypeFlags::TOKEN;
             }
-            CardType::Planeswalker => {
+            CardType::Planeswalker(_) => {
                 flags |= CardTypeFlags::PLANESWALKER;
             }
             CardType::Artifact => {
                 flags |= CardTypeFlags::ARTIFACT;
             }
+            CardType::Battle => {
+                flags |= CardTypeFlags::BATTLE;
+            }
+            CardType::Kindred => {
+                flags |= CardTypeFlags::KINDRED;
+            }
         }
         Card {
             card_id: 0, // GRE osztja ki
             name: name.into(),
             card_type,
             type_flags: flags,
+            supertypes: SuperTypeFlags::NONE,
+            subtypes: Vec::new(),
             mana_cost,
             attributes: Vec::new(),
             triggers: Vec::new(),
             activated_abilities: Vec::new(),
             attached_to: None,
+            controller: Player::Us,
+            counters: HashMap::new(),
+            tapped: false,
+            modal: None,
+            additional_costs: Vec::new(),
+            optional_costs: Vec::new(),
+            flashback: None,
         }
     }
+    /// Fel nem ismert lap helyőrzője: nincs típusa, költsége és képessége.
+    pub fn hidden() -> Self {
+        let mut card = Card::new(HIDDEN_CARD, CardType::Sorcery, ManaCost::free());
+        card.type_flags = CardTypeFlags::NONE;
+        card
+    }
+    pub fn is_hidden(&self) -> bool {
+        self.name == HIDDEN_CARD
+    }
     /// Egy triggert és attribútumot ad a kártyához.
     pub fn with(mut self, trigger: Trigger, attr: impl CardAttribute + 'static) -> Self {
         self.triggers.push(trigger);
         self.attributes.push(Box::new(attr));
         self
     }
+    /// Modális varázslat módjai
+    pub fn with_modes(mut self, modal: ModalSpell) -> Self {
+        self.modal = Some(modal);
+        self
+    }
+    /// Kötelező plusz költség (pl. "As an additional cost, sacrifice a creature")
+    pub fn with_additional_cost(mut self, cost: Cost) -> Self {
+        self.additional_costs.push(cost);
+        self
+    }
+    /// Kicker: ha kifizetik, a `Condition::SpellWasKicked` igaz a feloldáskor
+    pub fn with_kicker(mut self, cost: ManaCost) -> Self {
+        self.optional_costs.push(OptionalCost::kicker(cost));
+        self
+    }
+    /// Offspring: ha kifizetik, belépéskor egy 1/1-es token másolat is jön
+    pub fn with_offspring(mut self, cost: ManaCost) -> Self {
+        let additional_cost = cost.total();
+        self.optional_costs.push(OptionalCost::offspring(cost));
+        self.with(
+            Trigger::OnEnterBattlefield {
+                filter: TargetFilter::SelfCard,
+            },
+            OffspringAttribute { additional_cost },
+        )
+    }
+    /// Flashback: a temetőből is kijátszható a megadott költségért
+    pub fn with_flashback(mut self, cost: ManaCost) -> Self {
+        self.flashback = Some(cost);
+        self
+    }
     /// Egy activated ability-t is hozzáadhatunk
     pub fn with_activated(mut self, ability: ActivatedAbility) -> Self {
         self.activated_abilities.push(ability);
@@ -227,10 +344,135 @@ impl Card {
     }
 
     /// A token bitflag vagy bármely más bitflag hozzáadása
+    /// (pl. artifact creature: `CardType::Creature` + `CardTypeFlags::ARTIFACT`)
     pub fn with_added_type(mut self, flag: CardTypeFlags) -> Self {
         self.type_flags |= flag;
         self
     }
+    /// Szupertípus hozzáadása (Legendary, Basic, Snow, World)
+    pub fn with_supertype(mut self, flag: SuperTypeFlags) -> Self {
+        self.supertypes |= flag;
+        self
+    }
+    /// Nem-lény altípus hozzáadása (Forest, Aura, Role, Class, ...)
+    pub fn with_subtype(mut self, subtype: Subtype) -> Self {
+        if !self.subtypes.contains(&subtype) {
+            self.subtypes.push(subtype);
+        }
+        self
+    }
+    /// Az irányító játékos beállítása
+    pub fn with_controller(mut self, controller: Player) -> Self {
+        self.controller = controller;
+        self
+    }
+    /// Minden megadott típus-bit megvan-e a kártyán.
+    pub fn has_type(&self, flag: CardTypeFlags) -> bool {
+        self.type_flags.contains(flag)
+    }
+    /// Minden megadott szupertípus megvan-e a kártyán.
+    pub fn has_supertype(&self, flag: SuperTypeFlags) -> bool {
+        self.supertypes.contains(flag)
+    }
+    /// Altípus-egyezés; a lény altípusokat a `Creature::types`-ból nézi.
+    pub fn has_subtype(&self, subtype: &Subtype) -> bool {
+        match subtype {
+            Subtype::Creature(ct) => match &self.card_type {
+                CardType::Creature(cr) => cr.types.contains(ct),
+                _ => false,
+            },
+            other => self.subtypes.contains(other),
+        }
+    }
+    /// Planeswalker esetén az aktuális loyalty counterek száma.
+    pub fn loyalty(&self) -> Option<i32> {
+        match &self.card_type {
+            CardType::Planeswalker(pw) => Some(pw.loyalty),
+            _ => None,
+        }
+    }
+    /// Adott fajtájú counterek száma (loyalty esetén a planeswalker loyaltyja).
+    pub fn counter_count(&self, counter: &CounterType) -> u32 {
+        match (counter, &self.card_type) {
+            (CounterType::Loyalty, CardType::Planeswalker(pw)) => pw.loyalty.max(0) as u32,
+            _ => self.counters.get(counter).copied().unwrap_or(0),
+        }
+    }
+    /// Counterek felrakása. A +1/+1 és -1/-1 counterek a base statot is módosítják,
+    /// és párban azonnal kioltják egymást (state-based action).
+    pub fn add_counters(&mut self, counter: &CounterType, amount: u32) {
+        if amount == 0 {
+            return;
+        }
+        match (counter, &mut self.card_type) {
+            (CounterType::Loyalty, CardType::Planeswalker(pw)) => {
+                pw.loyalty += amount as i32;
+                return;
+            }
+            (CounterType::PlusOnePlusOne, CardType::Creature(cr)) => {
+                cr.power += amount as i32;
+                cr.toughness += amount as i32;
+            }
+            (CounterType::MinusOneMinusOne, CardType::Creature(cr)) => {
+                cr.power -= amount as i32;
+                cr.toughness -= amount as i32;
+            }
+            _ => {}
+        }
+        *self.counters.entry(counter.clone()).or_insert(0) += amount;
+
+        let plus = self.counter_count(&CounterType::PlusOnePlusOne);
+        let minus = self.counter_count(&CounterType::MinusOneMinusOne);
+        let annihilated = plus.min(minus);
+        if annihilated > 0 {
+            // A stat nem változik: a párok hatása nulla volt
+            for kind in [CounterType::PlusOnePlusOne, CounterType::MinusOneMinusOne] {
+                if let Some(n) = self.counters.get_mut(&kind) {
+                    *n -= annihilated;
+                }
+            }
+            debug!(
+                "'{}': {} pár +1/+1 és -1/-1 counter kioltotta egymást",
+                self.name, annihilated
+            );
+        }
+        self.counters.retain(|_, n| *n > 0);
+    }
+    /// Counterek levétele; visszaadja, ténylegesen hány került le.
+    pub fn remove_counters(&mut self, counter: &CounterType, amount: u32) -> u32 {
+        let removed = amount.min(self.counter_count(counter));
+        if removed == 0 {
+            return 0;
+        }
+        match (counter, &mut self.card_type) {
+            (CounterType::Loyalty, CardType::Planeswalker(pw)) => {
+                pw.loyalty -= removed as i32;
+                return removed;
+            }
+            (CounterType::PlusOnePlusOne, CardType::Creature(cr)) => {
+                cr.power -= removed as i32;
+                cr.toughness -= removed as i32;
+            }
+            (CounterType::MinusOneMinusOne, CardType::Creature(cr)) => {
+                cr.power += removed as i32;
+                cr.toughness += removed as i32;
+            }
+            _ => {}
+        }
+        if let Some(n) = self.counters.get_mut(counter) {
+            *n -= removed;
+        }
+        self.counters.retain(|_, n| *n > 0);
+        removed
+    }
+    /// Minden counter-fajta, ami jelenleg a kártyán van (loyaltyval együtt).
+    pub fn counter_kinds(&self) -> Vec<CounterType> {
+        let mut kinds: Vec<CounterType> = self.counters.keys().cloned().collect();
+        if self.loyalty().is_some_and(|l| l > 0) {
+            kinds.push(CounterType::Loyalty);
+        }
+        kinds
+    }
     /// A creature powerjét átírja (ha creature)
     pub fn with_power(mut self, new_power: i32) -> Self {
         if let CardType::Creature(ref mut c) = self.card_type {
@@ -335,11 +577,10 @@ impl Card {
             self.name, ephemeral_power
         );
 
-        // 3) +1/+1 countereket (vagy egyéb countereket) is beleszámítjuk
-        let plus_one_sum = self.find_plus_one_counters();
+        // 3) A +1/+1 és -1/-1 counterek már a base powerben vannak (`Card::add_counters`)
         info!(
-            "get_current_power('{}'): plus_one_counters => +{}",
-            self.name, plus_one_sum
+            "get_current_power('{}'): counters => {:?}",
+            self.name, self.counters
         );
 
         // 4) Aura-szerű buffok: ha van pl. 'Monster' aura csatolva,
@@ -351,7 +592,7 @@ impl Card {
         );
 
         // 5) Összeadjuk az egészet
-        let total = base_power + ephemeral_power + plus_one_sum + aura_buff;
+        let total = base_power + ephemeral_power + aura_buff;
         info!(
             "get_current_power('{}'): total power = {}",
             self.name, total
@@ -389,11 +630,10 @@ impl Card {
             self.name, ephemeral_toughness
         );
 
-        // 3) +1/+1 counterek -> +1 toughness mindegyik
-        let plus_one_sum = self.find_plus_one_counters();
+        // 3) A counterek már a base toughnessben vannak (`Card::add_counters`)
         info!(
-            "get_current_toughness('{}'): plus_one_counters => +{}",
-            self.name, plus_one_sum
+            "get_current_toughness('{}'): counters => {:?}",
+            self.name, self.counters
         );
 
         // 4) aura/tárgy buffok
@@ -404,7 +644,7 @@ impl Card {
         );
 
         // 5) Összegezés
-        let total = base_toughness + ephemeral_toughness + plus_one_sum + aura_buff;
+        let total = base_toughness + ephemeral_toughness + aura_buff;
         info!(
             "get_current_toughness('{}'): total toughness = {}",
             self.name, total
@@ -413,7 +653,7 @@ impl Card {
         total
     }
     /// Lekérdezzük az _aktuális_ power/toughness értéket,
-    /// figyelembe véve a bázis-statsot, +1/+1 countereket,
+    /// figyelembe véve a bázis-statsot (counterekkel együtt),
     /// aura tokeneket, ephemeral buffokat, stb.
     pub fn current_power_toughness(&self, gre: &Gre) -> (i32, i32) {
         // 1) Ha nem Creature, visszaadjuk (0,0).
@@ -426,14 +666,8 @@ impl Card {
         let mut power = creature.power;
         let mut toughness = creature.toughness;
 
-        // 2) Ha vannak +1/+1 counterek, hozzáadjuk.
-        // (Attól függ, hogy a Te kódban a +1/+1 counterek ténylegesen
-        //  beleszámolódnak-e a base-powerbe, vagy tárolsz valahol
-        //  `plus_one_counter_count: i32` mezőt, stb.)
-        // Például:
-        let plus_one_count = self.find_plus_one_counters();
-        power += plus_one_count;
-        toughness += plus_one_count;
+        // 2) A +1/+1 és -1/-1 counterek a `Card::add_counters` révén
+        //    már a base statokban vannak, itt nincs teendő.
 
         // 3) Ha vannak aura tokenek, amelyek +X/+Y-t és/vagy képességet adnak,
         //    akkor a GRE-ben a "battlefield_creatures" map-ben
@@ -463,32 +697,6 @@ impl Card {
         // ... és még folytathatnánk
         (power, toughness)
     }
-    /// Megnézzük, van-e valamilyen +1/+1 counter a kártyán.
-    /// Például, ha a Card attribute-jei között szerepel az AddCounterAttribute,
-    /// vagy ha van valami “self.num_plus_one_counters” típusú mező, akkor abból összeadjuk.
-    pub fn find_plus_one_counters(&self) -> i32 {
-        let mut total = 0;
-
-        // 1) Ha van dedikált `num_plus_one_counters` meződ, azt ide beírhatod:
-        // total += self.num_plus_one_counters;
-
-        // 2) Ha a countereket a Card attributes‐ben tárolod,
-        //    akkor végignézheted a `self.attributes` listát.
-        // Példa:
-        for attr in &self.attributes {
-            // futásidőn megkérdezzük, hogy ez a trait-obj vajon AddCounterAttribute–e
-            if let Some(a) = attr.as_any().downcast_ref::<AddCounterAttribute>() {
-                if a.counter == CounterType::PlusOnePlusOne {
-                    // a.amount a “+1/+1 counter” mennyisége
-                    total += a.amount as i32;
-                }
-            }
-            // (plusz hasonló megoldás, ha a FirstTimePerTurnAttribute generált +1/+1–et, stb.)
-        }
-
-        total
-    }
-
     /// Ha ez a kártya "Monster" enchantment–token (vagy bármely más aura),
     /// akkor visszaadja, hogy mekkora buffot ad a “gazda” lénynek.
     /// Példa: Monster esetén +1/+1.
@@ -507,11 +715,47 @@ impl Card {
         }
     }
 
+    /// Melyik színű manát tud termelni ez a kártya (színenként 1, ha igen).
+    /// Alapföldeknél a név dönt, egyébként az AddMana aktivált képességek.
+    pub fn produced_mana(&self) -> ManaCost {
+        let mut produced = ManaCost::free();
+        match self.name.as_str() {
+            MOUNTAIN | ROCKFACE_VILLAGE => produced.red = 1,
+            FOREST => produced.green = 1,
+            _ => {}
+        }
+        for abil in &self.activated_abilities {
+            let options = match &abil.effect {
+                Effect::ChooseSome { options, .. } => options.clone(),
+                other => vec![other.clone()],
+            };
+            for opt in options {
+                if let Effect::AddMana {
+                    colorless,
+                    red,
+                    blue,
+                    green,
+                    black,
+                    white,
+                } = opt
+                {
+                    produced.colorless = produced.colorless.max(colorless.min(1));
+                    produced.red = produced.red.max(red.min(1));
+                    produced.green = produced.green.max(green.min(1));
+                    produced.blue = produced.blue.max(blue.min(1));
+                    produced.white = produced.white.max(white.min(1));
+                    produced.black = produced.black.max(black.min(1));
+                }
+            }
+        }
+        produced
+    }
+
     fn sum_aura_power_bonuses(&self, gre: &Gre) -> i32 {
         let mut sum = 0;
 
         // Végignézzük a GRE-ben, mely enchantment tokenek vannak éppen a battlefielden
-        for (_id, aura_card) in &gre.battlefield_creatures {
+        for aura_card in gre.battlefield_creatures.values() {
             // Csak az enchantment token érdekel
             if aura_card.type_flags.contains(CardTypeFlags::ENCHANTMENT)
                 && aura_card.type_flags.contains(CardTypeFlags::TOKEN)
@@ -534,16 +778,14 @@ impl Card {
 
     fn sum_aura_toughness_bonuses(&self, gre: &Gre) -> i32 {
         let mut sum = 0;
-        for (_id, aura_card) in &gre.battlefield_creatures {
+        for aura_card in gre.battlefield_creatures.values() {
             if aura_card.type_flags.contains(CardTypeFlags::ENCHANTMENT)
                 && aura_card.type_flags.contains(CardTypeFlags::TOKEN)
                 && aura_card.attached_to == Some(self.card_id)
-            {
-                if aura_card.name == "Monster" {
+                && aura_card.name == "Monster" {
                     sum += 1;
                 }
                 // Egyéb aura tokenek ...
-            }
         }
         sum
     }
@@ -578,7 +820,6 @@ pub fn build_card_library() -> HashMap<String, Card> {
                 filter: TargetFilter::SelfCard,
             },
             ProliferateAttribute {
-                counter: CounterType::PlusOnePlusOne,
                 player: PlayerSelector::Controller,
             },
         )
@@ -695,17 +936,17 @@ pub fn build_card_library() -> HashMap<String, Card> {
             Trigger::OnTargetedFirstTimeEachTurn {
                 filter: TargetFilter::SelfCard,
             },
-            FirstTimePerTurnAttribute {
-                base_trigger: Trigger::OnTargetedFirstTimeEachTurn {
+            ConditionalAttribute {
+                trigger: Trigger::OnTargetedFirstTimeEachTurn {
                     filter: TargetFilter::SelfCard,
                 },
-                reset_phase: GamePhase::End,
-                action: Effect::AddCounter {
+                condition: Condition::FirstTimeThisTurn,
+                effect_if_true: Effect::AddCounter {
                     counter: CounterType::PlusOnePlusOne,
                     amount: 1,
                     target: TargetFilter::SelfCard,
                 },
-                used: false,
+                effect_if_false: None,
             },
         ),
     );
@@ -805,10 +1046,14 @@ pub fn build_card_library() -> HashMap<String, Card> {
                 target: TargetFilter::SelfCard,
             },
             activated_this_turn: false,
+            loyalty_change: 0,
+            additional_costs: Vec::new(),
+            timing: ActivationTiming::Instant,
+            once_each_turn: false,
         }),
     );
 
-    // -- FONTOS: Manifold Mouse beépített Offspring 2
+    // Manifold Mouse: Offspring {2}
 
     lib.insert(
         "Manifold Mouse".into(),
@@ -847,19 +1092,7 @@ pub fn build_card_library() -> HashMap<String, Card> {
                 ],
             },
         )
-        .with(
-            Trigger::OnCastResolved,
-            ChooseOnConditionAttribute {
-                choose: 1,
-                options: vec![
-                    Effect::Damage {
-                        amount: Amount::Fixed(0),
-                        target: TargetFilter::AnyTarget,
-                    },
-                    Effect::Offspring { cost: 2 },
-                ],
-            },
-        ),
+        .with_offspring(ManaCost::new(2, 0, 0, 0, 0, 0)),
     );
 
     // Slickshot Show-Off
@@ -919,6 +1152,10 @@ pub fn build_card_library() -> HashMap<String, Card> {
                 white: 0,
             },
             activated_this_turn: false,
+            loyalty_change: 0,
+            additional_costs: Vec::new(),
+            timing: ActivationTiming::Instant,
+            once_each_turn: false,
         }),
     );
 
@@ -953,17 +1190,17 @@ pub fn build_card_library() -> HashMap<String, Card> {
             Trigger::OnTargetedFirstTimeEachTurn {
                 filter: TargetFilter::SelfCard,
             },
-            FirstTimePerTurnAttribute {
-                base_trigger: Trigger::OnTargetedFirstTimeEachTurn {
+            ConditionalAttribute {
+                trigger: Trigger::OnTargetedFirstTimeEachTurn {
                     filter: TargetFilter::SelfCard,
                 },
-                reset_phase: GamePhase::End,
-                action: Effect::ExileThenPlayFromExile {
+                condition: Condition::FirstTimeThisTurn,
+                effect_if_true: Effect::ExileThenPlayFromExile {
                     count: 1,
                     player: PlayerSelector::Controller,
                     duration: Duration::EndOfTurn,
                 },
-                used: false,
+                effect_if_false: None,
             },
         ),
     );
@@ -1081,6 +1318,7 @@ pub fn build_card_library() -> HashMap<String, Card> {
             CardType::Enchantment,
             ManaCost::new(0, 1, 0, 0, 0, 0),
         )
+        .with_subtype(Subtype::Enchantment(EnchantmentType::Aura))
         .with(
             Trigger::OnCastResolved,
             BuffAttribute {
@@ -1128,8 +1366,9 @@ pub fn build_card_library() -> HashMap<String, Card> {
         Card::new(
             BURST_LIGHTNING,
             CardType::Instant,
-            ManaCost::new(4, 1, 0, 0, 0, 0),
+            ManaCost::new(0, 1, 0, 0, 0, 0),
         )
+        .with_kicker(ManaCost::new(4, 0, 0, 0, 0, 0))
         .with(
             Trigger::OnCastResolved,
             ConditionalAttribute {
@@ -1232,6 +1471,10 @@ pub fn build_card_library() -> HashMap<String, Card> {
                 ],
             },
             activated_this_turn: false,
+            loyalty_change: 0,
+            additional_costs: Vec::new(),
+            timing: ActivationTiming::Instant,
+            once_each_turn: false,
         }),
     );
 
@@ -1263,6 +1506,10 @@ pub fn build_card_library() -> HashMap<String, Card> {
                 white: 0,
             },
             activated_this_turn: false,
+            loyalty_change: 0,
+            additional_costs: Vec::new(),
+            timing: ActivationTiming::Instant,
+            once_each_turn: false,
         }),
     );
 
@@ -1282,27 +1529,22 @@ pub fn build_card_library() -> HashMap<String, Card> {
             }),
             ManaCost::new(0, 0, 1, 0, 0, 0),
         )
-        .with(
-            Trigger::OnEnterBattlefield {
-                filter: TargetFilter::SelfCard,
-            },
-            OffspringAttribute { additional_cost: 2 },
-        )
+        .with_offspring(ManaCost::new(2, 0, 0, 0, 0, 0))
         .with(
             Trigger::OnTargetedFirstTimeEachTurn {
                 filter: TargetFilter::ControllerCreature,
             },
-            FirstTimePerTurnAttribute {
-                base_trigger: Trigger::OnTargetedFirstTimeEachTurn {
+            ConditionalAttribute {
+                trigger: Trigger::OnTargetedFirstTimeEachTurn {
                     filter: TargetFilter::ControllerCreature,
                 },
-                reset_phase: GamePhase::End,
-                action: Effect::AddCounter {
+                condition: Condition::FirstTimeThisTurn,
+                effect_if_true: Effect::AddCounter {
                     counter: CounterType::PlusOnePlusOne,
                     amount: 1,
                     target: TargetFilter::ControllerCreature,
                 },
-                used: false,
+                effect_if_false: None,
             },
         ),
     );
@@ -1393,6 +1635,7 @@ pub fn build_card_library() -> HashMap<String, Card> {
             CardType::Enchantment,
             ManaCost::new(1, 0, 1, 0, 0, 0),
         )
+        .with_subtype(Subtype::Enchantment(EnchantmentType::Class))
         .with(
             Trigger::OnEnterBattlefield {
                 filter: TargetFilter::SelfCard,
@@ -1516,31 +1759,29 @@ pub fn build_card_library() -> HashMap<String, Card> {
         .with_activated(ActivatedAbility {
             cost: ManaCost::new(0, 0, 1, 0, 0, 0),
             condition: Condition::Always,
-            effect: Effect::TargetedEffects {
-                sub_effects: vec![
-                    Effect::Exile {
-                        target: TargetFilter::CardInGraveyard,
-                    },
-                    Effect::Conditional {
-                        condition: Condition::ExiledCardWasCreature,
-                        effect_if_true: Box::new(Effect::TargetedEffects {
-                            sub_effects: vec![
-                                Effect::AddCounter {
-                                    counter: CounterType::PlusOnePlusOne,
-                                    amount: 1,
-                                    target: TargetFilter::SelfCard,
-                                },
-                                Effect::GainLife {
-                                    amount: 1,
-                                    player: PlayerSelector::Controller,
-                                },
-                            ],
-                        }),
-                        effect_if_false: None,
-                    },
-                ],
+            // A temetőből száműzött lap a költség része; ha lény volt: +1/+1 counter és 1 élet
+            effect: Effect::Conditional {
+                condition: Condition::ExiledCardWasCreature,
+                effect_if_true: Box::new(Effect::TargetedEffects {
+                    sub_effects: vec![
+                        Effect::AddCounter {
+                            counter: CounterType::PlusOnePlusOne,
+                            amount: 1,
+                            target: TargetFilter::SelfCard,
+                        },
+                        Effect::GainLife {
+                            amount: 1,
+                            player: PlayerSelector::Controller,
+                        },
+                    ],
+                }),
+                effect_if_false: None,
             },
             activated_this_turn: false,
+            loyalty_change: 0,
+            additional_costs: vec![Cost::ExileFromGraveyard(TargetFilter::CardInGraveyard)],
+            timing: ActivationTiming::Instant,
+            once_each_turn: false,
         }),
     );
 
@@ -1552,34 +1793,35 @@ pub fn build_card_library() -> HashMap<String, Card> {
             CardType::Instant,
             ManaCost::new(0, 0, 3, 0, 0, 0),
         )
-        .with(
-            Trigger::OnCastResolved,
-            ChooseOnConditionAttribute {
-                choose: 1,
-                options: vec![
-                    Effect::DrawCards {
-                        count: 1,
-                        player: PlayerSelector::Controller,
-                    },
-                    Effect::TargetedEffects {
-                        sub_effects: vec![
-                            Effect::AddCounter {
-                                counter: CounterType::PlusOnePlusOne,
-                                amount: 1,
-                                target: TargetFilter::ControllerCreature,
-                            },
-                            Effect::DamageByTargetPower {
-                                source: TargetFilter::ControllerCreature,
-                                target: TargetFilter::OpponentCreature,
-                            },
-                        ],
+        .with_modes(ModalSpell::choose_one(vec![
+            SpellMode::new(
+                "Search for a creature or land card",
+                vec![Effect::DrawCards {
+                    count: 1,
+                    player: PlayerSelector::Controller,
+                }],
+            ),
+            SpellMode::new(
+                "+1/+1 counter on your creature, it deals damage to an opponent's creature",
+                vec![
+                    Effect::AddCounter {
+                        counter: CounterType::PlusOnePlusOne,
+                        amount: 1,
+                        target: TargetFilter::ControllerCreature,
                     },
-                    Effect::Destroy {
-                        target: TargetFilter::Artifact,
+                    Effect::DamageByTargetPower {
+                        source: TargetFilter::ControllerCreature,
+                        target: TargetFilter::OpponentCreature,
                     },
                 ],
-            },
-        ),
+            ),
+            SpellMode::new(
+                "Destroy target artifact or enchantment",
+                vec![Effect::Destroy {
+                    target: TargetFilter::Artifact.or(TargetFilter::Enchantment),
+                }],
+            ),
+        ])),
     );
 
     // Surrak, Elusive Hunter
@@ -1598,6 +1840,7 @@ pub fn build_card_library() -> HashMap<String, Card> {
             }),
             ManaCost::new(2, 0, 1, 0, 0, 0),
         )
+        .with_supertype(SuperTypeFlags::LEGENDARY)
         .with(
             Trigger::OnTargeted {
                 filter: TargetFilter::ControllerCreature,
@@ -1628,13 +1871,13 @@ pub fn build_card_library() -> HashMap<String, Card> {
             Trigger::OnCounterAdded {
                 filter: TargetFilter::ControllerCreature,
             },
-            FirstTimePerTurnAttribute {
-                base_trigger: Trigger::OnCounterAdded {
+            ConditionalAttribute {
+                trigger: Trigger::OnCounterAdded {
                     filter: TargetFilter::ControllerCreature,
                 },
-                reset_phase: GamePhase::End,
-                action: Effect::DrawCardsCounted,
-                used: false,
+                condition: Condition::OnceEachTurn,
+                effect_if_true: Effect::DrawCardsCounted,
+                effect_if_false: None,
             },
         ),
     );
@@ -1655,6 +1898,7 @@ pub fn build_card_library() -> HashMap<String, Card> {
             }),
             ManaCost::new(1, 0, 2, 0, 0, 0),
         )
+        .with_supertype(SuperTypeFlags::LEGENDARY)
         .with_activated(ActivatedAbility {
             cost: ManaCost::free(),
             condition: Condition::Always,
@@ -1674,6 +1918,10 @@ pub fn build_card_library() -> HashMap<String, Card> {
                 ],
             },
             activated_this_turn: false,
+            loyalty_change: 0,
+            additional_costs: Vec::new(),
+            timing: ActivationTiming::Instant,
+            once_each_turn: false,
         })
         .with_activated(ActivatedAbility {
             cost: ManaCost::new(3, 0, 2, 0, 0, 0),
@@ -1683,6 +1931,10 @@ pub fn build_card_library() -> HashMap<String, Card> {
                 duration: Duration::EndOfTurn,
             },
             activated_this_turn: false,
+            loyalty_change: 0,
+            additional_costs: Vec::new(),
+            timing: ActivationTiming::Instant,
+            once_each_turn: false,
         }),
     );
 
@@ -1846,24 +2098,10 @@ pub fn build_card_library() -> HashMap<String, Card> {
         VIVIEN_REID.into(),
         Card::new(
             VIVIEN_REID,
-            CardType::Planeswalker(Planeswalker { loyalty: 0 }),
+            CardType::Planeswalker(Planeswalker::new(5)),
             ManaCost::new(3, 0, 2, 0, 0, 0),
         )
-        .with(
-            Trigger::OnEnterBattlefield {
-                filter: TargetFilter::SelfCard,
-            },
-            TriggeredEffectAttribute {
-                trigger: Trigger::OnEnterBattlefield {
-                    filter: TargetFilter::SelfCard,
-                },
-                effect: Effect::AddCounter {
-                    counter: CounterType::Loyalty,
-                    amount: 5,
-                    target: TargetFilter::SelfCard,
-                },
-            },
-        )
+        .with_supertype(SuperTypeFlags::LEGENDARY)
         .with_activated(ActivatedAbility {
             cost: ManaCost::free(),
             condition: Condition::FirstTimeThisTurn,
@@ -1873,6 +2111,9 @@ pub fn build_card_library() -> HashMap<String, Card> {
             },
             activated_this_turn: false,
             loyalty_change: 1,
+            additional_costs: Vec::new(),
+            timing: ActivationTiming::Sorcery,
+            once_each_turn: false,
         })
         .with_activated(ActivatedAbility {
             cost: ManaCost::free(),
@@ -1882,6 +2123,9 @@ pub fn build_card_library() -> HashMap<String, Card> {
             },
             activated_this_turn: false,
             loyalty_change: -3,
+            additional_costs: Vec::new(),
+            timing: ActivationTiming::Sorcery,
+            once_each_turn: false,
         }),
     );
 
@@ -1890,24 +2134,10 @@ pub fn build_card_library() -> HashMap<String, Card> {
         UGIN_EYE_OF_THE_STORMS.into(),
         Card::new(
             UGIN_EYE_OF_THE_STORMS,
-            CardType::Planeswalker(Planeswalker { loyalty: 0 }),
+            CardType::Planeswalker(Planeswalker::new(7)),
             ManaCost::new(7, 0, 0, 0, 0, 0),
         )
-        .with(
-            Trigger::OnEnterBattlefield {
-                filter: TargetFilter::SelfCard,
-            },
-            TriggeredEffectAttribute {
-                trigger: Trigger::OnEnterBattlefield {
-                    filter: TargetFilter::SelfCard,
-                },
-                effect: Effect::AddCounter {
-                    counter: CounterType::Loyalty,
-                    amount: 7,
-                    target: TargetFilter::SelfCard,
-                },
-            },
-        )
+        .with_supertype(SuperTypeFlags::LEGENDARY)
         .with_activated(ActivatedAbility {
             cost: ManaCost::free(),
             condition: Condition::FirstTimeThisTurn,
@@ -1925,6 +2155,9 @@ pub fn build_card_library() -> HashMap<String, Card> {
             },
             activated_this_turn: false,
             loyalty_change: 2,
+            additional_costs: Vec::new(),
+            timing: ActivationTiming::Sorcery,
+            once_each_turn: false,
         })
         .with_activated(ActivatedAbility {
             cost: ManaCost::free(),
@@ -1939,6 +2172,9 @@ pub fn build_card_library() -> HashMap<String, Card> {
             },
             activated_this_turn: false,
             loyalty_change: 0,
+            additional_costs: Vec::new(),
+            timing: ActivationTiming::Sorcery,
+            once_each_turn: false,
         })
         .with_activated(ActivatedAbility {
             cost: ManaCost::free(),
@@ -1950,6 +2186,9 @@ pub fn build_card_library() -> HashMap<String, Card> {
             },
             activated_this_turn: false,
             loyalty_change: -11,
+            additional_costs: Vec::new(),
+            timing: ActivationTiming::Sorcery,
+            once_each_turn: false,
         }),
     );
 
@@ -1969,6 +2208,10 @@ pub fn build_card_library() -> HashMap<String, Card> {
                     white: 0,
                 },
                 activated_this_turn: false,
+                loyalty_change: 0,
+                additional_costs: Vec::new(),
+                timing: ActivationTiming::Instant,
+                once_each_turn: false,
             })
             .with_activated(ActivatedAbility {
                 cost: ManaCost::new(2, 0, 1, 0, 0, 0),
@@ -1988,6 +2231,10 @@ pub fn build_card_library() -> HashMap<String, Card> {
                     ],
                 },
                 activated_this_turn: false,
+                loyalty_change: 0,
+                additional_costs: Vec::new(),
+                timing: ActivationTiming::Sorcery,
+                once_each_turn: false,
             }),
     );
     lib.insert(
@@ -1998,12 +2245,16 @@ pub fn build_card_library() -> HashMap<String, Card> {
     // Basic lands
     lib.insert(
         MOUNTAIN.into(),
-        Card::new(MOUNTAIN, CardType::Land, ManaCost::free()),
+        Card::new(MOUNTAIN, CardType::Land, ManaCost::free())
+            .with_supertype(SuperTypeFlags::BASIC)
+            .with_subtype(Subtype::Land(LandType::Mountain)),
     );
 
     lib.insert(
         FOREST.into(),
-        Card::new(FOREST, CardType::Land, ManaCost::free()),
+        Card::new(FOREST, CardType::Land, ManaCost::free())
+            .with_supertype(SuperTypeFlags::BASIC)
+            .with_subtype(Subtype::Land(LandType::Forest)),
     );
 
     lib
@@ -3,7 +3,6 @@
 use std::collections::HashMap;
 use crate::app::card_attribute::KeywordAbility;
 use crate::app::card_library::Creature;
-use crate::app::card_attribute::PlayerSelector;
 
 /// Internal representation of a creature in combat
 #[derive(Debug)]
@@ -12,7 +11,18 @@ struct CombatCreature {
     pub toughness: i32,
     pub damage_taken: i32,
     pub abilities: Vec<KeywordAbility>,
-    pub controller: PlayerSelector,
+}
+
+/// Raw result of `CombatEngine::resolve`
+#[derive(Debug)]
+struct Resolution {
+    surviving_attackers: Vec<bool>,
+    surviving_blockers: Vec<bool>,
+    /// Unblocked damage per attacker
+    unblocked: HashMap<usize, i32>,
+    life_gain: i32,
+    /// Damage between creatures: (source, recipient, damage), by internal index
+    creature_damage: Vec<(usize, usize, i32)>,
 }
 
 /// A combat pairing: an attacker index and its blockers
@@ -22,11 +32,43 @@ struct CombatGroup {
     blockers: Vec<usize>,
 }
 
+/// What an attacking creature is attacking
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttackTarget {
+    Player,
+    /// Planeswalker by GRE card_id
+    Planeswalker(u64),
+}
+
+/// A creature in combat: attacker or blocker, by index into its side
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Combatant {
+    Attacker(usize),
+    Blocker(usize),
+}
+
+/// Result of combat with attack targets
+#[derive(Debug, Default)]
+pub struct CombatOutcome {
+    pub surviving_attackers: Vec<bool>,
+    pub surviving_blockers: Vec<bool>,
+    /// Unblocked damage dealt to the defending player
+    pub player_damage: i32,
+    /// Unblocked damage dealt to each attacked planeswalker (card_id -> damage)
+    pub planeswalker_damage: HashMap<u64, i32>,
+    /// Every attacker that dealt damage past its blockers: (attacker, what it hit, damage)
+    pub hits: Vec<(usize, AttackTarget, i32)>,
+    /// Damage dealt between attackers and blockers: (source, recipient, damage)
+    pub creature_damage: Vec<(Combatant, Combatant, i32)>,
+    pub life_gain: i32,
+}
+
 /// Central engine for combat resolution
 pub struct CombatEngine;
 
 impl CombatEngine {
     /// Resolves combat given attackers, attacker creatures, blockers, and prevents lifegain flag.
+    /// Every attacker attacks the defending player.
     /// Returns (surviving_attackers, surviving_blockers, unblocked_damage, life_gain)
     pub fn resolve_combat(
         attackers: &[usize],
@@ -35,6 +77,74 @@ impl CombatEngine {
         blocks: &HashMap<usize, Vec<usize>>,
         prevent_lifegain: &mut bool,
     ) -> (Vec<bool>, Vec<bool>, i32, i32) {
+        let r = Self::resolve(attackers, attack_side, block_side, blocks, prevent_lifegain);
+        (
+            r.surviving_attackers,
+            r.surviving_blockers,
+            r.unblocked.values().sum(),
+            r.life_gain,
+        )
+    }
+
+    /// Like `resolve_combat`, but unblocked damage goes to each attacker's target;
+    /// attackers missing from `attack_targets` attack the player.
+    pub fn resolve_combat_with_targets(
+        attackers: &[usize],
+        attack_side: &[Creature],
+        block_side: &[Creature],
+        blocks: &HashMap<usize, Vec<usize>>,
+        attack_targets: &HashMap<usize, AttackTarget>,
+        prevent_lifegain: &mut bool,
+    ) -> CombatOutcome {
+        let Resolution {
+            surviving_attackers,
+            surviving_blockers,
+            unblocked,
+            life_gain,
+            creature_damage,
+        } = Self::resolve(attackers, attack_side, block_side, blocks, prevent_lifegain);
+        let offset = attack_side.len();
+        let combatant = |i: usize| {
+            if i < offset {
+                Combatant::Attacker(i)
+            } else {
+                Combatant::Blocker(i - offset)
+            }
+        };
+        let mut outcome = CombatOutcome {
+            surviving_attackers,
+            surviving_blockers,
+            creature_damage: creature_damage
+                .into_iter()
+                .map(|(src, dst, dmg)| (combatant(src), combatant(dst), dmg))
+                .collect(),
+            life_gain,
+            ..Default::default()
+        };
+        let mut unblocked: Vec<(usize, i32)> = unblocked.into_iter().collect();
+        unblocked.sort_unstable();
+        for (atk, dmg) in unblocked {
+            let target = attack_targets.get(&atk).copied().unwrap_or(AttackTarget::Player);
+            match target {
+                AttackTarget::Player => outcome.player_damage += dmg,
+                AttackTarget::Planeswalker(id) => {
+                    *outcome.planeswalker_damage.entry(id).or_insert(0) += dmg
+                }
+            }
+            if dmg > 0 {
+                outcome.hits.push((atk, target, dmg));
+            }
+        }
+        outcome
+    }
+
+    fn resolve(
+        attackers: &[usize],
+        attack_side: &[Creature],
+        block_side: &[Creature],
+        blocks: &HashMap<usize, Vec<usize>>,
+        prevent_lifegain: &mut bool,
+    ) -> Resolution {
         // 1) Build internal creature list
         let mut creatures = Vec::new();
         for cr in attack_side.iter() {
@@ -43,7 +153,6 @@ impl CombatEngine {
                 toughness: cr.toughness,
                 damage_taken: 0,
                 abilities: cr.abilities.clone(),
-                controller: PlayerSelector::Controller,
             });
         }
         let offset = creatures.len();
@@ -53,7 +162,6 @@ impl CombatEngine {
                 toughness: cr.toughness,
                 damage_taken: 0,
                 abilities: cr.abilities.clone(),
-                controller: PlayerSelector::Opponent,
             });
         }
 
@@ -71,7 +179,8 @@ impl CombatEngine {
         }
 
         // 3) First Strike / Double Strike first hits
-        let mut unblocked_dmg = 0;
+        let mut unblocked_dmg: HashMap<usize, i32> = HashMap::new();
+        let mut creature_dmg: Vec<(usize, usize, i32)> = Vec::new();
         for grp in &groups {
             // attacker first-strike
             if grp.blockers.is_empty() {
@@ -83,7 +192,7 @@ impl CombatEngine {
                     .abilities
                     .contains(&KeywordAbility::DoubleStrike)
                 {
-                    unblocked_dmg += creatures[grp.attacker].power;
+                    *unblocked_dmg.entry(grp.attacker).or_insert(0) += creatures[grp.attacker].power;
                 }
             } else if creatures[grp.attacker]
                 .abilities
@@ -103,6 +212,9 @@ impl CombatEngine {
                     };
                     let dmg = rem.min(needed);
                     creatures[blk].damage_taken += dmg;
+                    if dmg > 0 {
+                        creature_dmg.push((grp.attacker, blk, dmg));
+                    }
                     rem -= dmg;
                 }
             }
@@ -116,6 +228,9 @@ impl CombatEngine {
                     .contains(&KeywordAbility::DoubleStrike)
                 {
                     creatures[grp.attacker].damage_taken += creatures[blk].power;
+                    if creatures[blk].power > 0 {
+                        creature_dmg.push((blk, grp.attacker, creatures[blk].power));
+                    }
                 }
             }
         }
@@ -130,7 +245,7 @@ impl CombatEngine {
                 .contains(&KeywordAbility::DoubleStrike);
             if !fs_only {
                 if grp.blockers.is_empty() {
-                    unblocked_dmg += creatures[grp.attacker].power;
+                    *unblocked_dmg.entry(grp.attacker).or_insert(0) += creatures[grp.attacker].power;
                 } else {
                     let mut rem = creatures[grp.attacker].power;
                     for &blk in &grp.blockers {
@@ -142,6 +257,9 @@ impl CombatEngine {
                         };
                         let dmg = rem.min(needed);
                         creatures[blk].damage_taken += dmg;
+                        if dmg > 0 {
+                            creature_dmg.push((grp.attacker, blk, dmg));
+                        }
                         rem -= dmg;
                     }
                 }
@@ -156,6 +274,9 @@ impl CombatEngine {
                     .contains(&KeywordAbility::DoubleStrike);
                 if !blk_fs_only {
                     creatures[grp.attacker].damage_taken += creatures[blk].power;
+                    if creatures[blk].power > 0 {
+                        creature_dmg.push((blk, grp.attacker, creatures[blk].power));
+                    }
                 }
             }
         }
@@ -185,6 +306,12 @@ impl CombatEngine {
             life_gain
         };
 
-        (survives_att, survives_blk, unblocked_dmg, actual_gain)
+        Resolution {
+            surviving_attackers: survives_att,
+            surviving_blockers: survives_blk,
+            unblocked: unblocked_dmg,
+            life_gain: actual_gain,
+            creature_damage: creature_dmg,
+        }
     }
 }
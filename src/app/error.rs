@@ -2,6 +2,9 @@
 
 use thiserror::Error;
 
+use crate::app::input_driver::InputError;
+use crate::app::screen_state::{GameOutcome, ScreenState};
+
 #[derive(Debug, Error)]
 pub enum AppError {
     #[error("OCR error: {0}")]
@@ -21,9 +24,26 @@ pub enum AppError {
         available_colorless: u32,
     },
 
+    #[error("Hand index {index} out of range ({hand_size} card(s) in hand)")]
+    HandIndexOutOfRange { index: usize, hand_size: usize },
+
     #[error("Invalid state transition: {0}")]
     InvalidStateTransition(String),
 
+    #[error("Timed out after {secs}s waiting for {what} (last screen: {last:?})")]
+    ScreenTimeout {
+        what: String,
+        secs: u64,
+        last: ScreenState,
+    },
+
+    /// A wait saw the victory/defeat screen: the game is over, whatever the state was doing.
+    #[error("Game over: {0:?}")]
+    GameOver(GameOutcome),
+
+    #[error("Input error: {0}")]
+    Input(#[from] InputError),
+
     #[error("Other error: {0}")]
     Other(String),
 }
\ No newline at end of file
@@ -1,12 +1,10 @@
 // app/game_state.rs
 
-use crate::app::card_library::Card;
-use crate::app::card_attribute::Effect;
-use tracing::{error, info, warn};
-use crate::app::card_library::build_card_library;
+use crate::app::card_attribute::{CounterType, Zone};
+use crate::app::card_library::{Card, CardType};
 use crate::app::bot::Bot;
-use crate::app::{game_state, gre};
 pub use crate::app::gre::StackEntry;
+use crate::app::gre::{Gre, Target};
 
 #[derive(Debug, Default, Clone)]
 pub struct GameState {
@@ -24,18 +22,78 @@ pub struct GameState {
     pub mana_available: u32,
     pub land_played_this_turn: bool,
     pub stack: Vec<StackEntry>,
+    /// Engedéllyel a kézen kívülről (száműzetés, temető) kijátszható lapjaink
+    pub castable_from_zones: Vec<Card>,
+    /// A most aktiválható képességeink (`ability_actions`)
+    pub activatable: Vec<GameAction>,
 }
 #[derive(Debug, Clone)]
+#[allow(clippy::large_enum_variant)]
 pub enum GameEvent {
     SpellResolved(String),
     CreatureDied(Card),
     TurnEnded,
     Custom(String),
     PhaseChange(GamePhase),
-    OnCombatDamage,
     ManaAdded(u64),
-    CounterAdded(u64, u32),
+    /// (card_id, counter fajta, darabszám)
+    CounterAdded(u64, CounterType, u32),
     Targeted(u64),
+    /// (játékos, elvesztett életpont)
+    LifeLost(Player, u32),
+    /// (játékos, nyert életpont)
+    LifeGained(Player, u32),
+    /// Varázslat kijátszása (a lap már a stacken van)
+    SpellCast { spell: Card, controller: Player },
+    /// Támadó lény kijelölése
+    AttackDeclared { attacker: u64 },
+    /// Blokkoló lény kijelölése
+    BlockDeclared { blocker: u64, attacker: u64 },
+    /// Sebzés: a forrás (ha ismert), a célpont és a mennyiség
+    DamageDealt {
+        source: Option<u64>,
+        target: Target,
+        amount: u32,
+        combat: bool,
+    },
+    /// Zónaváltás; `from: None`, ha nem tudjuk, honnan jön (pl. token)
+    ZoneChange {
+        card: Card,
+        from: Option<Zone>,
+        to: Zone,
+    },
+    /// Ciklizálás (a lap már a temetőben van)
+    Cycled { card: Card, controller: Player },
+}
+
+/// Eseményfajta: a "körönként először" könyvelés kulcsa
+pub type EventKind = std::mem::Discriminant<GameEvent>;
+
+impl GameEvent {
+    pub fn kind(&self) -> EventKind {
+        std::mem::discriminant(self)
+    }
+
+    /// Az esemény lapjának id-je: a meghalt / zónát váltó / ciklizált lap, a varázslat,
+    /// vagy a célzott / countert kapó / manát adó / támadó / blokkoló / sebzett permanens.
+    pub fn subject(&self) -> Option<u64> {
+        match self {
+            GameEvent::CreatureDied(card)
+            | GameEvent::ZoneChange { card, .. }
+            | GameEvent::Cycled { card, .. }
+            | GameEvent::SpellCast { spell: card, .. } => Some(card.card_id),
+            GameEvent::Targeted(id)
+            | GameEvent::CounterAdded(id, _, _)
+            | GameEvent::ManaAdded(id)
+            | GameEvent::AttackDeclared { attacker: id }
+            | GameEvent::BlockDeclared { blocker: id, .. }
+            | GameEvent::DamageDealt {
+                target: Target::Permanent(id),
+                ..
+            } => Some(*id),
+            _ => None,
+        }
+    }
 }
 
 /// Internal game phases for effect timing
@@ -77,16 +135,48 @@ impl GameState {
             _ => GameResult::Ongoing,
         }
     }
+    /// A saját battlefieldünk most aktiválható képességei akcióként; a `card_idx`
+    /// a `battlefield` indexe, a lapokat a GRE a `card_id` alapján ismeri fel.
+    /// A mana költséget a kliens fizeti, a `mana_available`-ből; mana képesség nincs
+    /// köztük, azokat a kliens maga használja fizetéskor.
+    pub fn ability_actions(&self, gre: &Gre) -> Vec<GameAction> {
+        self.battlefield
+            .iter()
+            .enumerate()
+            .flat_map(|(card_idx, card)| {
+                gre.client_paid_abilities(card.card_id, Player::Us, self.mana_available)
+                    .into_iter()
+                    .filter(move |&i| {
+                        !gre.battlefield_creatures[&card.card_id].activated_abilities[i]
+                            .is_mana_ability()
+                    })
+                    .map(move |ability_idx| GameAction::ActivateAbility { card_idx, ability_idx })
+            })
+            .collect()
+    }
+
+    /// A kézen kívülről most kijátszható lapjaink akcióként; az index a
+    /// `castable_from_zones`-ba mutat. A mana költséget a kliens fizeti, a `mana_available`-ből.
+    pub fn zone_actions(&self, gre: &Gre) -> Vec<GameAction> {
+        self.castable_from_zones
+            .iter()
+            .enumerate()
+            .filter(|(_, card)| gre.can_play_client_paid(card.card_id, Player::Us, self.mana_available))
+            .map(|(i, card)| match card.card_type {
+                CardType::Land => GameAction::PlayLandFromZone(i),
+                _ => GameAction::CastFromZone(i),
+            })
+            .collect()
+    }
+
     pub fn goto_phase(&mut self, _phase: GamePhase) {
         // TODO
     }
     /// Pull fields from the Bot into the persistent GameState.
     pub fn update_from_bot(&mut self, bot: &Bot) {
-        let library = build_card_library();
-
         // Update hand from OCR texts
         self.hand = bot.cards_texts.iter()
-            .filter_map(|text| library.get(text).cloned())
+            .filter_map(|text| bot.resolve_text(text).cloned())
             .collect();
 
         // Update battlefields
@@ -97,6 +187,12 @@ impl GameState {
         self.mana_available = bot.land_number;
         self.land_played_this_turn = bot.land_played_this_turn;
 
+        // Engedéllyel kijátszható lapok a száműzetésből / temetőből
+        self.castable_from_zones = bot.gre.castable_from_zones(Player::Us);
+
+        // Aktiválható képességek (a battlefield és a mana után)
+        self.activatable = self.ability_actions(&bot.gre);
+
         // Update stack snapshot
         self.stack = bot.gre.stack
             .iter()
@@ -108,7 +204,7 @@ impl GameState {
 
 
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Player { Us, Opponent }
 
 impl Player {
@@ -121,10 +217,14 @@ impl Player {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum GameAction {
     PlayLand(usize),
     CastSpell(usize),
+    /// Föld kijátszása a `castable_from_zones`-ból
+    PlayLandFromZone(usize),
+    /// Varázslat kijátszása a `castable_from_zones`-ból
+    CastFromZone(usize),
     AttackWith(Vec<usize>),
     ActivateAbility{card_idx:usize,ability_idx:usize},
     PassPriority,
@@ -137,13 +237,25 @@ pub trait Strategy {
 pub struct SimpleHeuristic;
 impl Strategy for SimpleHeuristic {
     fn decide(&mut self, state:&GameState)->GameAction {
-        // play land if possible
+        // play land if possible; a lejáró engedélyű (száműzött) földet előbb
         if !state.land_played_this_turn {
-            if let Some(i) = state.hand.iter().position(|c| matches!(c.card_type, crate::app::card_library::CardType::Land)) {
+            if let Some(i) = state.castable_from_zones.iter().position(|c| matches!(c.card_type, CardType::Land)) {
+                return GameAction::PlayLandFromZone(i);
+            }
+            if let Some(i) = state.hand.iter().position(|c| matches!(c.card_type, CardType::Land)) {
                 return GameAction::PlayLand(i);
             }
         }
-        // cast first affordable
+        // cast first affordable, a kézen kívüli lapokat előbb
+        for (i,c) in state.castable_from_zones.iter().enumerate() {
+            if !matches!(c.card_type, CardType::Land) && c.mana_cost.total() <= state.mana_available {
+                return GameAction::CastFromZone(i);
+            }
+        }
+        // a lent lévő permanensek képességei
+        if let Some(action) = state.activatable.first() {
+            return action.clone();
+        }
         for (i,c) in state.hand.iter().enumerate() {
             let cost = c.mana_cost.total();
             if cost <= state.mana_available { return GameAction::CastSpell(i); }
@@ -2,18 +2,18 @@
 
 use tracing::{info, warn};
 use crate::app::game_state::GameState;
-use crate::app::gre::{PriorityEntry, StackEntry};
+use crate::app::gre::PriorityEntry;
 use crate::app::card_library::{build_card_library, Card, CardTypeFlags};
 use crate::app::ocr;
-use crate::app::creature_positions::{get_own_creature_positions, get_opponent_creature_positions};
-use crate::app::cards_positions::get_card_positions;
-use crate::app::ocr::{read_creature_text};
+use crate::app::battlefield_reader::{cards_from_readings, read_battlefield_side};
+use crate::app::name_resolver::NameResolver;
 use crate::app::ui::{get_average_color, is_color_within_tolerance};
+use crate::app::layout::layout;
 use std::collections::{HashMap, BinaryHeap};
-use crate::app::bot::Bot;
 
 
 /// Generic branch counter (odd/even).
+#[allow(clippy::too_many_arguments)]
 pub fn count_branch(
     y1: i32,
     region_height: i32,
@@ -56,17 +56,15 @@ pub fn detect_creature_count_for_side(
         "detect_creature_count_for_side({}, {}, {})",
         screen_width, screen_height, is_opponent
     );
-    let sw = screen_width as f64;
-    let sh = screen_height as f64;
-    let (y1_norm, y2_norm) = if is_opponent {
-        (101.761, 104.891)
-    } else {
-        (185.141, 188.731)
-    };
-    let y1 = ((y1_norm / 381.287) * sh).floor() as i32;
-    let y2 = ((y2_norm / 381.287) * sh).floor() as i32;
+    let layout = layout();
+    let side = if is_opponent { "opp" } else { "own" };
+    let (y1_norm, y2_norm) = layout.pair(&format!("battlefield.{}.ocr_y", side));
+    let y1 = layout.sy(y1_norm, screen_height).floor() as i32;
+    let y2 = layout.sy(y2_norm, screen_height).floor() as i32;
     let region_h = y2 - y1;
-    let rect_w = ((4.4 / 677.292) * sw).floor() as i32;
+    // count_probe: mintavétel szélessége, első eltolás, lépésköz
+    let probe = layout.get("battlefield.count_probe").unwrap_or(&[4.4, 34.492, 69.0]);
+    let rect_w = layout.sx(probe[0], screen_width).floor() as i32;
     let center_x = (screen_width as i32) / 2 - rect_w / 2;
 
     let target_color = (210, 175, 157);
@@ -76,9 +74,8 @@ pub fn detect_creature_count_for_side(
     info!("  center color: {:?}", center_color);
     let center_is_card = is_color_within_tolerance(center_color, target_color, tol);
 
-    let scale = sw / 677.292;
-    let step = (69.0 * scale).floor() as i32;
-    let first = (34.492 * scale).floor() as i32;
+    let step = layout.sx(probe[2], screen_width).floor() as i32;
+    let first = layout.sx(probe[1], screen_width).floor() as i32;
 
     if center_is_card {
         count_branch(y1, region_h, rect_w, center_x, tol, target_color, 1, step, step, 7)
@@ -93,24 +90,10 @@ pub fn load_side_creatures(
     screen_height: u32,
     is_opponent: bool,
 ) -> HashMap<String, Card> {
-    let mut map = HashMap::new();
     let library = build_card_library();
-    let count = detect_creature_count_for_side(screen_width, screen_height, is_opponent);
-    let positions = if is_opponent {
-        get_opponent_creature_positions(count, screen_width, screen_height)
-    } else {
-        get_own_creature_positions(count, screen_width, screen_height)
-    };
-
-    for (i, pos) in positions.into_iter().enumerate() {
-        let name = read_creature_text(pos, i + 1, is_opponent, screen_width, screen_height);
-        if let Some(card) = library.get(&name) {
-            map.insert(name.clone(), card.clone());
-        } else if !name.is_empty() {
-            warn!("Unknown OCR creature `{}` on {}", name, if is_opponent{"opponent"}else{"own"} );
-        }
-    }
-    map
+    let resolver = NameResolver::from_library(&library);
+    let readings = read_battlefield_side(screen_width, screen_height, is_opponent);
+    cards_from_readings(&readings, &library, &resolver, is_opponent)
 }
 
 /// Centralized GameState updater.
@@ -118,6 +101,12 @@ pub struct GameStateUpdater {
     pub state: GameState,
 }
 
+impl Default for GameStateUpdater {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl GameStateUpdater {
     pub fn new() -> Self {
         Self { state: GameState::default() }
@@ -138,8 +127,9 @@ impl GameStateUpdater {
     /// Update hand from OCR texts.
     pub fn update_hand(&mut self, cards_texts: &[String], library: &HashMap<String, Card>) {
         self.state.hand.clear();
+        let resolver = NameResolver::from_library(library);
         for txt in cards_texts {
-            if let Some(card) = library.get(txt) {
+            if let Some(card) = resolver.resolve_card(library, txt) {
                 self.state.hand.push(card.clone());
             } else {
                 warn!("OCR nem egyeztetett kártya: {}", txt);
@@ -192,6 +182,7 @@ impl GameStateUpdater {
 
 
     /// Refresh all GameState fields.
+    #[allow(clippy::too_many_arguments)]
     pub fn refresh_all(
                &mut self,
                 screen_width:  u32,
@@ -0,0 +1,305 @@
+// src/app/gre/activation.rs
+
+//! Aktivált képességek: időzítés, "körönként egyszer" és planeswalker korlátok,
+//! a költségek atomikus kifizetése, a mana képességek azonnali (stack nélküli)
+//! feloldása, és permanensenként a most aktiválható képességek listája.
+
+use tracing::{debug, info, warn};
+
+use crate::app::card_attribute::{Condition, CounterType};
+use crate::app::card_library::{Card, CardType, CardTypeFlags, ManaCost};
+use crate::app::game_state::Player;
+use crate::app::gre::{
+    ActivatedAbility, ActivationTiming, ConditionContext, Cost, Gre, PaidCosts, StackEntry,
+};
+
+/// Ugyanaz a képesség-e, az `activated_this_turn` jelzőtől eltekintve
+fn same_ability(a: &ActivatedAbility, b: &ActivatedAbility) -> bool {
+    let mut a = a.clone();
+    a.activated_this_turn = b.activated_this_turn;
+    a == *b
+}
+
+impl Gre {
+    /// A képesség összes nem-mana költsége: {T}, a kártyán felsorolt plusz költségek,
+    /// végül a forrás feláldozása.
+    fn activation_costs(ability: &ActivatedAbility) -> Vec<Cost> {
+        let mut costs = Vec::new();
+        if ability.condition == Condition::Tap {
+            costs.push(Cost::TapSelf);
+        }
+        costs.extend(ability.additional_costs.iter().cloned());
+        if ability.condition == Condition::SacrificeSelf {
+            costs.push(Cost::SacrificeSelf);
+        }
+        costs
+    }
+
+    /// Aktiválták-e már ebben a körben (a battlefielden lévő példány szerint, ha ott van)
+    fn activated_this_turn(&self, source: &Card, ability: &ActivatedAbility) -> bool {
+        self.battlefield_creatures
+            .get(&source.card_id)
+            .and_then(|c| {
+                c.activated_abilities
+                    .iter()
+                    .find(|a| same_ability(a, ability))
+            })
+            .map_or(ability.activated_this_turn, |a| a.activated_this_turn)
+    }
+
+    /// Aktiválható-e a képesség.
+    /// Planeswalker loyalty képesség: sorcery-speed, permanensenként körönként egy,
+    /// és negatív loyalty költséget csak elég counterrel lehet fizetni.
+    /// Minden képességnél: időzítés, "once each turn", és a költségek kifizethetősége.
+    pub fn can_activate(
+        &self,
+        source: &Card,
+        ability: &ActivatedAbility,
+        controller: Player,
+    ) -> bool {
+        self.can_activate_paying(source, ability, controller, &ability.cost)
+    }
+
+    /// Mint a `can_activate`, de a mana költséget a kliens fizeti (a bot a játékban
+    /// csapolja a földeket): csak azt nézzük, hogy az összege belefér-e az `available_mana`-ba.
+    pub fn can_activate_client_paid(
+        &self,
+        source: &Card,
+        ability: &ActivatedAbility,
+        controller: Player,
+        available_mana: u32,
+    ) -> bool {
+        ability.cost.total() <= available_mana
+            && self.can_activate_paying(source, ability, controller, &ManaCost::free())
+    }
+
+    /// Az aktiválhatóság ellenőrzése, a pool-ból fizetendő mana költség `mana`.
+    fn can_activate_paying(
+        &self,
+        source: &Card,
+        ability: &ActivatedAbility,
+        controller: Player,
+        mana: &ManaCost,
+    ) -> bool {
+        if let Some(card) = self.battlefield_creatures.get(&source.card_id)
+            && card.controller != controller
+        {
+            debug!(
+                "can_activate() -> '{}': not controlled by {:?}",
+                source.name, controller
+            );
+            return false;
+        }
+        let activated = self.activated_this_turn(source, ability);
+        if let Some(loyalty) = self
+            .battlefield_creatures
+            .get(&source.card_id)
+            .and_then(|c| c.loyalty())
+            .or_else(|| source.loyalty())
+        {
+            if !self.is_sorcery_speed(controller) {
+                debug!("can_activate() -> '{}': not sorcery speed", source.name);
+                return false;
+            }
+            if activated {
+                debug!(
+                    "can_activate() -> '{}': already activated this turn",
+                    source.name
+                );
+                return false;
+            }
+            if loyalty + ability.loyalty_change < 0 {
+                debug!(
+                    "can_activate() -> '{}': loyalty {} cannot pay {}",
+                    source.name, loyalty, ability.loyalty_change
+                );
+                return false;
+            }
+        }
+        if ability.timing == ActivationTiming::Sorcery && !self.is_sorcery_speed(controller) {
+            debug!("can_activate() -> '{}': only as a sorcery", source.name);
+            return false;
+        }
+        if ability.once_each_turn && activated {
+            debug!("can_activate() -> '{}': only once each turn", source.name);
+            return false;
+        }
+        if let Err(e) = self.check_costs(
+            controller,
+            Some(source.card_id),
+            mana,
+            &Self::activation_costs(ability),
+        ) {
+            debug!("can_activate() -> '{}': {}", source.name, e);
+            return false;
+        }
+        // A {T} és a feláldozás költség, fent már ellenőriztük
+        let ctx = ConditionContext::new(controller)
+            .with_source(Some(source.card_id))
+            .with_activated(activated);
+        self.evaluate_condition(&ability.condition, &ctx)
+    }
+
+    /// Aktivál egy képességet; `false`, ha most nem aktiválható.
+    /// A mana, loyalty és nem-mana költségek aktiváláskor, együtt fizetődnek (vagy egyik sem).
+    /// A mana képesség azonnal feloldódik, nem kerül a stackre.
+    pub fn activate_ability(
+        &mut self,
+        source: Card,
+        ability: ActivatedAbility,
+        controller: Player,
+    ) -> bool {
+        info!(
+            "activate_ability() -> source='{}', condition={:?}, effect={:?}",
+            source.name, ability.condition, ability.effect
+        );
+        if !self.can_activate(&source, &ability, controller) {
+            warn!(
+                "activate_ability() -> '{}' cannot activate {:?} now",
+                source.name, ability.effect
+            );
+            return false;
+        }
+        let source_id = source.card_id;
+        let costs = Self::activation_costs(&ability);
+        let paid = match self.pay_costs(controller, Some(source_id), &ability.cost, &costs) {
+            Ok(paid) => paid,
+            Err(e) => {
+                warn!("activate_ability() -> '{}': {}", source.name, e);
+                return false;
+            }
+        };
+
+        if let Some(card) = self.battlefield_creatures.get_mut(&source_id) {
+            if card.type_flags.contains(CardTypeFlags::PLANESWALKER) {
+                // Planeswalker: körönként egy loyalty képesség, bármelyik is volt
+                for abil in card.activated_abilities.iter_mut() {
+                    abil.activated_this_turn = true;
+                }
+            } else if let Some(abil) = card
+                .activated_abilities
+                .iter_mut()
+                .find(|a| same_ability(a, &ability))
+            {
+                abil.activated_this_turn = true;
+            }
+        }
+        if ability.loyalty_change != 0 {
+            self.change_loyalty(source_id, ability.loyalty_change);
+        }
+
+        if ability.is_mana_ability() {
+            self.resolve_mana_ability(source, ability, controller, paid);
+            return true;
+        }
+
+        self.push_to_stack(StackEntry::ActivatedAbility {
+            source,
+            ability,
+            controller,
+            paid,
+        });
+        // 0 loyalty: state-based action, a képesség ettől még feloldódik
+        self.check_planeswalker_loyalty();
+        true
+    }
+
+    /// Mana képesség: a stack érintése és a prioritás visszaállítása nélkül oldódik fel.
+    fn resolve_mana_ability(
+        &mut self,
+        source: Card,
+        ability: ActivatedAbility,
+        controller: Player,
+        paid: PaidCosts,
+    ) {
+        info!(
+            "  -> Resolving mana ability of '{}' immediately",
+            source.name
+        );
+        let prev_controller = std::mem::replace(&mut self.resolving_controller, controller);
+        let prev_source = self.current_source_card.replace(source);
+        let prev_costs = std::mem::replace(&mut self.resolving_costs, paid);
+        self.handle_effect(ability.effect);
+        self.resolving_costs = prev_costs;
+        self.current_source_card = prev_source;
+        self.resolving_controller = prev_controller;
+    }
+
+    /// A permanens most aktiválható képességeinek indexei (`GameAction::ActivateAbility`-hez).
+    pub fn activatable_abilities(&self, card_id: u64, controller: Player) -> Vec<usize> {
+        let Some(card) = self.battlefield_creatures.get(&card_id) else {
+            return Vec::new();
+        };
+        (0..card.activated_abilities.len())
+            .filter(|&i| self.can_activate(card, &card.activated_abilities[i], controller))
+            .collect()
+    }
+
+    /// Mint az `activatable_abilities`, de a mana költséget a kliens fizeti
+    /// (`can_activate_client_paid`).
+    pub fn client_paid_abilities(
+        &self,
+        card_id: u64,
+        controller: Player,
+        available_mana: u32,
+    ) -> Vec<usize> {
+        let Some(card) = self.battlefield_creatures.get(&card_id) else {
+            return Vec::new();
+        };
+        (0..card.activated_abilities.len())
+            .filter(|&i| {
+                self.can_activate_client_paid(
+                    card,
+                    &card.activated_abilities[i],
+                    controller,
+                    available_mana,
+                )
+            })
+            .collect()
+    }
+
+    /// A battlefielden lévő permanens `ability_idx`. képességének aktiválása.
+    pub fn activate_ability_at(
+        &mut self,
+        card_id: u64,
+        ability_idx: usize,
+        controller: Player,
+    ) -> bool {
+        let Some(card) = self.battlefield_creatures.get(&card_id) else {
+            warn!(
+                "activate_ability_at() -> id={} is not on the battlefield",
+                card_id
+            );
+            return false;
+        };
+        let Some(ability) = card.activated_abilities.get(ability_idx).cloned() else {
+            warn!(
+                "activate_ability_at() -> '{}' has no ability #{}",
+                card.name, ability_idx
+            );
+            return false;
+        };
+        let source = card.clone();
+        self.activate_ability(source, ability, controller)
+    }
+
+    /// Untap step: a játékos permanensei kiegyenesednek, a lényei már nem summoning sick-ek.
+    /// Stun counteres tappolt permanensről untap helyett egy stun counter kerül le.
+    pub fn untap_step(&mut self, player: Player) {
+        debug!("untap_step() -> {:?}", player);
+        for card in self
+            .battlefield_creatures
+            .values_mut()
+            .filter(|c| c.controller == player)
+        {
+            if card.tapped && card.remove_counters(&CounterType::Stun, 1) == 1 {
+                info!("    '{}' stays tapped, loses a stun counter", card.name);
+            } else {
+                card.tapped = false;
+            }
+            if let CardType::Creature(cr) = &mut card.card_type {
+                cr.summoning_sickness = false;
+            }
+        }
+    }
+}
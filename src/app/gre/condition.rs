@@ -0,0 +1,233 @@
+// src/app/gre/condition.rs
+
+//! Feltételek kiértékelése: aktiváláskor (`can_activate`), a `Conditional` effekt
+//! feloldásakor és a triggerek kiváltásakor (intervening-if) ugyanaz a kiértékelő fut.
+//! Az eseményfüggő részfeltételeket (`FirstTimeThisTurn`, `OnceEachTurn`, `EventCard`, ...)
+//! a kiváltáskor rögzítjük, a játékállapotra vonatkozókat feloldáskor újra megnézzük.
+//! A "körönként először" könyvelés laponként / forrásonként és eseményfajtánként megy,
+//! és minden kör végén (a miénk és az ellenfélé is) nullázódik.
+
+use tracing::debug;
+
+use crate::app::card_attribute::{
+    Comparison, Condition, Effect, FilterContext, PlayerSelector, TargetFilter, Zone,
+};
+use crate::app::card_library::{Card, CardTypeFlags};
+use crate::app::game_state::{EventKind, GameEvent, Player};
+use crate::app::gre::{Gre, PaidCosts};
+
+/// A feltétel kiértékelésének környezete
+#[derive(Debug, Clone, Copy)]
+pub struct ConditionContext<'a> {
+    /// Akihez képest a "you" értendő
+    pub controller: Player,
+    pub source: Option<u64>,
+    /// A kiváltó esemény (triggernél)
+    pub event: Option<&'a GameEvent>,
+    /// Aktiválásnál: aktiválták-e már ebben a körben
+    pub activated_this_turn: bool,
+    /// Feloldáskor a kifizetett költségek; `None` aktiváláskor (a költséget külön ellenőrizzük)
+    pub paid: Option<&'a PaidCosts>,
+}
+
+impl<'a> ConditionContext<'a> {
+    pub fn new(controller: Player) -> Self {
+        Self {
+            controller,
+            source: None,
+            event: None,
+            activated_this_turn: false,
+            paid: None,
+        }
+    }
+
+    pub fn with_source(mut self, source: Option<u64>) -> Self {
+        self.source = source;
+        self
+    }
+
+    pub fn with_event(mut self, event: &'a GameEvent) -> Self {
+        self.event = Some(event);
+        self
+    }
+
+    pub fn with_activated(mut self, activated_this_turn: bool) -> Self {
+        self.activated_this_turn = activated_this_turn;
+        self
+    }
+
+    pub fn with_paid(mut self, paid: &'a PaidCosts) -> Self {
+        self.paid = Some(paid);
+        self
+    }
+}
+
+impl Gre {
+    /// A feloldás alatt álló stack-elem környezete (irányító, forrás, kifizetett költségek)
+    pub fn resolving_condition_context(&self) -> ConditionContext<'_> {
+        ConditionContext::new(self.resolving_controller)
+            .with_source(self.current_source_card.as_ref().map(|c| c.card_id))
+            .with_paid(&self.resolving_costs)
+    }
+
+    /// Az esemény lapja: a meghalt / zónát váltó / ciklizált lap, a kijátszott varázslat,
+    /// vagy a célzott / countert kapó / manát adó / támadó / blokkoló / sebzett permanens.
+    pub fn event_card<'a>(&'a self, event: &'a GameEvent) -> Option<&'a Card> {
+        match event {
+            GameEvent::CreatureDied(card)
+            | GameEvent::ZoneChange { card, .. }
+            | GameEvent::Cycled { card, .. } => Some(card),
+            GameEvent::SpellCast { spell, .. } => Some(spell),
+            _ => event
+                .subject()
+                .and_then(|id| self.battlefield_creatures.get(&id)),
+        }
+    }
+
+    /// Melyik zónában van az esemény lapja.
+    fn event_zone(event: Option<&GameEvent>) -> Zone {
+        match event {
+            Some(GameEvent::CreatureDied(_) | GameEvent::Cycled { .. }) => Zone::Graveyard,
+            Some(GameEvent::ZoneChange { to, .. }) => *to,
+            Some(GameEvent::SpellCast { .. }) => Zone::Stack,
+            _ => Zone::Battlefield,
+        }
+    }
+
+    /// Hányszor történt ilyen fajta esemény a lappal ebben a körben
+    pub fn times_this_turn(&self, card_id: u64, kind: EventKind) -> u32 {
+        self.events_this_turn
+            .get(&(card_id, kind))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Tett-e már a forrás triggert a stackre erre a fajta eseményre ebben a körben
+    fn triggered_this_turn(&self, source: Option<u64>, event: &GameEvent) -> bool {
+        source.is_some_and(|id| self.triggered_this_turn.contains(&(id, event.kind())))
+    }
+
+    /// Az egyetlen feltétel-kiértékelő.
+    pub fn evaluate_condition(&self, condition: &Condition, ctx: &ConditionContext) -> bool {
+        let controller = ctx.controller;
+        let player = |selector: &PlayerSelector| match selector {
+            PlayerSelector::Controller => controller,
+            PlayerSelector::Opponent | PlayerSelector::AnyPlayer => controller.opponent(),
+        };
+        let event_card = ctx.event.and_then(|e| self.event_card(e));
+        let result = match condition {
+            Condition::Always => true,
+            Condition::OpponentLostLifeThisTurn => self.lost_life_this_turn(controller.opponent()),
+            Condition::FirstTimeThisTurn => match ctx.event {
+                Some(event) => match event.subject() {
+                    Some(id) => self.times_this_turn(id, event.kind()) <= 1,
+                    None => !self.triggered_this_turn(ctx.source, event),
+                },
+                None => !ctx.activated_this_turn,
+            },
+            Condition::OnceEachTurn => match ctx.event {
+                Some(event) => !self.triggered_this_turn(ctx.source, event),
+                None => !ctx.activated_this_turn,
+            },
+            Condition::SpellWasNonCreature => {
+                event_card.is_some_and(|c| !c.has_type(CardTypeFlags::CREATURE))
+            }
+            // Aktiváláskor a költség-ellenőrzés nézi, feloldáskor már ki van fizetve
+            Condition::Tap => true,
+            Condition::SacrificeSelf => ctx.paid.is_none_or(|p| p.sacrificed_source),
+            Condition::SpellWasKicked => ctx.paid.is_some_and(|p| p.kicked()),
+            Condition::ExiledCardWasCreature => {
+                ctx.paid.is_some_and(|p| p.exiled_creature()) || self.last_exiled_card_was_creature
+            }
+            Condition::HasCreaturePower4OrMore => self.evaluate_condition(
+                &Condition::you_control(
+                    TargetFilter::Creature.and(TargetFilter::Power(Comparison::AtLeast, 4)),
+                ),
+                ctx,
+            ),
+            Condition::Count { filter, cmp, count } => {
+                let fctx = FilterContext::new(controller, ctx.source, Zone::Battlefield);
+                let n = self
+                    .battlefield_creatures
+                    .values()
+                    .filter(|c| filter.matches(c, &fctx))
+                    .count();
+                cmp.holds(n as i32, *count as i32)
+            }
+            Condition::SpellsCastThisTurn {
+                player: p,
+                cmp,
+                count,
+            } => cmp.holds(
+                self.player(player(p)).spells_cast_this_turn as i32,
+                *count as i32,
+            ),
+            Condition::LifeTotal {
+                player: p,
+                cmp,
+                amount,
+            } => cmp.holds(self.player(player(p)).life, *amount),
+            Condition::EventCard(filter) => {
+                let fctx =
+                    FilterContext::new(controller, ctx.source, Self::event_zone(ctx.event));
+                event_card.is_some_and(|c| filter.matches(c, &fctx))
+            }
+            Condition::And(conditions) => {
+                conditions.iter().all(|c| self.evaluate_condition(c, ctx))
+            }
+            Condition::Or(conditions) => conditions.iter().any(|c| self.evaluate_condition(c, ctx)),
+            Condition::Not(inner) => !self.evaluate_condition(inner, ctx),
+        };
+        debug!("evaluate_condition() -> {:?} = {}", condition, result);
+        result
+    }
+
+    /// Az eseményfüggő részfeltételek lecserélése a kiváltáskori értékükre.
+    pub fn bind_event_conditions(&self, condition: Condition, ctx: &ConditionContext) -> Condition {
+        match condition {
+            c if c.depends_on_event() => Condition::constant(self.evaluate_condition(&c, ctx)),
+            Condition::And(conditions) => Condition::And(
+                conditions
+                    .into_iter()
+                    .map(|c| self.bind_event_conditions(c, ctx))
+                    .collect(),
+            ),
+            Condition::Or(conditions) => Condition::Or(
+                conditions
+                    .into_iter()
+                    .map(|c| self.bind_event_conditions(c, ctx))
+                    .collect(),
+            ),
+            Condition::Not(inner) => {
+                Condition::Not(Box::new(self.bind_event_conditions(*inner, ctx)))
+            }
+            other => other,
+        }
+    }
+
+    /// Intervening-if: a kiváltáskor hamis feltételű (else-ág nélküli) `Conditional`
+    /// trigger nem kerül a stackre (`None`); egyébként az eseményfüggő részeket rögzítjük.
+    pub fn check_intervening_if(&self, effect: Effect, ctx: &ConditionContext) -> Option<Effect> {
+        match effect {
+            Effect::Conditional {
+                condition,
+                effect_if_true,
+                effect_if_false,
+            } => {
+                if effect_if_false.is_none() && !self.evaluate_condition(&condition, ctx) {
+                    debug!(
+                        "check_intervening_if() -> {:?} is false, no trigger",
+                        condition
+                    );
+                    return None;
+                }
+                Some(Effect::Conditional {
+                    condition: self.bind_event_conditions(condition, ctx),
+                    effect_if_true,
+                    effect_if_false,
+                })
+            }
+            other => Some(other),
+        }
+    }
+}
@@ -0,0 +1,494 @@
+// src/app/gre/costs.rs
+
+//! Költségmodell: plusz mana költségek (kicker, offspring), nem-mana költségek
+//! (feláldozás, eldobás, életfizetés, száműzés temetőből) és költségcsökkentések.
+//! A ténylegesen kifizetett költségeket a stack-objektum `PaidCosts`-ban őrzi,
+//! így a feloldáskor (pl. `Condition::SpellWasKicked`) lekérdezhetők.
+
+use thiserror::Error;
+use tracing::{debug, info, warn};
+
+use crate::app::card_attribute::{FilterContext, KeywordAbility, TargetFilter, Zone};
+use crate::app::card_library::{Card, CardType, CardTypeFlags, ManaCost};
+use crate::app::game_state::{GameEvent, Player};
+use crate::app::gre::{Gre, StackEntry, Target};
+
+/// Egy költség-elem a kártya mana költségén felül
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Cost {
+    /// Plusz mana (pl. kicker {4})
+    Mana(ManaCost),
+    /// {T}: a forrás tappolása (lénynél summoning sickness nélkül)
+    TapSelf,
+    /// A forrás feláldozása
+    SacrificeSelf,
+    /// Egy saját, a szűrőnek megfelelő lény feláldozása
+    SacrificeCreature(TargetFilter),
+    /// Ennyi lap eldobása a kézből
+    Discard(u32),
+    /// Életfizetés
+    PayLife(u32),
+    /// Egy, a szűrőnek megfelelő lap száműzése bármelyik temetőből
+    ExileFromGraveyard(TargetFilter),
+}
+
+/// Az opcionális költség fajtája; ezt rögzítjük a stacken, ha kifizették
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OptionalCostKind {
+    Kicker,
+    Offspring,
+}
+
+impl OptionalCostKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            OptionalCostKind::Kicker => "Kicker",
+            OptionalCostKind::Offspring => "Offspring",
+        }
+    }
+}
+
+/// Kijátszáskor választható plusz költség
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OptionalCost {
+    pub kind: OptionalCostKind,
+    pub costs: Vec<Cost>,
+}
+
+impl OptionalCost {
+    pub fn kicker(mana: ManaCost) -> Self {
+        Self {
+            kind: OptionalCostKind::Kicker,
+            costs: vec![Cost::Mana(mana)],
+        }
+    }
+
+    pub fn offspring(mana: ManaCost) -> Self {
+        Self {
+            kind: OptionalCostKind::Offspring,
+            costs: vec![Cost::Mana(mana)],
+        }
+    }
+}
+
+/// Folyamatos költségcsökkentés: a `controller` szűrőnek megfelelő varázslatai
+/// `generic`-kel kevesebb generikus manába kerülnek, amíg a forrás a battlefielden van.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CostReduction {
+    pub source: Option<u64>,
+    pub controller: Player,
+    pub filter: TargetFilter,
+    pub generic: u32,
+}
+
+/// A stack-objektumon rögzített, ténylegesen kifizetett költségek
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PaidCosts {
+    pub optional: Vec<OptionalCostKind>,
+    pub sacrificed: Vec<Card>,
+    pub sacrificed_source: bool,
+    pub discarded: u32,
+    pub life_paid: u32,
+    pub exiled: Vec<Card>,
+}
+
+impl PaidCosts {
+    pub fn paid(&self, kind: OptionalCostKind) -> bool {
+        self.optional.contains(&kind)
+    }
+
+    pub fn kicked(&self) -> bool {
+        self.paid(OptionalCostKind::Kicker)
+    }
+
+    pub fn exiled_creature(&self) -> bool {
+        self.exiled
+            .iter()
+            .any(|c| matches!(c.card_type, CardType::Creature(_)))
+    }
+}
+
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum CostError {
+    #[error("Not enough mana: {needed:?} needed, pool has {available}")]
+    NotEnoughMana { needed: ManaCost, available: u32 },
+    #[error("Source is tapped or summoning sick")]
+    CannotTap,
+    #[error("No creature to sacrifice")]
+    NothingToSacrifice,
+    #[error("Cannot discard {needed} card(s) from a hand of {hand}")]
+    NotEnoughCards { needed: u32, hand: u32 },
+    #[error("Cannot pay {needed} life with {life} life")]
+    NotEnoughLife { needed: u32, life: i32 },
+    #[error("No matching card in any graveyard")]
+    NothingToExile,
+    /// Modális varázslatnál nincs elég legális mód (vagy célpont)
+    #[error("No legal choice of modes")]
+    NoLegalModes,
+}
+
+fn add_mana_cost(a: &ManaCost, b: &ManaCost) -> ManaCost {
+    ManaCost::new(
+        a.colorless + b.colorless,
+        a.red + b.red,
+        a.green + b.green,
+        a.blue + b.blue,
+        a.white + b.white,
+        a.black + b.black,
+    )
+}
+
+/// A költséglista mana része és nem-mana része külön
+fn split_costs(costs: &[Cost]) -> (ManaCost, Vec<Cost>) {
+    let mut mana = ManaCost::free();
+    let mut other = Vec::new();
+    for cost in costs {
+        match cost {
+            Cost::Mana(m) => mana = add_mana_cost(&mana, m),
+            c => other.push(c.clone()),
+        }
+    }
+    (mana, other)
+}
+
+/// Feláldozásnál a legkisebb értékű lényt adjuk oda, tokent előbb
+fn sacrifice_value(card: &Card) -> (bool, i32) {
+    let stats = match &card.card_type {
+        CardType::Creature(cr) => cr.power + cr.toughness,
+        _ => 0,
+    };
+    (!card.has_type(CardTypeFlags::TOKEN), stats)
+}
+
+impl Gre {
+    pub fn add_cost_reduction(&mut self, reduction: CostReduction) {
+        debug!("add_cost_reduction() -> {:?}", reduction);
+        self.cost_reductions.push(reduction);
+    }
+
+    /// A kártya mana költsége a csökkentések után; csak a generikus rész csökkenhet.
+    pub fn effective_cost(&self, card: &Card, controller: Player) -> ManaCost {
+        let reduction: u32 = self
+            .cost_reductions
+            .iter()
+            .filter(|r| {
+                r.controller == controller
+                    && r.filter.matches(
+                        card,
+                        &FilterContext {
+                            controller: Some(controller),
+                            source: r.source,
+                            zone: None,
+                        },
+                    )
+            })
+            .filter(|r| {
+                r.source
+                    .is_none_or(|id| self.battlefield_creatures.contains_key(&id))
+            })
+            .map(|r| r.generic)
+            .sum();
+        let mut cost = card.mana_cost.clone();
+        cost.colorless = cost.colorless.saturating_sub(reduction);
+        cost
+    }
+
+    /// Tappolható-e a permanens költségként: nincs tappolva, és ha lény, akkor
+    /// nem summoning sick (vagy haste-e van).
+    pub fn can_tap(&self, card_id: u64) -> bool {
+        match self.battlefield_creatures.get(&card_id) {
+            Some(card) if card.tapped => false,
+            Some(card) => match &card.card_type {
+                CardType::Creature(cr) => {
+                    !cr.summoning_sickness || cr.abilities.contains(&KeywordAbility::Haste)
+                }
+                _ => true,
+            },
+            None => false,
+        }
+    }
+
+    fn sacrifice_candidates(&self, controller: Player, filter: &TargetFilter) -> Vec<u64> {
+        let mut candidates: Vec<&Card> = self
+            .permanents_matching(&filter.clone().and(TargetFilter::ControllerCreature), controller)
+            .into_iter()
+            .filter_map(|id| self.battlefield_creatures.get(&id))
+            .collect();
+        candidates.sort_by_key(|c| (sacrifice_value(c), c.card_id));
+        candidates.into_iter().map(|c| c.card_id).collect()
+    }
+
+    /// Temetőből száműzendő lap: előbb az ellenfél temetője, azon belül a lények
+    /// (Scavenging Ooze így kap +1/+1 countert és életet), a legutóbbi lap elöl.
+    fn graveyard_exile_choice(
+        &self,
+        controller: Player,
+        filter: &TargetFilter,
+    ) -> Option<(Player, usize)> {
+        let ctx = self.filter_context(controller, Zone::Graveyard);
+        [controller.opponent(), controller]
+            .into_iter()
+            .flat_map(|p| {
+                let graveyard = &self.player(p).graveyard;
+                (0..graveyard.len())
+                    .rev()
+                    .filter(move |&i| filter.matches(&graveyard[i], &ctx))
+                    .map(move |i| {
+                        (
+                            p,
+                            i,
+                            matches!(graveyard[i].card_type, CardType::Creature(_)),
+                        )
+                    })
+            })
+            .enumerate()
+            .min_by_key(|(order, (_, _, creature))| (!creature, *order))
+            .map(|(_, (p, i, _))| (p, i))
+    }
+
+    /// Kifizethető-e most a mana és a nem-mana költségek összessége.
+    pub fn check_costs(
+        &self,
+        controller: Player,
+        source: Option<u64>,
+        mana: &ManaCost,
+        costs: &[Cost],
+    ) -> Result<(), CostError> {
+        let (extra_mana, others) = split_costs(costs);
+        let needed = add_mana_cost(mana, &extra_mana);
+        let state = self.player(controller);
+        if !state.mana_pool.can_pay(&needed) {
+            return Err(CostError::NotEnoughMana {
+                needed,
+                available: state.mana_pool.total(),
+            });
+        }
+        let mut sacrifices = 0;
+        let mut discards = 0;
+        let mut life = 0;
+        let mut exiles = 0;
+        for cost in &others {
+            match cost {
+                Cost::Mana(_) => {}
+                Cost::TapSelf => {
+                    if !source.is_some_and(|id| self.can_tap(id)) {
+                        return Err(CostError::CannotTap);
+                    }
+                }
+                Cost::SacrificeSelf => {
+                    if !source.is_some_and(|id| self.battlefield_creatures.contains_key(&id)) {
+                        return Err(CostError::NothingToSacrifice);
+                    }
+                }
+                Cost::SacrificeCreature(filter) => {
+                    sacrifices += 1;
+                    if self.sacrifice_candidates(controller, filter).len() < sacrifices {
+                        return Err(CostError::NothingToSacrifice);
+                    }
+                }
+                Cost::Discard(n) => discards += n,
+                Cost::PayLife(n) => life += n,
+                Cost::ExileFromGraveyard(_) => exiles += 1,
+            }
+        }
+        if discards > state.hand_size {
+            return Err(CostError::NotEnoughCards {
+                needed: discards,
+                hand: state.hand_size,
+            });
+        }
+        if life > 0 && state.life < life as i32 {
+            return Err(CostError::NotEnoughLife {
+                needed: life,
+                life: state.life,
+            });
+        }
+        let graveyard_cards: usize = [Player::Us, Player::Opponent]
+            .iter()
+            .map(|p| self.player(*p).graveyard.len())
+            .sum();
+        if exiles > graveyard_cards {
+            return Err(CostError::NothingToExile);
+        }
+        for cost in &others {
+            if let Cost::ExileFromGraveyard(filter) = cost
+                && self.graveyard_exile_choice(controller, filter).is_none()
+            {
+                return Err(CostError::NothingToExile);
+            }
+        }
+        Ok(())
+    }
+
+    /// Kifizeti a költségeket (előbb ellenőriz, így vagy mind, vagy semmi).
+    pub fn pay_costs(
+        &mut self,
+        controller: Player,
+        source: Option<u64>,
+        mana: &ManaCost,
+        costs: &[Cost],
+    ) -> Result<PaidCosts, CostError> {
+        self.check_costs(controller, source, mana, costs)?;
+        let (extra_mana, others) = split_costs(costs);
+        let needed = add_mana_cost(mana, &extra_mana);
+        if needed.total() > 0 {
+            self.player_mut(controller).mana_pool.pay(&needed);
+            debug!("    {:?} paid {:?}", controller, needed);
+        }
+
+        let mut paid = PaidCosts::default();
+        for cost in others {
+            match cost {
+                Cost::Mana(_) => {}
+                Cost::TapSelf => {
+                    if let Some(card) =
+                        source.and_then(|id| self.battlefield_creatures.get_mut(&id))
+                    {
+                        debug!("    tapping '{}' (id={})", card.name, card.card_id);
+                        card.tapped = true;
+                    }
+                }
+                Cost::SacrificeSelf => {
+                    if let Some(card) = source.and_then(|id| self.battlefield_creatures.remove(&id))
+                    {
+                        info!(
+                            "    sacrificing source '{}' (id={})",
+                            card.name, card.card_id
+                        );
+                        paid.sacrificed_source = true;
+                        paid.sacrificed.push(card.clone());
+                        self.sacrificed(card, controller);
+                    }
+                }
+                Cost::SacrificeCreature(filter) => {
+                    let id = self.sacrifice_candidates(controller, &filter)[0];
+                    if let Some(card) = self.battlefield_creatures.remove(&id) {
+                        info!("    sacrificing '{}' (id={})", card.name, id);
+                        paid.sacrificed.push(card.clone());
+                        self.sacrificed(card, controller);
+                    }
+                }
+                Cost::Discard(n) => {
+                    self.discard(controller, n);
+                    paid.discarded += n;
+                }
+                Cost::PayLife(n) => {
+                    self.lose_life(controller, n);
+                    paid.life_paid += n;
+                }
+                Cost::ExileFromGraveyard(filter) => {
+                    if let Some(card) = self.exile_from_graveyard(controller, &filter) {
+                        paid.exiled.push(card);
+                    }
+                }
+            }
+        }
+        Ok(paid)
+    }
+
+    /// Egy lap száműzése temetőből (ld. `graveyard_exile_choice`); `None`, ha nincs ilyen.
+    pub fn exile_from_graveyard(
+        &mut self,
+        controller: Player,
+        filter: &TargetFilter,
+    ) -> Option<Card> {
+        let (owner, idx) = self.graveyard_exile_choice(controller, filter)?;
+        let card = self.player_mut(owner).graveyard.remove(idx);
+        info!("    exiling '{}' from {:?}'s graveyard", card.name, owner);
+        self.last_exiled_card_was_creature = matches!(card.card_type, CardType::Creature(_));
+        Some(card)
+    }
+
+    fn sacrificed(&mut self, card: Card, controller: Player) {
+        self.leave_battlefield_to_graveyard(card, controller);
+    }
+
+    /// Varázslat kijátszása a teljes költségével, `target` célponttal (pl. aura, pump).
+    /// Modális varázslatnál a módokat és a célpontjaikat kijátszáskor választjuk. Az opcionális költségekről (kicker,
+    /// offspring) a `DecisionProvider` dönt, de csak a kifizethetőket kínáljuk fel;
+    /// a kifizetett költségek a stacken lévő varázslattal együtt utaznak.
+    pub fn cast_spell(
+        &mut self,
+        card: Card,
+        controller: Player,
+        target: Option<Card>,
+    ) -> Result<(), CostError> {
+        let base = self.effective_cost(&card, controller);
+        let mut costs = card.additional_costs.clone();
+        if let Err(e) = self.check_costs(controller, None, &base, &costs) {
+            warn!("cast_spell() -> cannot cast '{}': {}", card.name, e);
+            return Err(e);
+        }
+        let modes = if card.modal.is_some() {
+            let Some(modes) = self.choose_spell_modes(&card, controller) else {
+                warn!("cast_spell() -> '{}' has no legal mode choice", card.name);
+                return Err(CostError::NoLegalModes);
+            };
+            let labels: Vec<&str> = modes.iter().map(|m| m.label.as_str()).collect();
+            info!("    '{}' modes: {:?}", card.name, labels);
+            modes
+        } else {
+            Vec::new()
+        };
+        let targeted: Vec<u64> = modes
+            .iter()
+            .flat_map(|m| m.targets.iter())
+            .filter_map(|(_, t)| match t {
+                Target::Permanent(id) => Some(*id),
+                Target::Player(_) => None,
+            })
+            .chain(target.as_ref().map(|t| t.card_id))
+            .collect();
+
+        let mut chosen = Vec::new();
+        for optional in &card.optional_costs {
+            let with_optional: Vec<Cost> = costs.iter().chain(&optional.costs).cloned().collect();
+            if self
+                .check_costs(controller, None, &base, &with_optional)
+                .is_err()
+            {
+                debug!(
+                    "    {} is not affordable for '{}'",
+                    optional.kind.label(),
+                    card.name
+                );
+                continue;
+            }
+            let mana = split_costs(&optional.costs).0.total();
+            if self.decide_optional_cost(optional.kind.label(), mana) {
+                costs = with_optional;
+                chosen.push(optional.kind);
+            }
+        }
+
+        let mut paid = self.pay_costs(controller, None, &base, &costs)?;
+        paid.optional = chosen;
+        info!(
+            "{:?} casts '{}', target={:?} (optional costs paid: {:?})",
+            controller,
+            card.name,
+            target.as_ref().map(|t| t.name.as_str()),
+            paid.optional
+        );
+        self.note_spell_cast(controller);
+        self.push_to_stack(StackEntry::Spell {
+            card: card.clone(),
+            controller,
+            target_creature: target,
+            modes,
+            paid,
+        });
+        self.trigger_event(
+            GameEvent::SpellCast {
+                spell: card,
+                controller,
+            },
+            &mut Vec::new(),
+            controller,
+        );
+        for id in targeted {
+            self.trigger_event(GameEvent::Targeted(id), &mut Vec::new(), controller);
+        }
+        Ok(())
+    }
+}
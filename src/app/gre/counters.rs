@@ -0,0 +1,301 @@
+// src/app/gre/counters.rs
+
+use tracing::{debug, info, warn};
+
+use crate::app::card_attribute::{CounterType, Zone};
+use crate::app::card_library::{Card, CardType, CardTypeFlags};
+use crate::app::game_state::{GameEvent, Player};
+use crate::app::gre::decision::Target;
+use crate::app::gre::Gre;
+
+/// Proliferate során választható célpont: permanens vagy játékos
+pub type ProliferateTarget = Target;
+
+impl Gre {
+    /// Counterek felrakása egy permanensre; OnCounterAdded triggert vált ki,
+    /// majd lefuttatja a 0 toughness / 0 loyalty state-based actionöket.
+    pub fn add_counters(&mut self, card_id: u64, counter: CounterType, amount: u32) {
+        if amount == 0 {
+            return;
+        }
+        if counter.is_player_counter() {
+            warn!("add_counters() -> {:?} is a player counter, ignoring", counter);
+            return;
+        }
+        let Some(card) = self.battlefield_creatures.get_mut(&card_id) else {
+            warn!("add_counters() -> no permanent with id={}", card_id);
+            return;
+        };
+        card.add_counters(&counter, amount);
+        info!(
+            "    '{}' (id={}) gets {} {:?} counter(s) -> {:?}",
+            card.name, card_id, amount, counter, card.counters
+        );
+        self.trigger_event(
+            GameEvent::CounterAdded(card_id, counter, amount),
+            &mut Vec::new(),
+            Player::Us,
+        );
+        self.check_zero_toughness();
+        self.check_planeswalker_loyalty();
+    }
+
+    /// Counterek levétele egy permanensről; visszaadja, hány került le.
+    pub fn remove_counters(&mut self, card_id: u64, counter: CounterType, amount: u32) -> u32 {
+        let removed = match self.battlefield_creatures.get_mut(&card_id) {
+            Some(card) => card.remove_counters(&counter, amount),
+            None => 0,
+        };
+        if removed > 0 {
+            debug!(
+                "    removed {} {:?} counter(s) from id={}",
+                removed, counter, card_id
+            );
+            self.check_zero_toughness();
+            self.check_planeswalker_loyalty();
+        }
+        removed
+    }
+
+    /// A képernyőn látott counter-jelvény átvétele: a +1/+1 (vagy ha már van, a -1/-1)
+    /// counterek számát `seen`-re állítja. Állapot-szinkron, nem vált ki triggert.
+    pub fn sync_counter_badge(&mut self, card_id: u64, seen: u32) {
+        let Some(card) = self.battlefield_creatures.get_mut(&card_id) else {
+            return;
+        };
+        let kind = if card.counter_count(&CounterType::MinusOneMinusOne) > 0 {
+            CounterType::MinusOneMinusOne
+        } else {
+            CounterType::PlusOnePlusOne
+        };
+        let current = card.counter_count(&kind);
+        if seen > current {
+            card.add_counters(&kind, seen - current);
+        } else {
+            card.remove_counters(&kind, current - seen);
+        }
+        info!(
+            "    '{}' (id={}) {:?} counters synced from screen: {} -> {}",
+            card.name, card_id, kind, current, seen
+        );
+        self.check_zero_toughness();
+    }
+
+    /// Shield counter: sebzés vagy destroy helyett egy lekerül.
+    /// `true`, ha a shield megakadályozta az eseményt.
+    pub fn consume_shield(&mut self, card_id: u64) -> bool {
+        let Some(card) = self.battlefield_creatures.get_mut(&card_id) else {
+            return false;
+        };
+        if card.remove_counters(&CounterType::Shield, 1) == 1 {
+            info!("    '{}' (id={}) loses a shield counter instead", card.name, card_id);
+            return true;
+        }
+        false
+    }
+
+    /// A battlefieldről már levett permanens temetőbe kerül: lénynél halál-esemény,
+    /// egyébként zónaváltás. Finality counterrel a temető helyett exile-ba kerül.
+    pub fn leave_battlefield_to_graveyard(&mut self, card: Card, actor: Player) {
+        if card.counter_count(&CounterType::Finality) > 0 {
+            info!(
+                "    '{}' (id={}) has a finality counter, exiled instead",
+                card.name, card.card_id
+            );
+            if !card.has_type(CardTypeFlags::TOKEN) {
+                self.player_mut(card.controller).exile.push(card.clone());
+            }
+            self.trigger_event(
+                GameEvent::ZoneChange {
+                    card,
+                    from: Some(Zone::Battlefield),
+                    to: Zone::Exile,
+                },
+                &mut Vec::new(),
+                actor,
+            );
+        } else if matches!(card.card_type, CardType::Creature(_)) {
+            self.trigger_event(GameEvent::CreatureDied(card), &mut Vec::new(), actor);
+        } else {
+            self.put_into_graveyard(card.clone());
+            self.trigger_event(
+                GameEvent::ZoneChange {
+                    card,
+                    from: Some(Zone::Battlefield),
+                    to: Zone::Graveyard,
+                },
+                &mut Vec::new(),
+                actor,
+            );
+        }
+    }
+
+    /// Játékos counterek (poison, energy, experience) hozzáadása.
+    pub fn add_player_counters(&mut self, player: Player, counter: CounterType, amount: u32) {
+        if !counter.is_player_counter() {
+            warn!(
+                "add_player_counters() -> {:?} is not a player counter, ignoring",
+                counter
+            );
+            return;
+        }
+        let total = {
+            let entry = self
+                .player_mut(player)
+                .counters
+                .entry(counter.clone())
+                .or_insert(0);
+            *entry += amount;
+            *entry
+        };
+        info!(
+            "    {:?} gets {} {:?} counter(s) -> {}",
+            player, amount, counter, total
+        );
+    }
+
+    /// Játékos counterek elköltése (pl. energy); `false`, ha nincs elég.
+    pub fn pay_player_counters(&mut self, player: Player, counter: CounterType, amount: u32) -> bool {
+        let Some(entry) = self.player_mut(player).counters.get_mut(&counter) else {
+            return amount == 0;
+        };
+        if *entry < amount {
+            return false;
+        }
+        *entry -= amount;
+        true
+    }
+
+    /// Adott játékos adott fajtájú countereinek száma.
+    pub fn player_counter(&self, player: Player, counter: &CounterType) -> u32 {
+        self.player(player).counters.get(counter).copied().unwrap_or(0)
+    }
+
+    /// Minden permanens és játékos, amin van legalább egy counter.
+    pub fn proliferate_candidates(&self) -> Vec<ProliferateTarget> {
+        let mut ids: Vec<u64> = self
+            .battlefield_creatures
+            .iter()
+            .filter(|(_, c)| !c.counter_kinds().is_empty())
+            .map(|(&id, _)| id)
+            .collect();
+        ids.sort();
+        let mut out: Vec<ProliferateTarget> =
+            ids.into_iter().map(ProliferateTarget::Permanent).collect();
+        for player in [Player::Us, Player::Opponent] {
+            if self.player(player).counters.values().any(|&n| n > 0) {
+                out.push(ProliferateTarget::Player(player));
+            }
+        }
+        out
+    }
+
+    /// Alapértelmezett választás: ami nekünk jó (saját +1/+1, loyalty, energy...;
+    /// ellenfélen -1/-1, stun, poison), azt proliferate-eljük.
+    pub fn default_proliferate_choice(
+        &self,
+        candidates: &[ProliferateTarget],
+    ) -> Vec<ProliferateTarget> {
+        candidates
+            .iter()
+            .copied()
+            .filter(|t| {
+                let (owner, kinds) = match t {
+                    ProliferateTarget::Permanent(id) => match self.battlefield_creatures.get(id) {
+                        Some(c) => (c.controller, c.counter_kinds()),
+                        None => return false,
+                    },
+                    ProliferateTarget::Player(p) => (
+                        *p,
+                        self.player(*p)
+                            .counters
+                            .iter()
+                            .filter(|(_, n)| **n > 0)
+                            .map(|(k, _)| k.clone())
+                            .collect(),
+                    ),
+                };
+                let score: i32 = kinds
+                    .iter()
+                    .map(|k| {
+                        let good_for_owner = !matches!(
+                            k,
+                            CounterType::MinusOneMinusOne
+                                | CounterType::Stun
+                                | CounterType::Poison
+                                | CounterType::Finality
+                        );
+                        if good_for_owner == (owner == Player::Us) { 1 } else { -1 }
+                    })
+                    .sum();
+                score > 0
+            })
+            .collect()
+    }
+
+    /// Proliferate a kiválasztott célpontokra: mindegyik minden meglévő
+    /// counter-fajtájából kap még egyet.
+    pub fn proliferate(&mut self, chosen: &[ProliferateTarget]) {
+        for target in chosen {
+            match *target {
+                ProliferateTarget::Permanent(id) => {
+                    let kinds = match self.battlefield_creatures.get(&id) {
+                        Some(c) => c.counter_kinds(),
+                        None => continue,
+                    };
+                    for kind in kinds {
+                        self.add_counters(id, kind, 1);
+                    }
+                }
+                ProliferateTarget::Player(player) => {
+                    let kinds: Vec<CounterType> = self
+                        .player(player)
+                        .counters
+                        .iter()
+                        .filter(|(_, n)| **n > 0)
+                        .map(|(k, _)| k.clone())
+                        .collect();
+                    for kind in kinds {
+                        self.add_player_counters(player, kind, 1);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Az `Effect::Proliferate` végrehajtása: a `DecisionProvider` dönti el,
+    /// mely célpontok kapnak countert.
+    pub fn execute_proliferate(&mut self) {
+        let candidates = self.proliferate_candidates();
+        if candidates.is_empty() {
+            info!("Proliferate: nothing has counters, nothing to do");
+            return;
+        }
+        let chosen = self.choose_proliferate(&candidates);
+        info!(
+            "Proliferate: candidates={:?}, chosen={:?}",
+            candidates, chosen
+        );
+        self.proliferate(&chosen);
+    }
+
+    /// State-based action: 0 vagy kisebb toughnessű lények meghalnak.
+    pub fn check_zero_toughness(&mut self) {
+        let dead: Vec<u64> = self
+            .battlefield_creatures
+            .iter()
+            .filter(|(_, c)| match &c.card_type {
+                CardType::Creature(cr) => cr.toughness + cr.ephemeral_toughness <= 0,
+                _ => false,
+            })
+            .map(|(&id, _)| id)
+            .collect();
+        for id in dead {
+            if let Some(card) = self.battlefield_creatures.remove(&id) {
+                info!("    '{}' (id={}) dies with 0 toughness", card.name, id);
+                self.leave_battlefield_to_graveyard(card, Player::Us);
+            }
+        }
+    }
+}
+
@@ -0,0 +1,472 @@
+// src/app/gre/decision.rs
+
+//! Döntési pontok: a GRE minden szabály szerinti választásnál (mód, célpont,
+//! opcionális költség, sebzés-kiosztási sorrend, proliferate, scry) a
+//! `DecisionProvider`-t kérdezi, és a meghozott döntéseket `decision_log`-ba írja,
+//! így egy játszma döntései `ScriptedDecisions`-szel visszajátszhatók.
+
+use std::collections::VecDeque;
+use tracing::{debug, info, warn};
+
+use crate::app::card_attribute::Effect;
+use crate::app::card_library::{Card, CardType, CardTypeFlags, Creature};
+use crate::app::game_state::Player;
+use crate::app::gre::Gre;
+
+/// Választható célpont: permanens vagy játékos
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Target {
+    Permanent(u64),
+    Player(Player),
+}
+
+/// Egy meghozott döntés (a naplóban és a szkriptben)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Decision {
+    Modes(Vec<usize>),
+    Target(Option<Target>),
+    PayCost(bool),
+    DamageOrder(Vec<usize>),
+    Proliferate(Vec<Target>),
+    Scry(Vec<bool>),
+}
+
+/// A szabályok által megkövetelt választások forrása.
+pub trait DecisionProvider {
+    /// `min..=max` darab különböző mód indexe az `options` közül (módonként az effektjei)
+    fn choose_modes(&mut self, gre: &Gre, options: &[Vec<Effect>], min: usize, max: usize) -> Vec<usize>;
+    /// Célpont az `effect`-hez a `candidates` közül
+    fn choose_target(&mut self, gre: &Gre, effect: &Effect, candidates: &[Target]) -> Option<Target>;
+    /// Kifizetjük-e az opcionális költséget (pl. Offspring)
+    fn pay_optional_cost(&mut self, gre: &Gre, what: &str, cost: u32) -> bool;
+    /// A támadó sebzés-kiosztási sorrendje a blokkolói közt (indexek a `blockers`-be)
+    fn damage_assignment_order(
+        &mut self,
+        gre: &Gre,
+        attacker: &Creature,
+        blockers: &[Creature],
+    ) -> Vec<usize>;
+    /// Proliferate: a jelöltek közül a kiválasztottak
+    fn choose_proliferate(&mut self, gre: &Gre, candidates: &[Target]) -> Vec<Target>;
+    /// Scry: a felfedett lapok közül melyik kerüljön alulra (`true`)
+    fn scry(&mut self, gre: &Gre, revealed: &[Card]) -> Vec<bool>;
+}
+
+/// Scry-nál ennyi földön felül a felfedett földeket alulra tesszük
+const SCRY_LAND_TARGET: usize = 5;
+
+fn remaining_toughness(cr: &Creature) -> i32 {
+    cr.toughness + cr.ephemeral_toughness
+}
+
+/// Az élő bot döntései: egyszerű, determinisztikus heurisztikák.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HeuristicDecisions;
+
+impl DecisionProvider for HeuristicDecisions {
+    /// A módok választása ingyenes: a lehető legtöbb, a kártyaleírás sorrendjében
+    fn choose_modes(&mut self, _gre: &Gre, options: &[Vec<Effect>], _min: usize, max: usize) -> Vec<usize> {
+        (0..max.min(options.len())).collect()
+    }
+
+    /// Sebzésnél: az ellenfél legerősebb lénye / planeswalkere, amit megöl; különben
+    /// az ellenfél. Egyéb effektnél az első ellenséges, végül az első jelölt.
+    fn choose_target(&mut self, gre: &Gre, effect: &Effect, candidates: &[Target]) -> Option<Target> {
+        let opponent = gre.resolving_controller.opponent();
+        let enemy = |t: &&Target| match t {
+            Target::Player(p) => *p == opponent,
+            Target::Permanent(id) => gre
+                .battlefield_creatures
+                .get(id)
+                .is_some_and(|c| c.controller == opponent),
+        };
+        if let Effect::Damage { amount, .. } = effect {
+            let damage = gre.amount_value(amount);
+            let kill = candidates
+                .iter()
+                .filter(enemy)
+                .filter_map(|t| match t {
+                    Target::Permanent(id) => gre.battlefield_creatures.get(id).map(|c| (t, c)),
+                    Target::Player(_) => None,
+                })
+                .filter_map(|(t, c)| match (&c.card_type, c.loyalty()) {
+                    (CardType::Creature(cr), _) if remaining_toughness(cr) <= damage => {
+                        Some((t, cr.power + cr.ephemeral_power))
+                    }
+                    (_, Some(loyalty)) if loyalty <= damage => Some((t, loyalty)),
+                    _ => None,
+                })
+                .max_by_key(|(_, value)| *value);
+            if let Some((t, _)) = kill {
+                return Some(*t);
+            }
+            if candidates.contains(&Target::Player(opponent)) {
+                return Some(Target::Player(opponent));
+            }
+        }
+        candidates.iter().find(enemy).or(candidates.first()).copied()
+    }
+
+    fn pay_optional_cost(&mut self, _gre: &Gre, _what: &str, _cost: u32) -> bool {
+        true
+    }
+
+    /// A leggyengébb blokkolóval kezdünk, hogy minél több meghaljon
+    fn damage_assignment_order(
+        &mut self,
+        _gre: &Gre,
+        _attacker: &Creature,
+        blockers: &[Creature],
+    ) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..blockers.len()).collect();
+        order.sort_by_key(|&i| remaining_toughness(&blockers[i]));
+        order
+    }
+
+    fn choose_proliferate(&mut self, gre: &Gre, candidates: &[Target]) -> Vec<Target> {
+        gre.default_proliferate_choice(candidates)
+    }
+
+    /// Elég föld mellett a felfedett földek alulra kerülnek
+    fn scry(&mut self, gre: &Gre, revealed: &[Card]) -> Vec<bool> {
+        let lands = gre
+            .battlefield_creatures
+            .values()
+            .filter(|c| c.controller == gre.resolving_controller && c.has_type(CardTypeFlags::LAND))
+            .count();
+        revealed
+            .iter()
+            .map(|c| lands >= SCRY_LAND_TARGET && c.has_type(CardTypeFlags::LAND))
+            .collect()
+    }
+}
+
+/// Előre megadott döntések sorban (tesztekhez és visszajátszáshoz). Ha a szkript
+/// elfogyott, vagy a következő elem más fajta döntés, a heurisztika dönt.
+#[derive(Debug, Clone, Default)]
+pub struct ScriptedDecisions {
+    script: VecDeque<Decision>,
+    fallback: HeuristicDecisions,
+}
+
+impl ScriptedDecisions {
+    pub fn new(script: impl IntoIterator<Item = Decision>) -> Self {
+        Self {
+            script: script.into_iter().collect(),
+            fallback: HeuristicDecisions,
+        }
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.script.len()
+    }
+
+    fn next<T>(&mut self, kind: &str, take: impl FnOnce(&Decision) -> Option<T>) -> Option<T> {
+        let found = self.script.front().and_then(take);
+        match found {
+            Some(_) => {
+                self.script.pop_front();
+            }
+            None => warn!(
+                "ScriptedDecisions: no scripted {} decision (next: {:?}), using heuristic",
+                kind,
+                self.script.front()
+            ),
+        }
+        found
+    }
+}
+
+impl DecisionProvider for ScriptedDecisions {
+    fn choose_modes(&mut self, gre: &Gre, options: &[Vec<Effect>], min: usize, max: usize) -> Vec<usize> {
+        self.next("modes", |d| match d {
+            Decision::Modes(m) => Some(m.clone()),
+            _ => None,
+        })
+        .unwrap_or_else(|| self.fallback.choose_modes(gre, options, min, max))
+    }
+
+    fn choose_target(&mut self, gre: &Gre, effect: &Effect, candidates: &[Target]) -> Option<Target> {
+        self.next("target", |d| match d {
+            Decision::Target(t) => Some(*t),
+            _ => None,
+        })
+        .unwrap_or_else(|| self.fallback.choose_target(gre, effect, candidates))
+    }
+
+    fn pay_optional_cost(&mut self, gre: &Gre, what: &str, cost: u32) -> bool {
+        self.next("pay cost", |d| match d {
+            Decision::PayCost(b) => Some(*b),
+            _ => None,
+        })
+        .unwrap_or_else(|| self.fallback.pay_optional_cost(gre, what, cost))
+    }
+
+    fn damage_assignment_order(
+        &mut self,
+        gre: &Gre,
+        attacker: &Creature,
+        blockers: &[Creature],
+    ) -> Vec<usize> {
+        self.next("damage order", |d| match d {
+            Decision::DamageOrder(o) => Some(o.clone()),
+            _ => None,
+        })
+        .unwrap_or_else(|| self.fallback.damage_assignment_order(gre, attacker, blockers))
+    }
+
+    fn choose_proliferate(&mut self, gre: &Gre, candidates: &[Target]) -> Vec<Target> {
+        self.next("proliferate", |d| match d {
+            Decision::Proliferate(p) => Some(p.clone()),
+            _ => None,
+        })
+        .unwrap_or_else(|| self.fallback.choose_proliferate(gre, candidates))
+    }
+
+    fn scry(&mut self, gre: &Gre, revealed: &[Card]) -> Vec<bool> {
+        self.next("scry", |d| match d {
+            Decision::Scry(s) => Some(s.clone()),
+            _ => None,
+        })
+        .unwrap_or_else(|| self.fallback.scry(gre, revealed))
+    }
+}
+
+/// AI kereséshez: minden döntési pontnál felsorolja az alternatívákat, a `path`
+/// szerinti indexűt választja (a path után az elsőt), és feljegyzi, hány
+/// alternatíva volt. `next_path` a következő, még be nem járt ágat adja.
+#[derive(Debug, Clone, Default)]
+pub struct SearchDecisions {
+    path: Vec<usize>,
+    pos: usize,
+    arities: Vec<usize>,
+}
+
+impl SearchDecisions {
+    pub fn new(path: Vec<usize>) -> Self {
+        Self {
+            path,
+            pos: 0,
+            arities: Vec::new(),
+        }
+    }
+
+    /// Az eddigi döntési pontok alternatíváinak száma
+    pub fn arities(&self) -> &[usize] {
+        &self.arities
+    }
+
+    /// A ténylegesen bejárt út (a path-on túli pontokon 0)
+    pub fn taken(&self) -> Vec<usize> {
+        (0..self.arities.len())
+            .map(|i| self.path.get(i).copied().unwrap_or(0).min(self.arities[i] - 1))
+            .collect()
+    }
+
+    /// Mélységi bejárás: az utolsó még növelhető döntést lépteti, az utána jövőket elhagyja.
+    pub fn next_path(&self) -> Option<Vec<usize>> {
+        let mut taken = self.taken();
+        while let Some(last) = taken.pop() {
+            if last + 1 < self.arities[taken.len()] {
+                taken.push(last + 1);
+                return Some(taken);
+            }
+        }
+        None
+    }
+
+    fn pick<T>(&mut self, alternatives: Vec<T>) -> T {
+        let arity = alternatives.len();
+        let idx = self.path.get(self.pos).copied().unwrap_or(0).min(arity - 1);
+        debug!("SearchDecisions: point {} -> {}/{}", self.pos, idx, arity);
+        self.arities.push(arity);
+        self.pos += 1;
+        alternatives
+            .into_iter()
+            .nth(idx)
+            .expect("index within alternatives")
+    }
+}
+
+/// Ennél több elemnél a részhalmazokat (2^n ág) nem soroljuk fel, a heurisztika dönt
+pub const MAX_SUBSET_SEARCH: usize = 10;
+
+/// `n` elem összes részhalmaza bitmaszkként (az üres halmazzal kezdve);
+/// `n` legfeljebb `MAX_SUBSET_SEARCH`.
+fn subsets(n: usize) -> Vec<Vec<bool>> {
+    assert!(n <= MAX_SUBSET_SEARCH, "subsets({}) would enumerate 2^{} branches", n, n);
+    (0..1usize << n)
+        .map(|mask| (0..n).map(|i| mask & (1 << i) != 0).collect())
+        .collect()
+}
+
+fn permutations(items: &[usize]) -> Vec<Vec<usize>> {
+    if items.len() <= 1 {
+        return vec![items.to_vec()];
+    }
+    let mut out = Vec::new();
+    for (i, &first) in items.iter().enumerate() {
+        let mut rest = items.to_vec();
+        rest.remove(i);
+        for mut tail in permutations(&rest) {
+            tail.insert(0, first);
+            out.push(tail);
+        }
+    }
+    out
+}
+
+impl DecisionProvider for SearchDecisions {
+    fn choose_modes(&mut self, gre: &Gre, options: &[Vec<Effect>], min: usize, max: usize) -> Vec<usize> {
+        if options.len() > MAX_SUBSET_SEARCH {
+            return HeuristicDecisions.choose_modes(gre, options, min, max);
+        }
+        let (min, max) = (min.min(options.len()), max.min(options.len()));
+        let combos = subsets(options.len())
+            .into_iter()
+            .filter(|s| (min..=max).contains(&s.iter().filter(|&&b| b).count()))
+            .map(|s| s.iter().enumerate().filter(|(_, b)| **b).map(|(i, _)| i).collect())
+            .collect();
+        self.pick(combos)
+    }
+
+    fn choose_target(&mut self, _gre: &Gre, _effect: &Effect, candidates: &[Target]) -> Option<Target> {
+        if candidates.is_empty() {
+            return None;
+        }
+        self.pick(candidates.iter().copied().map(Some).collect())
+    }
+
+    fn pay_optional_cost(&mut self, _gre: &Gre, _what: &str, _cost: u32) -> bool {
+        self.pick(vec![true, false])
+    }
+
+    fn damage_assignment_order(
+        &mut self,
+        _gre: &Gre,
+        _attacker: &Creature,
+        blockers: &[Creature],
+    ) -> Vec<usize> {
+        let identity: Vec<usize> = (0..blockers.len()).collect();
+        self.pick(permutations(&identity))
+    }
+
+    fn choose_proliferate(&mut self, gre: &Gre, candidates: &[Target]) -> Vec<Target> {
+        if candidates.len() > MAX_SUBSET_SEARCH {
+            return HeuristicDecisions.choose_proliferate(gre, candidates);
+        }
+        let chosen = subsets(candidates.len())
+            .into_iter()
+            .map(|s| {
+                candidates
+                    .iter()
+                    .zip(s)
+                    .filter(|(_, b)| *b)
+                    .map(|(t, _)| *t)
+                    .collect()
+            })
+            .collect();
+        self.pick(chosen)
+    }
+
+    fn scry(&mut self, gre: &Gre, revealed: &[Card]) -> Vec<bool> {
+        if revealed.len() > MAX_SUBSET_SEARCH {
+            return HeuristicDecisions.scry(gre, revealed);
+        }
+        self.pick(subsets(revealed.len()))
+    }
+}
+
+impl Gre {
+    /// A provider idejére egy heurisztikát teszünk a helyére, hogy `&Gre`-t kaphasson.
+    fn ask_decisions<R>(&mut self, f: impl FnOnce(&mut dyn DecisionProvider, &Gre) -> R) -> R {
+        let mut provider = std::mem::replace(&mut self.decisions, Box::new(HeuristicDecisions));
+        let out = f(provider.as_mut(), self);
+        self.decisions = provider;
+        out
+    }
+
+    fn record_decision(&mut self, decision: Decision) {
+        info!("Decision: {:?}", decision);
+        self.decision_log.push(decision);
+    }
+
+    /// `min..=max` (legfeljebb az opciók száma) különböző, létező mód; érvénytelen
+    /// válasznál a heurisztika dönt.
+    pub fn choose_modes(&mut self, options: &[Vec<Effect>], min: usize, max: usize) -> Vec<usize> {
+        let (min, max) = (min.min(options.len()), max.min(options.len()));
+        let mut modes = self.ask_decisions(|p, gre| p.choose_modes(gre, options, min, max));
+        modes.sort_unstable();
+        modes.dedup();
+        if !(min..=max).contains(&modes.len()) || modes.iter().any(|&i| i >= options.len()) {
+            warn!("choose_modes() -> invalid choice {:?} for {}..={} of {}", modes, min, max, options.len());
+            modes = HeuristicDecisions.choose_modes(self, options, min, max);
+        }
+        self.record_decision(Decision::Modes(modes.clone()));
+        modes
+    }
+
+    /// Célpont a jelöltek közül; jelölten kívüli válasznál a heurisztika dönt.
+    pub fn choose_target(&mut self, effect: &Effect, candidates: &[Target]) -> Option<Target> {
+        if candidates.is_empty() {
+            return None;
+        }
+        let mut target = self.ask_decisions(|p, gre| p.choose_target(gre, effect, candidates));
+        if target.is_some_and(|t| !candidates.contains(&t)) {
+            warn!("choose_target() -> {:?} is not a candidate", target);
+            target = HeuristicDecisions.choose_target(self, effect, candidates);
+        }
+        self.record_decision(Decision::Target(target));
+        target
+    }
+
+    pub fn decide_optional_cost(&mut self, what: &str, cost: u32) -> bool {
+        let pay = self.ask_decisions(|p, gre| p.pay_optional_cost(gre, what, cost));
+        self.record_decision(Decision::PayCost(pay));
+        pay
+    }
+
+    /// A blokkolók sorrendje indexekként; ha nem permutáció, marad az eredeti sorrend.
+    pub fn damage_assignment_order(&mut self, attacker: &Creature, blockers: &[Creature]) -> Vec<usize> {
+        let mut order = self.ask_decisions(|p, gre| p.damage_assignment_order(gre, attacker, blockers));
+        let mut sorted = order.clone();
+        sorted.sort_unstable();
+        if sorted != (0..blockers.len()).collect::<Vec<_>>() {
+            warn!("damage_assignment_order() -> {:?} is not a permutation", order);
+            order = (0..blockers.len()).collect();
+        }
+        self.record_decision(Decision::DamageOrder(order.clone()));
+        order
+    }
+
+    pub fn choose_proliferate(&mut self, candidates: &[Target]) -> Vec<Target> {
+        let mut chosen = self.ask_decisions(|p, gre| p.choose_proliferate(gre, candidates));
+        chosen.retain(|t| candidates.contains(t));
+        chosen.dedup();
+        self.record_decision(Decision::Proliferate(chosen.clone()));
+        chosen
+    }
+
+    /// Scry: lapnként `true`, ha alulra kerül.
+    pub fn scry(&mut self, revealed: &[Card]) -> Vec<bool> {
+        let mut bottom = self.ask_decisions(|p, gre| p.scry(gre, revealed));
+        bottom.resize(revealed.len(), false);
+        self.record_decision(Decision::Scry(bottom.clone()));
+        bottom
+    }
+
+    /// "Any target": minden lény és planeswalker a battlefielden, valamint mindkét játékos.
+    pub fn any_target_candidates(&self) -> Vec<Target> {
+        let mut ids: Vec<u64> = self
+            .battlefield_creatures
+            .values()
+            .filter(|c| matches!(c.card_type, CardType::Creature(_)) || c.loyalty().is_some())
+            .map(|c| c.card_id)
+            .collect();
+        ids.sort();
+        let mut out: Vec<Target> = ids.into_iter().map(Target::Permanent).collect();
+        out.push(Target::Player(Player::Opponent));
+        out.push(Target::Player(Player::Us));
+        out
+    }
+}
@@ -1,16 +1,13 @@
 // src/app/gre/effect_resolution.rs
 
 use crate::app::card_attribute::{
-    Amount, Condition, CounterType, Duration, Effect, PlayerSelector, TargetFilter, Trigger,
-    TriggeredEffectAttribute,
+    Amount, Duration, Effect, EnchantmentType, PlayerSelector, Subtype,
+    TargetFilter, Trigger, TriggeredEffectAttribute, Zone,
 };
 use crate::app::card_library::CardTypeFlags;
-use crate::app::card_library::{Card, CardType, Creature, ManaCost};
+use crate::app::card_library::{Card, CardType, ManaCost};
 use crate::app::game_state::{GameEvent, GamePhase, Player};
-use crate::app::gre::Gre;
-use crate::app::gre::gre_structs::DelayedEffect;
-use crate::app::gre::gre_structs::ReplacementEffect;
-use crate::app::gre::stack::StackEntry;
+use crate::app::gre::{Gre, ManaPool, OptionalCostKind, Target};
 use tracing::{debug, info, warn};
 
 /// Végső effectkezelő: Replacement + Continuous + Execute
@@ -50,6 +47,22 @@ impl Gre {
         }
     }
 
+    /// Az `Amount` értéke a forráskártyához képest (forrás nélkül 0).
+    pub fn amount_value(&self, amount: &Amount) -> i32 {
+        match (amount, &self.current_source_card) {
+            (Amount::Fixed(v), _) => *v,
+            (Amount::SourcePower, Some(src)) => match &src.card_type {
+                CardType::Creature(cr) => cr.power + cr.ephemeral_power,
+                _ => 0,
+            },
+            (Amount::SourceToughness, Some(src)) => match &src.card_type {
+                CardType::Creature(cr) => cr.toughness + cr.ephemeral_toughness,
+                _ => 0,
+            },
+            (_, None) => 0,
+        }
+    }
+
     /// A tényleges "egy effect" végrehajtása
     pub fn execute(&mut self, effect: Effect) {
         info!("GRE.execute() → Indul az effect végrehajtása: {:?}", effect);
@@ -60,8 +73,18 @@ impl Gre {
                 duration,
                 target,
             } => {
-                match (duration, target) {
-                    (Duration::EndOfTurn, TargetFilter::ExactCardID(cid)) => {
+                let effect = Effect::ModifyStats {
+                    power_delta,
+                    toughness_delta,
+                    duration: duration.clone(),
+                    target: target.clone(),
+                };
+                let Some(cid) = self.resolve_permanent_target(&target, &effect) else {
+                    info!("ModifyStats: nincs legális célpont ({:?}), átugorjuk.", target);
+                    return;
+                };
+                match duration {
+                    Duration::EndOfTurn => {
                         // 1) Kikeressük a battlefield_creatures mapből:
                         if let Some(card) = self.battlefield_creatures.get_mut(&cid) {
                             // Ha ez creature, hozzáadjuk az ephemeral buffot:
@@ -85,10 +108,10 @@ impl Gre {
                         }
                     }
 
-                    (Duration::Permanent, TargetFilter::ExactCardID(cid)) => {
+                    Duration::Permanent => {
                         // Ez a "maradandó" stat-módosítás (pl. CreateEnchantmentToken)
-                        if let Some(card) = self.battlefield_creatures.get_mut(&cid) {
-                            if let CardType::Creature(ref mut cr) = card.card_type {
+                        if let Some(card) = self.battlefield_creatures.get_mut(&cid)
+                            && let CardType::Creature(ref mut cr) = card.card_type {
                                 // Ráadásul itt közvetlenül a base power/toughness-t növeljük
                                 cr.power += power_delta;
                                 cr.toughness += toughness_delta;
@@ -97,12 +120,11 @@ impl Gre {
                                     power_delta, toughness_delta, card.name, cid
                                 );
                             }
-                        }
                     }
 
-                    (_, _) => {
+                    _ => {
                         // minden más esetet logolj, vagy hagyd üresen
-                        info!("ModifyStats: ismeretlen target/duration, átugorjuk.");
+                        info!("ModifyStats: ismeretlen duration, átugorjuk.");
                     }
                 }
             }
@@ -125,7 +147,8 @@ impl Gre {
                     );
 
                     // Létrehozunk egy token card-ot
-                    let mut aura_card = Card::new(&name, CardType::Enchantment, ManaCost::free());
+                    let mut aura_card = Card::new(&name, CardType::Enchantment, ManaCost::free())
+                        .with_subtype(Subtype::Enchantment(EnchantmentType::Role));
                     aura_card.type_flags |= CardTypeFlags::TOKEN;
 
                     // Rácsatoljuk a megcélzott creature-re
@@ -213,82 +236,38 @@ impl Gre {
                 amount,
                 target,
             } => {
-                match target {
-                    TargetFilter::ExactCardID(cid) => {
-                        if let Some(card) = self.battlefield_creatures.get_mut(&cid) {
-                            if let CardType::Creature(ref mut cr) = card.card_type {
-                                if counter == CounterType::PlusOnePlusOne {
-                                    cr.power += amount as i32;
-                                    cr.toughness += amount as i32;
-                                    info!(
-                                        "    '{}' gets {} +1/+1 counter(s) -> now base {}/{}",
-                                        card.name, amount, cr.power, cr.toughness
-                                    );
-                                }
-                            }
-                            if let CardType::Planeswalker(ref mut pw) = card.card_type {
-                                if counter == CounterType::Loyalty {
-                                    pw.loyalty += amount as i32;
-                                    info!("    '{}' gets {} loyalty counter(s)", card.name, amount);
-                                }
-                            }
-                            // Trigger OnCounterAdded events
-                            self.trigger_event(
-                                GameEvent::CounterAdded(cid, amount),
-                                &mut Vec::new(),
-                                Player::Us,
-                            );
-                        }
-                    }
-                    TargetFilter::SelfCard => {
-                        if let Some(ref src) = self.current_source_card {
-                            let cid = src.card_id;
-                            if let Some(card) = self.battlefield_creatures.get_mut(&cid) {
-                                if let CardType::Creature(ref mut cr) = card.card_type {
-                                    if counter == CounterType::PlusOnePlusOne {
-                                        cr.power += amount as i32;
-                                        cr.toughness += amount as i32;
-                                        info!(
-                                            "    '{}' gets {} +1/+1 counter(s) -> now base {}/{}",
-                                            card.name, amount, cr.power, cr.toughness
-                                        );
-                                    }
-                                }
-                                if let CardType::Planeswalker(ref mut pw) = card.card_type {
-                                    if counter == CounterType::Loyalty {
-                                        pw.loyalty += amount as i32;
-                                        info!(
-                                            "    '{}' gets {} loyalty counter(s)",
-                                            card.name, amount
-                                        );
-                                    }
-                                }
-                                self.trigger_event(
-                                    GameEvent::CounterAdded(cid, amount),
-                                    &mut Vec::new(),
-                                    Player::Us,
-                                );
-                            }
-                        }
-                    }
-                    _ => {
-                        warn!("AddCounter: unsupported target filter {:?}", target);
-                    }
+                let effect = Effect::AddCounter {
+                    counter: counter.clone(),
+                    amount,
+                    target: target.clone(),
+                };
+                match self.resolve_permanent_target(&target, &effect) {
+                    Some(cid) => self.add_counters(cid, counter, amount),
+                    None => warn!("AddCounter: no legal target for {:?}", target),
                 }
             }
 
+            Effect::Proliferate { player } => {
+                info!("Proliferate effect: player={:?}", player);
+                self.execute_proliferate();
+            }
+
             Effect::RemoveAbility { ability, target } => {
                 info!(
                     "RemoveAbility effect: ability={:?}, target={:?}",
                     ability, target
                 );
-                match target {
-                    TargetFilter::ExactCardID(id) => {
+                let effect = Effect::RemoveAbility {
+                    ability,
+                    target: target.clone(),
+                };
+                match self.resolve_permanent_target(&target, &effect) {
+                    Some(id) => {
                         info!(
                             "  RemoveAbility – megpróbáljuk kikeresni a battlefielden card_id={}",
                             id
                         );
-                        if let Some(mut c) = self.battlefield_creatures.get_mut(&id) {
+                        if let Some(c) = self.battlefield_creatures.get_mut(&id) {
                             info!(
                                 "  Megtaláltuk a kártyát ('{}', id={}), abilities törlése.",
                                 c.name, c.card_id
@@ -311,8 +290,8 @@ impl Gre {
                             );
                         }
                     }
-                    _ => {
-                        warn!("RemoveAbility target nem ExactCardID, átugorjuk.");
+                    None => {
+                        warn!("RemoveAbility: nincs legális célpont ({:?}), átugorjuk.", target);
                     }
                 }
             }
@@ -328,6 +307,18 @@ impl Gre {
                     "AddMana effect: adding mana -> +({}, {}, {}, {}, {}, {})",
                     colorless, red, blue, green, black, white
                 );
+                let controller = self.resolving_controller;
+                self.add_mana(
+                    controller,
+                    ManaPool {
+                        colorless,
+                        red,
+                        blue,
+                        green,
+                        black,
+                        white,
+                    },
+                );
                 if let Some(src) = &self.current_source_card {
                     self.trigger_event(
                         GameEvent::ManaAdded(src.card_id),
@@ -339,36 +330,27 @@ impl Gre {
                 }
             }
             Effect::Damage { amount, target } => {
-                let damage_value = match amount {
-                    Amount::Fixed(v) => v,
-                    Amount::SourcePower => {
-                        if let Some(ref src) = self.current_source_card {
-                            if let CardType::Creature(cr) = &src.card_type {
-                                cr.power + cr.ephemeral_power
-                            } else {
-                                0
-                            }
-                        } else {
-                            0
-                        }
-                    }
-                    Amount::SourceToughness => {
-                        if let Some(ref src) = self.current_source_card {
-                            if let CardType::Creature(cr) = &src.card_type {
-                                cr.toughness + cr.ephemeral_toughness
-                            } else {
-                                0
-                            }
-                        } else {
-                            0
-                        }
-                    }
-                };
+                let damage_value = self.amount_value(&amount);
                 info!("Damage effect: amount={} target={:?}", damage_value, target);
                 match target {
+                    TargetFilter::ExactCardID(cid)
+                        if self
+                            .battlefield_creatures
+                            .get(&cid)
+                            .is_some_and(|c| c.loyalty().is_some()) =>
+                    {
+                        self.emit_damage_dealt(Target::Permanent(cid), damage_value);
+                        self.damage_planeswalker(cid, damage_value);
+                    }
+                    TargetFilter::ExactCardID(cid) if damage_value > 0 && self.consume_shield(cid) => {}
                     TargetFilter::ExactCardID(cid) => {
-                        if let Some(card) = self.battlefield_creatures.get_mut(&cid) {
-                            if let CardType::Creature(ref mut cr) = card.card_type {
+                        if self.battlefield_creatures.get(&cid).is_some_and(|c| {
+                            matches!(c.card_type, CardType::Creature(_))
+                        }) {
+                            self.emit_damage_dealt(Target::Permanent(cid), damage_value);
+                        }
+                        if let Some(card) = self.battlefield_creatures.get_mut(&cid)
+                            && let CardType::Creature(ref mut cr) = card.card_type {
                                 info!(
                                     "  Dealing {} damage to creature '{}' (id={})",
                                     damage_value, card.name, cid
@@ -377,21 +359,48 @@ impl Gre {
                                     info!("  -> Lethal damage, '{}'(id={}) dies", card.name, cid);
                                     let dead_card = card.clone();
                                     self.battlefield_creatures.remove(&cid);
-                                    self.trigger_event(
-                                        GameEvent::CreatureDied(dead_card),
-                                        &mut Vec::new(),
-                                        Player::Us,
-                                    );
+                                    self.leave_battlefield_to_graveyard(dead_card, Player::Us);
                                 }
                             }
-                        }
                     }
                     TargetFilter::Player => {
-                        info!("  Damage to player: {}", damage_value);
-                        self.opponent_lost_life_this_turn = true;
+                        let player = self.resolving_controller.opponent();
+                        self.emit_damage_dealt(Target::Player(player), damage_value);
+                        self.deal_damage_to_player(player, damage_value);
+                    }
+                    TargetFilter::AnyTarget if damage_value <= 0 => {
+                        info!("  No damage to deal, no target chosen");
+                    }
+                    TargetFilter::AnyTarget => {
+                        let candidates = self.any_target_candidates();
+                        let effect = Effect::Damage {
+                            amount: Amount::Fixed(damage_value),
+                            target: TargetFilter::AnyTarget,
+                        };
+                        match self.choose_target(&effect, &candidates) {
+                            Some(Target::Player(player)) => {
+                                self.emit_damage_dealt(Target::Player(player), damage_value);
+                                self.deal_damage_to_player(player, damage_value)
+                            }
+                            Some(Target::Permanent(cid)) => self.execute(Effect::Damage {
+                                amount: Amount::Fixed(damage_value),
+                                target: TargetFilter::ExactCardID(cid),
+                            }),
+                            None => info!("  No legal target for damage"),
+                        }
                     }
-                    _ => {
-                        info!("  Damage effect with unsupported target: {:?}", target);
+                    filter => {
+                        let effect = Effect::Damage {
+                            amount: Amount::Fixed(damage_value),
+                            target: filter.clone(),
+                        };
+                        match self.resolve_permanent_target(&filter, &effect) {
+                            Some(cid) => self.execute(Effect::Damage {
+                                amount: Amount::Fixed(damage_value),
+                                target: TargetFilter::ExactCardID(cid),
+                            }),
+                            None => info!("  No legal target for damage ({:?})", filter),
+                        }
                     }
                 }
             }
@@ -400,37 +409,25 @@ impl Gre {
                     "DamageByTargetPower effect: source={:?}, target={:?}",
                     source, target
                 );
+                let effect = Effect::DamageByTargetPower {
+                    source: source.clone(),
+                    target: target.clone(),
+                };
                 let mut dmg_amount = 0;
-                if let TargetFilter::ExactCardID(src_id) = source {
-                    if let Some(src_card) = self.battlefield_creatures.get(&src_id) {
-                        if let CardType::Creature(cr) = &src_card.card_type {
+                if let Some(src_id) = self.resolve_permanent_target(&source, &effect)
+                    && let Some(src_card) = self.battlefield_creatures.get(&src_id)
+                        && let CardType::Creature(cr) = &src_card.card_type {
                             dmg_amount = cr.power + cr.ephemeral_power;
                             info!(
                                 "  Source creature '{}' (id={}) power={} -> damage {}",
                                 src_card.name, src_id, cr.power, dmg_amount
                             );
                         }
-                    }
-                }
-                if dmg_amount == 0 {
-                    if let Some(src_card) = self
-                        .battlefield_creatures
-                        .values()
-                        .find(|c| matches!(c.card_type, CardType::Creature(_)))
-                    {
-                        if let CardType::Creature(cr) = &src_card.card_type {
-                            dmg_amount = cr.power + cr.ephemeral_power;
-                            info!(
-                                "  Using '{}' as source, damage={}",
-                                src_card.name, dmg_amount
-                            );
-                        }
-                    }
-                }
                 if dmg_amount > 0 {
-                    if let TargetFilter::ExactCardID(tid) = target {
-                        if let Some(tgt_card) = self.battlefield_creatures.get_mut(&tid) {
-                            if let CardType::Creature(ref mut tgt_cr) = tgt_card.card_type {
+                    if let Some(tid) = self.resolve_permanent_target(&target, &effect) {
+                        self.emit_damage_dealt(Target::Permanent(tid), dmg_amount);
+                        if let Some(tgt_card) = self.battlefield_creatures.get_mut(&tid)
+                            && let CardType::Creature(ref mut tgt_cr) = tgt_card.card_type {
                                 info!(
                                     "  Dealing {} damage to '{}' (id={})",
                                     dmg_amount, tgt_card.name, tid
@@ -439,14 +436,9 @@ impl Gre {
                                     info!("  -> Lethal damage, target dies");
                                     let dead_card = tgt_card.clone();
                                     self.battlefield_creatures.remove(&tid);
-                                    self.trigger_event(
-                                        GameEvent::CreatureDied(dead_card),
-                                        &mut Vec::new(),
-                                        Player::Us,
-                                    );
+                                    self.leave_battlefield_to_graveyard(dead_card, Player::Us);
                                 }
                             }
-                        }
                     } else {
                         info!("  No target creature specified, skipping damage");
                     }
@@ -456,8 +448,11 @@ impl Gre {
             }
             Effect::TapTarget { target } => {
                 info!("TapTarget effect: target={:?}", target);
-                match target {
-                    TargetFilter::ExactCardID(tid) => {
+                let effect = Effect::TapTarget {
+                    target: target.clone(),
+                };
+                match self.resolve_permanent_target(&target, &effect) {
+                    Some(tid) => {
                         if let Some(card) = self.battlefield_creatures.get_mut(&tid) {
                             info!("  Tapping card '{}' (id={})", card.name, tid);
                             for abil in card.activated_abilities.iter_mut() {
@@ -465,33 +460,8 @@ impl Gre {
                             }
                         }
                     }
-                    TargetFilter::SelfCard => {
-                        if let Some(ref src) = self.current_source_card {
-                            let sid = src.card_id;
-                            if let Some(card) = self.battlefield_creatures.get_mut(&sid) {
-                                info!("  Tapping source card '{}' (id={})", card.name, sid);
-                                for abil in card.activated_abilities.iter_mut() {
-                                    abil.activated_this_turn = true;
-                                }
-                            }
-                        }
-                    }
-                    TargetFilter::ControllerCreature => {
-                        if let Some((_cid, card)) = self
-                            .battlefield_creatures
-                            .iter_mut()
-                            .find(|(_, c)| matches!(c.card_type, CardType::Creature(_)))
-                        {
-                            info!("  Tapping card '{}' (id={})", card.name, card.card_id);
-                            for abil in card.activated_abilities.iter_mut() {
-                                abil.activated_this_turn = true;
-                            }
-                        } else {
-                            warn!("  TapTarget: no ControllerCreature found to tap");
-                        }
-                    }
-                    _ => {
-                        warn!("  TapTarget: unsupported target {:?}", target);
+                    None => {
+                        warn!("  TapTarget: no legal target for {:?}", target);
                     }
                 }
             }
@@ -500,20 +470,22 @@ impl Gre {
                     "BuffAllByMaxPower: filter={:?}, duration={:?}",
                     filter, duration
                 );
-                let mut max_power_val = 0;
-                if let TargetFilter::ControllerCreature = filter {
-                    for card in self.battlefield_creatures.values() {
-                        if let CardType::Creature(cr) = &card.card_type {
-                            max_power_val = max_power_val.max(cr.power + cr.ephemeral_power);
-                        }
-                    }
-                } else {
-                    warn!("  BuffAllByMaxPower: unsupported filter {:?}", filter);
-                    return;
-                }
+                let ids = self.permanents_matching(&filter, self.resolving_controller);
+                let max_power_val = ids
+                    .iter()
+                    .filter_map(|id| self.battlefield_creatures.get(id))
+                    .filter_map(|card| match &card.card_type {
+                        CardType::Creature(cr) => Some(cr.power + cr.ephemeral_power),
+                        _ => None,
+                    })
+                    .max()
+                    .unwrap_or(0);
                 info!("  Max power among filtered creatures = {}", max_power_val);
                 let mut delayed_reverts: Vec<Effect> = Vec::new();
-                for (&cid, card) in self.battlefield_creatures.iter_mut() {
+                for cid in ids {
+                    let Some(card) = self.battlefield_creatures.get_mut(&cid) else {
+                        continue;
+                    };
                     if let CardType::Creature(ref mut cr) = card.card_type {
                         match duration {
                             Duration::EndOfTurn => {
@@ -559,103 +531,39 @@ impl Gre {
                     "AddCounterAll: counter={:?}, amount={:?}, filter={:?}",
                     counter, amount, filter
                 );
-                let count_value = match amount {
-                    Amount::Fixed(v) => v,
-                    Amount::SourcePower => {
-                        if let Some(ref src) = self.current_source_card {
-                            if let CardType::Creature(cr) = &src.card_type {
-                                cr.power + cr.ephemeral_power
-                            } else {
-                                0
-                            }
-                        } else {
-                            0
-                        }
-                    }
-                    Amount::SourceToughness => {
-                        if let Some(ref src) = self.current_source_card {
-                            if let CardType::Creature(cr) = &src.card_type {
-                                cr.toughness + cr.ephemeral_toughness
-                            } else {
-                                0
-                            }
-                        } else {
-                            0
-                        }
-                    }
-                };
-                if count_value == 0 {
+                let count_value = self.amount_value(&amount);
+                if count_value <= 0 {
                     info!("  AddCounterAll: amount resolved to 0, nothing to do");
                     return;
                 }
-                match filter {
-                    TargetFilter::ControllerCreature => {
-                        let mut to_trigger: Vec<GameEvent> = Vec::new();
-                        for (&cid, card) in self.battlefield_creatures.iter_mut() {
-                            if let CardType::Creature(ref mut cr) = card.card_type {
-                                if counter == CounterType::PlusOnePlusOne {
-                                    cr.power += count_value;
-                                    cr.toughness += count_value;
-                                    info!(
-                                        "    '{}' kap {} db +1/+1 countert => most base {}/{}",
-                                        card.name, count_value, cr.power, cr.toughness
-                                    );
-                                } else if counter == CounterType::Loyalty {
-                                    if let CardType::Planeswalker = card.card_type {
-                                        info!(
-                                            "    '{}' gets {} loyalty counter(s)",
-                                            card.name, count_value
-                                        );
-                                    }
-                                }
-                                to_trigger.push(GameEvent::CounterAdded(cid, count_value as u32));
-                            }
-                        }
-                        // iter_mut után: már hívhatunk nyugodtan &mut self metódusokat.
-                        let mut empty_bf: Vec<Card> = Vec::new();
-                        for ev in to_trigger {
-                            self.trigger_event(ev, &mut empty_bf, Player::Us);
-                        }
-                    }
-                    _ => {
-                        warn!("AddCounterAll: unsupported filter {:?}", filter);
-                    }
+                for cid in self.permanents_matching(&filter, self.resolving_controller) {
+                    self.add_counters(cid, counter.clone(), count_value as u32);
                 }
             }
             Effect::Destroy { target } => {
                 info!("Destroy effect: target={:?}", target);
                 match target {
+                    TargetFilter::ExactCardID(cid) if self.consume_shield(cid) => {}
                     TargetFilter::ExactCardID(cid) => {
                         if let Some(card) = self.battlefield_creatures.remove(&cid) {
                             info!(
                                 "  '{}' (id={}) destroyed and removed from battlefield",
                                 card.name, cid
                             );
-                            if matches!(card.card_type, CardType::Creature(_)) {
-                                self.trigger_event(
-                                    GameEvent::CreatureDied(card.clone()),
-                                    &mut Vec::new(),
-                                    Player::Us,
-                                );
-                            }
+                            self.leave_battlefield_to_graveyard(card, self.resolving_controller);
                         }
                     }
-                    TargetFilter::Artifact => {
-                        if let Some((&aid, _)) = self
-                            .battlefield_creatures
-                            .iter()
-                            .find(|(_, c)| matches!(c.card_type, CardType::Artifact))
-                        {
-                            if let Some(card) = self.battlefield_creatures.remove(&aid) {
-                                info!("  Artifact '{}' (id={}) destroyed", card.name, aid);
-                            }
-                        } else {
-                            info!("  No artifact found to destroy");
+                    filter => {
+                        let effect = Effect::Destroy {
+                            target: filter.clone(),
+                        };
+                        match self.resolve_permanent_target(&filter, &effect) {
+                            Some(cid) => self.execute(Effect::Destroy {
+                                target: TargetFilter::ExactCardID(cid),
+                            }),
+                            None => info!("  No permanent matching {:?} found to destroy", filter),
                         }
                     }
-                    _ => {
-                        warn!("Destroy: unsupported target filter {:?}", target);
-                    }
                 }
             }
             Effect::Exile { target } => {
@@ -666,14 +574,36 @@ impl Gre {
                             info!("  '{}' (id={}) exiled from battlefield", card.name, cid);
                             self.last_exiled_card_was_creature =
                                 matches!(card.card_type, CardType::Creature(_));
+                            self.trigger_event(
+                                GameEvent::ZoneChange {
+                                    card,
+                                    from: Some(Zone::Battlefield),
+                                    to: Zone::Exile,
+                                },
+                                &mut Vec::new(),
+                                self.resolving_controller,
+                            );
                         }
                     }
                     TargetFilter::CardInGraveyard => {
-                        info!("  Exiling a card from graveyard (assuming creature card)");
-                        self.last_exiled_card_was_creature = true;
+                        if self
+                            .exile_from_graveyard(self.resolving_controller, &target)
+                            .is_none()
+                        {
+                            info!("  No card in any graveyard to exile");
+                            self.last_exiled_card_was_creature = false;
+                        }
                     }
-                    _ => {
-                        warn!("Exile: unsupported target filter {:?}", target);
+                    filter => {
+                        let effect = Effect::Exile {
+                            target: filter.clone(),
+                        };
+                        match self.resolve_permanent_target(&filter, &effect) {
+                            Some(cid) => self.execute(Effect::Exile {
+                                target: TargetFilter::ExactCardID(cid),
+                            }),
+                            None => warn!("Exile: no legal target for {:?}", filter),
+                        }
                     }
                 }
             }
@@ -683,25 +613,8 @@ impl Gre {
                 effect_if_false,
             } => {
                 info!("Conditional effect: condition={:?}", condition);
-                let cond_met = match condition {
-                    Condition::OpponentLostLifeThisTurn => self.opponent_lost_life_this_turn,
-                    Condition::FirstTimeThisTurn => true,
-                    Condition::SpellWasNonCreature => false,
-                    Condition::Tap => false,
-                    Condition::SacrificeSelf => false,
-                    Condition::Always => true,
-                    Condition::SpellWasKicked => false,
-                    Condition::HasCreaturePower4OrMore => {
-                        self.battlefield_creatures.values().any(|c| {
-                            if let CardType::Creature(cr) = &c.card_type {
-                                cr.power + cr.ephemeral_power >= 4
-                            } else {
-                                false
-                            }
-                        })
-                    }
-                    Condition::ExiledCardWasCreature => self.last_exiled_card_was_creature,
-                };
+                let cond_met =
+                    self.evaluate_condition(&condition, &self.resolving_condition_context());
                 if cond_met {
                     info!("  Condition met, executing effect_if_true");
                     self.handle_effect(*effect_if_true);
@@ -713,9 +626,11 @@ impl Gre {
                 }
             }
             Effect::DrawCardsCounted => {
-                info!("DrawCardsCounted effect: resolving as draw action (no direct state change)");
-                // In this engine, actual card draw is not simulated beyond logging
-                info!("  (DrawCardsCounted resolved)");
+                // Annyi lap, ahány counter a legutóbbi CounterAdded eseménnyel került fel
+                let count = self.last_counters_added;
+                info!("DrawCardsCounted effect: drawing {} card(s)", count);
+                let player = self.resolving_controller;
+                self.draw_cards(player, count);
             }
             Effect::TargetedEffects { sub_effects } => {
                 info!("TargetedEffects: sub_effects len={}", sub_effects.len());
@@ -748,6 +663,15 @@ impl Gre {
                             | Effect::ChooseSome { .. } => {
                                 self.handle_effect(subeff);
                             }
+                            // A forrásra vonatkozó effektnek nem kell célpont
+                            e if !e.target_filters().is_empty()
+                                && e
+                                    .target_filters()
+                                    .iter()
+                                    .all(|f| **f == TargetFilter::SelfCard) =>
+                            {
+                                self.handle_effect(e);
+                            }
                             _ => {
                                 warn!("    Skipping sub_effect {:?} due to missing target", subeff);
                             }
@@ -776,13 +700,20 @@ impl Gre {
                     "Offspring effect: cost={}. Megnézzük a current_source_card-ot...",
                     cost
                 );
-                if let Some(ref src) = self.current_source_card {
+                if !self.resolving_costs.paid(OptionalCostKind::Offspring) {
+                    info!("  Az Offspring költséget nem fizették ki, nincs klón.");
+                } else if let Some(ref src) = self.current_source_card {
                     debug!(
                         "  source_card='{}' (id={}). Készítünk belőle klónt tokenként.",
                         src.name, src.card_id
                     );
-                    let cloned =
+                    let mut cloned =
                         Card::clone_card(src, Some(1), Some(1), Some(CardTypeFlags::TOKEN));
+                    // Új ID-t kap; a klón belépése már nem vált ki újabb Offspringet
+                    cloned.card_id = 0;
+                    self.resolving_costs
+                        .optional
+                        .retain(|k| *k != OptionalCostKind::Offspring);
                     Gre::create_clone_card(self, cloned);
                     info!("  Offspring klón token sikeresen létrehozva.");
                 } else {
@@ -801,32 +732,26 @@ impl Gre {
 
             // --- "Kettős" opció effektek (pl. Offspring vagy no-op)
             Effect::ChooseSome { choose, options } => {
-                // Itt a prototípus-kódban mindig az 1. választást hívnánk,
-                // de a valós UI-ban a user dönti el.
-                // Hogy illusztráljuk, hívjuk az 'nth' effectet:
                 if choose == 0 || options.is_empty() {
                     info!("ChooseSome => nincs választott effect (choose=0).");
                 } else {
-                    // tegyük fel, fixen az utolsó választást hívjuk:
-                    let idx = choose.min(options.len());
-                    let chosen = options[idx - 1].clone();
-                    info!(
-                        "ChooseSome => a(z) {}. effectet hajtjuk végre: {:?}",
-                        idx, chosen
-                    );
-                    self.handle_effect(chosen);
+                    let modes: Vec<Vec<Effect>> = options.iter().map(|e| vec![e.clone()]).collect();
+                    for idx in self.choose_modes(&modes, choose, choose) {
+                        info!("ChooseSome => a(z) {}. mód: {:?}", idx + 1, options[idx]);
+                        self.handle_effect(options[idx].clone());
+                    }
                 }
             }
 
             // --- A többi effect pl. "PreventLifeGain"
             Effect::PreventLifeGain { player, duration } => {
-                let flag = match player {
-                    PlayerSelector::Controller => &mut self.prevent_life_gain_us,
-                    PlayerSelector::Opponent => &mut self.prevent_life_gain_opponent,
-                    PlayerSelector::AnyPlayer => {
-                        info!("PreventLifeGain(AnyPlayer) -> nem kezelt");
-                        return;
-                    }
+                if player == PlayerSelector::AnyPlayer {
+                    info!("PreventLifeGain(AnyPlayer) -> nem kezelt");
+                    return;
+                }
+                let flag = match self.select_player(&player) {
+                    Player::Us => &mut self.prevent_life_gain_us,
+                    Player::Opponent => &mut self.prevent_life_gain_opponent,
                 };
                 match duration {
                     Duration::Permanent => {
@@ -848,23 +773,80 @@ impl Gre {
                 }
             }
 
-            // Például life gain
             Effect::GainLife { amount, player } => {
-                let prevented = match player {
-                    PlayerSelector::Controller => self.prevent_life_gain_us,
-                    PlayerSelector::Opponent => self.prevent_life_gain_opponent,
-                    PlayerSelector::AnyPlayer => false,
+                let player = self.select_player(&player);
+                info!("{:?} GAIN LIFE: +{}", player, amount);
+                self.gain_life(player, amount);
+            }
+            Effect::DrawCards { count, player } => {
+                let player = self.select_player(&player);
+                self.draw_cards(player, count);
+            }
+            Effect::ExileTop { count, player } => {
+                let player = self.select_player(&player);
+                info!("ExileTop effect: {:?} exiles the top {} card(s)", player, count);
+                self.exile_top(player, count);
+            }
+            Effect::Scry { count, player } => {
+                let player = self.select_player(&player);
+                info!("Scry effect: {:?} scries {}", player, count);
+                self.scry_library(player, count);
+            }
+            Effect::ExileThenPlayFromExile {
+                count,
+                player,
+                duration,
+            } => {
+                let owner = self.select_player(&player);
+                let controller = self.resolving_controller;
+                info!(
+                    "ExileThenPlayFromExile effect: {} card(s) of {:?}, playable by {:?} ({:?})",
+                    count, owner, controller, duration
+                );
+                self.exile_then_play(owner, count, controller, &duration);
+            }
+            Effect::GrantAbility {
+                ability,
+                duration,
+                target,
+            } => {
+                info!(
+                    "GrantAbility effect: ability={:?}, duration={:?}, target={:?}",
+                    ability, duration, target
+                );
+                let effect = Effect::GrantAbility {
+                    ability,
+                    duration: duration.clone(),
+                    target: target.clone(),
                 };
-                if prevented {
-                    info!(
-                        "Life gain {} for {:?} meghiúsul (PreventLifeGain).",
-                        amount, player
-                    );
-                } else {
-                    info!(
-                        "{:?} GAIN LIFE: +{} (state-ben még nem frissítjük).",
-                        player, amount
-                    );
+                let id = self.resolve_permanent_target(&target, &effect);
+                let granted = id
+                    .and_then(|id| self.battlefield_creatures.get_mut(&id))
+                    .and_then(|c| match c.card_type {
+                        CardType::Creature(ref mut cr) => {
+                            // Ha már megvolt, a kör végén sem vesszük el
+                            let added = !cr.abilities.contains(&ability);
+                            if added {
+                                cr.abilities.push(ability);
+                            }
+                            info!("  -> '{}' (id={}) gains {:?}", c.name, c.card_id, ability);
+                            Some((c.card_id, added))
+                        }
+                        _ => None,
+                    });
+                match (granted, duration) {
+                    (Some((cid, true)), Duration::EndOfTurn) => {
+                        self.schedule_delayed(
+                            Effect::RemoveAbility {
+                                ability,
+                                target: TargetFilter::ExactCardID(cid),
+                            },
+                            GamePhase::End,
+                            vec![],
+                        );
+                    }
+                    (Some(_), _) => {}
+                    (None, _) => warn!("  GrantAbility: nincs legális célpont, átugorjuk."),
                 }
             }
 
@@ -876,6 +858,7 @@ impl Gre {
     }
 }
 
+#[allow(clippy::only_used_in_recursion)]
 pub fn replace_targeted_filter_with_exact(gre: &Gre, effect: Effect, tcard: &Card) -> Effect {
     debug!(
         "replace_targeted_filter_with_exact() - Start: effect={:?}, tcard='{}'(id={})",
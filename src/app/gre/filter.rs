@@ -0,0 +1,109 @@
+// src/app/gre/filter.rs
+
+//! A `TargetFilter` kiértékelése a játékállapoton. Minden effekt, költség és
+//! célpont-ellenőrzés innen kérdezi le, mely lapok felelnek meg egy szűrőnek;
+//! a "you control" / "an opponent controls" a feloldás alatt álló képesség
+//! irányítójához képest értendő.
+
+use tracing::debug;
+
+use crate::app::card_attribute::{Effect, FilterContext, TargetFilter, Zone};
+use crate::app::card_library::Card;
+use crate::app::game_state::Player;
+use crate::app::gre::{Gre, Target};
+
+impl Gre {
+    /// Kiértékelési környezet a `controller` szemszögéből; a forrás a feloldás alatt álló lap.
+    pub fn filter_context(&self, controller: Player, zone: Zone) -> FilterContext {
+        FilterContext::new(
+            controller,
+            self.current_source_card.as_ref().map(|c| c.card_id),
+            zone,
+        )
+    }
+
+    /// Illeszkedik-e a battlefielden lévő permanens a szűrőre.
+    pub fn permanent_matches(
+        &self,
+        card_id: u64,
+        filter: &TargetFilter,
+        controller: Player,
+    ) -> bool {
+        let ctx = self.filter_context(controller, Zone::Battlefield);
+        self.battlefield_creatures
+            .get(&card_id)
+            .is_some_and(|c| filter.matches(c, &ctx))
+    }
+
+    /// A szűrőnek megfelelő permanensek, id szerint növekvő sorrendben.
+    pub fn permanents_matching(&self, filter: &TargetFilter, controller: Player) -> Vec<u64> {
+        let ctx = self.filter_context(controller, Zone::Battlefield);
+        let mut ids: Vec<u64> = self
+            .battlefield_creatures
+            .values()
+            .filter(|c| filter.matches(c, &ctx))
+            .map(|c| c.card_id)
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    /// A szűrőnek megfelelő lapok az `owner` egy nem-battlefield zónájában
+    /// (a zóna sorrendjében; a stacket nem követjük).
+    pub fn zone_cards_matching(
+        &self,
+        owner: Player,
+        zone: Zone,
+        filter: &TargetFilter,
+        controller: Player,
+    ) -> Vec<&Card> {
+        let ctx = self.filter_context(controller, zone);
+        let state = self.player(owner);
+        let cards: &[Card] = match zone {
+            Zone::Graveyard => &state.graveyard,
+            Zone::Exile => &state.exile,
+            Zone::Hand => &state.hand,
+            Zone::Library => &state.library,
+            Zone::Battlefield | Zone::Stack => &[],
+        };
+        cards.iter().filter(|c| filter.matches(c, &ctx)).collect()
+    }
+
+    /// Trigger-szűrő: illeszkedik-e az esemény alanya a figyelő permanens szemszögéből
+    /// (`SelfCard` = maga a figyelő, `ControllerCreature` = a figyelő irányítójának lénye).
+    pub fn trigger_subject_matches(
+        &self,
+        filter: &TargetFilter,
+        listener: &Card,
+        subject: &Card,
+        zone: Zone,
+    ) -> bool {
+        let ctx = FilterContext::new(listener.controller, Some(listener.card_id), zone);
+        filter.matches(subject, &ctx)
+    }
+
+    /// Egycélpontos effekt permanens-célpontja: pontos id, a forrás lap, vagy a
+    /// szűrőnek megfelelő jelöltek közül a `DecisionProvider` választása.
+    pub fn resolve_permanent_target(
+        &mut self,
+        filter: &TargetFilter,
+        effect: &Effect,
+    ) -> Option<u64> {
+        let id = match filter {
+            TargetFilter::ExactCardID(id) => Some(*id),
+            TargetFilter::SelfCard => self.current_source_card.as_ref().map(|c| c.card_id),
+            filter => {
+                let controller = self.resolving_controller;
+                let candidates = self
+                    .target_candidates(filter, controller)
+                    .unwrap_or_default();
+                match self.choose_target(effect, &candidates) {
+                    Some(Target::Permanent(id)) => Some(id),
+                    _ => None,
+                }
+            }
+        };
+        debug!("resolve_permanent_target() -> {:?} => {:?}", filter, id);
+        id.filter(|id| self.battlefield_creatures.contains_key(id))
+    }
+}
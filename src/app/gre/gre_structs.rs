@@ -3,8 +3,19 @@
 use crate::app::card_attribute::{Condition, Effect};
 use crate::app::card_library::ManaCost;
 use crate::app::game_state::GamePhase;
+use crate::app::gre::costs::Cost;
 use std::fmt;
 
+/// Mikor aktiválható a képesség
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ActivationTiming {
+    /// Bármikor, amikor prioritásunk van
+    #[default]
+    Instant,
+    /// "Activate only as a sorcery" (a loyalty képességek is)
+    Sorcery,
+}
+
 /// Aktivált képesség struktúrája
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ActivatedAbility {
@@ -13,6 +24,30 @@ pub struct ActivatedAbility {
     pub effect: Effect,
     pub activated_this_turn: bool,
     pub loyalty_change: i32,
+    /// Nem-mana (vagy plusz) költségek, aktiváláskor fizetjük (pl. száműzés temetőből)
+    pub additional_costs: Vec<Cost>,
+    pub timing: ActivationTiming,
+    /// "Activate only once each turn" (képességenként, nem permanensenként, mint a planeswalkernél)
+    pub once_each_turn: bool,
+}
+
+impl ActivatedAbility {
+    /// Mana képesség: manát ad, nincs célpontja és nem loyalty képesség;
+    /// a stack használata nélkül, azonnal oldódik fel.
+    pub fn is_mana_ability(&self) -> bool {
+        fn adds_mana(effect: &Effect) -> bool {
+            match effect {
+                Effect::AddMana { .. } => true,
+                Effect::ChooseSome { options, .. } => {
+                    !options.is_empty() && options.iter().all(adds_mana)
+                }
+                _ => false,
+            }
+        }
+        self.loyalty_change == 0
+            && self.timing == ActivationTiming::Instant
+            && adds_mana(&self.effect)
+    }
 }
 
 /// Késleltetett effekt
@@ -24,11 +59,14 @@ pub struct DelayedEffect {
     pub depends_on: Vec<usize>,
 }
 
+/// Replacement függvény: `Some(új effektek)`, ha lecseréli az eredetit
+pub type ReplacementFn = Box<dyn Fn(&Effect) -> Option<Vec<Effect>>>;
+
 /// ReplacementEffect a replacement effectekhez.
 /// Itt a beépített Debug és Clone implementációk speciálisak a closure miatt.
 pub struct ReplacementEffect {
     pub priority: u8,
-    pub f: Box<dyn Fn(&Effect) -> Option<Vec<Effect>>>,
+    pub f: ReplacementFn,
 }
 
 // Debug: ne próbáljuk a closure-t formázni
@@ -5,23 +5,47 @@ use std::collections::{BinaryHeap, HashMap, HashSet};
 use tracing::{debug, info, warn};
 
 use crate::app::card_attribute::{
-    Condition, CreatureType, Duration, Effect, PlayerSelector, TargetFilter, Trigger,
+    CounterType, Effect, EnchantmentType, Subtype,
+    TargetFilter, Trigger, Zone,
 };
 use crate::app::card_library::CardTypeFlags;
 use crate::app::card_library::{Card, CardType, Creature, ManaCost};
-use crate::app::game_state::{GameEvent, GamePhase, Player};
+use crate::app::game_state::{EventKind, GameEvent, GamePhase, Player};
 
 // A többi saját mod
+pub mod costs;
+pub mod activation;
+pub mod condition;
+pub mod counters;
+pub mod decision;
 pub mod effect_resolution;
+pub mod filter;
 pub mod gre_structs;
+pub mod modal;
+pub mod permissions;
+pub mod player_state;
 pub mod stack;
 pub mod trigger;
+pub mod zones;
 
 // Publikus újra-exportálás, hogy kívülről elérhető legyen
 use crate::app::gre::effect_resolution::replace_targeted_filter_with_exact;
-pub use gre_structs::ActivatedAbility;
+pub use condition::ConditionContext;
+pub use costs::{Cost, CostError, CostReduction, OptionalCost, OptionalCostKind, PaidCosts};
+pub use counters::ProliferateTarget;
+pub use decision::{
+    Decision, DecisionProvider, HeuristicDecisions, ScriptedDecisions, SearchDecisions, Target,
+    MAX_SUBSET_SEARCH,
+};
+pub use gre_structs::{ActivatedAbility, ActivationTiming};
+pub use modal::ChosenMode;
+pub use permissions::{CastPermission, CastZone};
+pub use player_state::{ManaPool, PlayerState};
 pub use stack::{PriorityEntry, StackEntry};
 
+/// Folyamatos effekt: minden végrehajtandó effektet módosíthat
+pub type ContinuousEffectFn = Box<dyn Fn(&mut Effect)>;
+
 /// Ez lesz a "Game Rules Engine" (GRE) maga
 pub struct Gre {
     /// A stack
@@ -37,11 +61,9 @@ pub struct Gre {
     pub passes: u8,
 
     pub replacement_effects: Vec<ReplacementEffect>,
-    pub continuous_effects: Vec<Box<dyn Fn(&mut Effect)>>,
+    pub continuous_effects: Vec<ContinuousEffectFn>,
 
-    /// Életvesztés jelzései
-    pub opponent_lost_life_this_turn: bool,
-    pub us_lost_life_this_turn: bool,
+    /// Életnyerés tiltása (PreventLifeGain)
     pub prevent_life_gain_opponent: bool,
     pub prevent_life_gain_us: bool,
 
@@ -52,6 +74,33 @@ pub struct Gre {
 
     pub current_source_card: Option<Card>,
     pub last_exiled_card_was_creature: bool,
+
+    /// Az aktuális fázis és az aktív játékos (sorcery-speed ellenőrzéshez)
+    pub current_phase: GamePhase,
+    pub active_player: Player,
+
+    /// Játékosonkénti állapot (élet, kéz, mana pool, counterek, körönkénti számlálók)
+    pub players: HashMap<Player, PlayerState>,
+    /// A feloldás alatt álló stack-elem irányítója; ehhez képest értjük a `PlayerSelector`-t
+    pub resolving_controller: Player,
+    /// A szabályok szerinti választások forrása (alapból heurisztika)
+    pub decisions: Box<dyn DecisionProvider>,
+    /// A meghozott döntések sorrendben (visszajátszáshoz)
+    pub decision_log: Vec<Decision>,
+    /// Folyamatos költségcsökkentések
+    pub cost_reductions: Vec<CostReduction>,
+    /// A feloldás alatt álló stack-elem kifizetett költségei (kicker, feláldozás, ...)
+    pub resolving_costs: PaidCosts,
+    /// Kézen kívüli kijátszási engedélyek (száműzetés, temető)
+    pub cast_permissions: Vec<CastPermission>,
+    /// A legutóbbi CounterAdded esemény darabszáma (DrawCardsCounted)
+    pub last_counters_added: u32,
+    /// Laponként és eseményfajtánként az ebben a körben történt események száma
+    /// (`FirstTimeThisTurn`)
+    pub events_this_turn: HashMap<(u64, EventKind), u32>,
+    /// Forrásonként és eseményfajtánként: tett-e már triggert a stackre ebben a körben
+    /// (`OnceEachTurn`)
+    pub triggered_this_turn: HashSet<(u64, EventKind)>,
 }
 
 impl Gre {
@@ -67,18 +116,38 @@ impl Gre {
             passes: 0,
             replacement_effects: Vec::new(),
             continuous_effects: Vec::new(),
-            opponent_lost_life_this_turn: false,
-            us_lost_life_this_turn: false,
             prevent_life_gain_opponent: false,
             prevent_life_gain_us: false,
             battlefield_creatures: HashMap::new(),
             death_triggers_this_turn: Vec::new(),
             current_source_card: None,
             last_exiled_card_was_creature: false,
+            current_phase: GamePhase::Beginning,
+            active_player: starting_player,
+            players: [Player::Us, Player::Opponent]
+                .into_iter()
+                .map(|p| (p, PlayerState::default()))
+                .collect(),
+            resolving_controller: Player::Us,
+            decisions: Box::new(HeuristicDecisions),
+            decision_log: Vec::new(),
+            cost_reductions: Vec::new(),
+            resolving_costs: PaidCosts::default(),
+            cast_permissions: Vec::new(),
+            last_counters_added: 0,
+            events_this_turn: HashMap::new(),
+            triggered_this_turn: HashSet::new(),
         }
     }
 }
 
+impl Gre {
+    pub fn with_decisions(mut self, decisions: impl DecisionProvider + 'static) -> Self {
+        self.decisions = Box::new(decisions);
+        self
+    }
+}
+
 impl Default for Gre {
     fn default() -> Self {
         Gre::new(Player::Us)
@@ -88,11 +157,15 @@ impl Default for Gre {
 // Metódusok, amiket itt hagyunk (például):
 impl Gre {
     pub fn on_turn_end(&mut self) {
-        info!("on_turn_end() -> turn is ending, reset life-lost flags & death_triggers.");
-        self.opponent_lost_life_this_turn = false;
-        self.us_lost_life_this_turn = false;
+        info!("on_turn_end() -> turn is ending, reset per-turn player counts & death_triggers.");
+        for state in self.players.values_mut() {
+            state.reset_turn();
+        }
         self.death_triggers_this_turn.clear();
+        self.events_this_turn.clear();
+        self.triggered_this_turn.clear();
         self.last_exiled_card_was_creature = false;
+        self.expire_cast_permissions();
         // ...
         for (_id, card) in self.battlefield_creatures.iter_mut() {
             for abil in card.activated_abilities.iter_mut() {
@@ -101,14 +174,23 @@ impl Gre {
         }
     }
 
-    pub fn can_activate(&self, ability: &crate::app::gre::ActivatedAbility) -> bool {
-        !ability.activated_this_turn
-            && match ability.condition {
-                Condition::OpponentLostLifeThisTurn => self.opponent_lost_life_this_turn,
-                Condition::FirstTimeThisTurn => !ability.activated_this_turn,
-                Condition::Always => true,
-                _ => false,
-            }
+    pub fn set_phase(&mut self, phase: GamePhase) {
+        debug!("set_phase() -> {:?}", phase);
+        self.current_phase = phase;
+        // Fázisváltáskor a mana pool kiürül
+        for state in self.players.values_mut() {
+            state.mana_pool = ManaPool::default();
+        }
+    }
+
+    /// Sorcery-speed: a saját main fázisunkban, üres stack mellett.
+    pub fn is_sorcery_speed(&self, controller: Player) -> bool {
+        self.active_player == controller
+            && matches!(
+                self.current_phase,
+                GamePhase::PreCombatMain | GamePhase::PostCombatMain
+            )
+            && self.stack.is_empty()
     }
 
     pub fn reset_priority(&mut self) {
@@ -116,13 +198,10 @@ impl Gre {
         self.passes = 0;
     }
 
+    /// Minden bejegyzés ugyanazzal a prioritással kerül a stackre, így csak a
+    /// sorrend (LIFO) dönt: a később rárakott spell vagy trigger oldódik fel előbb.
     pub fn push_to_stack(&mut self, entry: StackEntry) {
-        let prio = if matches!(entry, StackEntry::ActivatedAbility { .. }) {
-            3
-        } else {
-            1
-        };
-        self.push(entry, prio);
+        self.push(entry, 1);
         self.reset_priority();
     }
 
@@ -140,54 +219,67 @@ impl Gre {
         });
     }
 
-    pub fn cast_spell_with_target(&mut self, card: Card, controller: Player, target: Card) {
-        // Először mentsük ki a card_id–t (és ha kell, a nevet is).
-        let target_id = target.card_id;
-        let target_name = target.name.clone(); // ha a nevét is ki akarod írni
-
-        info!(
-            "{:?} casts '{}', target='{}'",
-            controller, card.name, target_name
-        );
-
-        // Ezután konstruáljuk a StackEntry::Spell‐t, ezzel "belemovoljuk" a 'target'‐et.
-        let entry = StackEntry::Spell {
-            card,
-            controller,
-            target_creature: Some(target),
-        };
-        self.push(entry, 0);
-        self.reset_priority();
+    /// Loyalty counterek hozzáadása/levétele egy planeswalkerről.
+    pub fn change_loyalty(&mut self, card_id: u64, delta: i32) {
+        if delta > 0 {
+            self.add_counters(card_id, CounterType::Loyalty, delta as u32);
+        } else if let Some(card) = self.battlefield_creatures.get_mut(&card_id) {
+            card.remove_counters(&CounterType::Loyalty, delta.unsigned_abs());
+        }
+        if let Some(loyalty) = self.battlefield_creatures.get(&card_id).and_then(|c| c.loyalty()) {
+            info!("    id={} loyalty is now {}", card_id, loyalty);
+        }
+    }
 
-        // Végül meghívjuk a “Targeted” eseményt a kimentett 'target_id' alapján
-        self.trigger_event(GameEvent::Targeted(target_id), &mut Vec::new(), controller);
+    /// Planeswalkernek okozott sebzés: ennyi loyalty counter lekerül róla.
+    pub fn damage_planeswalker(&mut self, card_id: u64, amount: i32) {
+        if amount <= 0 {
+            return;
+        }
+        match self.battlefield_creatures.get(&card_id) {
+            Some(card) if card.loyalty().is_some() => {
+                info!(
+                    "  Dealing {} damage to planeswalker '{}' (id={})",
+                    amount, card.name, card_id
+                );
+            }
+            _ => {
+                warn!("damage_planeswalker() -> id={} is not a planeswalker", card_id);
+                return;
+            }
+        }
+        self.change_loyalty(card_id, -amount);
+        self.check_planeswalker_loyalty();
     }
 
-    pub fn activate_ability(
-        &mut self,
-        source: Card,
-        ability: crate::app::gre::ActivatedAbility,
-        controller: Player,
-    ) {
-        info!(
-            "activate_ability() -> source='{}', condition={:?}, effect={:?}",
-            source.name, ability.condition, ability.effect
-        );
-        self.push_to_stack(StackEntry::ActivatedAbility {
-            source,
-            ability,
-            controller,
-        });
-        // If a Planeswalker is activating an ability, mark all its abilities as used this turn
-        if let Some(card) = self.battlefield_creatures.get_mut(&source.card_id) {
-            if card.type_flags.contains(CardTypeFlags::PLANESWALKER) {
-                for abil in card.activated_abilities.iter_mut() {
-                    abil.activated_this_turn = true;
-                }
+    /// State-based action: a 0 loyaltys planeswalkerek a temetőbe kerülnek.
+    pub fn check_planeswalker_loyalty(&mut self) {
+        let dead: Vec<u64> = self
+            .battlefield_creatures
+            .iter()
+            .filter(|(_, c)| c.loyalty().is_some_and(|l| l <= 0))
+            .map(|(&id, _)| id)
+            .collect();
+        for id in dead {
+            if let Some(card) = self.battlefield_creatures.remove(&id) {
+                info!(
+                    "    '{}' (id={}) is put into graveyard due to 0 loyalty",
+                    card.name, id
+                );
+                let controller = card.controller;
+                self.leave_battlefield_to_graveyard(card, controller);
             }
         }
     }
 
+    /// A battlefielden lévő planeswalkerek (pl. támadási célpontnak).
+    pub fn planeswalkers(&self) -> Vec<&Card> {
+        self.battlefield_creatures
+            .values()
+            .filter(|c| c.loyalty().is_some())
+            .collect()
+    }
+
     pub fn resolve_stack(&mut self) {
         info!("resolve_stack() -> start resolving all stack entries...");
         while let Some(pe) = self.stack.pop() {
@@ -197,7 +289,11 @@ impl Gre {
                     card,
                     controller,
                     target_creature,
+                    modes,
+                    paid,
                 } => {
+                    self.resolving_controller = controller;
+                    self.resolving_costs = paid;
                     // Itt mentsük el lokálisan a célpontot
                     let local_target = target_creature.clone();
 
@@ -226,9 +322,9 @@ impl Gre {
                                             }
                                             Effect::CreateEnchantmentToken {
                                                 name,
-                                                power_buff,
-                                                toughness_buff,
-                                                ability,
+                                                power_buff: _,
+                                                toughness_buff: _,
+                                                ability: _,
                                             } => {
                                                 // Pl.:
                                                 info!(
@@ -243,7 +339,8 @@ impl Gre {
                                                     CardType::Enchantment,
                                                     ManaCost::free(),
                                                 )
-                                                .with_added_type(CardTypeFlags::TOKEN);
+                                                .with_added_type(CardTypeFlags::TOKEN)
+                                                .with_subtype(Subtype::Enchantment(EnchantmentType::Role));
 
                                                 aura_card.attached_to = Some(actual_target.card_id);
 
@@ -281,12 +378,17 @@ impl Gre {
                         }
                     }
 
+                    if !modes.is_empty() {
+                        self.resolve_modes(&c, &modes, controller);
+                    }
+
                     // További események pl. SpellResolved...
                     self.trigger_event(
                         GameEvent::SpellResolved(c.name.clone()),
                         &mut Vec::new(),
                         controller,
                     );
+                    self.resolving_costs = PaidCosts::default();
                 }
 
                 StackEntry::TriggeredAbility {
@@ -294,6 +396,7 @@ impl Gre {
                     effect,
                     controller,
                 } => {
+                    self.resolving_controller = controller;
                     info!("  -> Resolving TriggeredAbility: effect={:?}", effect);
                     self.current_source_card = source;
                     self.handle_effect(effect);
@@ -304,29 +407,19 @@ impl Gre {
                     source,
                     ability,
                     controller,
+                    paid,
                 } => {
+                    self.resolving_controller = controller;
+                    self.resolving_costs = paid;
                     info!(
                         "  -> Resolving ActivatedAbility: effect={:?}",
                         ability.effect
                     );
+                    // A loyalty költséget már az aktiváláskor kifizettük
                     self.current_source_card = Some(source);
                     self.handle_effect(ability.effect.clone());
-                    // Apply loyalty cost or gain for Planeswalker abilities
-                    if ability.loyalty_change != 0 {
-                        if let Some(card) = self.battlefield_creatures.get_mut(&source.card_id) {
-                            if let CardType::Planeswalker(ref mut pw) = card.card_type {
-                                pw.loyalty += ability.loyalty_change;
-                                info!("    '{}'s loyalty is now {}", card.name, pw.loyalty);
-                                if pw.loyalty <= 0 {
-                                    self.battlefield_creatures.remove(&source.card_id);
-                                    info!(
-                                        "    '{}' (id={}) is put into graveyard due to 0 loyalty",
-                                        card.name, source.card_id
-                                    );
-                                }
-                            }
-                        }
-                    }
+                    self.current_source_card = None;
+                    self.resolving_costs = PaidCosts::default();
                 }
             }
         }
@@ -342,6 +435,7 @@ impl Gre {
                     effect,
                     controller,
                 } => {
+                    self.resolving_controller = controller;
                     info!("  -> top is TriggeredAbility, effect={:?}", effect);
                     self.current_source_card = source;
                     self.handle_effect(effect);
@@ -351,17 +445,17 @@ impl Gre {
                     source,
                     ability,
                     controller,
+                    paid,
                 } => {
+                    self.resolving_controller = controller;
+                    self.resolving_costs = paid;
                     info!("  -> top is ActivatedAbility, effect={:?}", ability.effect);
                     self.current_source_card = Some(source);
                     self.handle_effect(ability.effect.clone());
                     self.current_source_card = None;
+                    self.resolving_costs = PaidCosts::default();
                 }
-                StackEntry::Spell {
-                    card,
-                    controller,
-                    target_creature,
-                } => {
+                StackEntry::Spell { card, .. } => {
                     info!(
                         "  -> top is Spell '{}', (just popping, not auto-resolving).",
                         card.name
@@ -385,7 +479,7 @@ impl Gre {
         });
         // Legyen csökkenő sorrend a priority alapján
         self.replacement_effects
-            .sort_by(|a, b| b.priority.cmp(&a.priority));
+            .sort_by_key(|r| std::cmp::Reverse(r.priority));
     }
 
     /// Continuous (folyamatos) effect hozzáadása
@@ -472,7 +566,22 @@ impl Gre {
             card.name, new_id
         );
 
+        // Planeswalker a nyomtatott loyaltyval megegyező loyalty counterrel lép be
+        let mut starting_loyalty = 0;
+        if let CardType::Planeswalker(ref mut pw) = card.card_type
+            && pw.loyalty == 0 {
+                pw.loyalty = pw.starting_loyalty;
+                starting_loyalty = pw.starting_loyalty;
+            }
+
         self.battlefield_creatures.insert(new_id, card.clone());
+        if starting_loyalty > 0 {
+            self.trigger_event(
+                GameEvent::CounterAdded(new_id, CounterType::Loyalty, starting_loyalty as u32),
+                &mut Vec::new(),
+                Player::Us,
+            );
+        }
 
         // OnEnterBattlefield triggerek
         let effects = card.trigger_by(&Trigger::OnEnterBattlefield {
@@ -483,9 +592,23 @@ impl Gre {
             card.name,
             effects.len()
         );
+        // Az ETB effektek forrása maga a belépő lap (pl. Offspring ezt klónozza)
+        let prev_source = self.current_source_card.replace(card.clone());
         for eff in effects {
             self.handle_effect(eff);
         }
+        self.current_source_card = prev_source;
+
+        // A többi permanens "whenever ... enters" triggerei
+        self.trigger_event(
+            GameEvent::ZoneChange {
+                card: card.clone(),
+                from: None,
+                to: Zone::Battlefield,
+            },
+            &mut Vec::new(),
+            card.controller,
+        );
     }
 
     pub fn create_creature_token(
@@ -0,0 +1,181 @@
+// src/app/gre/modal.rs
+
+use tracing::{info, warn};
+
+use crate::app::card_attribute::{Effect, SpellMode, TargetFilter};
+use crate::app::card_library::Card;
+use crate::app::game_state::Player;
+use crate::app::gre::{Gre, Target};
+
+/// Egy kiválasztott mód és a célpontjai (szűrőnként, a mód szűrőinek sorrendjében)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChosenMode {
+    pub index: usize,
+    pub label: String,
+    pub targets: Vec<(TargetFilter, Target)>,
+}
+
+impl ChosenMode {
+    fn target_for(&self, filter: &TargetFilter) -> Option<Target> {
+        self.targets
+            .iter()
+            .find(|(f, _)| f == filter)
+            .map(|(_, t)| *t)
+    }
+}
+
+impl Gre {
+    /// A szűrőnek megfelelő választható célpontok a `controller` szemszögéből;
+    /// `None`, ha a szűrő nem célzó (SelfCard, Player, pontos ID, ...).
+    pub fn target_candidates(&self, filter: &TargetFilter, controller: Player) -> Option<Vec<Target>> {
+        let candidates = match filter {
+            TargetFilter::AnyTarget => self.any_target_candidates(),
+            TargetFilter::Player
+            | TargetFilter::SelfCard
+            | TargetFilter::ExactCardID(_)
+            | TargetFilter::CardInGraveyard => return None,
+            filter => self
+                .permanents_matching(filter, controller)
+                .into_iter()
+                .map(Target::Permanent)
+                .collect(),
+        };
+        Some(candidates)
+    }
+
+    /// A mód célzó szűrői, ismétlés nélkül
+    pub fn mode_target_filters(&self, mode: &SpellMode, controller: Player) -> Vec<TargetFilter> {
+        let mut out: Vec<TargetFilter> = Vec::new();
+        for filter in mode.effects.iter().flat_map(|e| e.target_filters()) {
+            if !out.contains(filter) && self.target_candidates(filter, controller).is_some() {
+                out.push(filter.clone());
+            }
+        }
+        out
+    }
+
+    /// Választható-e a mód: minden célzó szűrőjéhez van legalább egy célpont.
+    pub fn mode_is_legal(&self, mode: &SpellMode, controller: Player) -> bool {
+        self.mode_target_filters(mode, controller).iter().all(|f| {
+            self.target_candidates(f, controller)
+                .is_some_and(|c| !c.is_empty())
+        })
+    }
+
+    /// Módok és célpontok kiválasztása kijátszáskor (a `DecisionProvider`-rel);
+    /// `None`, ha nincs elég legális mód.
+    pub fn choose_spell_modes(&mut self, card: &Card, controller: Player) -> Option<Vec<ChosenMode>> {
+        let modal = card.modal.as_ref()?;
+        let legal: Vec<usize> = (0..modal.modes.len())
+            .filter(|&i| self.mode_is_legal(&modal.modes[i], controller))
+            .collect();
+        if legal.len() < modal.min {
+            info!(
+                "'{}': only {} legal mode(s), {} required",
+                card.name,
+                legal.len(),
+                modal.min
+            );
+            return None;
+        }
+        let options: Vec<Vec<Effect>> = legal
+            .iter()
+            .map(|&i| modal.modes[i].effects.clone())
+            .collect();
+        let picked = self.choose_modes(&options, modal.min, modal.max);
+
+        let prev_controller = self.resolving_controller;
+        self.resolving_controller = controller;
+        let mut chosen = Vec::new();
+        for idx in picked.into_iter().map(|i| legal[i]) {
+            let mode = &modal.modes[idx];
+            let mut targets = Vec::new();
+            for filter in self.mode_target_filters(mode, controller) {
+                let candidates = self.target_candidates(&filter, controller).unwrap_or_default();
+                let effect = mode
+                    .effects
+                    .iter()
+                    .find(|e| e.target_filters().contains(&&filter))
+                    .expect("filter comes from the mode's effects");
+                match self.choose_target(effect, &candidates) {
+                    Some(t) => targets.push((filter, t)),
+                    None => {
+                        self.resolving_controller = prev_controller;
+                        return None;
+                    }
+                }
+            }
+            chosen.push(ChosenMode {
+                index: idx,
+                label: mode.label.clone(),
+                targets,
+            });
+        }
+        self.resolving_controller = prev_controller;
+        Some(chosen)
+    }
+
+    fn target_still_legal(&self, filter: &TargetFilter, target: Target, controller: Player) -> bool {
+        match target {
+            Target::Player(_) => true,
+            Target::Permanent(_) => self
+                .target_candidates(filter, controller)
+                .is_some_and(|c| c.contains(&target)),
+        }
+    }
+
+    /// A kiválasztott módok feloldása sorrendben. Az illegálissá vált célpontú effektek
+    /// kimaradnak; ha minden célpont illegális, a varázslat nem old fel (fizzle).
+    pub fn resolve_modes(&mut self, card: &Card, modes: &[ChosenMode], controller: Player) {
+        let all_targets: Vec<(&TargetFilter, Target)> = modes
+            .iter()
+            .flat_map(|m| m.targets.iter().map(|(f, t)| (f, *t)))
+            .collect();
+        if !all_targets.is_empty()
+            && all_targets
+                .iter()
+                .all(|(f, t)| !self.target_still_legal(f, *t, controller))
+        {
+            info!("'{}': every target is gone, the spell fizzles", card.name);
+            return;
+        }
+        let Some(modal) = card.modal.as_ref() else {
+            warn!("resolve_modes() -> '{}' is not modal", card.name);
+            return;
+        };
+        for chosen in modes {
+            let Some(mode) = modal.modes.get(chosen.index) else {
+                continue;
+            };
+            info!("'{}' mode: {} -> {:?}", card.name, mode.label, chosen.targets);
+            for effect in &mode.effects {
+                let illegal = effect.target_filters().iter().any(|f| {
+                    chosen
+                        .target_for(f)
+                        .is_some_and(|t| !self.target_still_legal(f, t, controller))
+                });
+                if illegal {
+                    info!("  target no longer legal, skipping {:?}", effect);
+                    continue;
+                }
+                let damage_to_player = match effect {
+                    Effect::Damage { amount, target } => match chosen.target_for(target) {
+                        Some(Target::Player(p)) => Some((p, self.amount_value(amount))),
+                        _ => None,
+                    },
+                    _ => None,
+                };
+                if let Some((player, damage)) = damage_to_player {
+                    self.deal_damage_to_player(player, damage);
+                    continue;
+                }
+                let retargeted = effect.clone().map_targets(&|f| match chosen.target_for(&f) {
+                    Some(Target::Permanent(id)) => TargetFilter::ExactCardID(id),
+                    Some(Target::Player(p)) if p == controller.opponent() => TargetFilter::Player,
+                    _ => f,
+                });
+                self.handle_effect(retargeted);
+            }
+        }
+    }
+}
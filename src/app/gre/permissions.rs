@@ -0,0 +1,248 @@
+// src/app/gre/permissions.rs
+
+//! Kijátszási engedélyek kézen kívüli zónákból: "kijátszhatod a száműzött lapot a
+//! következő köröd végéig" (impulse draw), földek a száműzetésből, és flashback-szerű
+//! kijátszás a temetőből (opcionálisan más mana költséggel).
+//! Az engedélyeket a legális akciók generátora (`GameState::zone_actions`) és a bot
+//! stratégiája is figyelembe veszi; a kliens ezeket a lapokat a kéz jobb szélén mutatja.
+
+use tracing::{debug, info, warn};
+
+use crate::app::card_attribute::Duration;
+use crate::app::card_library::{Card, CardType, ManaCost};
+use crate::app::game_state::Player;
+use crate::app::gre::Gre;
+
+/// Melyik zónából szól az engedély
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CastZone {
+    Exile,
+    Graveyard,
+}
+
+/// "Ezt a lapot kijátszhatod ebből a zónából" engedély
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CastPermission {
+    pub card_id: u64,
+    pub player: Player,
+    pub zone: CastZone,
+    /// Alternatív mana költség (pl. flashback); `None`: a lap saját költsége
+    pub cost: Option<ManaCost>,
+    /// Hány kör vége van még hátra; `None`: nem jár le
+    pub turns_left: Option<u32>,
+}
+
+impl CastPermission {
+    /// `EndOfTurn`: a mostani kör végéig, `NextTurnEnd`: a következő kör végéig.
+    pub fn new(card_id: u64, player: Player, zone: CastZone, duration: &Duration) -> Self {
+        let turns_left = match duration {
+            Duration::EndOfTurn => Some(1),
+            Duration::NextTurnEnd => Some(2),
+            Duration::Permanent => None,
+        };
+        Self {
+            card_id,
+            player,
+            zone,
+            cost: None,
+            turns_left,
+        }
+    }
+
+    pub fn with_cost(mut self, cost: ManaCost) -> Self {
+        self.cost = Some(cost);
+        self
+    }
+}
+
+impl Gre {
+    pub fn grant_cast_permission(&mut self, permission: CastPermission) {
+        debug!("grant_cast_permission() -> {:?}", permission);
+        self.cast_permissions.push(permission);
+    }
+
+    fn zone_cards(&self, zone: CastZone) -> impl Iterator<Item = &Card> {
+        self.players.values().flat_map(move |s| match zone {
+            CastZone::Exile => s.exile.iter(),
+            CastZone::Graveyard => s.graveyard.iter(),
+        })
+    }
+
+    fn find_in_zone(&self, zone: CastZone, card_id: u64) -> Option<&Card> {
+        self.zone_cards(zone).find(|c| c.card_id == card_id)
+    }
+
+    fn take_from_zone(&mut self, zone: CastZone, card_id: u64) -> Option<Card> {
+        self.cast_permissions.retain(|p| p.card_id != card_id);
+        self.players.values_mut().find_map(|s| {
+            let cards = match zone {
+                CastZone::Exile => &mut s.exile,
+                CastZone::Graveyard => &mut s.graveyard,
+            };
+            let idx = cards.iter().position(|c| c.card_id == card_id)?;
+            Some(cards.remove(idx))
+        })
+    }
+
+    fn permission_for(&self, card_id: u64, player: Player) -> Option<&CastPermission> {
+        self.cast_permissions
+            .iter()
+            .find(|p| p.card_id == card_id && p.player == player)
+    }
+
+    /// A lap, ahogy az engedéllyel kijátszanánk: a `player` irányítja, és az
+    /// alternatív költség (ha van) felülírja a nyomtatottat. Fel nem ismert lapot
+    /// nem játszunk ki.
+    fn permitted_card(&self, permission: &CastPermission) -> Option<Card> {
+        let mut card = self
+            .find_in_zone(permission.zone, permission.card_id)
+            .filter(|c| !c.is_hidden())?
+            .clone()
+            .with_controller(permission.player);
+        if let Some(cost) = &permission.cost {
+            card.mana_cost = cost.clone();
+        }
+        Some(card)
+    }
+
+    /// A `player` engedélyei sorrendben, ahogy a kliens a kéz jobb szélén mutatja őket:
+    /// (card_id, fel nem ismert-e a lap).
+    pub fn permission_slots(&self, player: Player) -> Vec<(u64, bool)> {
+        self.cast_permissions
+            .iter()
+            .filter(|p| p.player == player)
+            .filter_map(|p| {
+                self.find_in_zone(p.zone, p.card_id)
+                    .map(|c| (p.card_id, c.is_hidden()))
+            })
+            .collect()
+    }
+
+    /// Egy fel nem ismert (helyőrző) lapot a képernyőn azonosított `card`-ra cserél;
+    /// az id és az irányító marad, így az engedély ehhez a laphoz kötődik.
+    pub fn reveal_zone_card(&mut self, card_id: u64, card: &Card) -> bool {
+        let slot = self.players.values_mut().find_map(|s| {
+            s.exile
+                .iter_mut()
+                .chain(s.graveyard.iter_mut())
+                .find(|c| c.card_id == card_id && c.is_hidden())
+        });
+        let Some(slot) = slot else {
+            warn!("reveal_zone_card() -> no hidden card with id={}", card_id);
+            return false;
+        };
+        info!("    hidden card id={} is '{}'", card_id, card.name);
+        let mut revealed = card.clone().with_controller(slot.controller);
+        revealed.card_id = card_id;
+        *slot = revealed;
+        true
+    }
+
+    /// A `player` engedéllyel kijátszható lapjai (zónától függetlenül, időzítés nélkül),
+    /// az engedély szerinti költséggel
+    pub fn castable_from_zones(&self, player: Player) -> Vec<Card> {
+        self.cast_permissions
+            .iter()
+            .filter(|p| p.player == player)
+            .filter_map(|p| self.permitted_card(p))
+            .collect()
+    }
+
+    /// Most kijátszható-e a lap az engedéllyel: föld csak sorcery-speed-ben, körönként
+    /// egy; varázslat az időzítése szerint és ha a költsége kifizethető.
+    pub fn can_play_with_permission(&self, card_id: u64, player: Player) -> bool {
+        self.can_play_from_zone(card_id, player, None)
+    }
+
+    /// Mint a `can_play_with_permission`, de a mana költséget a kliens fizeti: csak az
+    /// összege kell beleférjen az `available_mana`-ba.
+    pub fn can_play_client_paid(&self, card_id: u64, player: Player, available_mana: u32) -> bool {
+        self.can_play_from_zone(card_id, player, Some(available_mana))
+    }
+
+    fn can_play_from_zone(&self, card_id: u64, player: Player, client_mana: Option<u32>) -> bool {
+        let Some(card) = self
+            .permission_for(card_id, player)
+            .and_then(|p| self.permitted_card(p))
+        else {
+            return false;
+        };
+        match card.card_type {
+            CardType::Land => {
+                self.is_sorcery_speed(player) && self.player(player).lands_played_this_turn == 0
+            }
+            CardType::Instant => self.can_cast(&card, player, client_mana),
+            _ => self.is_sorcery_speed(player) && self.can_cast(&card, player, client_mana),
+        }
+    }
+
+    fn can_cast(&self, card: &Card, player: Player, client_mana: Option<u32>) -> bool {
+        let cost = self.effective_cost(card, player);
+        let pool_cost = match client_mana {
+            Some(available) if cost.total() > available => return false,
+            Some(_) => ManaCost::free(),
+            None => cost,
+        };
+        self.check_costs(player, None, &pool_cost, &card.additional_costs)
+            .is_ok()
+    }
+
+    /// Lap kijátszása engedéllyel: a föld a körönkénti földkijátszást használja,
+    /// minden más a szokásos (vagy az alternatív) költséggel kerül a stackre.
+    /// `false`, ha nem sikerült; a lap ilyenkor a zónájában marad.
+    pub fn play_from_zone(&mut self, card_id: u64, player: Player) -> bool {
+        let Some(permission) = self.permission_for(card_id, player).cloned() else {
+            warn!(
+                "play_from_zone() -> {:?} may not play id={}",
+                player, card_id
+            );
+            return false;
+        };
+        let Some(card) = self.permitted_card(&permission) else {
+            warn!(
+                "play_from_zone() -> id={} is not in {:?}",
+                card_id, permission.zone
+            );
+            return false;
+        };
+        if !self.can_play_with_permission(card_id, player) {
+            warn!(
+                "play_from_zone() -> {:?} cannot play '{}' now",
+                player, card.name
+            );
+            return false;
+        }
+
+        if matches!(card.card_type, CardType::Land) {
+            self.take_from_zone(permission.zone, card_id);
+            info!(
+                "{:?} plays land '{}' from {:?}",
+                player, card.name, permission.zone
+            );
+            self.note_land_played(player);
+            let mut land = card;
+            self.enter_battlefield(&mut land);
+            return true;
+        }
+
+        if let Err(e) = self.cast_spell(card.clone(), player, None) {
+            warn!("play_from_zone() -> cannot cast '{}': {}", card.name, e);
+            return false;
+        }
+        info!("  '{}' was cast from {:?}", card.name, permission.zone);
+        self.take_from_zone(permission.zone, card_id);
+        true
+    }
+
+    /// Kör vége: a lejárt engedélyek törlése
+    pub fn expire_cast_permissions(&mut self) {
+        self.cast_permissions
+            .retain_mut(|p| match p.turns_left.as_mut() {
+                Some(n) => {
+                    *n -= 1;
+                    *n > 0
+                }
+                None => true,
+            });
+    }
+}
@@ -0,0 +1,301 @@
+// src/app/gre/player_state.rs
+
+use std::collections::HashMap;
+use tracing::{debug, info};
+
+use crate::app::card_attribute::{CounterType, Duration, PlayerSelector};
+use crate::app::card_library::{Card, CardTypeFlags, ManaCost};
+use crate::app::game_state::{GameEvent, Player};
+use crate::app::gre::{CastPermission, CastZone, Gre};
+
+/// Kezdő életpont
+pub const STARTING_LIFE: i32 = 20;
+/// Ennyi poison counterrel a játékos veszít
+pub const LETHAL_POISON: u32 = 10;
+
+/// Mana pool; fázisváltáskor kiürül.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ManaPool {
+    pub colorless: u32,
+    pub red: u32,
+    pub blue: u32,
+    pub green: u32,
+    pub black: u32,
+    pub white: u32,
+}
+
+/// Pontosan a költségnyi mana; a generikus rész színtelenként.
+impl From<&ManaCost> for ManaPool {
+    fn from(cost: &ManaCost) -> Self {
+        ManaPool {
+            colorless: cost.colorless,
+            red: cost.red,
+            blue: cost.blue,
+            green: cost.green,
+            black: cost.black,
+            white: cost.white,
+        }
+    }
+}
+
+impl ManaPool {
+    pub fn total(&self) -> u32 {
+        self.colorless + self.red + self.blue + self.green + self.black + self.white
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.total() == 0
+    }
+
+    pub fn add(&mut self, other: ManaPool) {
+        self.colorless += other.colorless;
+        self.red += other.red;
+        self.blue += other.blue;
+        self.green += other.green;
+        self.black += other.black;
+        self.white += other.white;
+    }
+
+    /// Kifizethető-e a költség: a színes részt a saját színéből, a generikusat bármiből.
+    pub fn can_pay(&self, cost: &ManaCost) -> bool {
+        let mut pool = *self;
+        pool.pay(cost)
+    }
+
+    /// A költség levonása; `false` (és változatlan pool), ha nem fizethető.
+    pub fn pay(&mut self, cost: &ManaCost) -> bool {
+        let mut left = *self;
+        for (have, need) in [
+            (&mut left.red, cost.red),
+            (&mut left.green, cost.green),
+            (&mut left.blue, cost.blue),
+            (&mut left.white, cost.white),
+            (&mut left.black, cost.black),
+        ] {
+            if *have < need {
+                return false;
+            }
+            *have -= need;
+        }
+        let mut generic = cost.colorless;
+        for have in [
+            &mut left.colorless,
+            &mut left.red,
+            &mut left.green,
+            &mut left.blue,
+            &mut left.white,
+            &mut left.black,
+        ] {
+            let used = generic.min(*have);
+            *have -= used;
+            generic -= used;
+        }
+        if generic > 0 {
+            return false;
+        }
+        *self = left;
+        true
+    }
+}
+
+/// Egy játékos állapota a GRE-ben
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlayerState {
+    pub life: i32,
+    pub hand_size: u32,
+    pub mana_pool: ManaPool,
+    pub lands_played_this_turn: u32,
+    pub spells_cast_this_turn: u32,
+    /// Ebben a körben elvesztett / nyert életpontok (a valódi életváltozásokból)
+    pub life_lost_this_turn: u32,
+    pub life_gained_this_turn: u32,
+    /// Játékos counterek (poison, energy, experience)
+    pub counters: HashMap<CounterType, u32>,
+    /// A temetőbe került (nem token) lapok, a legutóbbi a végén
+    pub graveyard: Vec<Card>,
+    /// Az ismert könyvtár, a teteje a végén
+    pub library: Vec<Card>,
+    /// A kézben lévő ismert lapok (legfeljebb `hand_size` darab)
+    pub hand: Vec<Card>,
+    /// Száműzött lapok, a legutóbbi a végén
+    pub exile: Vec<Card>,
+}
+
+impl Default for PlayerState {
+    fn default() -> Self {
+        Self {
+            life: STARTING_LIFE,
+            hand_size: 0,
+            mana_pool: ManaPool::default(),
+            lands_played_this_turn: 0,
+            spells_cast_this_turn: 0,
+            life_lost_this_turn: 0,
+            life_gained_this_turn: 0,
+            counters: HashMap::new(),
+            graveyard: Vec::new(),
+            library: Vec::new(),
+            hand: Vec::new(),
+            exile: Vec::new(),
+        }
+    }
+}
+
+impl PlayerState {
+    pub fn poison(&self) -> u32 {
+        self.counters.get(&CounterType::Poison).copied().unwrap_or(0)
+    }
+
+    pub fn lost_life_this_turn(&self) -> bool {
+        self.life_lost_this_turn > 0
+    }
+
+    /// 0 vagy kevesebb élet, vagy 10 poison counter
+    pub fn has_lost(&self) -> bool {
+        self.life <= 0 || self.poison() >= LETHAL_POISON
+    }
+
+    /// Kör végén nullázódó számlálók
+    pub fn reset_turn(&mut self) {
+        self.lands_played_this_turn = 0;
+        self.spells_cast_this_turn = 0;
+        self.life_lost_this_turn = 0;
+        self.life_gained_this_turn = 0;
+        self.mana_pool = ManaPool::default();
+    }
+}
+
+impl Gre {
+    pub fn player(&self, player: Player) -> &PlayerState {
+        &self.players[&player]
+    }
+
+    pub fn player_mut(&mut self, player: Player) -> &mut PlayerState {
+        self.players.entry(player).or_default()
+    }
+
+    /// Az effekt `PlayerSelector`-a a feloldás alatt álló stack-elem irányítójához képest.
+    /// `AnyPlayer`: a gép ellenfelet választ.
+    pub fn select_player(&self, selector: &PlayerSelector) -> Player {
+        match selector {
+            PlayerSelector::Controller => self.resolving_controller,
+            PlayerSelector::Opponent | PlayerSelector::AnyPlayer => {
+                self.resolving_controller.opponent()
+            }
+        }
+    }
+
+    pub fn lost_life_this_turn(&self, player: Player) -> bool {
+        self.player(player).lost_life_this_turn()
+    }
+
+    pub fn life_gain_prevented(&self, player: Player) -> bool {
+        match player {
+            Player::Us => self.prevent_life_gain_us,
+            Player::Opponent => self.prevent_life_gain_opponent,
+        }
+    }
+
+    /// Életvesztés; LifeLost eseményt vált ki.
+    pub fn lose_life(&mut self, player: Player, amount: u32) {
+        if amount == 0 {
+            return;
+        }
+        let life = {
+            let state = self.player_mut(player);
+            state.life -= amount as i32;
+            state.life_lost_this_turn += amount;
+            state.life
+        };
+        info!("    {:?} loses {} life -> {}", player, amount, life);
+        self.trigger_event(GameEvent::LifeLost(player, amount), &mut Vec::new(), player);
+    }
+
+    /// Életnyerés, hacsak PreventLifeGain nem tiltja; `false`, ha meghiúsult.
+    pub fn gain_life(&mut self, player: Player, amount: u32) -> bool {
+        if amount == 0 {
+            return true;
+        }
+        if self.life_gain_prevented(player) {
+            info!("    Life gain {} for {:?} meghiúsul (PreventLifeGain).", amount, player);
+            return false;
+        }
+        let life = {
+            let state = self.player_mut(player);
+            state.life += amount as i32;
+            state.life_gained_this_turn += amount;
+            state.life
+        };
+        info!("    {:?} gains {} life -> {}", player, amount, life);
+        self.trigger_event(GameEvent::LifeGained(player, amount), &mut Vec::new(), player);
+        true
+    }
+
+    /// Sebzés játékosnak: (infect nélkül) életvesztés.
+    pub fn deal_damage_to_player(&mut self, player: Player, amount: i32) {
+        if amount <= 0 {
+            return;
+        }
+        info!("  Dealing {} damage to {:?}", amount, player);
+        self.lose_life(player, amount as u32);
+    }
+
+    /// Kézméret a képernyőről (a kijátszott lapokat a bot veszi ki a kézből).
+    pub fn set_hand_size(&mut self, player: Player, hand_size: u32) {
+        let state = self.player_mut(player);
+        state.hand_size = hand_size;
+        state.hand.truncate(hand_size as usize);
+    }
+
+    pub fn note_land_played(&mut self, player: Player) {
+        self.player_mut(player).lands_played_this_turn += 1;
+    }
+
+    pub fn note_spell_cast(&mut self, player: Player) {
+        self.player_mut(player).spells_cast_this_turn += 1;
+    }
+
+    pub fn add_mana(&mut self, player: Player, mana: ManaPool) {
+        let state = self.player_mut(player);
+        state.mana_pool.add(mana);
+        debug!("    {:?} mana pool: {:?}", player, state.mana_pool);
+    }
+
+    /// A lap a tulajdonosa temetőjébe kerül; a token megszűnik.
+    pub fn put_into_graveyard(&mut self, card: Card) {
+        if card.has_type(CardTypeFlags::TOKEN) {
+            debug!("    token '{}' ceases to exist", card.name);
+            return;
+        }
+        info!("    '{}' is put into {:?}'s graveyard", card.name, card.controller);
+        let mut card = card;
+        if card.card_id == 0 {
+            card.card_id = self.next_card_id;
+            self.next_card_id += 1;
+        }
+        // Flashback: a temetőből a tulajdonosa kijátszhatja
+        if let Some(cost) = card.flashback.clone() {
+            self.grant_cast_permission(
+                CastPermission::new(
+                    card.card_id,
+                    card.controller,
+                    CastZone::Graveyard,
+                    &Duration::Permanent,
+                )
+                .with_cost(cost),
+            );
+        }
+        self.player_mut(card.controller).graveyard.push(card);
+    }
+
+    /// A képernyőről olvasott életek felülírják a számoltat (nem életváltozás-esemény).
+    /// A nem pozitív érték sikertelen OCR-t jelent, azt kihagyjuk.
+    pub fn sync_life_totals(&mut self, us: i32, opponent: i32) {
+        for (player, life) in [(Player::Us, us), (Player::Opponent, opponent)] {
+            let state = self.player_mut(player);
+            if life > 0 && state.life != life {
+                debug!("sync_life_totals() -> {:?}: {} -> {}", player, state.life, life);
+                state.life = life;
+            }
+        }
+    }
+}
@@ -1,17 +1,23 @@
 // src/app/gre/stack.rs
 
-use std::fmt;
 use crate::app::game_state::Player;
 use crate::app::card_library::Card;
 use crate::app::gre::gre_structs::ActivatedAbility;
+use crate::app::gre::modal::ChosenMode;
+use crate::app::gre::costs::PaidCosts;
 
 /// StackEntry: Spell, TriggeredAbility, ActivatedAbility
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[allow(clippy::large_enum_variant)]
 pub enum StackEntry {
     Spell {
         card: Card,
         controller: Player,
         target_creature: Option<Card>,
+        /// Modális varázslatnál a kijátszáskor kiválasztott módok
+        modes: Vec<ChosenMode>,
+        /// A kijátszáskor kifizetett opcionális és nem-mana költségek
+        paid: PaidCosts,
     },
     TriggeredAbility {
         source: Option<Card>,
@@ -21,7 +27,9 @@ pub enum StackEntry {
     ActivatedAbility {
         source: Card,
         ability: ActivatedAbility,
-        controller: Player
+        controller: Player,
+        /// Az aktiváláskor kifizetett nem-mana költségek
+        paid: PaidCosts,
     },
 }
 
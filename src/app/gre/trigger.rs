@@ -1,24 +1,25 @@
 // src/app/gre/trigger.rs
 
-use crate::app::card_attribute::TargetFilter;
-use crate::app::card_attribute::{Duration, PlayerSelector};
-use crate::app::card_attribute::{Effect, Trigger};
+//! Eseménybusz: a motor `GameEvent`-eket küld, a permanensek triggerei minta szerint
+//! iratkoznak fel rájuk (`Gre::subscribes`). Egy esemény összes kiváltott triggerét
+//! begyűjtjük, és APNAP sorrendben tesszük a stackre: előbb az aktív játékosét, így
+//! a nem aktív játékos triggerei oldódnak fel először.
+
+use crate::app::card_attribute::{Effect, PlayerSelector, TargetFilter, Trigger, Zone};
 use crate::app::card_library::Card;
-use crate::app::card_library::CardType::Creature;
-use crate::app::card_library::CardTypeFlags;
 use crate::app::game_state::{GameEvent, GamePhase, Player};
-use crate::app::gre::Gre; // hivatkozunk a Gre struktúrára
-use crate::app::gre::gre_structs::DelayedEffect;
-use crate::app::gre::gre_structs::ReplacementEffect;
 use crate::app::gre::stack::StackEntry;
-use tracing::{debug, info, warn};
+use crate::app::gre::{ConditionContext, Gre, Target};
+use tracing::{debug, info};
 
 impl Gre {
-    /// Események (pl. OnCastResolved) kiváltása a battlefielden lévő kártyákra
+    /// Esemény küldése. A figyelők: a battlefield permanensei, az esemény saját lapja
+    /// (a meghalt / ciklizált lap), és a `battlefield`-ben átadott, a GRE által nem
+    /// követett lapok. A `controller` az eseményt kiváltó játékos (pl. aki célzott).
     pub fn trigger_event(
         &mut self,
         event: GameEvent,
-        battlefield: &mut Vec<Card>,
+        battlefield: &mut [Card],
         controller: Player,
     ) {
         info!(
@@ -27,318 +28,283 @@ impl Gre {
             controller,
             battlefield.len()
         );
+        self.note_event(&event);
+        let batch = self.collect_event_triggers(&event, battlefield, controller);
+        let batch = self.filter_intervening_if(batch, &event);
+        self.reset_priority();
+        self.put_triggers_on_stack(batch, &event);
+    }
 
-        // Ha CreatureDied, nézzük meg a death_triggers_this_turn listát
-        if let GameEvent::CreatureDied(ref died_card) = event {
-            info!(
-                "  Checking death_triggers_this_turn, died_card='{}'(id={})",
-                died_card.name, died_card.card_id
-            );
-            let mut to_trigger = Vec::new();
-            let mut indices_to_remove = Vec::new();
-
-            for (i, (tracked_creature, eff)) in self.death_triggers_this_turn.iter().enumerate() {
-                if tracked_creature == died_card {
-                    debug!("    -> Found a death-trigger effect: {:?}", eff);
-                    to_trigger.push(eff.clone());
-                    indices_to_remove.push(i);
+    /// Az esemény könyvelése a triggerek begyűjtése előtt.
+    fn note_event(&mut self, event: &GameEvent) {
+        if let Some(id) = event.subject() {
+            *self.events_this_turn.entry((id, event.kind())).or_insert(0) += 1;
+        }
+        match event {
+            GameEvent::CreatureDied(died_card) => {
+                info!(
+                    "  Checking death_triggers_this_turn, died_card='{}'(id={})",
+                    died_card.name, died_card.card_id
+                );
+                self.put_into_graveyard(died_card.clone());
+                let (fired, kept): (Vec<_>, Vec<_>) = self
+                    .death_triggers_this_turn
+                    .drain(..)
+                    .partition(|(tracked, _)| tracked == died_card);
+                self.death_triggers_this_turn = kept;
+                for (_, eff) in fired {
+                    debug!("    handle_effect from death_triggers_this_turn: {:?}", eff);
+                    self.handle_effect(eff);
                 }
             }
-            // Lefuttatjuk
-            for eff in to_trigger {
-                debug!("    handle_effect from death_triggers_this_turn: {:?}", eff);
-                self.handle_effect(eff);
+            GameEvent::CounterAdded(_, _, count) => self.last_counters_added = *count,
+            _ => {}
+        }
+    }
+
+    /// A figyelő `trigger`-e feliratkozott-e az eseményre. `subject` az esemény
+    /// permanens-alanyát keresi (battlefield, majd a hívó által átadott lapok).
+    fn subscribes(
+        &self,
+        trigger: &Trigger,
+        listener: &Card,
+        event: &GameEvent,
+        actor: Player,
+        subject: &dyn Fn(u64) -> Option<Card>,
+    ) -> bool {
+        let matches = |filter: &TargetFilter, card: &Card, zone: Zone| {
+            self.trigger_subject_matches(filter, listener, card, zone)
+        };
+        let permanent = |filter: &TargetFilter, id: u64| {
+            subject(id).is_some_and(|card| matches(filter, &card, Zone::Battlefield))
+        };
+        let player_matches = |selector: &PlayerSelector, player: Player| match selector {
+            PlayerSelector::Controller => player == listener.controller,
+            PlayerSelector::Opponent => player != listener.controller,
+            PlayerSelector::AnyPlayer => true,
+        };
+        match (trigger, event) {
+            (Trigger::OnDeath { filter }, GameEvent::CreatureDied(card)) => {
+                matches(filter, card, Zone::Graveyard)
             }
-            // Töröljük
-            for &i in indices_to_remove.iter().rev() {
-                self.death_triggers_this_turn.remove(i);
+            // A belépő lap saját ETB-jét az `enter_battlefield` azonnal futtatja
+            (
+                Trigger::OnEnterBattlefield { filter },
+                GameEvent::ZoneChange {
+                    card,
+                    to: Zone::Battlefield,
+                    ..
+                },
+            ) => {
+                !(listener.card_id == card.card_id && *filter == TargetFilter::SelfCard)
+                    && matches(filter, card, Zone::Battlefield)
             }
-        }
-        if let GameEvent::Targeted(tid) = event {
-            let mut batch = Vec::new();
-            // Végigmegyünk a GRE saját 'battlefield_creatures' mapjén
-            // (ez a te belső nyilvántartásod a lényekről).
-            for (_id, c) in self.battlefield_creatures.iter_mut() {
-                if c.card_id == tid {
-                    // Ha a kártyában van OnTargetedFirstTimeEachTurn { filter: SelfCard },
-                    // akkor ez a "trigger_by(...)" hívás lekéri az effect(ek)et.
-                    let triggered_effects = c.trigger_by(&Trigger::OnTargetedFirstTimeEachTurn {
-                        filter: TargetFilter::SelfCard,
-                    });
-                    for eff in triggered_effects {
-                        batch.push((c.clone(), eff));
-                    }
-                }
+            (Trigger::OnSpellCast { filter }, GameEvent::SpellCast { spell, controller }) => {
+                *controller == listener.controller && filter.matches(spell)
             }
-            // A begyűjtött effecteket stackre tesszük TriggeredAbility formájában:
-            for (source_card, eff) in batch {
-                self.push_to_stack(StackEntry::TriggeredAbility {
-                    source: Some(source_card),
-                    effect: eff,
-                    controller,
-                });
+            (Trigger::OnAttack { filter }, GameEvent::AttackDeclared { attacker }) => {
+                permanent(filter, *attacker)
             }
-        }
-        if let GameEvent::ManaAdded(id) = event {
-            let mut batch = Vec::new();
-            for (_cid, c) in self.battlefield_creatures.iter_mut() {
-                if c.card_id == id {
-                    let effects = c.trigger_by(&Trigger::OnAddMana {
-                        filter: TargetFilter::SelfCard,
-                    });
-                    for eff in effects {
-                        batch.push((c.clone(), eff));
-                    }
-                }
-                if c.card_id != id {
-                    let effects = c.trigger_by(&Trigger::OnAddMana {
-                        filter: TargetFilter::ControllerCreature,
-                    });
-                    for eff in effects {
-                        batch.push((c.clone(), eff));
-                    }
-                }
+            (
+                Trigger::OnAttackWithCreatureType { creature_type },
+                GameEvent::AttackDeclared { attacker },
+            ) => permanent(
+                &TargetFilter::CreatureType(creature_type.clone())
+                    .and(TargetFilter::ControlledBy(PlayerSelector::Controller)),
+                *attacker,
+            ),
+            (Trigger::OnBlock { filter }, GameEvent::BlockDeclared { blocker, .. }) => {
+                permanent(filter, *blocker)
             }
-            for (source_card, eff) in batch {
-                match eff {
-                    Effect::Delayed {
-                        effect,
-                        phase,
-                        deps,
-                    } => {
-                        let id = self.schedule_delayed(*effect.clone(), phase, deps.clone());
-                        info!(
-                            "    -> Scheduled delayed effect #{} from OnAddMana trigger",
-                            id
-                        );
-                    }
-                    e => {
-                        let prio = match &e {
-                            Effect::ModifyStats { .. } | Effect::Proliferate { .. } => 2,
-                            _ => 1,
-                        };
-                        info!(
-                            "    -> Pushing TriggeredAbility to stack (prio={}), effect={:?}",
-                            prio, e
-                        );
-                        self.push(
-                            StackEntry::TriggeredAbility {
-                                source: Some(source_card.clone()),
-                                effect: e,
-                                controller,
-                            },
-                            prio,
-                        );
-                    }
-                }
+            (
+                Trigger::OnCombatDamage { filter },
+                GameEvent::DamageDealt {
+                    source: Some(source),
+                    combat: true,
+                    ..
+                },
+            ) => permanent(filter, *source),
+            (
+                Trigger::OnDealtDamage { filter },
+                GameEvent::DamageDealt {
+                    target: Target::Permanent(id),
+                    ..
+                },
+            ) => permanent(filter, *id),
+            (Trigger::OnTargeted { filter, player }, GameEvent::Targeted(id)) => {
+                player_matches(player, actor) && permanent(filter, *id)
             }
-        }
-        if let GameEvent::CounterAdded(id, count) = event {
-            let mut batch = Vec::new();
-            let card_exists = self.battlefield_creatures.contains_key(&id);
-            for (_cid, c) in self.battlefield_creatures.iter_mut() {
-                if c.card_id == id {
-                    let effects = c.trigger_by(&Trigger::OnCounterAdded {
-                        filter: TargetFilter::SelfCard,
-                    });
-                    for eff in effects {
-                        batch.push((c.clone(), eff));
-                    }
-                }
-                if card_exists {
-                    let effects = c.trigger_by(&Trigger::OnCounterAdded {
-                        filter: TargetFilter::ControllerCreature,
-                    });
-                    for eff in effects {
-                        batch.push((c.clone(), eff));
-                    }
-                }
+            // Az "első alkalom" feltételét a kártya `FirstTimeThisTurn` feltétele nézi
+            (Trigger::OnTargetedFirstTimeEachTurn { filter }, GameEvent::Targeted(id)) => {
+                permanent(filter, *id)
             }
-            for (source_card, eff) in batch {
-                let mut effect_to_push = eff.clone();
-                if let Effect::DrawCardsCounted = eff {
-                    let n = count;
-                    info!(
-                        "    Converting DrawCardsCounted to DrawCards({}) for '{}'",
-                        n, source_card.name
-                    );
-                    effect_to_push = Effect::DrawCards {
-                        count: n,
-                        player: PlayerSelector::Controller,
-                    };
-                }
-                match effect_to_push {
-                    Effect::Delayed {
-                        effect,
-                        phase,
-                        deps,
-                    } => {
-                        let id = self.schedule_delayed(*effect.clone(), phase, deps.clone());
-                        info!(
-                            "    -> Scheduled delayed effect #{} from OnCounterAdded trigger",
-                            id
-                        );
-                    }
-                    e => {
-                        let prio = match &e {
-                            Effect::ModifyStats { .. } | Effect::Proliferate { .. } => 2,
-                            _ => 1,
-                        };
-                        info!(
-                            "    -> Pushing TriggeredAbility to stack (prio={}), effect={:?}",
-                            prio, e
-                        );
-                        self.push(
-                            StackEntry::TriggeredAbility {
-                                source: Some(source_card.clone()),
-                                effect: e,
-                                controller,
-                            },
-                            prio,
-                        );
-                    }
-                }
+            // A manát adó permanens a saját `ControllerCreature` triggerét nem váltja ki
+            (Trigger::OnAddMana { filter }, GameEvent::ManaAdded(id)) => {
+                (listener.card_id != *id || *filter == TargetFilter::SelfCard)
+                    && permanent(filter, *id)
             }
-        }
-
-        // A battlefield kártyáin végigmegyünk
-        let mut batch = Vec::new();
-        for card in battlefield.iter_mut() {
-            let effects = match &event {
-                GameEvent::SpellResolved(_spell_name) => {
-                    card.trigger_by(&crate::app::card_attribute::Trigger::OnCastResolved)
-                }
-                GameEvent::CreatureDied(_) => {
-                    card.trigger_by(&crate::app::card_attribute::Trigger::OnDeath {
-                        filter: TargetFilter::SelfCard,
-                    })
-                }
-                GameEvent::TurnEnded => {
-                    card.trigger_by(&crate::app::card_attribute::Trigger::AtPhase {
-                        phase: GamePhase::End,
-                        player: PlayerSelector::AnyPlayer,
-                    })
-                }
-                GameEvent::PhaseChange(p) => {
-                    card.trigger_by(&crate::app::card_attribute::Trigger::AtPhase {
-                        phase: *p,
-                        player: PlayerSelector::AnyPlayer,
-                    })
-                }
-                _ => Vec::new(),
-            };
-            if !effects.is_empty() {
-                debug!(
-                    "  Card '{}': {} trigger-effect(s)",
-                    card.name,
-                    effects.len()
-                );
+            (Trigger::OnCounterAdded { filter }, GameEvent::CounterAdded(id, _, _)) => {
+                permanent(filter, *id)
+            }
+            (Trigger::OnCycle { filter }, GameEvent::Cycled { card, controller }) => {
+                *controller == listener.controller && matches(filter, card, Zone::Graveyard)
             }
-            for eff in effects {
-                debug!("    effect => {:?}", eff);
-                batch.push((card.clone(), eff));
+            (Trigger::AtPhase { phase, player }, GameEvent::PhaseChange(p)) => {
+                phase == p && player_matches(player, self.active_player)
             }
+            (
+                Trigger::AtPhase {
+                    phase: GamePhase::End,
+                    player,
+                },
+                GameEvent::TurnEnded,
+            ) => player_matches(player, self.active_player),
+            // Az `OnCastResolved` a varázslat saját hatása, a `resolve_stack` futtatja
+            _ => false,
         }
+    }
 
-        self.reset_priority();
-
-        // A begyűjtött effectek stackre rakása / delayed schedule
-        for (source_card, eff) in batch {
-            match eff {
-                Effect::Delayed {
-                    effect,
-                    phase,
-                    deps,
-                } => {
-                    let id = self.schedule_delayed(*effect.clone(), phase, deps.clone());
-                    info!(
-                        "    -> Scheduled delayed effect #{} from normal trigger",
-                        id
-                    );
-                }
-                e => {
-                    let prio = match &e {
-                        Effect::ModifyStats { .. } | Effect::Proliferate { .. } => 2,
-                        _ => 1,
-                    };
-                    info!(
-                        "    -> Pushing TriggeredAbility to stack (prio={}), effect={:?}",
-                        prio, e
-                    );
-                    self.push(
-                        StackEntry::TriggeredAbility {
-                            source: Some(source_card),
-                            effect: e,
-                            controller,
-                        },
-                        prio,
-                    );
-                }
+    /// A figyelő eseményre feliratkozott triggerei (mindegyik egyszer).
+    fn subscribed_triggers(
+        &self,
+        listener: &Card,
+        event: &GameEvent,
+        actor: Player,
+        subject: &dyn Fn(u64) -> Option<Card>,
+    ) -> Vec<Trigger> {
+        let mut subscribed: Vec<Trigger> = Vec::new();
+        for trigger in &listener.triggers {
+            if !subscribed.contains(trigger)
+                && self.subscribes(trigger, listener, event, actor, subject)
+            {
+                subscribed.push(trigger.clone());
             }
         }
+        subscribed
     }
 
-    /// BFS/DFS jellegű trigger-lánc bejárás a belső `battlefield_creatures` map-en
-    pub fn trigger_event_tree(&mut self, event: GameEvent, controller: Player) {
-        info!(
-            "trigger_event_tree() -> event={:?}, BFS/DFS-based. Searching root permanents...",
-            event
-        );
+    /// A feliratkozott triggerek kiváltása a figyelő lapon.
+    fn fire_triggers(listener: &mut Card, triggers: Vec<Trigger>) -> Vec<(Card, Effect)> {
+        let mut fired = Vec::new();
+        for trigger in triggers {
+            for eff in listener.trigger_by(&trigger) {
+                debug!("  '{}' {:?} => {:?}", listener.name, trigger, eff);
+                fired.push((listener.clone(), eff));
+            }
+        }
+        fired
+    }
 
-        let root_ids: Vec<u64> = self
-            .battlefield_creatures
-            .values()
-            .filter(|c| c.attached_to.is_none())
-            .map(|c| c.card_id)
+    /// Az esemény összes kiváltott triggere, figyelőnként id szerinti sorrendben.
+    fn collect_event_triggers(
+        &mut self,
+        event: &GameEvent,
+        external: &mut [Card],
+        actor: Player,
+    ) -> Vec<(Card, Effect)> {
+        let untracked: Vec<Card> = external
+            .iter()
+            .filter(|c| !self.battlefield_creatures.contains_key(&c.card_id))
+            .cloned()
             .collect();
+        let lookup = |gre: &Gre, id: u64| {
+            gre.battlefield_creatures
+                .get(&id)
+                .or_else(|| untracked.iter().find(|c| c.card_id == id))
+                .cloned()
+        };
 
-        debug!("  Found {} root(s): {:?}", root_ids.len(), root_ids);
-
-        for rid in root_ids {
-            self.traverse_trigger_tree(rid, &event, controller);
+        let mut batch = Vec::new();
+        let mut ids: Vec<u64> = self.battlefield_creatures.keys().copied().collect();
+        ids.sort();
+        for id in ids {
+            let triggers = match self.battlefield_creatures.get(&id) {
+                Some(listener) => {
+                    self.subscribed_triggers(listener, event, actor, &|sid| lookup(self, sid))
+                }
+                None => continue,
+            };
+            if let Some(listener) = self.battlefield_creatures.get_mut(&id) {
+                batch.extend(Self::fire_triggers(listener, triggers));
+            }
         }
 
-        self.reset_priority();
-    }
-
-    pub fn traverse_trigger_tree(&mut self, card_id: u64, event: &GameEvent, controller: Player) {
-        debug!("    traverse_trigger_tree() -> card_id={}", card_id);
-
-        let mut card = if let Some(card) = self.battlefield_creatures.remove(&card_id) {
-            card
-        } else {
-            debug!("      -> Card not found in battlefield_creatures, returning.");
-            return;
+        // Az esemény saját lapja már nincs a battlefielden (utolsó ismert állapot)
+        let event_card_id = match event {
+            GameEvent::CreatureDied(card) | GameEvent::Cycled { card, .. } => {
+                let mut card = card.clone();
+                let triggers =
+                    self.subscribed_triggers(&card, event, actor, &|sid| lookup(self, sid));
+                batch.extend(Self::fire_triggers(&mut card, triggers));
+                Some(card.card_id)
+            }
+            _ => None,
         };
 
-        let triggered_effects = self.event_to_triggers(event, &mut card);
-
-        if !triggered_effects.is_empty() {
-            debug!(
-                "      -> card '{}' triggered {} effect(s)",
-                card.name,
-                triggered_effects.len()
-            );
+        for card in external.iter_mut() {
+            if self.battlefield_creatures.contains_key(&card.card_id)
+                || event_card_id == Some(card.card_id)
+            {
+                continue;
+            }
+            let triggers = self.subscribed_triggers(card, event, actor, &|sid| lookup(self, sid));
+            batch.extend(Self::fire_triggers(card, triggers));
         }
+        batch
+    }
+
+    /// Intervening-if ellenőrzés a kiváltáskor, a trigger forrásának szemszögéből.
+    fn filter_intervening_if(
+        &self,
+        batch: Vec<(Card, Effect)>,
+        event: &GameEvent,
+    ) -> Vec<(Card, Effect)> {
+        batch
+            .into_iter()
+            .filter_map(|(source, eff)| {
+                let ctx = ConditionContext::new(source.controller)
+                    .with_source(Some(source.card_id))
+                    .with_event(event);
+                let eff = self.check_intervening_if(eff, &ctx)?;
+                Some((source, eff))
+            })
+            .collect()
+    }
 
-        for eff in triggered_effects {
+    /// APNAP: az aktív játékos triggerei kerülnek előbb a stackre (a sorrenden belül
+    /// a begyűjtés sorrendje marad); a késleltetett effekteket ütemezzük.
+    fn put_triggers_on_stack(&mut self, mut batch: Vec<(Card, Effect)>, event: &GameEvent) {
+        let active = self.active_player;
+        batch.sort_by_key(|(source, _)| source.controller != active);
+        for (source_card, eff) in batch {
+            let controller = source_card.controller;
+            self.triggered_this_turn
+                .insert((source_card.card_id, event.kind()));
+            let eff = match event {
+                GameEvent::CounterAdded(_, _, count) => Self::bind_counted_draw(eff, *count),
+                _ => eff,
+            };
             match eff {
                 Effect::Delayed {
                     effect,
                     phase,
                     deps,
                 } => {
-                    let id = self.schedule_delayed(*effect.clone(), phase, deps);
+                    let id = self.schedule_delayed(*effect, phase, deps);
                     info!(
-                        "        Scheduled delayed effect #{} from traverse_trigger_tree",
-                        id
+                        "    -> Scheduled delayed effect #{} from '{}'",
+                        id, source_card.name
                     );
                 }
                 e => {
-                    info!("        Push TriggeredAbility on stack. effect={:?}", e);
+                    // Minden trigger egy prioritáson: a sorrendet csak az APNAP adja
+                    info!(
+                        "    -> Pushing TriggeredAbility to stack (controller={:?}), effect={:?}",
+                        controller, e
+                    );
                     self.push(
                         StackEntry::TriggeredAbility {
-                            source: Some(card.clone()),
+                            source: Some(source_card),
                             effect: e,
                             controller,
                         },
@@ -347,70 +313,42 @@ impl Gre {
                 }
             }
         }
+    }
 
-        debug!(
-            "      -> inserting card '{}' (id={}) back to battlefield_creatures",
-            card.name, card_id
-        );
-        self.battlefield_creatures.insert(card_id, card);
-
-        let child_ids: Vec<u64> = self
-            .battlefield_creatures
-            .values()
-            .filter(|c2| c2.attached_to == Some(card_id))
-            .map(|c2| c2.card_id)
-            .collect();
-
-        debug!(
-            "      -> found {} child(ren): {:?}",
-            child_ids.len(),
-            child_ids
-        );
-        for cid in child_ids {
-            self.traverse_trigger_tree(cid, event, controller);
+    /// `DrawCardsCounted`: annyi lap, ahány counter a kiváltó eseménnyel került fel
+    /// (a `Conditional` ágain belül is).
+    fn bind_counted_draw(effect: Effect, count: u32) -> Effect {
+        match effect {
+            Effect::DrawCardsCounted => Effect::DrawCards {
+                count,
+                player: PlayerSelector::Controller,
+            },
+            Effect::Conditional {
+                condition,
+                effect_if_true,
+                effect_if_false,
+            } => Effect::Conditional {
+                condition,
+                effect_if_true: Box::new(Self::bind_counted_draw(*effect_if_true, count)),
+                effect_if_false: effect_if_false
+                    .map(|e| Box::new(Self::bind_counted_draw(*e, count))),
+            },
+            other => other,
         }
     }
 
-    fn event_to_triggers(&mut self, event: &GameEvent, card: &mut Card) -> Vec<Effect> {
-        debug!(
-            "        event_to_triggers(): event={:?}, card='{}'",
-            event, card.name
-        );
-        let res = match event {
-            GameEvent::SpellResolved(_spell_name) => {
-                card.trigger_by(&crate::app::card_attribute::Trigger::OnCastResolved)
-            }
-            GameEvent::CreatureDied(died_card) => {
-                if died_card.card_id == card.card_id {
-                    card.trigger_by(&crate::app::card_attribute::Trigger::OnDeath {
-                        filter: TargetFilter::SelfCard,
-                    })
-                } else {
-                    Vec::new()
-                }
-            }
-            GameEvent::TurnEnded => {
-                card.trigger_by(&crate::app::card_attribute::Trigger::AtPhase {
-                    phase: GamePhase::End,
-                    player: PlayerSelector::AnyPlayer,
-                })
-            }
-            GameEvent::PhaseChange(p) => {
-                card.trigger_by(&crate::app::card_attribute::Trigger::AtPhase {
-                    phase: *p,
-                    player: PlayerSelector::AnyPlayer,
-                })
-            }
-            _ => Vec::new(),
-        };
-
-        if !res.is_empty() {
-            debug!(
-                "          -> card '{}' returned {} effect(s)",
-                card.name,
-                res.len()
-            );
+    /// Nem harci sebzés eseménye; a forrás a feloldás alatt álló lap.
+    pub fn emit_damage_dealt(&mut self, target: Target, amount: i32) {
+        if amount <= 0 {
+            return;
         }
-        res
+        let event = GameEvent::DamageDealt {
+            source: self.current_source_card.as_ref().map(|c| c.card_id),
+            target,
+            amount: amount as u32,
+            combat: false,
+        };
+        let controller = self.resolving_controller;
+        self.trigger_event(event, &mut Vec::new(), controller);
     }
 }
@@ -0,0 +1,173 @@
+// src/app/gre/zones.rs
+
+//! Rejtett zónák: könyvtár, kéz és száműzetés (exile) játékosonként.
+//! A kézen kívüli kijátszási engedélyek a `permissions` modulban vannak.
+//! A könyvtárat és a kezet csak annyira ismerjük, amennyit beolvastunk; a kézméret
+//! (`hand_size`) ettől függetlenül mindig pontos. Az ismeretlen lapok, ha nyilvános
+//! zónába kerülnek (száműzetés, temető), `Card::hidden` helyőrzőként jelennek meg.
+
+use tracing::{debug, info};
+
+use crate::app::card_attribute::Duration;
+use crate::app::card_library::Card;
+use crate::app::game_state::{GameEvent, Player};
+use crate::app::gre::{CastPermission, CastZone, Gre};
+
+impl Gre {
+    /// Az ismert könyvtár beállítása (a lista vége a könyvtár teteje)
+    pub fn set_library(&mut self, player: Player, cards: Vec<Card>) {
+        debug!("set_library() -> {:?}: {} card(s)", player, cards.len());
+        self.player_mut(player).library = cards;
+    }
+
+    /// A kéz a képernyőről: `hand_size` lap, ebből a felismertek a `cards`.
+    pub fn set_hand(&mut self, player: Player, hand_size: u32, cards: Vec<Card>) {
+        let state = self.player_mut(player);
+        state.hand_size = hand_size;
+        state.hand = cards;
+        state.hand.truncate(hand_size as usize);
+        debug!(
+            "set_hand() -> {:?}: {} card(s), {} known",
+            player,
+            hand_size,
+            state.hand.len()
+        );
+    }
+
+    /// Lap húzása: ismert könyvtárból a lap a kézbe kerül, egyébként csak a kézméret nő.
+    pub fn draw_cards(&mut self, player: Player, count: u32) {
+        let state = self.player_mut(player);
+        for _ in 0..count {
+            if let Some(card) = state.library.pop() {
+                debug!("    {:?} draws '{}'", player, card.name);
+                state.hand.push(card);
+            }
+            state.hand_size += 1;
+        }
+        info!(
+            "    {:?} draws {} card(s) -> hand {}",
+            player, count, state.hand_size
+        );
+    }
+
+    /// Eldobás a kézből: előbb az ismeretlen lapok (helyőrzőként), aztán a legutóbb
+    /// ismertek kerülnek a temetőbe.
+    pub fn discard(&mut self, player: Player, count: u32) {
+        let (unknown, discarded) = {
+            let state = self.player_mut(player);
+            let count = count.min(state.hand_size) as usize;
+            let unknown = (state.hand_size as usize)
+                .saturating_sub(state.hand.len())
+                .min(count);
+            state.hand_size -= count as u32;
+            let keep = state.hand.len().saturating_sub(count - unknown);
+            (unknown, state.hand.split_off(keep))
+        };
+        info!(
+            "    {:?} discards {} card(s) -> hand {}",
+            player,
+            count,
+            self.player(player).hand_size
+        );
+        for _ in 0..unknown {
+            self.put_into_graveyard(Card::hidden().with_controller(player));
+        }
+        for card in discarded {
+            self.put_into_graveyard(card);
+        }
+    }
+
+    /// Ciklizálás: a kéz `index`-edik lapja a temetőbe kerül, helyette húzunk egyet.
+    /// A ciklizálás költségét a hívó fizeti; `false`, ha nincs ilyen lap a kézben.
+    pub fn cycle(&mut self, player: Player, index: usize) -> bool {
+        let state = self.player_mut(player);
+        if index >= state.hand.len() {
+            return false;
+        }
+        let mut card = state.hand.remove(index);
+        state.hand_size = state.hand_size.saturating_sub(1);
+        card.controller = player;
+        if card.card_id == 0 {
+            card.card_id = self.next_card_id;
+            self.next_card_id += 1;
+        }
+        info!("    {:?} cycles '{}'", player, card.name);
+        self.put_into_graveyard(card.clone());
+        self.draw_cards(player, 1);
+        self.trigger_event(
+            GameEvent::Cycled {
+                card,
+                controller: player,
+            },
+            &mut Vec::new(),
+            player,
+        );
+        true
+    }
+
+    /// A könyvtár felső `count` lapja száműzetésbe kerül; a száműzött lapok id-t kapnak.
+    /// Az ismert könyvtár alatt ismeretlen lapok vannak: ezek helyőrzőként kerülnek ki.
+    pub fn exile_top(&mut self, player: Player, count: u32) -> Vec<u64> {
+        let mut exiled = Vec::new();
+        for _ in 0..count {
+            let mut card = self
+                .player_mut(player)
+                .library
+                .pop()
+                .unwrap_or_else(|| Card::hidden().with_controller(player));
+            if card.card_id == 0 {
+                card.card_id = self.next_card_id;
+                self.next_card_id += 1;
+            }
+            info!(
+                "    '{}' (id={}) is exiled from the top of the library",
+                card.name, card.card_id
+            );
+            exiled.push(card.card_id);
+            self.player_mut(player).exile.push(card);
+        }
+        exiled
+    }
+
+    /// Scry: a könyvtár felső `count` ismert lapjáról a `DecisionProvider` dönt; az
+    /// alulra tettek a könyvtár aljára kerülnek, a többi a tetején marad, sorrendben.
+    pub fn scry_library(&mut self, player: Player, count: u32) {
+        let library = &mut self.player_mut(player).library;
+        let top = library.len().saturating_sub(count as usize);
+        let revealed = library.split_off(top);
+        if revealed.is_empty() {
+            debug!("    {:?}'s known library is empty", player);
+            return;
+        }
+        let bottom = self.scry(&revealed);
+        let (to_bottom, to_top): (Vec<_>, Vec<_>) =
+            revealed.into_iter().zip(bottom).partition(|(_, b)| *b);
+        info!(
+            "    {:?} scries {}: {} to the bottom",
+            player,
+            to_bottom.len() + to_top.len(),
+            to_bottom.len()
+        );
+        let library = &mut self.player_mut(player).library;
+        library.splice(0..0, to_bottom.into_iter().map(|(c, _)| c));
+        library.extend(to_top.into_iter().map(|(c, _)| c));
+    }
+
+    /// Impulse draw: a felső `count` lapot száműzzük, és a `player` kijátszhatja őket.
+    pub fn exile_then_play(
+        &mut self,
+        owner: Player,
+        count: u32,
+        player: Player,
+        duration: &Duration,
+    ) {
+        for card_id in self.exile_top(owner, count) {
+            self.grant_cast_permission(CastPermission::new(
+                card_id,
+                player,
+                CastZone::Exile,
+                duration,
+            ));
+        }
+    }
+}
@@ -0,0 +1,214 @@
+// app/input_driver.rs
+
+//! Pluggable input backends. `multiplatform::move_cursor`/`click_left`/... go through
+//! the globally selected `InputDriver`: enigo for real play, a dry-run logger, or an
+//! assertion driver for tests.
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+use enigo::Key;
+use once_cell::sync::Lazy;
+use thiserror::Error;
+use tracing::{error, info};
+
+/// Errors an input driver can report.
+#[derive(Debug, Error)]
+pub enum InputError {
+    #[error("Input backend error: {0}")]
+    Backend(String),
+    #[error("Unexpected input action: expected {expected}, got {actual:?}")]
+    Unexpected { expected: String, actual: InputAction },
+}
+
+/// One input action, with the cursor position where it happened.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputAction {
+    MoveMouse { x: i32, y: i32 },
+    ClickLeft { x: i32, y: i32 },
+    ClickRight { x: i32, y: i32 },
+    Key(Key),
+}
+
+/// An input backend.
+pub trait InputDriver: Send + Sync {
+    fn move_mouse(&self, x: i32, y: i32) -> Result<(), InputError>;
+    fn click_left(&self) -> Result<(), InputError>;
+    fn click_right(&self) -> Result<(), InputError>;
+    fn send_key(&self, key: Key) -> Result<(), InputError>;
+    /// Current cursor position (used by calibration).
+    fn cursor_position(&self) -> Result<(i32, i32), InputError>;
+}
+
+/// Last cursor position plus the performed actions with their time offsets.
+#[derive(Debug, Default)]
+struct ActionLog {
+    cursor: (i32, i32),
+    actions: Vec<(Duration, InputAction)>,
+}
+
+impl ActionLog {
+    /// Turns a raw command into a positioned `InputAction` and stores it.
+    fn push(&mut self, elapsed: Duration, cmd: Command) -> InputAction {
+        let (x, y) = self.cursor;
+        let action = match cmd {
+            Command::Move(nx, ny) => {
+                self.cursor = (nx, ny);
+                InputAction::MoveMouse { x: nx, y: ny }
+            }
+            Command::Left => InputAction::ClickLeft { x, y },
+            Command::Right => InputAction::ClickRight { x, y },
+            Command::Key(k) => InputAction::Key(k),
+        };
+        self.actions.push((elapsed, action));
+        action
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Command {
+    Move(i32, i32),
+    Left,
+    Right,
+    Key(Key),
+}
+
+/// Does not touch the real mouse/keyboard; logs every intended action
+/// with a timestamp and screen coordinates.
+pub struct DryRunDriver {
+    started: Instant,
+    log: Mutex<ActionLog>,
+}
+
+impl Default for DryRunDriver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DryRunDriver {
+    pub fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            log: Mutex::new(ActionLog::default()),
+        }
+    }
+
+    /// Every action so far, with its offset from the driver's creation.
+    pub fn actions(&self) -> Vec<(Duration, InputAction)> {
+        self.log.lock().unwrap().actions.clone()
+    }
+
+    fn record(&self, cmd: Command) -> Result<(), InputError> {
+        let elapsed = self.started.elapsed();
+        let action = self.log.lock().unwrap().push(elapsed, cmd);
+        info!("[dry-run +{:.3}s] {:?}", elapsed.as_secs_f64(), action);
+        Ok(())
+    }
+}
+
+impl InputDriver for DryRunDriver {
+    fn move_mouse(&self, x: i32, y: i32) -> Result<(), InputError> {
+        self.record(Command::Move(x, y))
+    }
+    fn click_left(&self) -> Result<(), InputError> {
+        self.record(Command::Left)
+    }
+    fn click_right(&self) -> Result<(), InputError> {
+        self.record(Command::Right)
+    }
+    fn send_key(&self, key: Key) -> Result<(), InputError> {
+        self.record(Command::Key(key))
+    }
+    fn cursor_position(&self) -> Result<(i32, i32), InputError> {
+        Ok(self.log.lock().unwrap().cursor)
+    }
+}
+
+/// Test driver: checks every action against a queue of expected ones.
+/// Unexpected actions return `InputError::Unexpected` and are reported by `assert_done`.
+#[derive(Default)]
+pub struct AssertionDriver {
+    expected: Mutex<VecDeque<InputAction>>,
+    log: Mutex<ActionLog>,
+    failures: Mutex<Vec<InputError>>,
+    strict: bool,
+}
+
+impl AssertionDriver {
+    /// Only records; nothing is rejected.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Every action must match the next element of `expected`.
+    pub fn expecting(expected: impl IntoIterator<Item = InputAction>) -> Self {
+        Self {
+            expected: Mutex::new(expected.into_iter().collect()),
+            strict: true,
+            ..Self::default()
+        }
+    }
+
+    pub fn actions(&self) -> Vec<InputAction> {
+        self.log.lock().unwrap().actions.iter().map(|(_, a)| *a).collect()
+    }
+
+    /// Panics if an action was rejected or an expected one never happened.
+    pub fn assert_done(&self) {
+        let failures = self.failures.lock().unwrap();
+        assert!(failures.is_empty(), "unexpected input actions: {:?}", *failures);
+        let remaining = self.expected.lock().unwrap();
+        assert!(remaining.is_empty(), "expected input actions not performed: {:?}", *remaining);
+    }
+
+    fn record(&self, cmd: Command) -> Result<(), InputError> {
+        let action = self.log.lock().unwrap().push(Duration::ZERO, cmd);
+        if !self.strict {
+            return Ok(());
+        }
+        let expected = self.expected.lock().unwrap().pop_front();
+        if expected == Some(action) {
+            return Ok(());
+        }
+        let expected = expected.map_or("nothing".into(), |e| format!("{:?}", e));
+        error!("[assertion-driver] expected {}, got {:?}", expected, action);
+        self.failures.lock().unwrap().push(InputError::Unexpected {
+            expected: expected.clone(),
+            actual: action,
+        });
+        Err(InputError::Unexpected { expected, actual: action })
+    }
+}
+
+impl InputDriver for AssertionDriver {
+    fn move_mouse(&self, x: i32, y: i32) -> Result<(), InputError> {
+        self.record(Command::Move(x, y))
+    }
+    fn click_left(&self) -> Result<(), InputError> {
+        self.record(Command::Left)
+    }
+    fn click_right(&self) -> Result<(), InputError> {
+        self.record(Command::Right)
+    }
+    fn send_key(&self, key: Key) -> Result<(), InputError> {
+        self.record(Command::Key(key))
+    }
+    fn cursor_position(&self) -> Result<(i32, i32), InputError> {
+        Ok(self.log.lock().unwrap().cursor)
+    }
+}
+
+static INPUT_DRIVER: Lazy<RwLock<Option<Arc<dyn InputDriver>>>> = Lazy::new(|| RwLock::new(None));
+
+/// Replaces the globally used input driver.
+pub fn set_input_driver(driver: Arc<dyn InputDriver>) {
+    *INPUT_DRIVER.write().unwrap() = Some(driver);
+    info!("Input driver replaced");
+}
+
+/// The currently selected input driver, if any was selected.
+pub fn input_driver() -> Option<Arc<dyn InputDriver>> {
+    INPUT_DRIVER.read().unwrap().clone()
+}
@@ -0,0 +1,414 @@
+// app/layout.rs
+
+//! Resolution-independent layout profiles. A profile describes the hand fan,
+//! battlefield rows, buttons and OCR regions in "canvas" units; at runtime they are
+//! scaled to the actual screen. One profile per aspect ratio ships in `layouts/`,
+//! `MTGA_LAYOUT=<file>` overrides the choice, and `calibrate-layout` fits a new one.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+
+use once_cell::sync::Lazy;
+use thiserror::Error;
+use tracing::{error, info, warn};
+
+/// Errors of loading, parsing or fitting a profile.
+#[derive(Debug, Error)]
+pub enum LayoutError {
+    #[error("Malformed layout line {line}: {content}")]
+    Parse { line: usize, content: String },
+    #[error("Layout is missing `{0}`")]
+    Missing(String),
+    #[error("Layout key `{key}` needs {expected} value(s), got {actual}")]
+    Arity { key: String, expected: usize, actual: usize },
+    #[error("Calibration needs {0}")]
+    Calibration(String),
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// How a value scales: absolute positions get offset, sizes only scaled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Axis {
+    X,
+    Y,
+    Width,
+    Height,
+}
+
+/// Highest hand size / creature count a profile describes.
+pub const MAX_SLOTS: usize = 8;
+
+/// Keys every profile must define, with their value count.
+const REQUIRED: &[(&str, usize)] = &[
+    ("button.home", 2),
+    ("button.play", 2),
+    ("button.attack", 2),
+    ("button.keep", 2),
+    ("button.mulligan", 2),
+    ("region.start_order", 4),
+    ("region.main_button", 4),
+    ("region.life.opp", 4),
+    ("region.life.own", 4),
+    ("region.banner", 4),
+    ("region.play_label", 4),
+    ("region.mulligan_buttons", 4),
+    ("region.stack_top", 4),
+    ("hand.hover_y", 1),
+    ("hand.name_y", 2),
+    ("hand.art_y", 2),
+    ("hand.ocr_x", 2),
+    ("battlefield.own.ocr_y", 2),
+    ("battlefield.own.click_y", 2),
+    ("battlefield.opp.ocr_y", 2),
+    ("battlefield.opp.click_y", 2),
+    ("battlefield.count_probe", 3),
+    ("ability.button_y", 1),
+    ("ability.step", 1),
+    ("mulligan.center_x", 1),
+    ("mulligan.step", 1),
+    ("mulligan.half_name", 1),
+    ("mulligan.click_y", 1),
+    ("mulligan.ocr_y", 2),
+];
+
+/// Axis of every value of `key` (`len` values).
+pub fn axes(key: &str, len: usize) -> Vec<Axis> {
+    if key.starts_with("button.") || key.starts_with("point.") {
+        vec![Axis::X, Axis::Y]
+    } else if key.starts_with("region.") {
+        vec![Axis::X, Axis::Y, Axis::X, Axis::Y]
+    } else if key.ends_with("_y") {
+        vec![Axis::Y; len]
+    } else if matches!(
+        key,
+        "hand.ocr_x"
+            | "battlefield.count_probe"
+            | "ability.step"
+            | "mulligan.step"
+            | "mulligan.half_name"
+    ) {
+        vec![Axis::Width; len]
+    } else {
+        vec![Axis::X; len]
+    }
+}
+
+/// A loaded layout profile.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayoutProfile {
+    pub name: String,
+    /// Width and height of the coordinate space the values are given in.
+    pub canvas: (f64, f64),
+    values: BTreeMap<String, Vec<f64>>,
+}
+
+impl LayoutProfile {
+    /// Parses `key = v1 v2 ...` lines; `#` starts a comment.
+    pub fn parse(text: &str) -> Result<Self, LayoutError> {
+        let mut name = String::new();
+        let mut canvas = None;
+        let mut values = BTreeMap::new();
+        for (i, raw) in text.lines().enumerate() {
+            let line = raw.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            let bad = || LayoutError::Parse { line: i + 1, content: raw.to_string() };
+            let (key, value) = line.split_once('=').ok_or_else(bad)?;
+            let (key, value) = (key.trim(), value.trim());
+            if key == "name" {
+                name = value.to_string();
+                continue;
+            }
+            let nums = value
+                .split_whitespace()
+                .map(|v| v.parse::<f64>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| bad())?;
+            if key == "canvas" {
+                match nums.as_slice() {
+                    [w, h] if *w > 0.0 && *h > 0.0 => canvas = Some((*w, *h)),
+                    _ => return Err(bad()),
+                }
+            } else {
+                values.insert(key.to_string(), nums);
+            }
+        }
+        let canvas = canvas.ok_or_else(|| LayoutError::Missing("canvas".into()))?;
+        let profile = Self { name, canvas, values };
+        profile.validate()?;
+        Ok(profile)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, LayoutError> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), LayoutError> {
+        fs::write(path, self.to_text())?;
+        Ok(())
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut out = format!(
+            "# MTGA layout profile\nname = {}\ncanvas = {} {}\n\n",
+            self.name,
+            format_value(self.canvas.0),
+            format_value(self.canvas.1)
+        );
+        for (key, nums) in &self.values {
+            let nums: Vec<String> = nums.iter().map(|v| format_value(*v)).collect();
+            out.push_str(&format!("{} = {}\n", key, nums.join(" ")));
+        }
+        out
+    }
+
+    fn validate(&self) -> Result<(), LayoutError> {
+        let check = |key: &str, expected: usize| match self.values.get(key) {
+            None => Err(LayoutError::Missing(key.to_string())),
+            Some(v) if v.len() != expected => Err(LayoutError::Arity {
+                key: key.to_string(),
+                expected,
+                actual: v.len(),
+            }),
+            Some(_) => Ok(()),
+        };
+        for (key, expected) in REQUIRED {
+            check(key, *expected)?;
+        }
+        for n in 1..=MAX_SLOTS {
+            check(&format!("hand.{}", n), n)?;
+            check(&format!("battlefield.{}", n), 2 * n)?;
+        }
+        Ok(())
+    }
+
+    /// Built-in profiles, in `layouts/`.
+    pub fn builtin() -> Vec<LayoutProfile> {
+        [
+            include_str!("../../layouts/16x9.layout"),
+            include_str!("../../layouts/16x10.layout"),
+            include_str!("../../layouts/21x9.layout"),
+        ]
+        .iter()
+        .filter_map(|text| match Self::parse(text) {
+            Ok(p) => Some(p),
+            Err(e) => {
+                error!("Built-in layout is broken: {}", e);
+                None
+            }
+        })
+        .collect()
+    }
+
+    /// The built-in profile whose aspect ratio is closest to the screen's.
+    pub fn for_screen(screen_width: u32, screen_height: u32) -> LayoutProfile {
+        let aspect = screen_width as f64 / screen_height.max(1) as f64;
+        Self::builtin()
+            .into_iter()
+            .min_by(|a, b| (a.aspect() - aspect).abs().total_cmp(&(b.aspect() - aspect).abs()))
+            .expect("no built-in layout profiles")
+    }
+
+    pub fn aspect(&self) -> f64 {
+        self.canvas.0 / self.canvas.1
+    }
+
+    /// Raw values of `key`, in canvas units.
+    pub fn get(&self, key: &str) -> Option<&[f64]> {
+        self.values.get(key).map(|v| v.as_slice())
+    }
+
+    /// Sets (or adds) a raw value, e.g. a calibrated `point.*`.
+    pub fn set(&mut self, key: &str, values: Vec<f64>) {
+        self.values.insert(key.to_string(), values);
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.values.keys().map(|k| k.as_str())
+    }
+
+    /// `i`-th value of `key`; validated keys always have it.
+    fn num(&self, key: &str, i: usize) -> f64 {
+        match self.values.get(key).and_then(|v| v.get(i)) {
+            Some(v) => *v,
+            None => {
+                error!("Layout `{}` has no value #{} for `{}`", self.name, i, key);
+                0.0
+            }
+        }
+    }
+
+    /// Canvas x → screen pixels.
+    pub fn sx(&self, x: f64, screen_width: u32) -> f64 {
+        x / self.canvas.0 * screen_width as f64
+    }
+
+    /// Canvas y → screen pixels.
+    pub fn sy(&self, y: f64, screen_height: u32) -> f64 {
+        y / self.canvas.1 * screen_height as f64
+    }
+
+    /// A button's centre on screen.
+    pub fn button(&self, name: &str, screen_width: u32, screen_height: u32) -> (i32, i32) {
+        let key = format!("button.{}", name);
+        (
+            self.sx(self.num(&key, 0), screen_width).ceil() as i32,
+            self.sy(self.num(&key, 1), screen_height).ceil() as i32,
+        )
+    }
+
+    /// An OCR region on screen as (x1, y1, x2, y2), floored.
+    pub fn region(&self, name: &str, screen_width: u32, screen_height: u32) -> (u32, u32, u32, u32) {
+        let key = format!("region.{}", name);
+        (
+            self.sx(self.num(&key, 0), screen_width).floor() as u32,
+            self.sy(self.num(&key, 1), screen_height).floor() as u32,
+            self.sx(self.num(&key, 2), screen_width).floor() as u32,
+            self.sy(self.num(&key, 3), screen_height).floor() as u32,
+        )
+    }
+
+    /// Single value of `key`, in canvas units.
+    pub fn value(&self, key: &str) -> f64 {
+        self.num(key, 0)
+    }
+
+    /// Two values of `key` (a band or an offset pair), in canvas units.
+    pub fn pair(&self, key: &str) -> (f64, f64) {
+        (self.num(key, 0), self.num(key, 1))
+    }
+
+    /// Card centres of a `count`-card hand, in canvas units.
+    pub fn hand_centers(&self, count: usize) -> Option<&[f64]> {
+        self.get(&format!("hand.{}", count))
+    }
+
+    /// (x1, x2) of every creature slot for `count` creatures, in canvas units.
+    pub fn battlefield_slots(&self, count: usize) -> Option<Vec<(f64, f64)>> {
+        let flat = self.get(&format!("battlefield.{}", count))?;
+        Some(flat.chunks_exact(2).map(|c| (c[0], c[1])).collect())
+    }
+
+    /// Fits a new profile for the current screen from reference points: `references`
+    /// maps `button.*`/`point.*` keys of `self` to where they actually are on screen (pixels).
+    /// Each axis gets its own least-squares scale and offset, so windowed mode and
+    /// letterboxing are corrected as well. The result uses screen pixels as its canvas.
+    pub fn fit(
+        &self,
+        name: &str,
+        references: &[(String, (i32, i32))],
+        screen_width: u32,
+        screen_height: u32,
+    ) -> Result<LayoutProfile, LayoutError> {
+        let mut xs = Vec::new();
+        let mut ys = Vec::new();
+        for (key, (px, py)) in references {
+            let v = self
+                .get(key)
+                .filter(|v| v.len() == 2)
+                .ok_or_else(|| LayoutError::Missing(key.clone()))?;
+            xs.push((v[0], *px as f64));
+            ys.push((v[1], *py as f64));
+        }
+        let (ax, bx) = linear_fit(&xs).ok_or_else(|| {
+            LayoutError::Calibration("at least two reference points with different x".into())
+        })?;
+        let (ay, by) = linear_fit(&ys).ok_or_else(|| {
+            LayoutError::Calibration("at least two reference points with different y".into())
+        })?;
+        info!("Layout fit: x' = {:.4}x + {:.2}, y' = {:.4}y + {:.2}", ax, bx, ay, by);
+
+        let mut values = BTreeMap::new();
+        for (key, nums) in &self.values {
+            let mapped = nums
+                .iter()
+                .zip(axes(key, nums.len()))
+                .map(|(v, axis)| match axis {
+                    Axis::X => ax * v + bx,
+                    Axis::Y => ay * v + by,
+                    Axis::Width => ax * v,
+                    Axis::Height => ay * v,
+                })
+                .collect();
+            values.insert(key.clone(), mapped);
+        }
+        Ok(LayoutProfile {
+            name: name.to_string(),
+            canvas: (screen_width as f64, screen_height as f64),
+            values,
+        })
+    }
+}
+
+/// At most 6 decimals, trailing zeros dropped.
+fn format_value(v: f64) -> String {
+    let s = format!("{:.6}", v);
+    s.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+/// Least-squares `to = a * from + b`; `None` if `from` has no spread.
+fn linear_fit(points: &[(f64, f64)]) -> Option<(f64, f64)> {
+    let n = points.len() as f64;
+    if points.len() < 2 {
+        return None;
+    }
+    let mean_x = points.iter().map(|p| p.0).sum::<f64>() / n;
+    let mean_y = points.iter().map(|p| p.1).sum::<f64>() / n;
+    let var: f64 = points.iter().map(|p| (p.0 - mean_x).powi(2)).sum();
+    if var < 1e-9 {
+        return None;
+    }
+    let cov: f64 = points.iter().map(|p| (p.0 - mean_x) * (p.1 - mean_y)).sum();
+    let a = cov / var;
+    Some((a, mean_y - a * mean_x))
+}
+
+/// Points the user is asked to point at during calibration, spread over the screen.
+pub const CALIBRATION_POINTS: &[(&str, &str)] = &[
+    ("button.home", "the Home button (top left)"),
+    ("button.play", "the Play button (bottom right)"),
+    ("button.mulligan", "the Mulligan button on the mulligan screen"),
+    ("button.keep", "the Keep button on the mulligan screen"),
+];
+
+static LAYOUT: Lazy<RwLock<Option<Arc<LayoutProfile>>>> = Lazy::new(|| RwLock::new(None));
+
+/// Replaces the globally used layout profile.
+pub fn set_layout(profile: Arc<LayoutProfile>) {
+    info!("Layout profile `{}` selected", profile.name);
+    *LAYOUT.write().unwrap() = Some(profile);
+}
+
+/// The selected layout profile; the built-in 16:9 one until something else is selected.
+pub fn layout() -> Arc<LayoutProfile> {
+    if let Some(p) = LAYOUT.read().unwrap().clone() {
+        return p;
+    }
+    let default = Arc::new(LayoutProfile::for_screen(16, 9));
+    LAYOUT.write().unwrap().get_or_insert(default).clone()
+}
+
+/// `MTGA_LAYOUT=<file>` loads a profile, otherwise the built-in one closest to the
+/// screen's aspect ratio is used.
+pub fn init_layout(screen_width: u32, screen_height: u32) -> Result<(), LayoutError> {
+    if let Ok(path) = std::env::var("MTGA_LAYOUT") {
+        let profile = LayoutProfile::load(&path)?;
+        let aspect = screen_width as f64 / screen_height.max(1) as f64;
+        if (profile.aspect() - aspect).abs() > 0.05 {
+            warn!(
+                "Layout {} is for aspect {:.3}, the screen is {:.3}",
+                path,
+                profile.aspect(),
+                aspect
+            );
+        }
+        set_layout(Arc::new(profile));
+    } else {
+        set_layout(Arc::new(LayoutProfile::for_screen(screen_width, screen_height)));
+    }
+    Ok(())
+}
@@ -0,0 +1,243 @@
+// app/mulligan.rs
+
+use crate::app::card_library::{Card, CardType, ManaCost};
+use tracing::{debug, info};
+
+/// Küszöbértékek a kezdőkéz értékeléséhez.
+#[derive(Debug, Clone)]
+pub struct MulliganConfig {
+    /// A kéz méretéhez viszonyított minimális land-arány (7 lapnál ~2 land).
+    pub min_land_ratio: f64,
+    /// A kéz méretéhez viszonyított maximális land-arány (7 lapnál ~4 land).
+    pub max_land_ratio: f64,
+    /// Ennyi pont alatt mulligant kérünk.
+    pub keep_threshold: i32,
+    /// Mulligan-onként ennyivel engedékenyebb a küszöb.
+    pub threshold_step: i32,
+    /// Ekkora (vagy kisebb) kéznél már mindenképp tartunk.
+    pub min_hand_size: usize,
+}
+
+impl Default for MulliganConfig {
+    fn default() -> Self {
+        Self {
+            min_land_ratio: 2.0 / 7.0,
+            max_land_ratio: 4.0 / 7.0,
+            keep_threshold: 10,
+            threshold_step: 6,
+            min_hand_size: 5,
+        }
+    }
+}
+
+/// Egy kezdőkéz értékelésének részletei.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HandEvaluation {
+    pub land_count: usize,
+    pub spell_count: usize,
+    pub castable_one_drops: usize,
+    /// `curve[n]` = hány n manaértékű varázslat van a kézben (7+ az utolsóban).
+    pub curve: [usize; 8],
+    /// Azok a varázslatok, amelyek színét egyik kézben lévő land sem termeli.
+    pub off_color_spells: usize,
+    pub score: i32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MulliganDecision {
+    Keep,
+    Mulligan,
+}
+
+/// Kezdőkéz-értékelő London mulligan szabályokkal.
+#[derive(Debug, Clone, Default)]
+pub struct MulliganEvaluator {
+    pub config: MulliganConfig,
+}
+
+impl MulliganEvaluator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_config(config: MulliganConfig) -> Self {
+        Self { config }
+    }
+
+    /// Az ideális land-tartomány egy adott méretű kézhez.
+    fn land_range(&self, hand_size: usize) -> (usize, usize) {
+        let min = ((hand_size as f64) * self.config.min_land_ratio).round() as usize;
+        let max = ((hand_size as f64) * self.config.max_land_ratio).round() as usize;
+        (min.max(1), max.max(min.max(1)))
+    }
+
+    /// Pontozza a kezet: land-szám, curve, kijátszható egy-dropok, színek.
+    pub fn evaluate(&self, hand: &[Card]) -> HandEvaluation {
+        self.evaluate_in_hand_of(hand, hand.len())
+    }
+
+    /// Mint az `evaluate`, de `hand` csak a felismert lapok egy `hand_size` lapos kézből:
+    /// a land-tartomány a valódi kézmérethez igazodik.
+    pub fn evaluate_in_hand_of(&self, hand: &[Card], hand_size: usize) -> HandEvaluation {
+        let lands: Vec<&Card> = hand
+            .iter()
+            .filter(|c| matches!(c.card_type, CardType::Land))
+            .collect();
+        let spells: Vec<&Card> = hand
+            .iter()
+            .filter(|c| !matches!(c.card_type, CardType::Land))
+            .collect();
+
+        // A kézben lévő landek által termelt színek
+        let mut available = ManaCost::free();
+        for land in &lands {
+            let p = land.produced_mana();
+            available.red |= p.red;
+            available.green |= p.green;
+            available.blue |= p.blue;
+            available.white |= p.white;
+            available.black |= p.black;
+        }
+
+        let mut curve = [0usize; 8];
+        let mut castable_one_drops = 0;
+        let mut off_color_spells = 0;
+        let mut early_plays = 0usize;
+        let mut expensive = 0usize;
+        for spell in &spells {
+            let cmc = spell.mana_cost.total() as usize;
+            curve[cmc.min(7)] += 1;
+            let on_color = Self::colors_covered(&spell.mana_cost, &available);
+            if !on_color {
+                off_color_spells += 1;
+            }
+            if cmc == 1 && on_color && !lands.is_empty() {
+                castable_one_drops += 1;
+            }
+            if cmc <= lands.len() + 1 && on_color {
+                early_plays += 1;
+            }
+            if cmc > 4 {
+                expensive += 1;
+            }
+        }
+
+        let (min_lands, max_lands) = self.land_range(hand_size);
+        let land_count = lands.len();
+        let mut score = 0i32;
+        if land_count >= min_lands && land_count <= max_lands {
+            score += 10;
+        } else if land_count < min_lands {
+            score -= 15 * (min_lands - land_count) as i32;
+        } else {
+            score -= 15 * (land_count - max_lands) as i32;
+        }
+        score += 4 * castable_one_drops.min(2) as i32;
+        score += 2 * early_plays as i32;
+        score -= 2 * expensive as i32;
+        score -= 3 * off_color_spells as i32;
+
+        let eval = HandEvaluation {
+            land_count,
+            spell_count: spells.len(),
+            castable_one_drops,
+            curve,
+            off_color_spells,
+            score,
+        };
+        debug!("evaluate(): {:?}", eval);
+        eval
+    }
+
+    /// Keep vagy mulligan a már megtett mulliganek számának függvényében.
+    /// `hand` a felismert lapok, `hand_size` a ténylegesen kiosztott lapok száma (7);
+    /// a fel nem ismert slotok nem zsugorítják a kezet.
+    pub fn decide(
+        &self,
+        hand: &[Card],
+        hand_size: usize,
+        mulligans_taken: usize,
+    ) -> MulliganDecision {
+        let hand_size = hand_size.max(hand.len());
+        let final_size = hand_size.saturating_sub(mulligans_taken);
+        if final_size <= self.config.min_hand_size {
+            info!(
+                "decide(): final hand size {} <= {}, keeping.",
+                final_size, self.config.min_hand_size
+            );
+            return MulliganDecision::Keep;
+        }
+
+        let eval = self.evaluate_in_hand_of(hand, hand_size);
+        // A fel nem ismert lapok akár landek is lehetnek
+        let unknown = hand_size - hand.len();
+        if eval.land_count + unknown <= 1 || eval.land_count >= hand_size.saturating_sub(1) {
+            info!(
+                "decide(): {} land(s) in {} cards ({} unread) -> mulligan.",
+                eval.land_count, hand_size, unknown
+            );
+            return MulliganDecision::Mulligan;
+        }
+
+        let threshold =
+            self.config.keep_threshold - self.config.threshold_step * mulligans_taken as i32;
+        let decision = if eval.score >= threshold {
+            MulliganDecision::Keep
+        } else {
+            MulliganDecision::Mulligan
+        };
+        info!(
+            "decide(): score={} threshold={} (mulligans={}) -> {:?}",
+            eval.score, threshold, mulligans_taken, decision
+        );
+        decision
+    }
+
+    /// London mulligan: melyik `count` lapot tegyük a könyvtár aljára.
+    /// Mohón mindig azt a lapot vesszük ki, amelyik nélkül a maradék kéz a legjobb;
+    /// egyenlőségnél a drágább lap megy alulra. A visszaadott indexek növekvők.
+    pub fn choose_bottom(&self, hand: &[Card], count: usize) -> Vec<usize> {
+        let mut remaining: Vec<usize> = (0..hand.len()).collect();
+        let mut bottom = Vec::new();
+
+        for _ in 0..count.min(hand.len()) {
+            let mut best: Option<(usize, i32, u32)> = None;
+            for (pos, &idx) in remaining.iter().enumerate() {
+                let rest: Vec<Card> = remaining
+                    .iter()
+                    .filter(|&&i| i != idx)
+                    .map(|&i| hand[i].clone())
+                    .collect();
+                let score = self.evaluate(&rest).score;
+                let cmc = hand[idx].mana_cost.total();
+                let better = match best {
+                    None => true,
+                    Some((_, s, c)) => score > s || (score == s && cmc > c),
+                };
+                if better {
+                    best = Some((pos, score, cmc));
+                }
+            }
+            if let Some((pos, score, _)) = best {
+                let idx = remaining.remove(pos);
+                info!(
+                    "choose_bottom(): bottoming '{}' (idx={}), remaining score={}",
+                    hand[idx].name, idx, score
+                );
+                bottom.push(idx);
+            }
+        }
+
+        bottom.sort_unstable();
+        bottom
+    }
+
+    /// Minden színes költséget fedez-e a landek által termelhető színhalmaz.
+    fn colors_covered(cost: &ManaCost, available: &ManaCost) -> bool {
+        (cost.red == 0 || available.red > 0)
+            && (cost.green == 0 || available.green > 0)
+            && (cost.blue == 0 || available.blue > 0)
+            && (cost.white == 0 || available.white > 0)
+            && (cost.black == 0 || available.black > 0)
+    }
+}
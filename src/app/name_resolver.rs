@@ -0,0 +1,261 @@
+// app/name_resolver.rs
+
+//! Resolves OCR'd card names against the card library. OCR regularly misreads a
+//! letter or two ("Monastery Swiftspcar"), so instead of exact `HashMap` lookups
+//! every read is scored against all library names and the best one is returned
+//! with a confidence. Ambiguous and low-confidence reads are reported to the log.
+
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+use once_cell::sync::Lazy;
+use tracing::{debug, warn};
+
+use crate::app::card_library::Card;
+
+/// Thresholds used when accepting a match.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NameMatchConfig {
+    /// Below this the read is rejected.
+    pub min_confidence: f32,
+    /// Below this (but above `min_confidence`) the read is accepted with a warning.
+    pub warn_confidence: f32,
+    /// If the runner-up is within this distance of the best score, the read is ambiguous.
+    pub ambiguity_margin: f32,
+}
+
+impl Default for NameMatchConfig {
+    fn default() -> Self {
+        Self {
+            min_confidence: 0.75,
+            warn_confidence: 0.9,
+            ambiguity_margin: 0.05,
+        }
+    }
+}
+
+static NAME_MATCH_CONFIG: Lazy<RwLock<NameMatchConfig>> =
+    Lazy::new(|| RwLock::new(NameMatchConfig::default()));
+
+/// Replaces the globally used matching thresholds.
+pub fn set_name_match_config(config: NameMatchConfig) {
+    *NAME_MATCH_CONFIG.write().unwrap() = config;
+}
+
+/// The currently used matching thresholds.
+pub fn name_match_config() -> NameMatchConfig {
+    *NAME_MATCH_CONFIG.read().unwrap()
+}
+
+/// The best library name for an OCR read.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NameMatch {
+    pub name: String,
+    /// 0.0–1.0, 1.0 is an exact match after normalization.
+    pub confidence: f32,
+    /// Second best candidate, if any.
+    pub runner_up: Option<(String, f32)>,
+}
+
+impl NameMatch {
+    pub fn is_ambiguous(&self, margin: f32) -> bool {
+        self.runner_up
+            .as_ref()
+            .is_some_and(|(_, score)| self.confidence - score < margin)
+    }
+}
+
+/// Lowercase, letters/digits only, single spaces. OCR noise such as `|` or `'` disappears.
+pub fn normalize_name(text: &str) -> String {
+    text.chars()
+        .map(|c| if c.is_alphanumeric() { c.to_ascii_lowercase() } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Classic Levenshtein distance over chars.
+pub fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    let mut cur = vec![0; b.len() + 1];
+    for (i, ca) in a.chars().enumerate() {
+        cur[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != *cb);
+            cur[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(cur[j] + 1);
+        }
+        std::mem::swap(&mut prev, &mut cur);
+    }
+    prev[b.len()]
+}
+
+fn similarity(a: &str, b: &str) -> f32 {
+    let len = a.chars().count().max(b.chars().count());
+    if len == 0 {
+        return 0.0;
+    }
+    1.0 - edit_distance(a, b) as f32 / len as f32
+}
+
+/// Every token of `name` is matched to its best token in `text`, weighted by length.
+/// Handles reordered/truncated comma names ("Surrak, Elusive Hunter") and extra noise
+/// tokens around the name.
+fn token_similarity(name: &str, text: &str) -> f32 {
+    let text_tokens: Vec<&str> = text.split(' ').collect();
+    let mut total = 0usize;
+    let mut score = 0.0;
+    for token in name.split(' ') {
+        let len = token.chars().count();
+        let best = text_tokens
+            .iter()
+            .map(|t| similarity(token, t))
+            .fold(0.0f32, f32::max);
+        total += len;
+        score += best * len as f32;
+    }
+    if total == 0 { 0.0 } else { score / total as f32 }
+}
+
+/// Score of `text` (normalized) against library `name` (normalized).
+fn score(name: &str, text: &str) -> f32 {
+    let full = similarity(name, text);
+    let mut best = full;
+    // Token matching only counts if it covers multi-word names; a slight penalty keeps
+    // an equally good full-string match ahead.
+    if name.contains(' ') {
+        best = best.max(token_similarity(name, text) * 0.97);
+    }
+    best
+}
+
+/// Fuzzy matcher over a fixed set of names.
+#[derive(Debug, Clone)]
+pub struct NameResolver {
+    /// (normalized, original) pairs.
+    names: Vec<(String, String)>,
+    /// Normalized short names of comma cards ("surrak" → "Surrak, Elusive Hunter").
+    short_names: Vec<(String, String)>,
+    config: NameMatchConfig,
+}
+
+impl NameResolver {
+    pub fn new<I, S>(names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut out = Self {
+            names: Vec::new(),
+            short_names: Vec::new(),
+            config: name_match_config(),
+        };
+        for name in names {
+            let name = name.as_ref();
+            out.names.push((normalize_name(name), name.to_string()));
+            if let Some((short, _)) = name.split_once(',') {
+                out.short_names.push((normalize_name(short), name.to_string()));
+            }
+        }
+        out
+    }
+
+    pub fn from_library(library: &HashMap<String, Card>) -> Self {
+        Self::new(library.keys())
+    }
+
+    pub fn with_config(mut self, config: NameMatchConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Best candidate regardless of thresholds; `None` only for empty input or library.
+    pub fn best_match(&self, ocr_text: &str) -> Option<NameMatch> {
+        let text = normalize_name(ocr_text);
+        if text.is_empty() {
+            return None;
+        }
+        let mut scores: HashMap<&str, f32> = HashMap::new();
+        for (norm, original) in &self.names {
+            scores.insert(original, score(norm, &text));
+        }
+        for (short, original) in &self.short_names {
+            // Only the short name was read: good, but never better than the full name
+            let s = similarity(short, &text) * 0.95;
+            let entry = scores.entry(original).or_insert(0.0);
+            *entry = entry.max(s);
+        }
+        let mut ranked: Vec<(&str, f32)> = scores.into_iter().collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        let mut ranked = ranked.into_iter();
+        let (name, confidence) = ranked.next()?;
+        Some(NameMatch {
+            name: name.to_string(),
+            confidence,
+            runner_up: ranked.next().map(|(n, s)| (n.to_string(), s)),
+        })
+    }
+
+    /// Best candidate that passes the thresholds. Ambiguous or weak reads are logged.
+    pub fn resolve(&self, ocr_text: &str) -> Option<NameMatch> {
+        let m = self.best_match(ocr_text)?;
+        if m.confidence < self.config.min_confidence {
+            warn!(
+                "Name read `{}` rejected: best `{}` only {:.2}",
+                ocr_text, m.name, m.confidence
+            );
+            return None;
+        }
+        if m.confidence < 1.0 && m.is_ambiguous(self.config.ambiguity_margin) {
+            let (other, other_score) = m.runner_up.clone().unwrap_or_default();
+            warn!(
+                "Name read `{}` ambiguous: `{}` {:.2} vs `{}` {:.2}",
+                ocr_text, m.name, m.confidence, other, other_score
+            );
+            return None;
+        }
+        if m.confidence < self.config.warn_confidence {
+            warn!(
+                "Name read `{}` matched `{}` with low confidence {:.2}",
+                ocr_text, m.name, m.confidence
+            );
+        } else if m.confidence < 1.0 {
+            debug!("Name read `{}` matched `{}` ({:.2})", ocr_text, m.name, m.confidence);
+        }
+        Some(m)
+    }
+
+    /// The card of `library` that `ocr_text` reads as: an exact name first, then `resolve`.
+    pub fn resolve_card<'a>(
+        &self,
+        library: &'a HashMap<String, Card>,
+        ocr_text: &str,
+    ) -> Option<&'a Card> {
+        if let Some(card) = library.get(ocr_text) {
+            return Some(card);
+        }
+        library.get(&self.resolve(ocr_text)?.name)
+    }
+
+    /// Does `ocr_text` read as `name`? Used where the caller already has a candidate,
+    /// so there is no runner-up to compare against; the stricter `warn_confidence` applies.
+    pub fn text_matches(&self, name: &str, ocr_text: &str) -> bool {
+        let text = normalize_name(ocr_text);
+        let norm = normalize_name(name);
+        if text.is_empty() || norm.is_empty() {
+            return false;
+        }
+        text.contains(&norm) || score(&norm, &text) >= self.config.warn_confidence
+    }
+}
+
+/// Resolves `ocr_text` against `library` with the global thresholds. Builds a throwaway
+/// resolver over `library`: meant for small ad-hoc maps (one side of the battlefield);
+/// repeated lookups in the card library go through a kept `NameResolver::resolve_card`.
+pub fn resolve_card<'a>(library: &'a HashMap<String, Card>, ocr_text: &str) -> Option<&'a Card> {
+    if let Some(card) = library.get(ocr_text) {
+        return Some(card);
+    }
+    NameResolver::from_library(library).resolve_card(library, ocr_text)
+}
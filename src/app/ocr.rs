@@ -1,18 +1,18 @@
 // app/ocr.rs
 
-use image::Rgba;
-use std::{process::Command, thread::sleep, time::Duration};
+use std::{thread::sleep, time::Duration};
 use tracing::{error, info};
 use thiserror::Error;
 
-use screenshots::Screen;
-
 use image::{DynamicImage, ImageBuffer};
 use image::imageops::{crop_imm, resize, FilterType};
 
 use crate::app::{
-    cards_positions::get_card_positions,
+    cards_positions::{get_card_positions, get_mulligan_card_positions},
     creature_positions::CreaturePosition,
+    layout::layout,
+    ocr_engine::{ocr_engine, OcrRegion},
+    screen_source::screen_source,
     ui::{set_cursor_pos},
 };
 
@@ -73,19 +73,15 @@ pub fn white_invert_image(cropped: &DynamicImage) -> DynamicImage {
     DynamicImage::ImageRgba8(DynamicImage::ImageRgb8(out).to_rgba8())
 }
 
-/// Grab a full‐screen `DynamicImage`.
+/// Grab a full‐screen `DynamicImage` from the active `ScreenSource`.
 fn capture_screen() -> Option<DynamicImage> {
-    // Capture the primary monitor (first available screen)
-    let screens = Screen::all().ok()?;
-    let screen = screens.get(0)?;
-    // Capture a screenshot (screenshots::image::RgbaImage)
-    let buffer = screen.capture().ok()?;
-    // Convert screenshots::RgbaImage into your image::ImageBuffer<Rgba<u8>, Vec<u8>>
-    let (w, h) = (buffer.width(), buffer.height());
-    let raw = buffer.into_raw(); // Vec<u8> of RGBA pixels
-    let img_buf = ImageBuffer::<Rgba<u8>, Vec<u8>>::from_raw(w, h, raw)
-        .expect("from_raw failed: dimensions/raw length mismatch");
-    Some(DynamicImage::ImageRgba8(img_buf))
+    match screen_source().capture() {
+        Ok(img) => Some(img),
+        Err(e) => {
+            error!("capture_screen failed: {}", e);
+            None
+        }
+    }
 }
 
 /// If cropping fails, one of these errors will be returned.
@@ -142,40 +138,85 @@ pub fn crop_region(
     Ok(DynamicImage::ImageRgba8(sub))
 }
 
-/// Save `img` to `temp_filename`, run Tesseract, return sanitized text.
-fn run_tesseract_pipeline(img: &DynamicImage, temp_filename: &str) -> String {
-    if img.save(&temp_filename).is_err() {
-        error!("Cannot save temp OCR image: {}", temp_filename);
+/// Run the `region`'s OCR engine on `img`, return sanitized text.
+/// If `MTGA_OCR_DEBUG_DIR` is set, the input image is saved there as `debug_name`.
+fn run_ocr(img: &DynamicImage, region: OcrRegion, debug_name: &str) -> String {
+    if let Some(dir) = std::env::var_os("MTGA_OCR_DEBUG_DIR") {
+        let path = std::path::Path::new(&dir).join(debug_name);
+        if let Err(e) = img.save(&path) {
+            error!("Cannot save OCR debug image {}: {}", path.display(), e);
+        }
+    }
+    match ocr_engine(region).recognize(img) {
+        Ok(raw) => sanitize_ocr_text(raw.trim()),
+        Err(e) => {
+            error!("OCR failed on {}: {}", debug_name, e);
+            String::new()
+        }
+    }
+}
+
+/// Captures the screen, crops (x1,y1)–(x2,y2), preprocesses and OCRs it with `region`'s engine.
+pub fn read_region_text(
+    rect: (u32, u32, u32, u32),
+    region: OcrRegion,
+    debug_name: &str,
+) -> String {
+    match capture_screen() {
+        Some(screen) => read_image_region_text(&screen, rect, region, false, debug_name),
+        None => String::new(),
+    }
+}
+
+/// Like `read_region_text`, for text turned by 90° clockwise (a tapped card):
+/// the crop is turned back before OCR.
+pub fn read_rotated_region_text(
+    (x1, y1, x2, y2): (u32, u32, u32, u32),
+    region: OcrRegion,
+    debug_name: &str,
+) -> String {
+    let Some(screen) = capture_screen() else {
         return String::new();
+    };
+    match crop_region(&screen, x1, y1, x2, y2) {
+        Ok(cropped) => run_ocr(&preprocess_image(&cropped.rotate270()), region, debug_name),
+        Err(e) => {
+            error!("crop_region failed for {}: {}", debug_name, e);
+            String::new()
+        }
     }
-    let output = Command::new(r"C:\Program Files\Tesseract-OCR\tesseract.exe")
-        .arg(&temp_filename)
-        .arg("stdout")
-        .arg("-l").arg("eng")
-        .arg("--psm").arg("7")
-        .output();
-    if let Ok(o) = output {
-        if o.status.success() {
-            let raw = String::from_utf8_lossy(&o.stdout);
-            return sanitize_ocr_text(raw.trim());
-        } else {
-            error!("Tesseract failed on {}: {}", temp_filename, String::from_utf8_lossy(&o.stderr));
+}
+
+/// Crops (x1,y1)–(x2,y2) from an already captured `screen` and OCRs it, with the
+/// white-invert ("red button") pipeline if `white_invert`, the standard one otherwise.
+pub fn read_image_region_text(
+    screen: &DynamicImage,
+    (x1, y1, x2, y2): (u32, u32, u32, u32),
+    region: OcrRegion,
+    white_invert: bool,
+    debug_name: &str,
+) -> String {
+    let cropped = match crop_region(screen, x1, y1, x2, y2) {
+        Ok(img) => img,
+        Err(e) => {
+            error!("crop_region failed for {}: {}", debug_name, e);
+            return String::new();
         }
+    };
+    let processed = if white_invert {
+        white_invert_image(&cropped)
     } else {
-        error!("Failed to run Tesseract on {}", temp_filename);
-    }
-    String::new()
+        preprocess_image(&cropped)
+    };
+    run_ocr(&processed, region, debug_name)
 }
 
-/// Reads the “Start Order” label using the white‑invert pipeline and Tesseract,
-/// with full tracing of capture, crop, temp file and OCR result.
+/// Reads the “Start Order” label using the white‑invert pipeline and the OCR engine,
+/// with full tracing of capture, crop and OCR result.
 pub fn check_start_order_text(screen_width: u32, screen_height: u32) -> String {
     info!("check_start_order_text()");
     // 1) compute region
-    let x1 = (238.294 / 677.292 * screen_width as f64).floor() as u32;
-    let x2 = (437.764 / 677.292 * screen_width as f64).floor() as u32;
-    let y1 = (21.432 / 381.287 * screen_height as f64).floor() as u32;
-    let y2 = (43.454 / 381.287 * screen_height as f64).floor() as u32;
+    let (x1, y1, x2, y2) = layout().region("start_order", screen_width, screen_height);
     info!("  region coords: x1={}, y1={}, x2={}, y2={}", x1, y1, x2, y2);
 
     // 2) capture
@@ -202,12 +243,11 @@ pub fn check_start_order_text(screen_width: u32, screen_height: u32) -> String {
     // 4) preprocess (white‑invert)
     let processed = white_invert_image(&cropped);
 
-    // 5) save temp + log
-    let temp_filename = "temp_start_order.png";
-    info!("  saving temp OCR image as '{}'", temp_filename);
+    // 5) debug name (MTGA_OCR_DEBUG_DIR)
+    let debug_name = "start_order.png";
 
     // 6) OCR
-    let result = run_tesseract_pipeline(&processed, temp_filename);
+    let result = run_ocr(&processed, OcrRegion::ButtonLabel, debug_name);
     info!("  OCR start-order → {:?}", result);
     result
 }
@@ -221,10 +261,7 @@ pub fn check_main_region_text(
 ) -> String {
     info!("check_main_region_text(is_red_button={})", is_red_button);
     // 1) compute region
-    let x1 = (589.799 / 677.292 * screen_width as f64).floor() as u32;
-    let x2 = (665.000 / 677.292 * screen_width as f64).floor() as u32;
-    let y1 = (328.906 / 381.287 * screen_height as f64).floor() as u32;
-    let y2 = (341.871 / 381.287 * screen_height as f64).floor() as u32;
+    let (x1, y1, x2, y2) = layout().region("main_button", screen_width, screen_height);
     info!("  region coords: x1={}, y1={}, x2={}, y2={}", x1, y1, x2, y2);
 
     // 2) capture
@@ -257,16 +294,15 @@ pub fn check_main_region_text(
         preprocess_image(&cropped)
     };
 
-    // 5) save temp + log
-    let temp_filename = if is_red_button {
-        "temp_main_region_red.png"
+    // 5) debug name (MTGA_OCR_DEBUG_DIR)
+    let debug_name = if is_red_button {
+        "main_region_red.png"
     } else {
-        "temp_main_region.png"
+        "main_region.png"
     };
-    info!("  saving temp OCR image as '{}'", temp_filename);
 
     // 6) OCR
-    let result = run_tesseract_pipeline(&processed, temp_filename);
+    let result = run_ocr(&processed, OcrRegion::ButtonLabel, debug_name);
     info!("  OCR main region → {:?}", result);
     result
 }
@@ -311,14 +347,13 @@ pub fn read_creature_text(
     // 4) Preprocess
     let processed = preprocess_image(&cropped);
 
-    // 5) Build stable temp filename
+    // 5) Build stable debug name
     let parity = if index % 2 == 1 { "odd" } else { "even" };
     let side = if is_opponent { "opponents_creature" } else { "creature" };
-    let temp_filename = format!("temp_{}_{}_{}.png", parity, side, index);
-    info!("Saving temporary OCR image as '{}'", temp_filename);
+    let debug_name = format!("{}_{}_{}.png", parity, side, index);
 
-    // 6) Run Tesseract
-    let result = run_tesseract_pipeline(&processed, &temp_filename);
+    // 6) Run OCR
+    let result = run_ocr(&processed, OcrRegion::Generic, &debug_name);
     info!("OCR result for slot #{}: {:?}", index, result);
     result
 }
@@ -339,8 +374,11 @@ pub fn get_card_text(
             return String::new();
         }
     };
-    let hover_y = ((screen_height as f64) * 0.97).floor() as i32;
-    set_cursor_pos(pos.hover_x as i32, hover_y);
+    let layout = layout();
+    let hover_y = layout.sy(layout.value("hand.hover_y"), screen_height).floor() as i32;
+    if set_cursor_pos(pos.hover_x as i32, hover_y).is_err() {
+        return String::new();
+    }
     sleep(Duration::from_secs(2));
 
     // 2) Capture the full screen
@@ -353,8 +391,9 @@ pub fn get_card_text(
     };
 
     // 3) Crop to the card’s OCR region
-    let y1 = ((232.606 / 381.287) * screen_height as f64).floor() as u32;
-    let y2 = ((240.832 / 381.287) * screen_height as f64).floor() as u32;
+    let (name_y1, name_y2) = layout.pair("hand.name_y");
+    let y1 = layout.sy(name_y1, screen_height).floor() as u32;
+    let y2 = layout.sy(name_y2, screen_height).floor() as u32;
     let cropped = match crop_region(&screen, pos.ocr_x1, y1, pos.ocr_x2, y2) {
         Ok(img) => img,
         Err(e) => {
@@ -366,31 +405,69 @@ pub fn get_card_text(
     // 4) Preprocess for OCR
     let processed = preprocess_image(&cropped);
 
-    // 5) Save a stable temp file & run Tesseract
-    let tmp = format!("temp_card_{}.png", index);
-    info!("Saving temp OCR image as '{}'", tmp);
+    // 5) Run OCR
+    let debug_name = format!("card_{}.png", index);
     sleep(Duration::from_millis(500));
-    let result = run_tesseract_pipeline(&processed, &tmp);
+    let result = run_ocr(&processed, OcrRegion::Generic, &debug_name);
 
     info!("OCR result for card {}: {:?}", index, result);
     result
 }
 
+/// Reads the name of one card on the mulligan screen (no hover needed there).
+pub fn get_mulligan_card_text(
+    index: usize,
+    card_count: usize,
+    screen_width: u32,
+    screen_height: u32,
+) -> String {
+    let positions = get_mulligan_card_positions(card_count, screen_width, screen_height);
+    let pos = match positions.get(index) {
+        Some(p) => *p,
+        None => {
+            error!("get_mulligan_card_text(): index {} out of range ({} cards)", index, card_count);
+            return String::new();
+        }
+    };
+
+    let screen = match capture_screen() {
+        Some(img) => img,
+        None => {
+            error!("Failed to capture screen for mulligan card {}", index);
+            return String::new();
+        }
+    };
+    let cropped = match crop_region(&screen, pos.ocr_x1, pos.ocr_y1, pos.ocr_x2, pos.ocr_y2) {
+        Ok(img) => img,
+        Err(e) => {
+            error!("crop_region failed for mulligan card {}: {}", index, e);
+            return String::new();
+        }
+    };
+
+    let processed = preprocess_image(&cropped);
+    let debug_name = format!("mulligan_card_{}.png", index);
+    let result = run_ocr(&processed, OcrRegion::Generic, &debug_name);
+    info!("OCR result for mulligan card {}: {:?}", index, result);
+    result
+}
+
+/// Reads the name of the topmost card on the stack (`region.stack_top`).
+pub fn read_stack_top_text(screen_width: u32, screen_height: u32) -> String {
+    let rect = layout().region("stack_top", screen_width, screen_height);
+    let result = read_region_text(rect, OcrRegion::Generic, "stack_top.png");
+    info!("OCR result for the top of the stack: {:?}", result);
+    result
+}
+
 pub fn read_life_total(
     is_opponent: bool,
     screen_width: u32,
     screen_height: u32,
 ) -> i32 {
-    // Relatív koordináták: felső sáv ellenfélnek, alsó sáv nekünk
-    let (x1f, y1f, x2f, y2f) = if is_opponent {
-        (0.45, 0.03, 0.55, 0.10)
-    } else {
-        (0.45, 0.90, 0.55, 0.97)
-    };
-    let x1 = (x1f * screen_width as f64).floor() as u32;
-    let x2 = (x2f * screen_width as f64).floor() as u32;
-    let y1 = (y1f * screen_height as f64).floor() as u32;
-    let y2 = (y2f * screen_height as f64).floor() as u32;
+    // Felső sáv ellenfélnek, alsó sáv nekünk
+    let region = if is_opponent { "life.opp" } else { "life.own" };
+    let (x1, y1, x2, y2) = layout().region(region, screen_width, screen_height);
 
     // Képernyőfogás és vágás
     let screen = match capture_screen() {
@@ -404,8 +481,8 @@ pub fn read_life_total(
 
     // Előfeldolgozás és OCR
     let processed = preprocess_image(&cropped);
-    let temp_file = if is_opponent { "temp_opp_life.png" } else { "temp_us_life.png" };
-    let raw_text = run_tesseract_pipeline(&processed, temp_file);
+    let debug_name = if is_opponent { "opp_life.png" } else { "us_life.png" };
+    let raw_text = run_ocr(&processed, OcrRegion::LifeTotal, debug_name);
 
     // Csak a számjegyeket tartjuk meg, parse-oljuk
     raw_text
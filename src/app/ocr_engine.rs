@@ -0,0 +1,397 @@
+// app/ocr_engine.rs
+
+//! OCR backends behind the `OcrEngine` trait: a tesseract process (stdin-piped
+//! or via a temp file outside the repo) and a template matcher for fixed glyph sets.
+
+use std::collections::HashMap;
+use std::io::{Cursor, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::{Arc, RwLock};
+
+use image::{DynamicImage, GrayImage, ImageFormat};
+use image::imageops::{resize, FilterType};
+use once_cell::sync::Lazy;
+use thiserror::Error;
+use tracing::{debug, info, warn};
+
+/// Errors an OCR engine can report.
+#[derive(Debug, Error)]
+pub enum OcrError {
+    #[error("Failed to start OCR process {binary}: {reason}")]
+    Spawn { binary: String, reason: String },
+    #[error("OCR process failed: {0}")]
+    Failed(String),
+    #[error("Image encoding failed: {0}")]
+    Encode(String),
+    #[error("No templates loaded from {0}")]
+    NoTemplates(String),
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Turns an already preprocessed image into raw text.
+pub trait OcrEngine: Send + Sync {
+    fn recognize(&self, img: &DynamicImage) -> Result<String, OcrError>;
+}
+
+/// How the image reaches the tesseract process.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TesseractInput {
+    /// PNG bytes piped to stdin (`tesseract stdin stdout`)
+    Stdin,
+    /// PNG written into this directory first (for old tesseract builds)
+    TempFile(PathBuf),
+}
+
+/// External tesseract binary.
+#[derive(Debug, Clone)]
+pub struct TesseractEngine {
+    pub binary: PathBuf,
+    pub lang: String,
+    pub psm: u32,
+    pub input: TesseractInput,
+}
+
+impl Default for TesseractEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TesseractEngine {
+    /// `MTGA_TESSERACT` if set, otherwise the default install path on Windows
+    /// and `tesseract` from PATH elsewhere; single-line mode, stdin input.
+    pub fn new() -> Self {
+        let binary = std::env::var_os("MTGA_TESSERACT")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| {
+                if cfg!(windows) {
+                    PathBuf::from(r"C:\Program Files\Tesseract-OCR\tesseract.exe")
+                } else {
+                    PathBuf::from("tesseract")
+                }
+            });
+        Self {
+            binary,
+            lang: "eng".into(),
+            psm: 7,
+            input: TesseractInput::Stdin,
+        }
+    }
+
+    pub fn with_binary(mut self, binary: impl Into<PathBuf>) -> Self {
+        self.binary = binary.into();
+        self
+    }
+
+    pub fn with_input(mut self, input: TesseractInput) -> Self {
+        self.input = input;
+        self
+    }
+
+    pub fn with_psm(mut self, psm: u32) -> Self {
+        self.psm = psm;
+        self
+    }
+
+    fn command(&self, input_arg: &str) -> Command {
+        let mut cmd = Command::new(&self.binary);
+        cmd.arg(input_arg)
+            .arg("stdout")
+            .arg("-l")
+            .arg(&self.lang)
+            .arg("--psm")
+            .arg(self.psm.to_string());
+        cmd
+    }
+
+    fn spawn_error(&self, e: std::io::Error) -> OcrError {
+        OcrError::Spawn {
+            binary: self.binary.display().to_string(),
+            reason: e.to_string(),
+        }
+    }
+}
+
+fn encode_png(img: &DynamicImage) -> Result<Vec<u8>, OcrError> {
+    let mut bytes = Vec::new();
+    img.write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)
+        .map_err(|e| OcrError::Encode(e.to_string()))?;
+    Ok(bytes)
+}
+
+impl OcrEngine for TesseractEngine {
+    fn recognize(&self, img: &DynamicImage) -> Result<String, OcrError> {
+        let output = match &self.input {
+            TesseractInput::Stdin => {
+                let png = encode_png(img)?;
+                let mut child = self
+                    .command("stdin")
+                    .stdin(Stdio::piped())
+                    .stdout(Stdio::piped())
+                    .stderr(Stdio::piped())
+                    .spawn()
+                    .map_err(|e| self.spawn_error(e))?;
+                if let Some(mut stdin) = child.stdin.take() {
+                    stdin.write_all(&png)?;
+                }
+                child.wait_with_output()?
+            }
+            TesseractInput::TempFile(dir) => {
+                let path = dir.join(format!("mtga_ocr_{}.png", std::process::id()));
+                img.save(&path).map_err(|e| OcrError::Encode(e.to_string()))?;
+                let out = self
+                    .command(&path.to_string_lossy())
+                    .output()
+                    .map_err(|e| self.spawn_error(e));
+                let _ = std::fs::remove_file(&path);
+                out?
+            }
+        };
+        if !output.status.success() {
+            return Err(OcrError::Failed(
+                String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            ));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+}
+
+/// How templates are matched against the image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TemplateMode {
+    /// The image is split into glyphs at blank columns; each glyph is matched
+    /// separately and the labels are concatenated (life totals: `0`-`9`).
+    Glyphs,
+    /// The whole image is matched against whole-label templates (button texts).
+    Labels,
+}
+
+/// Template-matching OCR for fixed glyph sets. Templates are trimmed on load;
+/// the image is expected to be already preprocessed (dark text on light background
+/// or the reverse, consistently with the templates).
+pub struct TemplateEngine {
+    templates: Vec<(String, GrayImage)>,
+    mode: TemplateMode,
+    /// Minimum ratio of agreeing pixels to accept a match
+    pub min_score: f64,
+}
+
+/// Pixel value splitting "ink" from background.
+const INK_CUTOFF: u8 = 128;
+
+impl TemplateEngine {
+    pub fn new(mode: TemplateMode) -> Self {
+        Self {
+            templates: Vec::new(),
+            mode,
+            min_score: 0.8,
+        }
+    }
+
+    /// Adds a template under `label`, trimmed to its ink bounding box.
+    pub fn with_template(mut self, label: &str, img: &DynamicImage) -> Self {
+        let gray = img.to_luma8();
+        let trimmed = Self::trim(&gray).unwrap_or(gray);
+        self.templates.push((label.to_string(), trimmed));
+        self
+    }
+
+    pub fn with_min_score(mut self, min_score: f64) -> Self {
+        self.min_score = min_score;
+        self
+    }
+
+    /// Every `<label>.png` in `dir` becomes a template (e.g. `7.png`, `Next.png`).
+    /// Characters that cannot be in file names are spelled out: `slash.png`, `plus.png`.
+    pub fn from_dir(dir: impl AsRef<Path>, mode: TemplateMode) -> Result<Self, OcrError> {
+        let dir = dir.as_ref();
+        let mut engine = Self::new(mode);
+        let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)?
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| p.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("png")))
+            .collect();
+        paths.sort();
+        for path in paths {
+            let Some(label) = path.file_stem().map(|s| s.to_string_lossy().to_string()) else {
+                continue;
+            };
+            let label = match label.as_str() {
+                "slash" => "/".to_string(),
+                "plus" => "+".to_string(),
+                _ => label,
+            };
+            match image::open(&path) {
+                Ok(img) => engine = engine.with_template(&label, &img),
+                Err(e) => warn!("TemplateEngine: cannot load {}: {}", path.display(), e),
+            }
+        }
+        if engine.templates.is_empty() {
+            return Err(OcrError::NoTemplates(dir.display().to_string()));
+        }
+        info!("TemplateEngine: {} template(s) from {}", engine.templates.len(), dir.display());
+        Ok(engine)
+    }
+
+    /// Ink (dark) pixel test; the background is whichever is more frequent.
+    fn ink_mask(img: &GrayImage) -> Vec<bool> {
+        let dark = img.pixels().filter(|p| p[0] < INK_CUTOFF).count();
+        let dark_is_ink = dark * 2 <= img.pixels().len();
+        img.pixels().map(|p| (p[0] < INK_CUTOFF) == dark_is_ink).collect()
+    }
+
+    /// Ratio of agreeing ink/background pixels after scaling `img` to the template size.
+    fn score(img: &GrayImage, template: &GrayImage) -> f64 {
+        let (w, h) = template.dimensions();
+        if w == 0 || h == 0 || img.width() == 0 || img.height() == 0 {
+            return 0.0;
+        }
+        let scaled = resize(img, w, h, FilterType::Triangle);
+        let a = Self::ink_mask(&scaled);
+        let b = Self::ink_mask(template);
+        let same = a.iter().zip(b.iter()).filter(|(x, y)| x == y).count();
+        same as f64 / a.len() as f64
+    }
+
+    fn best_match(&self, img: &GrayImage) -> Option<(&str, f64)> {
+        self.templates
+            .iter()
+            .map(|(label, t)| (label.as_str(), Self::score(img, t)))
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .filter(|(_, s)| *s >= self.min_score)
+    }
+
+    /// Crops the ink bounding box; `None` if the image has no ink.
+    fn trim(img: &GrayImage) -> Option<GrayImage> {
+        let mask = Self::ink_mask(img);
+        let w = img.width() as usize;
+        let (mut x0, mut y0, mut x1, mut y1) = (usize::MAX, usize::MAX, 0, 0);
+        for (i, ink) in mask.iter().enumerate() {
+            if *ink {
+                let (x, y) = (i % w, i / w);
+                x0 = x0.min(x);
+                y0 = y0.min(y);
+                x1 = x1.max(x);
+                y1 = y1.max(y);
+            }
+        }
+        if x0 == usize::MAX {
+            return None;
+        }
+        Some(
+            image::imageops::crop_imm(img, x0 as u32, y0 as u32, (x1 - x0 + 1) as u32, (y1 - y0 + 1) as u32)
+                .to_image(),
+        )
+    }
+
+    /// Splits the image into glyphs at columns without ink.
+    fn split_glyphs(img: &GrayImage) -> Vec<GrayImage> {
+        let mask = Self::ink_mask(img);
+        let (w, h) = (img.width() as usize, img.height() as usize);
+        let column_has_ink = |x: usize| (0..h).any(|y| mask[y * w + x]);
+        let mut glyphs = Vec::new();
+        let mut start = None;
+        for x in 0..=w {
+            let ink = x < w && column_has_ink(x);
+            match (ink, start) {
+                (true, None) => start = Some(x),
+                (false, Some(s)) => {
+                    let glyph = image::imageops::crop_imm(img, s as u32, 0, (x - s) as u32, h as u32).to_image();
+                    if let Some(trimmed) = Self::trim(&glyph) {
+                        glyphs.push(trimmed);
+                    }
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+        glyphs
+    }
+}
+
+impl OcrEngine for TemplateEngine {
+    fn recognize(&self, img: &DynamicImage) -> Result<String, OcrError> {
+        let gray = img.to_luma8();
+        match self.mode {
+            TemplateMode::Labels => {
+                let Some(trimmed) = Self::trim(&gray) else {
+                    return Ok(String::new());
+                };
+                let best = self.best_match(&trimmed);
+                debug!("TemplateEngine(labels): best = {:?}", best);
+                Ok(best.map(|(l, _)| l.to_string()).unwrap_or_default())
+            }
+            TemplateMode::Glyphs => {
+                let mut out = String::new();
+                for glyph in Self::split_glyphs(&gray) {
+                    match self.best_match(&glyph) {
+                        Some((label, _)) => out.push_str(label),
+                        None => debug!("TemplateEngine(glyphs): unmatched glyph {:?}", glyph.dimensions()),
+                    }
+                }
+                Ok(out)
+            }
+        }
+    }
+}
+
+/// Which screen region a read belongs to; each can have its own engine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OcrRegion {
+    Generic,
+    LifeTotal,
+    ButtonLabel,
+    /// P/T box and counter badges of battlefield creatures
+    PowerToughness,
+}
+
+static OCR_ENGINES: Lazy<RwLock<HashMap<OcrRegion, Arc<dyn OcrEngine>>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
+
+/// Engine for a region (`Generic` is the fallback for the others).
+pub fn set_ocr_engine(region: OcrRegion, engine: Arc<dyn OcrEngine>) {
+    OCR_ENGINES.write().unwrap().insert(region, engine);
+    info!("OCR engine replaced for {:?}", region);
+}
+
+/// The engine for `region`; falls back to the generic one, then to a default tesseract.
+pub fn ocr_engine(region: OcrRegion) -> Arc<dyn OcrEngine> {
+    {
+        let engines = OCR_ENGINES.read().unwrap();
+        if let Some(e) = engines.get(&region).or_else(|| engines.get(&OcrRegion::Generic)) {
+            return e.clone();
+        }
+    }
+    let default: Arc<dyn OcrEngine> = Arc::new(TesseractEngine::new());
+    OCR_ENGINES
+        .write()
+        .unwrap()
+        .entry(OcrRegion::Generic)
+        .or_insert(default)
+        .clone()
+}
+
+/// Env-based setup: `MTGA_LIFE_TEMPLATES` / `MTGA_BUTTON_TEMPLATES` directories switch
+/// life totals / button labels to template matching; `MTGA_TESSERACT_TMP` makes the
+/// generic tesseract engine use a temp file in that directory instead of stdin.
+/// `MTGA_PT_TEMPLATES` does the same for creature P/T boxes (glyphs `0`–`9`, `plus`, `slash`).
+pub fn init_ocr_engines_from_env() -> Result<(), OcrError> {
+    if let Some(dir) = std::env::var_os("MTGA_TESSERACT_TMP") {
+        let engine = TesseractEngine::new().with_input(TesseractInput::TempFile(dir.into()));
+        set_ocr_engine(OcrRegion::Generic, Arc::new(engine));
+    }
+    if let Some(dir) = std::env::var_os("MTGA_LIFE_TEMPLATES") {
+        let engine = TemplateEngine::from_dir(dir, TemplateMode::Glyphs)?;
+        set_ocr_engine(OcrRegion::LifeTotal, Arc::new(engine));
+    }
+    if let Some(dir) = std::env::var_os("MTGA_BUTTON_TEMPLATES") {
+        let engine = TemplateEngine::from_dir(dir, TemplateMode::Labels)?;
+        set_ocr_engine(OcrRegion::ButtonLabel, Arc::new(engine));
+    }
+    if let Some(dir) = std::env::var_os("MTGA_PT_TEMPLATES") {
+        let engine = TemplateEngine::from_dir(dir, TemplateMode::Glyphs)?;
+        set_ocr_engine(OcrRegion::PowerToughness, Arc::new(engine));
+    }
+    Ok(())
+}
@@ -0,0 +1,241 @@
+// app/opponent_model.rs
+
+use crate::app::card_library::{Card, CardType, CardTypeFlags};
+use std::collections::HashMap;
+use tracing::{debug, info};
+
+/// Hol láttuk az ellenfél lapját.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SeenZone {
+    Battlefield,
+    Graveyard,
+    Stack,
+}
+
+/// Egy archetípus ismert paklilistája (lapnév → példányszám).
+#[derive(Debug, Clone)]
+pub struct ArchetypeDecklist {
+    pub name: String,
+    pub cards: HashMap<String, usize>,
+}
+
+impl ArchetypeDecklist {
+    pub fn new(name: &str, cards: &[(&str, usize)]) -> Self {
+        Self {
+            name: name.to_string(),
+            cards: cards.iter().map(|(n, c)| (n.to_string(), *c)).collect(),
+        }
+    }
+}
+
+/// A helyi archetípus-adatbázis.
+pub fn build_archetype_library() -> Vec<ArchetypeDecklist> {
+    vec![
+        ArchetypeDecklist::new(
+            "Mono-Red Aggro",
+            &[
+                ("Cacophony Scamp", 4),
+                ("Monastery Swiftspear", 4),
+                ("Electrostatic Infantry", 4),
+                ("Heartfire Hero", 4),
+                ("Felonious Rage", 2),
+                ("Monstrous Rage", 4),
+                ("Blazing Crescendo", 2),
+                ("Demonic Ruckus", 2),
+                ("Burst Lightning", 4),
+                ("Lightning Strike", 4),
+                ("Rockface Village", 4),
+                ("Mountain", 18),
+            ],
+        ),
+        ArchetypeDecklist::new(
+            "Mono-Green Stompy",
+            &[
+                ("Llanowar Elves", 4),
+                ("Gene Pollinator", 2),
+                ("Pawpatch Recruit", 4),
+                ("Badgermole Cub", 4),
+                ("Scavenging Ooze", 2),
+                ("Frenzied Baloth", 3),
+                ("Surrak, Elusive Hunter", 3),
+                ("Tyvar, the Pummeler", 2),
+                ("Ouroboroid", 2),
+                ("Agonasaur Rex", 2),
+                ("Vivien Reid", 1),
+                ("Hunter's Talent", 3),
+                ("Overprotect", 2),
+                ("Archdruid's Charm", 2),
+                ("Sylvan Scavenging", 1),
+                ("Terrasymbiosis", 1),
+                ("Warden of the Grove", 2),
+                ("Forest", 17),
+            ],
+        ),
+    ]
+}
+
+/// Az ellenfél modellje: minden látott lapot naplóz, és archetípusra illeszt.
+///
+/// A csatatérről minden körben újra OCR-ezünk, ezért ott a *egyszerre* látott
+/// maximális példányszámot tartjuk nyilván; a veremre került varázslatokat és a
+/// temetőbe került lapokat eseményenként számoljuk.
+#[derive(Debug, Clone)]
+pub struct OpponentModel {
+    archetypes: Vec<ArchetypeDecklist>,
+    /// Minden megfigyelés sorrendben.
+    pub log: Vec<(String, SeenZone)>,
+    battlefield_max: HashMap<String, usize>,
+    cast_count: HashMap<String, usize>,
+    graveyard_count: HashMap<String, usize>,
+}
+
+impl Default for OpponentModel {
+    fn default() -> Self {
+        Self::with_archetypes(build_archetype_library())
+    }
+}
+
+impl OpponentModel {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_archetypes(archetypes: Vec<ArchetypeDecklist>) -> Self {
+        Self {
+            archetypes,
+            log: Vec::new(),
+            battlefield_max: HashMap::new(),
+            cast_count: HashMap::new(),
+            graveyard_count: HashMap::new(),
+        }
+    }
+
+    /// Új meccs: elfelejtünk mindent, amit láttunk.
+    pub fn reset(&mut self) {
+        self.log.clear();
+        self.battlefield_max.clear();
+        self.cast_count.clear();
+        self.graveyard_count.clear();
+    }
+
+    /// Az ellenfél csatatérének aktuális OCR-képe.
+    pub fn observe_battlefield(&mut self, battlefield: &HashMap<String, Card>) {
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        for card in battlefield.values() {
+            if card.type_flags.contains(CardTypeFlags::TOKEN) {
+                continue;
+            }
+            *counts.entry(card.name.clone()).or_insert(0) += 1;
+        }
+        for (name, n) in counts {
+            let max = self.battlefield_max.entry(name.clone()).or_insert(0);
+            if n > *max {
+                debug!("OpponentModel: '{}' seen on battlefield ({} copies)", name, n);
+                for _ in *max..n {
+                    self.log.push((name.clone(), SeenZone::Battlefield));
+                }
+                *max = n;
+            }
+        }
+    }
+
+    /// Az ellenfél által kijátszott (veremre került) varázslat.
+    pub fn observe_spell(&mut self, name: &str) {
+        info!("OpponentModel: opponent cast '{}'", name);
+        *self.cast_count.entry(name.to_string()).or_insert(0) += 1;
+        self.log.push((name.to_string(), SeenZone::Stack));
+    }
+
+    /// Az ellenfél temetőjébe került lap.
+    pub fn observe_graveyard(&mut self, name: &str) {
+        info!("OpponentModel: '{}' went to opponent's graveyard", name);
+        *self.graveyard_count.entry(name.to_string()).or_insert(0) += 1;
+        self.log.push((name.to_string(), SeenZone::Graveyard));
+    }
+
+    /// Egy lapból legalább ennyi különböző példányt láttunk.
+    pub fn seen_count(&self, name: &str) -> usize {
+        let bf = self.battlefield_max.get(name).copied().unwrap_or(0);
+        let cast = self.cast_count.get(name).copied().unwrap_or(0);
+        let gy = self.graveyard_count.get(name).copied().unwrap_or(0);
+        bf.max(cast).max(gy)
+    }
+
+    /// Az összes eddig látott lapnév és példányszám.
+    pub fn seen_cards(&self) -> HashMap<String, usize> {
+        self.log
+            .iter()
+            .map(|(name, _)| (name.clone(), self.seen_count(name)))
+            .collect()
+    }
+
+    /// Archetípusok pontszáma: a látott lapok hányad része illik a listára
+    /// (a lista példányszámát meghaladó lapok nem számítanak). Csökkenő sorrend.
+    pub fn archetype_scores(&self) -> Vec<(String, f64)> {
+        let seen = self.seen_cards();
+        let total: usize = seen.values().sum();
+        let mut scores: Vec<(String, f64)> = self
+            .archetypes
+            .iter()
+            .map(|arch| {
+                if total == 0 {
+                    return (arch.name.clone(), 0.0);
+                }
+                let matched: usize = seen
+                    .iter()
+                    .map(|(name, n)| (*n).min(arch.cards.get(name).copied().unwrap_or(0)))
+                    .sum();
+                (arch.name.clone(), matched as f64 / total as f64)
+            })
+            .collect();
+        scores.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+        scores
+    }
+
+    /// A legvalószínűbb archetípus, ha legalább egy látott lap illik rá.
+    pub fn likely_archetype(&self) -> Option<&ArchetypeDecklist> {
+        let (name, score) = self.archetype_scores().into_iter().next()?;
+        if score <= 0.0 {
+            return None;
+        }
+        self.archetypes.iter().find(|a| a.name == name)
+    }
+
+    /// A valószínű archetípus még nem látott lapjai (lapnév → hátralévő példányszám).
+    pub fn likely_remaining_cards(&self) -> HashMap<String, usize> {
+        let Some(arch) = self.likely_archetype() else {
+            return HashMap::new();
+        };
+        arch.cards
+            .iter()
+            .filter_map(|(name, n)| {
+                let left = n.saturating_sub(self.seen_count(name));
+                (left > 0).then(|| (name.clone(), left))
+            })
+            .collect()
+    }
+
+    /// Hátralévő instantok, amelyeket az ellenfél `untapped_mana` szabad manából
+    /// ki tud játszani. Manaérték szerint növekvő sorrendben.
+    pub fn likely_instant_interaction(
+        &self,
+        untapped_mana: u32,
+        library: &HashMap<String, Card>,
+    ) -> Vec<Card> {
+        let mut result: Vec<Card> = self
+            .likely_remaining_cards()
+            .keys()
+            .filter_map(|name| library.get(name))
+            .filter(|c| matches!(c.card_type, CardType::Instant))
+            .filter(|c| c.mana_cost.total() <= untapped_mana)
+            .cloned()
+            .collect();
+        result.sort_by(|a, b| {
+            a.mana_cost
+                .total()
+                .cmp(&b.mana_cost.total())
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        result
+    }
+}
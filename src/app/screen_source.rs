@@ -0,0 +1,424 @@
+// app/screen_source.rs
+
+//! Pluggable screen-capture backends. Every perception call (`ocr`, `ui`,
+//! `multiplatform::get_pixel`) goes through the globally selected `ScreenSource`,
+//! so the whole pipeline can run against saved screenshots instead of a live monitor.
+
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+use image::{DynamicImage, ImageBuffer, Rgba};
+use once_cell::sync::Lazy;
+use screenshots::Screen;
+use thiserror::Error;
+use tracing::{debug, error, info, warn};
+
+/// Errors a screen source can report.
+#[derive(Debug, Error)]
+pub enum ScreenError {
+    #[error("Screen capture failed: {0}")]
+    Capture(String),
+    #[error("No frames available in {0}")]
+    NoFrames(String),
+    #[error("Failed to load frame {path}: {reason}")]
+    Load { path: String, reason: String },
+    #[error("Area {x},{y} {width}×{height} is outside of frame {frame_width}×{frame_height}")]
+    OutOfBounds {
+        x: i32,
+        y: i32,
+        width: u32,
+        height: u32,
+        frame_width: u32,
+        frame_height: u32,
+    },
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// A source of screen frames. Only `capture` is mandatory; area and pixel reads
+/// default to cropping the full frame.
+pub trait ScreenSource: Send + Sync {
+    /// The full current frame.
+    fn capture(&self) -> Result<DynamicImage, ScreenError>;
+
+    /// A `width`×`height` rectangle of the current frame starting at (x, y).
+    fn capture_area(&self, x: i32, y: i32, width: u32, height: u32) -> Result<DynamicImage, ScreenError> {
+        let frame = self.capture()?;
+        crop_frame(&frame, x, y, width, height)
+    }
+
+    /// RGB color of a single pixel.
+    fn pixel(&self, x: i32, y: i32) -> Result<(u8, u8, u8), ScreenError> {
+        let area = self.capture_area(x, y, 1, 1)?.to_rgba8();
+        let p = area.get_pixel(0, 0);
+        Ok((p[0], p[1], p[2]))
+    }
+
+    /// (width, height) of the frames in pixels.
+    fn size(&self) -> Result<(i32, i32), ScreenError> {
+        let frame = self.capture()?;
+        Ok((frame.width() as i32, frame.height() as i32))
+    }
+}
+
+/// Crop helper shared by the file-based sources. The origin must lie inside the frame;
+/// a rectangle reaching past the right or bottom edge is clamped to the frame.
+fn crop_frame(frame: &DynamicImage, x: i32, y: i32, width: u32, height: u32) -> Result<DynamicImage, ScreenError> {
+    let (fw, fh) = (frame.width(), frame.height());
+    let out_of_bounds = || ScreenError::OutOfBounds {
+        x,
+        y,
+        width,
+        height,
+        frame_width: fw,
+        frame_height: fh,
+    };
+    if x < 0 || y < 0 || x as u32 >= fw || y as u32 >= fh || width == 0 || height == 0 {
+        return Err(out_of_bounds());
+    }
+    let (x, y) = (x as u32, y as u32);
+    let right = x.checked_add(width).map_or(fw, |r| r.min(fw));
+    let bottom = y.checked_add(height).map_or(fh, |b| b.min(fh));
+    Ok(frame.crop_imm(x, y, right - x, bottom - y))
+}
+
+fn load_png(path: &Path) -> Result<DynamicImage, ScreenError> {
+    image::open(path).map_err(|e| ScreenError::Load {
+        path: path.display().to_string(),
+        reason: e.to_string(),
+    })
+}
+
+/// Converts a `screenshots` buffer into our `image` version.
+fn to_dynamic(width: u32, height: u32, raw: Vec<u8>) -> Result<DynamicImage, ScreenError> {
+    ImageBuffer::<Rgba<u8>, Vec<u8>>::from_raw(width, height, raw)
+        .map(DynamicImage::ImageRgba8)
+        .ok_or_else(|| ScreenError::Capture(format!("buffer size mismatch {}×{}", width, height)))
+}
+
+/// Live capture of the primary monitor via `screenshots`.
+#[derive(Debug, Default)]
+pub struct LiveScreen;
+
+impl ScreenSource for LiveScreen {
+    fn capture(&self) -> Result<DynamicImage, ScreenError> {
+        let screens = Screen::all().map_err(|e| ScreenError::Capture(e.to_string()))?;
+        let screen = screens
+            .first()
+            .ok_or_else(|| ScreenError::Capture("no screens detected".into()))?;
+        let buffer = screen.capture().map_err(|e| ScreenError::Capture(e.to_string()))?;
+        let (w, h) = (buffer.width(), buffer.height());
+        to_dynamic(w, h, buffer.into_raw())
+    }
+
+    fn capture_area(&self, x: i32, y: i32, width: u32, height: u32) -> Result<DynamicImage, ScreenError> {
+        let screen = Screen::from_point(x, y).map_err(|e| ScreenError::Capture(e.to_string()))?;
+        let buffer = screen
+            .capture_area(x.max(0), y.max(0), width, height)
+            .map_err(|e| ScreenError::Capture(e.to_string()))?;
+        let (w, h) = (buffer.width(), buffer.height());
+        to_dynamic(w, h, buffer.into_raw())
+    }
+
+    fn size(&self) -> Result<(i32, i32), ScreenError> {
+        let screens = Screen::all().map_err(|e| ScreenError::Capture(e.to_string()))?;
+        let screen = screens
+            .first()
+            .ok_or_else(|| ScreenError::Capture("no screens detected".into()))?;
+        let di = &screen.display_info;
+        Ok((di.width as i32, di.height as i32))
+    }
+}
+
+/// Cached frame of a file-based source: index + decoded image.
+type CachedFrame = Option<(usize, Arc<DynamicImage>)>;
+
+/// Serves the PNG files of a directory (sorted by file name) as frames.
+/// The current frame stays until `advance` or `select` is called.
+pub struct DirectoryScreenSource {
+    frames: Vec<PathBuf>,
+    current: Mutex<usize>,
+    cache: Mutex<CachedFrame>,
+}
+
+impl DirectoryScreenSource {
+    /// Collects every `*.png` in `dir`; errors if there is none.
+    pub fn new(dir: impl AsRef<Path>) -> Result<Self, ScreenError> {
+        let dir = dir.as_ref();
+        let mut frames: Vec<PathBuf> = fs::read_dir(dir)?
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| p.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("png")))
+            .collect();
+        frames.sort();
+        Self::from_files(frames).map_err(|_| ScreenError::NoFrames(dir.display().to_string()))
+    }
+
+    /// Explicit list of frame files, in playback order.
+    pub fn from_files(frames: Vec<PathBuf>) -> Result<Self, ScreenError> {
+        if frames.is_empty() {
+            return Err(ScreenError::NoFrames("<file list>".into()));
+        }
+        info!("DirectoryScreenSource: {} frame(s)", frames.len());
+        Ok(Self {
+            frames,
+            current: Mutex::new(0),
+            cache: Mutex::new(None),
+        })
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// Path of the frame currently served.
+    pub fn current_frame(&self) -> PathBuf {
+        self.frames[*self.current.lock().unwrap()].clone()
+    }
+
+    /// Steps to the next frame; returns `false` (and stays) on the last one.
+    pub fn advance(&self) -> bool {
+        let mut cur = self.current.lock().unwrap();
+        if *cur + 1 < self.frames.len() {
+            *cur += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Jumps to the frame whose file name is `name`.
+    pub fn select(&self, name: &str) -> bool {
+        match self
+            .frames
+            .iter()
+            .position(|p| p.file_name().is_some_and(|f| f == name))
+        {
+            Some(i) => {
+                *self.current.lock().unwrap() = i;
+                true
+            }
+            None => {
+                warn!("DirectoryScreenSource: no frame named {}", name);
+                false
+            }
+        }
+    }
+
+    fn frame(&self) -> Result<Arc<DynamicImage>, ScreenError> {
+        let idx = *self.current.lock().unwrap();
+        let mut cache = self.cache.lock().unwrap();
+        if let Some((cached_idx, img)) = cache.as_ref()
+            && *cached_idx == idx
+        {
+            return Ok(img.clone());
+        }
+        let img = Arc::new(load_png(&self.frames[idx])?);
+        debug!("DirectoryScreenSource: loaded {}", self.frames[idx].display());
+        *cache = Some((idx, img.clone()));
+        Ok(img)
+    }
+}
+
+impl ScreenSource for DirectoryScreenSource {
+    fn capture(&self) -> Result<DynamicImage, ScreenError> {
+        Ok((*self.frame()?).clone())
+    }
+
+    fn capture_area(&self, x: i32, y: i32, width: u32, height: u32) -> Result<DynamicImage, ScreenError> {
+        let frame = self.frame()?;
+        crop_frame(&frame, x, y, width, height)
+    }
+}
+
+/// Name of the manifest file inside a recorded session directory.
+pub const SESSION_MANIFEST: &str = "session.log";
+
+/// Wraps another source and saves full captures into a session directory
+/// (`frame_NNNNN.png` + `session.log` with `<elapsed_ms>\t<file>` lines).
+/// At most one frame is written per `min_interval`.
+pub struct SessionRecorder<S: ScreenSource> {
+    inner: S,
+    dir: PathBuf,
+    min_interval: Duration,
+    started: Instant,
+    state: Mutex<(usize, Option<Instant>)>,
+}
+
+impl<S: ScreenSource> SessionRecorder<S> {
+    pub fn new(inner: S, dir: impl AsRef<Path>) -> Result<Self, ScreenError> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)?;
+        fs::write(dir.join(SESSION_MANIFEST), "")?;
+        info!("SessionRecorder: recording into {}", dir.display());
+        Ok(Self {
+            inner,
+            dir,
+            min_interval: Duration::from_millis(500),
+            started: Instant::now(),
+            state: Mutex::new((0, None)),
+        })
+    }
+
+    pub fn with_min_interval(mut self, min_interval: Duration) -> Self {
+        self.min_interval = min_interval;
+        self
+    }
+
+    fn record(&self, frame: &DynamicImage) -> Result<(), ScreenError> {
+        let mut state = self.state.lock().unwrap();
+        let now = Instant::now();
+        if state.1.is_some_and(|last| now.duration_since(last) < self.min_interval) {
+            return Ok(());
+        }
+        let file = format!("frame_{:05}.png", state.0);
+        frame
+            .save(self.dir.join(&file))
+            .map_err(|e| ScreenError::Capture(e.to_string()))?;
+        let mut manifest = fs::OpenOptions::new()
+            .append(true)
+            .open(self.dir.join(SESSION_MANIFEST))?;
+        writeln!(manifest, "{}\t{}", now.duration_since(self.started).as_millis(), file)?;
+        *state = (state.0 + 1, Some(now));
+        Ok(())
+    }
+}
+
+impl<S: ScreenSource> ScreenSource for SessionRecorder<S> {
+    fn capture(&self) -> Result<DynamicImage, ScreenError> {
+        let frame = self.inner.capture()?;
+        if let Err(e) = self.record(&frame) {
+            error!("SessionRecorder: failed to record frame: {}", e);
+        }
+        Ok(frame)
+    }
+
+    fn capture_area(&self, x: i32, y: i32, width: u32, height: u32) -> Result<DynamicImage, ScreenError> {
+        self.inner.capture_area(x, y, width, height)
+    }
+
+    fn size(&self) -> Result<(i32, i32), ScreenError> {
+        self.inner.size()
+    }
+}
+
+/// How a recorded session picks the frame to serve.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplayMode {
+    /// The frame whose timestamp was reached since the replay started.
+    Realtime,
+    /// Only `DirectoryScreenSource`-style explicit stepping (`advance`).
+    Manual,
+}
+
+/// Replays a directory written by `SessionRecorder`.
+pub struct RecordedSession {
+    frames: DirectoryScreenSource,
+    timestamps: Vec<Duration>,
+    mode: ReplayMode,
+    started: Instant,
+}
+
+impl RecordedSession {
+    pub fn open(dir: impl AsRef<Path>, mode: ReplayMode) -> Result<Self, ScreenError> {
+        let dir = dir.as_ref();
+        let manifest = fs::read_to_string(dir.join(SESSION_MANIFEST))?;
+        let mut files = Vec::new();
+        let mut timestamps = Vec::new();
+        for line in manifest.lines().filter(|l| !l.trim().is_empty()) {
+            let Some((ms, file)) = line.split_once('\t') else {
+                warn!("RecordedSession: malformed manifest line {:?}", line);
+                continue;
+            };
+            let Ok(ms) = ms.trim().parse::<u64>() else {
+                warn!("RecordedSession: bad timestamp in {:?}", line);
+                continue;
+            };
+            timestamps.push(Duration::from_millis(ms));
+            files.push(dir.join(file.trim()));
+        }
+        let frames = DirectoryScreenSource::from_files(files)
+            .map_err(|_| ScreenError::NoFrames(dir.display().to_string()))?;
+        info!(
+            "RecordedSession: {} frame(s) from {} ({:?})",
+            frames.frame_count(),
+            dir.display(),
+            mode
+        );
+        Ok(Self {
+            frames,
+            timestamps,
+            mode,
+            started: Instant::now(),
+        })
+    }
+
+    pub fn frame_count(&self) -> usize {
+        self.frames.frame_count()
+    }
+
+    /// Manual mode: next frame; `false` on the last one.
+    pub fn advance(&self) -> bool {
+        self.frames.advance()
+    }
+
+    /// Index of the frame that should be served at `elapsed`.
+    pub fn frame_index_at(&self, elapsed: Duration) -> usize {
+        self.timestamps
+            .iter()
+            .rposition(|&t| t <= elapsed)
+            .unwrap_or(0)
+    }
+
+    fn sync(&self) {
+        if self.mode == ReplayMode::Realtime {
+            let idx = self.frame_index_at(self.started.elapsed());
+            *self.frames.current.lock().unwrap() = idx;
+        }
+    }
+}
+
+impl ScreenSource for RecordedSession {
+    fn capture(&self) -> Result<DynamicImage, ScreenError> {
+        self.sync();
+        self.frames.capture()
+    }
+
+    fn capture_area(&self, x: i32, y: i32, width: u32, height: u32) -> Result<DynamicImage, ScreenError> {
+        self.sync();
+        self.frames.capture_area(x, y, width, height)
+    }
+}
+
+static SCREEN_SOURCE: Lazy<RwLock<Arc<dyn ScreenSource>>> =
+    Lazy::new(|| RwLock::new(Arc::new(LiveScreen)));
+
+/// Replaces the globally used screen source (e.g. with a `DirectoryScreenSource`).
+pub fn set_screen_source(source: Arc<dyn ScreenSource>) {
+    *SCREEN_SOURCE.write().unwrap() = source;
+    info!("Screen source replaced");
+}
+
+/// The currently selected screen source.
+pub fn screen_source() -> Arc<dyn ScreenSource> {
+    SCREEN_SOURCE.read().unwrap().clone()
+}
+
+/// Env-based selection: `MTGA_SCREEN_DIR` (PNG directory), `MTGA_REPLAY_DIR`
+/// (recorded session, realtime) or `MTGA_RECORD_DIR` (live + recording).
+/// Without any of them the live screen stays selected.
+pub fn init_screen_source_from_env() -> Result<(), ScreenError> {
+    if let Ok(dir) = std::env::var("MTGA_SCREEN_DIR") {
+        set_screen_source(Arc::new(DirectoryScreenSource::new(&dir)?));
+        info!("Using screenshots from {}", dir);
+    } else if let Ok(dir) = std::env::var("MTGA_REPLAY_DIR") {
+        set_screen_source(Arc::new(RecordedSession::open(&dir, ReplayMode::Realtime)?));
+        info!("Replaying recorded session {}", dir);
+    } else if let Ok(dir) = std::env::var("MTGA_RECORD_DIR") {
+        set_screen_source(Arc::new(SessionRecorder::new(LiveScreen, &dir)?));
+        info!("Recording session into {}", dir);
+    }
+    Ok(())
+}
@@ -0,0 +1,404 @@
+// app/screen_state.rs
+
+//! Where the MTGA client is, from a single screenshot. The attack button colour, the
+//! OCR of a few fixed regions and optional template hashes are combined into a typed
+//! `ScreenState`, so the states wait on screens (with timeouts) instead of comparing
+//! OCR strings in busy loops.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+use image::{DynamicImage, GenericImageView};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use tracing::{debug, info, warn};
+
+use crate::app::card_hash::{CardHashError, CardHashIndex, PerceptualHash};
+use crate::app::error::AppError;
+use crate::app::layout::layout;
+use crate::app::ocr::{crop_region, read_image_region_text};
+use crate::app::ocr_engine::OcrRegion;
+use crate::app::screen_source::screen_source;
+use crate::app::ui::Color;
+
+/// Colour of the main (attack / next) button.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ButtonColor {
+    Red,
+    Blue,
+    /// No lit button
+    #[default]
+    Black,
+}
+
+impl ButtonColor {
+    pub fn of(c: Color) -> Self {
+        if c.r > 200 {
+            Self::Red
+        } else if c.b > 200 {
+            Self::Blue
+        } else {
+            Self::Black
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameOutcome {
+    Victory,
+    Defeat,
+}
+
+/// The screen the client is showing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScreenState {
+    Home,
+    /// Searching for an opponent
+    Queue,
+    Mulligan,
+    /// Our main phase: "Next", or "End Turn" once nothing is left before the end step
+    MainPhase { end_turn: bool },
+    /// "All Attack" (`selected == 0`) or "N Attackers"
+    DeclareAttackers { selected: u32 },
+    /// "No Blocks" (`selected == 0`) or "N Blockers"
+    DeclareBlockers { selected: u32 },
+    /// `prompt` when we hold priority on their turn ("Resolve", "Pass", "My Turn")
+    OpponentTurn { prompt: bool },
+    /// Choosing targets or options ("Cancel", "Done", "Submit")
+    WaitingForTarget,
+    GameOver(GameOutcome),
+    Unknown,
+}
+
+/// Template file stems (`<key>.png`, `<key>#2.png` …) and the screens they stand for.
+const TEMPLATE_KEYS: &[(&str, ScreenState)] = &[
+    ("home", ScreenState::Home),
+    ("queue", ScreenState::Queue),
+    ("mulligan", ScreenState::Mulligan),
+    ("main_phase", ScreenState::MainPhase { end_turn: false }),
+    ("declare_attackers", ScreenState::DeclareAttackers { selected: 0 }),
+    ("declare_blockers", ScreenState::DeclareBlockers { selected: 0 }),
+    ("opponent_turn", ScreenState::OpponentTurn { prompt: false }),
+    ("waiting_for_target", ScreenState::WaitingForTarget),
+    ("victory", ScreenState::GameOver(GameOutcome::Victory)),
+    ("defeat", ScreenState::GameOver(GameOutcome::Defeat)),
+];
+
+impl ScreenState {
+    /// Screen of a template file stem.
+    pub fn from_template_key(key: &str) -> Option<Self> {
+        TEMPLATE_KEYS.iter().find(|(k, _)| *k == key).map(|(_, s)| *s)
+    }
+
+    /// Layout region (`region.<name>`) a template of `key` is compared with.
+    pub fn template_region(key: &str) -> Option<&'static str> {
+        match Self::from_template_key(key)? {
+            Self::Home | Self::Queue => Some("play_label"),
+            Self::Mulligan => Some("mulligan_buttons"),
+            Self::GameOver(_) => Some("banner"),
+            _ => Some("main_button"),
+        }
+    }
+
+    pub fn is_game_over(&self) -> bool {
+        matches!(self, Self::GameOver(_))
+    }
+
+    /// Any screen of a running game (our or the opponent's turn, combat, targeting).
+    pub fn is_in_game(&self) -> bool {
+        matches!(
+            self,
+            Self::MainPhase { .. }
+                | Self::DeclareAttackers { .. }
+                | Self::DeclareBlockers { .. }
+                | Self::OpponentTurn { .. }
+                | Self::WaitingForTarget
+        )
+    }
+}
+
+/// Everything the classifier looks at on one screenshot.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ScreenFeatures {
+    pub button: ButtonColor,
+    /// OCR of `region.main_button` (white-invert pipeline on a red button)
+    pub main_text: String,
+    /// OCR of `region.banner`
+    pub banner_text: String,
+    /// OCR of `region.play_label`
+    pub play_text: String,
+    /// OCR of `region.mulligan_buttons`
+    pub mulligan_text: String,
+    /// Best template match that passed its threshold
+    pub template: Option<ScreenState>,
+}
+
+static ATTACKERS_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(\d+)\s+attackers?").unwrap());
+static BLOCKERS_RE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(\d+)\s+blockers?").unwrap());
+
+/// Screen implied by the main button label alone.
+pub fn classify_main_button(text: &str) -> Option<ScreenState> {
+    let text = text.to_lowercase();
+    let count = |re: &Regex| re.captures(&text).and_then(|c| c[1].parse().ok());
+    if text.contains("all attack") {
+        Some(ScreenState::DeclareAttackers { selected: 0 })
+    } else if let Some(selected) = count(&ATTACKERS_RE) {
+        Some(ScreenState::DeclareAttackers { selected })
+    } else if text.contains("no blocks") {
+        Some(ScreenState::DeclareBlockers { selected: 0 })
+    } else if let Some(selected) = count(&BLOCKERS_RE) {
+        Some(ScreenState::DeclareBlockers { selected })
+    } else if text.contains("opponent's turn") || text.contains("opponents turn") {
+        Some(ScreenState::OpponentTurn { prompt: false })
+    } else if text.contains("my turn") || text.contains("resolve") || text.contains("pass") {
+        Some(ScreenState::OpponentTurn { prompt: true })
+    } else if text.contains("end turn") {
+        Some(ScreenState::MainPhase { end_turn: true })
+    } else if text.contains("next") {
+        Some(ScreenState::MainPhase { end_turn: false })
+    } else if text.contains("cancel") || text.contains("done") || text.contains("submit") {
+        Some(ScreenState::WaitingForTarget)
+    } else {
+        None
+    }
+}
+
+/// Combines the features, strongest evidence first: the game-over banner, the main
+/// button, the mulligan buttons, the Play button and finally the template match.
+pub fn classify_features(f: &ScreenFeatures) -> ScreenState {
+    let banner = f.banner_text.to_lowercase();
+    if banner.contains("victory") {
+        return ScreenState::GameOver(GameOutcome::Victory);
+    }
+    if banner.contains("defeat") {
+        return ScreenState::GameOver(GameOutcome::Defeat);
+    }
+    if let Some(s @ ScreenState::GameOver(_)) = f.template {
+        return s;
+    }
+    if let Some(s) = classify_main_button(&f.main_text) {
+        return s;
+    }
+    let mulligan = f.mulligan_text.to_lowercase();
+    if mulligan.contains("mulligan") || mulligan.contains("keep") {
+        return ScreenState::Mulligan;
+    }
+    let play = f.play_text.to_lowercase();
+    if play.contains("play") {
+        return ScreenState::Home;
+    }
+    if play.contains("cancel") || play.contains("searching") {
+        return ScreenState::Queue;
+    }
+    f.template.unwrap_or(ScreenState::Unknown)
+}
+
+/// Screenshot → `ScreenState`. Templates are grouped by the layout region they are
+/// compared with, so one crop is hashed per region.
+#[derive(Debug, Clone, Default)]
+pub struct ScreenClassifier {
+    templates: BTreeMap<&'static str, CardHashIndex>,
+}
+
+impl ScreenClassifier {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `img` (a crop of the template's region) as a template for `key`.
+    /// Unknown keys are ignored with a warning.
+    pub fn with_template(mut self, key: &str, img: &DynamicImage) -> Self {
+        self.add_template(key, img);
+        self
+    }
+
+    fn add_template(&mut self, key: &str, img: &DynamicImage) {
+        let Some(region) = ScreenState::template_region(key) else {
+            warn!("Unknown screen template `{}` ignored", key);
+            return;
+        };
+        self.templates
+            .entry(region)
+            .or_insert_with(|| CardHashIndex::new().with_min_confidence(0.85))
+            .insert(key, PerceptualHash::of(img));
+    }
+
+    /// Loads `<key>.png` region crops from `dir`; a `#…` suffix marks extra variants.
+    pub fn load_templates(dir: impl AsRef<Path>) -> Result<Self, CardHashError> {
+        let mut files: Vec<_> = fs::read_dir(dir.as_ref())?
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| p.extension().is_some_and(|e| e.eq_ignore_ascii_case("png")))
+            .collect();
+        files.sort();
+
+        let mut classifier = Self::new();
+        for path in files {
+            let img = image::open(&path).map_err(|e| CardHashError::Image {
+                path: path.display().to_string(),
+                reason: e.to_string(),
+            })?;
+            let stem = path.file_stem().and_then(|s| s.to_str()).unwrap_or_default();
+            classifier.add_template(stem.split('#').next().unwrap_or(stem).trim(), &img);
+        }
+        Ok(classifier)
+    }
+
+    pub fn template_count(&self) -> usize {
+        self.templates.values().map(CardHashIndex::len).sum()
+    }
+
+    /// Reads every feature from `screen`.
+    pub fn features(&self, screen: &DynamicImage) -> ScreenFeatures {
+        let layout = layout();
+        let (w, h) = screen.dimensions();
+        let (bx, by) = layout.button("attack", w, h);
+        let button = if bx >= 0 && by >= 0 && (bx as u32) < w && (by as u32) < h {
+            let p = screen.get_pixel(bx as u32, by as u32);
+            ButtonColor::of(Color { r: p[0], g: p[1], b: p[2] })
+        } else {
+            ButtonColor::Black
+        };
+        let read = |region: &str, engine: OcrRegion, invert: bool| {
+            read_image_region_text(
+                screen,
+                layout.region(region, w, h),
+                engine,
+                invert,
+                &format!("screen_{}.png", region),
+            )
+        };
+
+        let template = self
+            .templates
+            .iter()
+            .filter_map(|(region, index)| {
+                let (x1, y1, x2, y2) = layout.region(region, w, h);
+                let crop = crop_region(screen, x1, y1, x2, y2).ok()?;
+                index.identify(&crop)
+            })
+            .max_by(|a, b| a.confidence.total_cmp(&b.confidence))
+            .and_then(|m| ScreenState::from_template_key(&m.name));
+
+        ScreenFeatures {
+            button,
+            main_text: read("main_button", OcrRegion::ButtonLabel, button == ButtonColor::Red),
+            banner_text: read("banner", OcrRegion::Generic, false),
+            play_text: read("play_label", OcrRegion::ButtonLabel, false),
+            mulligan_text: read("mulligan_buttons", OcrRegion::ButtonLabel, false),
+            template,
+        }
+    }
+
+    pub fn classify(&self, screen: &DynamicImage) -> ScreenState {
+        let features = self.features(screen);
+        let state = classify_features(&features);
+        debug!("Screen features {:?} → {:?}", features, state);
+        state
+    }
+}
+
+static SCREEN_CLASSIFIER: Lazy<RwLock<Option<Arc<ScreenClassifier>>>> = Lazy::new(|| RwLock::new(None));
+
+/// Replaces the globally used classifier.
+pub fn set_screen_classifier(classifier: Arc<ScreenClassifier>) {
+    *SCREEN_CLASSIFIER.write().unwrap() = Some(classifier);
+}
+
+/// The current classifier; without templates it relies on colour and OCR only.
+pub fn screen_classifier() -> Arc<ScreenClassifier> {
+    SCREEN_CLASSIFIER
+        .read()
+        .unwrap()
+        .clone()
+        .unwrap_or_else(|| Arc::new(ScreenClassifier::new()))
+}
+
+/// `MTGA_SCREEN_TEMPLATES=<dir>` loads template crops for the classifier.
+pub fn init_screen_templates_from_env() -> Result<(), CardHashError> {
+    if let Ok(dir) = std::env::var("MTGA_SCREEN_TEMPLATES") {
+        let classifier = ScreenClassifier::load_templates(&dir)?;
+        info!("Loaded {} screen template(s) from {}", classifier.template_count(), dir);
+        set_screen_classifier(Arc::new(classifier));
+    }
+    Ok(())
+}
+
+/// Classifies the current screen of the active `ScreenSource`.
+pub fn current_screen() -> ScreenState {
+    match screen_source().capture() {
+        Ok(img) => {
+            let state = screen_classifier().classify(&img);
+            info!("Screen: {:?}", state);
+            state
+        }
+        Err(e) => {
+            warn!("Screen capture for classification failed: {}", e);
+            ScreenState::Unknown
+        }
+    }
+}
+
+/// Polls the screen every `poll` until `on_screen` returns a value. `on_screen` may act
+/// on intermediate screens (click, press a key) and return `None` to keep waiting.
+/// A game-over screen that `on_screen` does not accept ends the wait with
+/// `AppError::GameOver`; after `timeout` it gives up with `AppError::ScreenTimeout`.
+pub fn wait_for<T>(
+    what: &str,
+    timeout: Duration,
+    poll: Duration,
+    on_screen: impl FnMut(ScreenState) -> Result<Option<T>, AppError>,
+) -> Result<T, AppError> {
+    poll_screen(what, timeout, poll, true, on_screen)
+}
+
+/// `wait_for` for waits that start on (or pass through) the results screens: a
+/// game-over screen is just another screen for `on_screen`, not an `AppError::GameOver`.
+pub fn wait_for_past_game_over<T>(
+    what: &str,
+    timeout: Duration,
+    poll: Duration,
+    on_screen: impl FnMut(ScreenState) -> Result<Option<T>, AppError>,
+) -> Result<T, AppError> {
+    poll_screen(what, timeout, poll, false, on_screen)
+}
+
+fn poll_screen<T>(
+    what: &str,
+    timeout: Duration,
+    poll: Duration,
+    stop_on_game_over: bool,
+    mut on_screen: impl FnMut(ScreenState) -> Result<Option<T>, AppError>,
+) -> Result<T, AppError> {
+    let start = Instant::now();
+    loop {
+        let screen = current_screen();
+        if let Some(value) = on_screen(screen)? {
+            return Ok(value);
+        }
+        if let (true, ScreenState::GameOver(outcome)) = (stop_on_game_over, screen) {
+            return Err(AppError::GameOver(outcome));
+        }
+        if start.elapsed() >= timeout {
+            return Err(AppError::ScreenTimeout {
+                what: what.to_string(),
+                secs: timeout.as_secs(),
+                last: screen,
+            });
+        }
+        sleep(poll);
+    }
+}
+
+/// Waits until the screen satisfies `accept` and returns it.
+pub fn wait_for_screen(
+    what: &str,
+    timeout: Duration,
+    poll: Duration,
+    accept: impl Fn(&ScreenState) -> bool,
+) -> Result<ScreenState, AppError> {
+    wait_for(what, timeout, poll, |s| Ok(accept(&s).then_some(s)))
+}
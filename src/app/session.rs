@@ -0,0 +1,101 @@
+// app/session.rs
+
+//! Unattended play across games: the recorded results, the limits of a session
+//! (number of games, run time) and getting from the results screens back home.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Local};
+use tracing::{info, warn};
+
+use crate::app::error::AppError;
+use crate::app::screen_state::{wait_for, wait_for_past_game_over, GameOutcome, ScreenState};
+use crate::app::ui::click_at;
+
+/// How one game ended.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameResult {
+    pub outcome: GameOutcome,
+    /// Opponent turns seen
+    pub turns: usize,
+    pub duration: Duration,
+    pub finished_at: DateTime<Local>,
+}
+
+/// When to stop queueing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionLimits {
+    pub max_games: u32,
+    pub max_duration: Duration,
+}
+
+impl Default for SessionLimits {
+    fn default() -> Self {
+        Self {
+            max_games: 3,
+            max_duration: Duration::from_secs(1200),
+        }
+    }
+}
+
+impl SessionLimits {
+    /// `MTGA_MAX_GAMES=<n>` and `MTGA_STOP_AFTER_MINUTES=<m>` override the defaults;
+    /// malformed values are reported and ignored.
+    pub fn from_env() -> Self {
+        let mut limits = Self::default();
+        if let Ok(v) = std::env::var("MTGA_MAX_GAMES") {
+            match v.trim().parse() {
+                Ok(n) => limits.max_games = n,
+                Err(_) => warn!("Ignoring MTGA_MAX_GAMES={:?}: not a number", v),
+            }
+        }
+        if let Ok(v) = std::env::var("MTGA_STOP_AFTER_MINUTES") {
+            match v.trim().parse::<u64>() {
+                Ok(m) => limits.max_duration = Duration::from_secs(m * 60),
+                Err(_) => warn!("Ignoring MTGA_STOP_AFTER_MINUTES={:?}: not a number", v),
+            }
+        }
+        limits
+    }
+
+    pub fn reached(&self, games_played: u32, elapsed: Duration) -> bool {
+        games_played >= self.max_games || elapsed >= self.max_duration
+    }
+}
+
+/// Wins and losses among `results`.
+pub fn tally(results: &[GameResult]) -> (usize, usize) {
+    let wins = results
+        .iter()
+        .filter(|r| r.outcome == GameOutcome::Victory)
+        .count();
+    (wins, results.len() - wins)
+}
+
+/// The results screens (banner, rewards, progress) take a click each to dismiss.
+const RESULTS_TIMEOUT: Duration = Duration::from_secs(120);
+const RESULTS_POLL: Duration = Duration::from_secs(2);
+
+/// Clicks `continue_at` until the home screen shows.
+pub fn leave_results_screen(continue_at: (i32, i32)) -> Result<(), AppError> {
+    wait_for_past_game_over("home screen", RESULTS_TIMEOUT, RESULTS_POLL, |screen| match screen {
+        ScreenState::Home => Ok(Some(())),
+        ScreenState::GameOver(_) | ScreenState::Unknown => {
+            click_at(continue_at.0, continue_at.1)?;
+            Ok(None)
+        }
+        _ => Ok(None),
+    })?;
+    info!("Back on the home screen.");
+    Ok(())
+}
+
+/// After a state failed: the game ended (the outcome is returned) or the client is
+/// back home (`None`, the game is lost track of). Anything else within the timeout is an error.
+pub fn recover_after_error(timeout: Duration) -> Result<Option<GameOutcome>, AppError> {
+    wait_for("game over or home screen", timeout, RESULTS_POLL, |screen| match screen {
+        ScreenState::GameOver(outcome) => Ok(Some(Some(outcome))),
+        ScreenState::Home => Ok(Some(None)),
+        _ => Ok(None),
+    })
+}
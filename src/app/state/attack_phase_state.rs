@@ -1,24 +1,31 @@
 // app/state/attack_phase_state.rs
 
 use crate::app::error::AppError;
-use crate::app::game_state::GamePhase;
+use crate::app::game_state::{GameEvent, GamePhase, Player};
 use std::{thread::sleep, time::Duration};
 use tracing::{info};
-use crate::app::combat_engine::CombatEngine;
-use regex::Regex;
 
 use crate::app::{
     bot::Bot,
-    ocr::check_main_region_text,
-    state::{State, second_main_phase_state::SecondMainPhaseState},
-    ui::{set_cursor_pos, left_click},
+    screen_state::{wait_for, wait_for_screen, ScreenState},
+    state::State,
+    ui::click_at,
 };
-use crate::app::ui::check_button_color;
+
+/// How long one step of the attack (button appearing, attackers confirmed) may take.
+const STEP_TIMEOUT: Duration = Duration::from_secs(60);
+const POLL: Duration = Duration::from_secs(2);
 
 pub struct AttackPhaseState {
     no_attack: bool,
 }
 
+impl Default for AttackPhaseState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl AttackPhaseState {
     pub fn new() -> Self {
         Self { no_attack: false }
@@ -33,7 +40,8 @@ impl State<AppError> for AttackPhaseState {
             self.no_attack = true;
             return Ok(());
         }
-        self.process_attack_phase(bot);
+        Self::log_opponent_interaction(bot);
+        self.process_attack_phase(bot)?;
         Ok(())
     }
 
@@ -48,12 +56,21 @@ impl State<AppError> for AttackPhaseState {
 }
 
 impl AttackPhaseState {
-    fn is_attackers_text(s: &str) -> bool {
-        // match “1 Attacker” or “2 or more Attackers”, in any case, with optional surrounding whitespace
-        let re = Regex::new(r"^\s*\d+\s+Attackers?\s*$").unwrap();
-        let result = re.is_match(s.trim());
-        info!("is_attackers_text(): input = {:?}, matches regex: {}", s, result);
-        result
+    /// Az ellenfél legfeljebb annyi landdel rendelkezhet, ahány köre volt;
+    /// ennyi szabad manából nézzük, milyen instantokra számíthatunk.
+    fn log_opponent_interaction(bot: &Bot) {
+        let untapped = bot.opponent_turn_counter as u32;
+        let threats = bot
+            .opponent_model
+            .likely_instant_interaction(untapped, &bot.card_library);
+        if let Some(arch) = bot.opponent_model.likely_archetype() {
+            info!(
+                "Opponent looks like '{}'; possible instant-speed interaction with {} mana: {:?}",
+                arch.name,
+                untapped,
+                threats.iter().map(|c| c.name.as_str()).collect::<Vec<_>>()
+            );
+        }
     }
 
     fn can_attack(bot: &Bot) -> bool {
@@ -66,86 +83,67 @@ impl AttackPhaseState {
         })
     }
 
-    pub fn process_attack_phase(&self, bot: &mut Bot) {
-        // 1) Wait for "All Attack" on red button
-        loop {
-            let main_text = check_main_region_text(
-                bot.screen_width as u32,
-                bot.screen_height as u32,
-                true,
-            );
-            info!("(Attack phase - red) Main region text: {}", main_text);
-
-            if main_text.contains("All Attack") {
-                // record which creatures will attack
-                bot.attacking = bot
-                    .battlefield_creatures
-                    .iter()
-                    .filter_map(|(name, card)| {
-                        if let crate::app::card_library::CardType::Creature(cr) = &card.card_type {
-                            if !cr.summoning_sickness {
-                                return Some(name.clone());
-                            }
-                        }
-                        None
-                    })
-                    .collect();
-                info!("Attacking creatures: {:?}", bot.attacking);
-
-                let (x, y) = bot.cords.attack_button;
-                set_cursor_pos(x, y);
-                left_click();
-                sleep(Duration::from_secs(1));
-                break;
-            }
-            if main_text.contains("Next") {
-                let (x, y) = bot.cords.attack_button;
-                set_cursor_pos(x, y);
-                left_click();
-                sleep(Duration::from_secs(1));
-            } else {
-                sleep(Duration::from_secs(2));
-            }
-        }
-
-        // 2) Wait for "X Attackers"
-        loop {
-            let is_red = check_button_color(&bot.cords) == "red";
-            let main_text = check_main_region_text(
-                bot.screen_width as u32,
-                bot.screen_height as u32,
-                is_red,
-            );
-            info!("(Attack phase - white) Main region text: {}", main_text);
+    pub fn process_attack_phase(&self, bot: &mut Bot) -> Result<(), AppError> {
+        let (x, y) = bot.cords.attack_button;
 
-            if Self::is_attackers_text(&main_text) {
-                let (x, y) = bot.cords.attack_button;
-                set_cursor_pos(x, y);
-                left_click();
+        // 1) Wait for "All Attack", stepping through "Next" prompts before combat
+        wait_for("declare attackers", STEP_TIMEOUT, POLL, |screen| match screen {
+            ScreenState::DeclareAttackers { selected: 0 } => Ok(Some(())),
+            ScreenState::MainPhase { end_turn: false } => {
+                click_at(x, y)?;
                 sleep(Duration::from_secs(1));
-                break;
-            } else {
-                sleep(Duration::from_secs(2));
+                Ok(None)
             }
+            _ => Ok(None),
+        })?;
+
+        // record which creatures will attack
+        bot.attacking = bot
+            .battlefield_creatures
+            .iter()
+            .filter_map(|(name, card)| {
+                if let crate::app::card_library::CardType::Creature(cr) = &card.card_type
+                    && !cr.summoning_sickness {
+                        return Some(name.clone());
+                    }
+                None
+            })
+            .collect();
+        info!("Attacking creatures: {:?}", bot.attacking);
+        bot.record_attack_declaration();
+        let mut attackers: Vec<_> = bot
+            .attacking
+            .iter()
+            .filter_map(|name| bot.battlefield_creatures.get(name).cloned())
+            .collect();
+        let attacker_ids: Vec<u64> = attackers.iter().map(|c| c.card_id).collect();
+        for attacker in attacker_ids {
+            bot.gre.trigger_event(
+                GameEvent::AttackDeclared { attacker },
+                &mut attackers,
+                Player::Us,
+            );
         }
+        bot.gre.resolve_stack();
+        click_at(x, y)?;
+        sleep(Duration::from_secs(1));
 
-        // 3) Click "Next" until it goes away
-        loop {
-            let main_text = check_main_region_text(
-                bot.screen_width as u32,
-                bot.screen_height as u32,
-                false,
-            );
-            info!("(Attack phase - post-attack Next loop) Main region text: {}", main_text);
+        // 2) Wait for "X Attackers" and confirm
+        wait_for_screen("attackers to be selected", STEP_TIMEOUT, POLL, |screen| {
+            matches!(screen, ScreenState::DeclareAttackers { selected } if *selected > 0)
+        })?;
+        click_at(x, y)?;
+        sleep(Duration::from_secs(1));
 
-            if main_text.contains("Next") {
-                let (x, y) = bot.cords.attack_button;
-                set_cursor_pos(x, y);
-                left_click();
+        // 3) Click "Next" until it goes away
+        wait_for("end of combat", STEP_TIMEOUT, POLL, |screen| match screen {
+            ScreenState::MainPhase { end_turn: false } => {
+                click_at(x, y)?;
                 sleep(Duration::from_secs(1));
-            } else {
-                break;
+                Ok(None)
             }
-        }
+            _ => Ok(Some(())),
+        })?;
+        Ok(())
     }
 }
@@ -1,33 +1,44 @@
 // src/app/state/combat_damage_state.rs
 
-use crate::app::card_attribute::{KeywordAbility, Trigger, TargetFilter};
-use crate::app::combat_engine::CombatEngine;
+use crate::app::combat_engine::{AttackTarget, CombatEngine, Combatant};
 use crate::app::error::AppError;
 use crate::app::game_state::{GameEvent, GamePhase, Player};
+use crate::app::gre::Target;
 use crate::app::bot::Bot;
-use crate::app::card_library::CardType;
+use crate::app::card_library::{CardType, CardTypeFlags};
 use crate::app::state::{second_main_phase_state::SecondMainPhaseState, State};
 use tracing::info;
 
 pub struct CombatDamageState;
 
+impl Default for CombatDamageState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl CombatDamageState {
     pub fn new() -> Self { Self }
 
     fn resolve_combat_damage(&self, bot: &mut Bot) {
         info!("CombatDamageState: resolving combat damage for attackers: {:?}", bot.attacking);
 
-        // 1) GRE triggers: OnCombatDamage képességek (pl. Lifelink, Deathtouch, stb.)
-        for atk_name in bot.attacking.iter() {
-            if let Some(atk_card) = bot.battlefield_creatures.get_mut(atk_name) {
-                let effects = atk_card.trigger_by(&Trigger::OnCombatDamage { filter: TargetFilter::SelfCard });
-                for eff in effects {
-                    bot.gre.handle_effect(eff);
-                }
-            }
-        }
+        // 1) Blokkolások bejelentése a GRE-nek (OnBlock triggerek)
+        bot.record_block_declaration();
+        let mut attackers: Vec<_> = bot
+            .attacking
+            .iter()
+            .filter_map(|name| bot.battlefield_creatures.get(name).cloned())
+            .collect();
 
         // 2) Harci eredmények kiszámolása a CombatEngine-nel
+        // A támadó oldal indexei a `battlefield_creatures` bejárási sorrendjét követik
+        let attack_ids: Vec<u64> = bot
+            .battlefield_creatures
+            .values()
+            .filter(|c| matches!(c.card_type, CardType::Creature(_)))
+            .map(|c| c.card_id)
+            .collect();
         let attack_vec = bot
             .battlefield_creatures
             .values()
@@ -39,6 +50,12 @@ impl CombatDamageState {
                 }
             })
             .collect::<Vec<_>>();
+        let block_ids: Vec<u64> = bot
+            .battlefield_opponent_creatures
+            .values()
+            .filter(|c| matches!(c.card_type, CardType::Creature(_)))
+            .map(|c| c.card_id)
+            .collect();
         let block_vec = bot
             .battlefield_opponent_creatures
             .values()
@@ -51,13 +68,40 @@ impl CombatDamageState {
             })
             .collect::<Vec<_>>();
 
-        let (surv_att, surv_blk, unblocked_dmg, life_gain) = CombatEngine::resolve_combat(
+        // Több blokkoló esetén a sebzés-kiosztási sorrendről a GRE döntési pontja dönt
+        let mut attackers_blocked: Vec<usize> = bot.combat_blocks.keys().copied().collect();
+        attackers_blocked.sort_unstable();
+        for attacker in attackers_blocked {
+            let blockers = bot.combat_blocks[&attacker].clone();
+            if blockers.len() < 2 || attacker >= attack_vec.len() {
+                continue;
+            }
+            let blocker_creatures: Vec<_> = blockers
+                .iter()
+                .filter_map(|&b| block_vec.get(b).cloned())
+                .collect();
+            if blocker_creatures.len() != blockers.len() {
+                continue;
+            }
+            let order = bot
+                .gre
+                .damage_assignment_order(&attack_vec[attacker], &blocker_creatures);
+            bot.combat_blocks
+                .insert(attacker, order.into_iter().map(|i| blockers[i]).collect());
+        }
+
+        let outcome = CombatEngine::resolve_combat_with_targets(
             &bot.combat_attackers,
             &attack_vec,
             &block_vec,
             &bot.combat_blocks,
+            &bot.combat_attack_targets,
             &mut bot.gre.prevent_life_gain_opponent,
         );
+        let surv_att = outcome.surviving_attackers;
+        let surv_blk = outcome.surviving_blockers;
+        let unblocked_dmg = outcome.player_damage;
+        let life_gain = outcome.life_gain;
 
         // 3) Túlélők kiszűrése a HashMap-ben
         bot.battlefield_creatures = bot
@@ -67,25 +111,80 @@ impl CombatDamageState {
             .filter(|&(_, &keep)| keep)
             .map(|((name, card), _)| (name.clone(), card.clone()))
             .collect();
-        bot.battlefield_opponent_creatures = bot
+        let (opp_survivors, opp_dead): (Vec<_>, Vec<_>) = bot
             .battlefield_opponent_creatures
             .iter()
             .zip(surv_blk.iter())
-            .filter(|&(_, &keep)| keep)
+            .partition(|&(_, &keep)| keep);
+        for ((name, card), _) in opp_dead {
+            if !card.type_flags.contains(CardTypeFlags::TOKEN) {
+                bot.opponent_model.observe_graveyard(name);
+            }
+        }
+        bot.battlefield_opponent_creatures = opp_survivors
+            .into_iter()
             .map(|((name, card), _)| (name.clone(), card.clone()))
             .collect();
 
         // 4) Unblocked damage az ellenfél életére
         if unblocked_dmg > 0 {
             info!("Applying {} unblocked damage to opponent", unblocked_dmg);
-            bot.updater.state.opponent_life_total =
-                bot.updater.state.opponent_life_total.saturating_sub(unblocked_dmg as i32);
+            bot.gre.deal_damage_to_player(Player::Opponent, unblocked_dmg);
+        }
+
+        // 4b) Planeswalkerekre mért sebzés: loyalty counterek levétele
+        for (&pw_id, &dmg) in outcome.planeswalker_damage.iter() {
+            info!("Applying {} combat damage to planeswalker id={}", dmg, pw_id);
+            bot.gre.damage_planeswalker(pw_id, dmg);
+        }
+
+        // 4c) Sebzés-események csak azokra, akiket a támadó ténylegesen megsebzett
+        for &(attacker, target, amount) in &outcome.hits {
+            let Some(&source) = attack_ids.get(attacker) else {
+                continue;
+            };
+            let target = match target {
+                AttackTarget::Player => Target::Player(Player::Opponent),
+                AttackTarget::Planeswalker(id) => Target::Permanent(id),
+            };
+            bot.gre.trigger_event(
+                GameEvent::DamageDealt {
+                    source: Some(source),
+                    target,
+                    amount: amount as u32,
+                    combat: true,
+                },
+                &mut attackers,
+                Player::Us,
+            );
+        }
+
+        // 4d) Támadók és blokkolók egymásnak okozott sebzése
+        let combatant_id = |c: Combatant| match c {
+            Combatant::Attacker(i) => attack_ids.get(i).copied(),
+            Combatant::Blocker(i) => block_ids.get(i).copied(),
+        };
+        for &(source, recipient, amount) in &outcome.creature_damage {
+            let (Some(source), Some(recipient)) = (combatant_id(source), combatant_id(recipient))
+            else {
+                continue;
+            };
+            bot.gre.trigger_event(
+                GameEvent::DamageDealt {
+                    source: Some(source),
+                    target: Target::Permanent(recipient),
+                    amount: amount as u32,
+                    combat: true,
+                },
+                &mut attackers,
+                Player::Us,
+            );
         }
 
         // 5) Lifelinkből származó életerő-nyerés
         if life_gain > 0 {
             info!("Gaining {} life from lifelink", life_gain);
-            bot.updater.state.life_total += life_gain;
+            bot.gre.gain_life(Player::Us, life_gain as u32);
         }
 
         // 6) GRE stack és delayed efektek
@@ -97,16 +196,24 @@ impl CombatDamageState {
             bot.screen_width as u32,
             bot.screen_height as u32,
             &bot.cards_texts,
-            &crate::app::card_library::build_card_library(),
+            &bot.card_library,
             bot.land_number,
             bot.land_played_this_turn,
             &bot.gre.stack,
             &mut bot.battlefield_creatures,
             &mut bot.battlefield_opponent_creatures,
         );
+        // A képernyőn látott életek a mérvadók; a GRE ezekhez igazodik
+        bot.gre.sync_life_totals(
+            bot.updater.state.life_total,
+            bot.updater.state.opponent_life_total,
+        );
+        bot.opponent_model
+            .observe_battlefield(&bot.battlefield_opponent_creatures);
         bot.attacking.clear();
         bot.combat_attackers.clear();
         bot.combat_blocks.clear();
+        bot.combat_attack_targets.clear();
     }
 }
 
@@ -7,7 +7,7 @@ use crate::app::game_state::GamePhase;
 
 use crate::app::{
     bot::Bot,
-    card_library::{build_card_library, CardType},
+    card_library::CardType,
     state::{
         State,
         attack_phase_state::AttackPhaseState,
@@ -30,6 +30,7 @@ impl State<AppError> for FirstMainPhaseState {
         self.play_land_step(bot);
         self.cast_main_phase_creatures(bot);
         self.cast_other_spells(bot);
+        bot.act_on_strategy();
         self.decide_attack_or_skip(bot);
 
         if bot.land_played_this_turn && bot.updater.state.mana_available == 0 {
@@ -54,6 +55,12 @@ impl State<AppError> for FirstMainPhaseState {
     }
 }
 
+impl Default for FirstMainPhaseState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl FirstMainPhaseState {
     pub fn new() -> Self {
         Self { skip_to_opponent: false }
@@ -74,6 +81,8 @@ impl FirstMainPhaseState {
             );
             bot.battlefield_creatures = ours;
             bot.battlefield_opponent_creatures = opps;
+            bot.opponent_model
+                .observe_battlefield(&bot.battlefield_opponent_creatures);
         } else {
             info!("First main phase of the game; skipping initial OCR refresh.");
             bot.first_main_phase_done = true;
@@ -98,8 +107,6 @@ impl FirstMainPhaseState {
 
     /// Itt integráljuk a creature-kijátszás előtti instant-célozást.
     fn cast_main_phase_creatures(&mut self, bot: &mut Bot) {
-        let library = build_card_library();
-
         loop {
             // Ellenőrizzük, van-e még legalább 1 kijátszható creature a kezünkben (mana is legyen).
             if !bot.can_cast_creature() {
@@ -134,7 +141,7 @@ impl FirstMainPhaseState {
                 }
 
                 // Insert new creature tapped
-                if let Some(mut card) = library.get(&name).cloned() {
+                if let Some(mut card) = bot.card_library.get(&name).cloned() {
                     // (1) Betesszük summoning sickness-szel
                     if let CardType::Creature(ref mut cr) = card.card_type {
                         cr.summoning_sickness = true;
@@ -149,13 +156,12 @@ impl FirstMainPhaseState {
                             .any(|attr| {
                                 let any = attr.as_ref() as &dyn Any;
                                 any.downcast_ref::<GrantAbilityAttribute>()
-                                    .map_or(false, |ga| ga.ability == KeywordAbility::Haste)
+                                    .is_some_and(|ga| ga.ability == KeywordAbility::Haste)
                             });
-                        if has_haste {
-                            if let CardType::Creature(ref mut cr) = entry.card_type {
+                        if has_haste
+                            && let CardType::Creature(ref mut cr) = entry.card_type {
                                 cr.summoning_sickness = false;
                             }
-                        }
                     }
                 }
 
@@ -181,6 +187,8 @@ impl FirstMainPhaseState {
                     );
                     bot.battlefield_creatures = ours;
                     bot.battlefield_opponent_creatures = opps;
+                    bot.opponent_model
+                        .observe_battlefield(&bot.battlefield_opponent_creatures);
 
                 } else {
                     info!("Either out of mana or no more creatures—no further battlefield reads.");
@@ -208,19 +216,18 @@ impl FirstMainPhaseState {
     fn decide_attack_or_skip(&mut self, bot: &mut Bot) {
         // if we still have non‐land spells to cast, go to attack
         if bot.land_number > 0 {
-            let lib = build_card_library();
-            let can_cast_more = bot.cards_texts.iter().any(|ocr| {
-                lib.values().any(|card| {
-                    !matches!(card.card_type, CardType::Land)
-                        && Bot::text_contains(&card.name, ocr)
-                        && {
+            let can_cast_more = bot
+                .cards_texts
+                .iter()
+                .filter_map(|ocr| bot.resolve_text(ocr))
+                .any(|card| {
+                    !matches!(card.card_type, CardType::Land) && {
                         let c = &card.mana_cost;
                         let col = c.colored();
                         let leftover = bot.land_number.saturating_sub(col);
                         bot.land_number >= col && leftover >= c.colorless
                     }
-                })
-            });
+                });
             if can_cast_more {
                 info!("Still spells to cast with available mana—proceeding to AttackPhase.");
                 self.skip_to_opponent = false;
@@ -8,16 +8,38 @@ use tracing::{warn, info};
 
 use crate::app::{
     bot::Bot,
+    card_library::Card,
+    cards_positions::get_mulligan_card_positions,
+    mulligan::{MulliganDecision, MulliganEvaluator},
     state::{State, first_main_phase_state::FirstMainPhaseState},
-    ui::{set_cursor_pos, check_button_color, press_key},
-    ocr::{check_main_region_text, check_start_order_text},
+    screen_state::{wait_for_screen, ScreenState},
+    ui::{set_cursor_pos, click_at},
+    ocr::{check_start_order_text, get_mulligan_card_text},
 };
 
-pub struct MulliganState {}
+/// Waiting for our first main phase after the opponent started.
+const FIRST_TURN_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// A mulligan képernyőn mindig 7 lap látszik (London mulligan).
+const OPENING_HAND_SIZE: usize = 7;
+
+pub struct MulliganState {
+    evaluator: MulliganEvaluator,
+    mulligans_taken: usize,
+}
+
+impl Default for MulliganState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl MulliganState {
     pub fn new() -> Self {
-        Self {}
+        Self {
+            evaluator: MulliganEvaluator::new(),
+            mulligans_taken: 0,
+        }
     }
 }
 
@@ -25,13 +47,15 @@ impl State<AppError> for MulliganState {
     fn update(&mut self, bot: &mut Bot) -> Result<(), AppError> {
         info!("MulliganState: starting mulligan phase.");
         Self::wait_for_start_order(self, bot);
-        info!("Mulligan selection done.");
 
         // short pause before moving on
         sleep(Duration::from_secs(1));
 
+        self.resolve_mulligans(bot)?;
+        info!("Mulligan selection done ({} mulligan(s)).", self.mulligans_taken);
+
         Self::wait_for_next_for_hover(self, bot);
-        Self::move_cursor_and_examine_cards(self, bot);
+        Self::move_cursor_and_examine_cards(self, bot)?;
 
         Ok(())
     }
@@ -60,13 +84,11 @@ impl MulliganState {
                 "Opponent Goes First" => {
                     bot.card_count = 8;
                     info!("Opponent starts; setting card_count = 8.");
-                    press_key(0x20);
                     break;
                 }
                 "You Go First" => {
                     bot.card_count = 7;
                     info!("We start; keeping card_count = 7.");
-                    press_key(0x20);
                     break;
                 }
                 _ if Instant::now().duration_since(bot.time_waiting_started) > bot.time_waiting_threshold => {
@@ -81,34 +103,120 @@ impl MulliganState {
     }
 
 
+    /// OCR the 7 cards on the mulligan screen; returns (screen index, card) for recognized ones.
+    fn read_mulligan_hand(bot: &Bot) -> Vec<(usize, Card)> {
+        let mut hand = Vec::new();
+        for i in 0..OPENING_HAND_SIZE {
+            let name = get_mulligan_card_text(
+                i,
+                OPENING_HAND_SIZE,
+                bot.screen_width as u32,
+                bot.screen_height as u32,
+            );
+            match bot.resolve_text(&name) {
+                Some(card) => hand.push((i, card.clone())),
+                None => warn!("Mulligan: unknown card text `{}` at slot {}", name, i),
+            }
+        }
+        hand
+    }
+
+    /// Keep/Mulligan loop, then London-bottom the chosen cards after keeping.
+    fn resolve_mulligans(&mut self, bot: &mut Bot) -> Result<(), AppError> {
+        loop {
+            let hand = Self::read_mulligan_hand(bot);
+            let cards: Vec<Card> = hand.iter().map(|(_, c)| c.clone()).collect();
+            let decision = if cards.is_empty() {
+                warn!("Mulligan: no card recognized, keeping by default.");
+                MulliganDecision::Keep
+            } else {
+                self.evaluator
+                    .decide(&cards, OPENING_HAND_SIZE, self.mulligans_taken)
+            };
+
+            match decision {
+                MulliganDecision::Mulligan => {
+                    let (x, y) = bot.cords.mulligan_button;
+                    info!("Mulligan #{}: clicking Mulligan at ({}, {})", self.mulligans_taken + 1, x, y);
+                    click_at(x, y)?;
+                    self.mulligans_taken += 1;
+                    sleep(Duration::from_secs(3));
+                }
+                MulliganDecision::Keep => {
+                    let (x, y) = bot.cords.keep_button;
+                    info!("Keeping hand: clicking Keep at ({}, {})", x, y);
+                    click_at(x, y)?;
+                    sleep(Duration::from_secs(2));
+                    if self.mulligans_taken > 0 {
+                        self.bottom_cards(bot, &hand)?;
+                    }
+                    break;
+                }
+            }
+        }
+        bot.card_count = bot.card_count.saturating_sub(self.mulligans_taken);
+        Ok(())
+    }
+
+    /// Select `mulligans_taken` cards to put on the bottom, then confirm with "Done".
+    fn bottom_cards(&self, bot: &mut Bot, hand: &[(usize, Card)]) -> Result<(), AppError> {
+        let cards: Vec<Card> = hand.iter().map(|(_, c)| c.clone()).collect();
+        let mut slots: Vec<usize> = self
+            .evaluator
+            .choose_bottom(&cards, self.mulligans_taken)
+            .into_iter()
+            .map(|i| hand[i].0)
+            .collect();
+        // Ha kevés lapot ismertünk fel, a fel nem ismert slotokkal töltjük fel
+        let unknown = (0..OPENING_HAND_SIZE).filter(|i| !hand.iter().any(|(s, _)| s == i));
+        for slot in unknown {
+            if slots.len() >= self.mulligans_taken {
+                break;
+            }
+            slots.push(slot);
+        }
+
+        let positions = get_mulligan_card_positions(
+            OPENING_HAND_SIZE,
+            bot.screen_width as u32,
+            bot.screen_height as u32,
+        );
+        for slot in slots {
+            if let Some(pos) = positions.get(slot) {
+                info!("London mulligan: bottoming slot {}", slot);
+                click_at(pos.click_x as i32, pos.click_y as i32)?;
+                sleep(Duration::from_millis(500));
+            }
+        }
+        let (x, y) = bot.cords.keep_button;
+        click_at(x, y)?;
+        sleep(Duration::from_secs(2));
+        Ok(())
+    }
+
     /// If opponent started (8 cards), wait for “Next” before we hover cards.
     fn wait_for_next_for_hover(&self, bot: &mut Bot) {
         if bot.card_count == 8 {
             info!("Opponent started; waiting for 'Next' to hover cards.");
-            loop {
-
-                let is_red = check_button_color(&bot.cords) == "red";
-                let txt = check_main_region_text(
-                    bot.screen_width as u32,
-                    bot.screen_height as u32,
-                    is_red,
-                );
-
-                if txt.contains("Next") {
-                    info!("Detected 'Next'; proceeding to hover cards.");
-                    break;
-                }
-                sleep(Duration::from_secs(2));
+            match wait_for_screen(
+                "our first main phase",
+                FIRST_TURN_TIMEOUT,
+                Duration::from_secs(2),
+                |screen| matches!(screen, ScreenState::MainPhase { .. }),
+            ) {
+                Ok(_) => info!("Detected 'Next'; proceeding to hover cards."),
+                Err(e) => warn!("{}; hovering cards anyway.", e),
             }
         }
     }
 
     /// Examine all cards (OCR) then center the cursor.
-    fn move_cursor_and_examine_cards(&self, bot: &mut Bot) {
+    fn move_cursor_and_examine_cards(&self, bot: &mut Bot) -> Result<(), AppError> {
         bot.examine_cards();
         let (cx, cy) = (bot.screen_width / 2, bot.screen_height / 2);
-        set_cursor_pos(cx, cy);
+        set_cursor_pos(cx, cy)?;
         info!("Cursor centered at ({}, {})", cx, cy);
         sleep(Duration::from_secs(1));
+        Ok(())
     }
 }
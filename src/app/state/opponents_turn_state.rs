@@ -1,19 +1,30 @@
 // app/state/opponents_turn_state.rs
 
 use crate::app::error::AppError;
-use crate::app::game_state::GamePhase;
+use crate::app::game_state::{GamePhase, Player};
 use std::{thread::sleep, time::Duration};
 use tracing::{info};
 
 use crate::app::{
     bot::Bot,
+    ocr::read_stack_top_text,
+    screen_state::{wait_for, ScreenState},
     state::{State, first_main_phase_state::FirstMainPhaseState},
     ui::press_key,
-    ocr::check_main_region_text,
 };
 
+/// An opponent's turn (with our responses) may take this long before we give up.
+const TURN_TIMEOUT: Duration = Duration::from_secs(600);
+const POLL: Duration = Duration::from_secs(2);
+
 pub struct OpponentsTurnState {}
 
+impl Default for OpponentsTurnState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl OpponentsTurnState {
     pub fn new() -> Self {
         Self {}
@@ -22,9 +33,14 @@ impl OpponentsTurnState {
 
 impl State<AppError> for OpponentsTurnState {
     fn update(&mut self, bot: &mut Bot) -> Result<(), AppError> {
+        // A mi körünk vége
         bot.on_turn_end();
+        bot.opponent_turn_counter += 1;
+        bot.gre.active_player = Player::Opponent;
         info!("OpponentsTurnState: handling opponent's turn.");
-        Self::process_opponents_turn(self, bot);
+        let result = Self::process_opponents_turn(self, bot);
+        bot.gre.active_player = Player::Us;
+        result?;
 
         // Mark that opponent turn has finished, so next draw should occur
         bot.last_opponent_turn = true;
@@ -44,33 +60,27 @@ impl State<AppError> for OpponentsTurnState {
 }
 
 impl OpponentsTurnState {
-    fn process_opponents_turn(&self, bot: &mut Bot) {
-        loop {
-            // read the red‐mode “button” region
-            let txt = check_main_region_text(
-                bot.screen_width as u32,
-                bot.screen_height as u32,
-                true,
-            );
-            info!("(Opponent turn – red) Main region text: {}", txt);
-
-            // whenever we see “My Turn”, “Resolve” or “Pass”, hit space
-            if txt.contains("My Turn")
-                || txt.contains("Resolve")
-                || txt.contains("Pass")
-            {
-                info!("Detected prompt (‘My Turn’/‘Resolve’/‘Pass’). Pressing Space.");
-                press_key(0x20);
+    fn process_opponents_turn(&self, bot: &mut Bot) -> Result<(), AppError> {
+        wait_for("our turn", TURN_TIMEOUT, POLL, |screen| match screen {
+            // whenever we hold priority (“My Turn”, “Resolve”, “Pass”), hit space
+            ScreenState::OpponentTurn { prompt: true } => {
+                // The opponent's spell waits on the stack: record it before letting it resolve
+                let text = read_stack_top_text(bot.screen_width as u32, bot.screen_height as u32);
+                if let Some(name) = bot.observe_opponent_spell(&text) {
+                    info!("Opponent's spell on the stack: '{}'", name);
+                }
+                info!("Detected priority prompt on the opponent's turn. Pressing Space.");
+                press_key(0x20)?;
+                Ok(None)
             }
-
-            // end the opponent’s turn when “Next” appears
-            if txt.contains("Next") {
-                info!("Detected ‘Next’. Ending opponent’s turn.");
-                bot.draw_card();
-                break;
-            }
-
-            sleep(Duration::from_secs(2));
-        }
+            // end the opponent’s turn when our main phase (“Next”) appears
+            ScreenState::MainPhase { .. } => Ok(Some(())),
+            _ => Ok(None),
+        })?;
+        info!("Detected our main phase. Ending opponent’s turn.");
+        // Az ellenfél köre is véget ér: a körönkénti számlálók neki is nullázódnak
+        bot.on_turn_end();
+        bot.draw_card();
+        Ok(())
     }
 }
\ No newline at end of file
@@ -9,10 +9,14 @@ use crate::app::{
     bot::Bot,
     state::{State, opponents_turn_state::OpponentsTurnState},
     ui::press_key,
-    ocr::check_main_region_text,
+    screen_state::{current_screen, wait_for, ScreenState},
     card_library::CardType::Creature,
 };
 
+/// Stepping to "End Turn" may take this long before we give up.
+const END_TURN_TIMEOUT: Duration = Duration::from_secs(60);
+const POLL: Duration = Duration::from_secs(2);
+
 pub struct SecondMainPhaseState {}
 
 
@@ -21,20 +25,21 @@ impl State<AppError> for SecondMainPhaseState {
         info!("SecondMainPhaseState: handling second main phase.");
 
         // 1. Első ellenőrzés: normál feldolgozás
-        if !self.initial_check(bot) {
+        if !self.initial_check() {
             return Ok(());
         }
 
-        // 2. Creature castolási akciók végrehajtása
+        // 2. Creature castolási akciók, utána a stratégia (képességek)
         bot.cast_creatures();
+        bot.act_on_strategy();
 
         // 3. Új ellenőrzés: ha most már "Opponent's Turn" szerepel a szövegben, kilépünk
-        if !self.post_cast_check(bot) {
+        if !self.post_cast_check() {
             return Ok(());
         }
 
         // 4. End Turn folyamat: red button feldolgozással
-        self.process_end_turn(bot);
+        self.process_end_turn()?;
 
         // 5. Állapot reset
         self.reset_state(bot);
@@ -53,6 +58,12 @@ impl State<AppError> for SecondMainPhaseState {
 
 
 
+impl Default for SecondMainPhaseState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl SecondMainPhaseState {
     pub fn new() -> Self {
         Self {}
@@ -60,14 +71,10 @@ impl SecondMainPhaseState {
 
     /// 1) Read the non-red “main” region once before any casts.
     ///    If we already see “Opponent's Turn”, bail out early.
-    fn initial_check(&self, bot: &mut Bot) -> bool {
-        let txt = check_main_region_text(
-            bot.screen_width as u32,
-            bot.screen_height as u32,
-            false,
-        );
-        info!("(Initial check) Main region text: {}", txt);
-        if txt.contains("Opponent's Turn") {
+    fn initial_check(&self) -> bool {
+        let screen = current_screen();
+        info!("(Initial check) Screen: {:?}", screen);
+        if screen == (ScreenState::OpponentTurn { prompt: false }) {
             info!("Detected 'Opponent's Turn' on entry; skipping second main.");
             return false;
         }
@@ -75,46 +82,32 @@ impl SecondMainPhaseState {
     }
 
     /// 3) After casting creatures, check again for “Opponent's Turn”.
-    fn post_cast_check(&self, bot: &mut Bot) -> bool {
-        let txt = check_main_region_text(
-            bot.screen_width as u32,
-            bot.screen_height as u32,
-            false,
-        );
-        info!("(Post-cast check) Main region text: {}", txt);
-        if txt.contains("Opponent's Turn") {
+    fn post_cast_check(&self) -> bool {
+        let screen = current_screen();
+        info!("(Post-cast check) Screen: {:?}", screen);
+        if screen == (ScreenState::OpponentTurn { prompt: false }) {
             info!("Detected 'Opponent's Turn' after casting; exiting.");
             return false;
         }
         true
     }
 
-    /// 4) Loop in red-mode until we see “End Turn”, tapping space on “Next” or “End Turn”.
-    fn process_end_turn(&self, bot: &mut Bot) {
-        loop {
-            let txt = check_main_region_text(
-                bot.screen_width as u32,
-                bot.screen_height as u32,
-                true,
-            );
-            info!("(End-turn red) Main region text: {}", txt);
-
-            if txt.contains("Next") {
+    /// 4) Step through “Next” until “End Turn” shows, then press it.
+    fn process_end_turn(&self) -> Result<(), AppError> {
+        wait_for("end turn", END_TURN_TIMEOUT, POLL, |screen| match screen {
+            ScreenState::MainPhase { end_turn: false } => {
                 info!("Detected 'Next'; pressing Space to advance.");
-                press_key(0x20);
+                press_key(0x20)?;
                 sleep(Duration::from_secs(1));
-                continue;
+                Ok(None)
             }
-
-            if txt.contains("End Turn") {
+            ScreenState::MainPhase { end_turn: true } => {
                 info!("Detected 'End Turn'; pressing Space to finish.");
-                press_key(0x20);
-                break;
+                press_key(0x20)?;
+                Ok(Some(()))
             }
-
-            // otherwise wait and retry
-            sleep(Duration::from_secs(2));
-        }
+            _ => Ok(None),
+        })
     }
 
 
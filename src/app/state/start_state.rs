@@ -1,6 +1,5 @@
 // src/app/state/start_state.rs
 
-use enigo::Key;
 use crate::app::error::AppError;
 use crate::app::game_state::GamePhase;
 use crate::app::bot::Bot;
@@ -11,6 +10,12 @@ use crate::multiplatform::{move_cursor, click_left};
 
 pub struct StartState;
 
+impl Default for StartState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl StartState {
     pub fn new() -> Self {
         Self
@@ -18,7 +23,7 @@ impl StartState {
 }
 
 
-///creature read test
+//creature read test
 /*
 /// Ez a függvény soha nem tér vissza (-> !), végtelen ciklusban olvassa a battlefieldet.
 fn battlefield_debug_loop(bot: &mut Bot) -> ! {
@@ -10,10 +10,17 @@ use crate::app::{
     bot::Bot,
     state::{State, first_main_phase_state::FirstMainPhaseState},
     cards_positions::get_card_positions,
-    ui::{left_click, set_cursor_pos},
+    layout::layout,
+    ui::click_at,
 };
 pub struct SubmitState {}
 
+impl Default for SubmitState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl SubmitState {
     pub fn new() -> Self {
         Self {}
@@ -26,9 +33,9 @@ impl State<AppError> for SubmitState {
         let positions = get_card_positions(bot.card_count, bot.screen_width as u32);
         if bot.card_count >= 4 {
             let pos = &positions[3];
-            let card_y = ((bot.screen_height as f64) * 0.97).ceil() as i32;
-            set_cursor_pos(pos.hover_x as i32, card_y);
-            left_click();
+            let layout = layout();
+            let card_y = layout.sy(layout.value("hand.hover_y"), bot.screen_height as u32).ceil() as i32;
+            click_at(pos.hover_x as i32, card_y)?;
             info!("Clicked 4th card for 'Submit 0'.");
         } else {
             warn!("Not enough cards for Submit 0 action.");
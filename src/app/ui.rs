@@ -6,8 +6,10 @@ use crate::multiplatform::move_cursor;
 use crate::multiplatform::get_pixel;
 use tracing::{debug, info, error};
 use std::{thread::sleep, time::Duration};
-use screenshots::Screen;
-use image::{DynamicImage, ImageBuffer, Rgba};
+use crate::app::input_driver::InputError;
+use crate::app::screen_source::screen_source;
+use crate::app::layout::layout;
+use crate::app::screen_state::ButtonColor;
 use chrono::Local;
 
 /// Represents an RGB color.
@@ -34,27 +36,25 @@ pub fn get_color(x: i32, y: i32) -> Color {
 
 /// Computes the average color over a rectangular region starting at (x, y).
 pub fn get_average_color(x: i32, y: i32, width: i32, height: i32) -> (u8, u8, u8) {
-    // 1) from_point i32-et vár
-    let screen = Screen::from_point(x, y)
-        .expect("Nem található képernyő a megadott koordinátán");
-
-    // 2) clamp x,y negatív esetet, de maradjon i32
+    // 1) clamp x,y negatív esetet, width,height legyen u32
     let xi = x.max(0);
     let yi = y.max(0);
-
-    // 3) width,height legyen u32 a capture_area-hoz
     let wu = width.max(0) as u32;
     let hu = height.max(0) as u32;
 
-    // 4) most már megfelelő típusokkal hívjuk
-    let image = screen
-        .capture_area(xi, yi, wu, hu)
-        .expect("Nem sikerült a képernyőrészlet rögzítése");
+    // 2) a képernyőrészlet az aktív ScreenSource-ból
+    let image = match screen_source().capture_area(xi, yi, wu, hu) {
+        Ok(img) => img,
+        Err(e) => {
+            error!("[get_average_color] capture failed: {}", e);
+            return (0, 0, 0);
+        }
+    };
 
-    // 5) a visszakapott ImageBuffer-ből nyers RGBA-bytek
-    let raw = image.into_raw();
+    // 3) nyers RGBA-bytek
+    let raw = image.to_rgba8().into_raw();
 
-    // 6) összegzés csatornánként
+    // 4) összegzés csatornánként
     let mut sum_r = 0u64;
     let mut sum_g = 0u64;
     let mut sum_b = 0u64;
@@ -64,14 +64,11 @@ pub fn get_average_color(x: i32, y: i32, width: i32, height: i32) -> (u8, u8, u8
         sum_b += chunk[2] as u64;
     }
 
-    // 7) átlagolás és visszaadás
-    let count = (wu as u64).saturating_mul(hu as u64);
-    if count == 0 {
-        (0, 0, 0)
-    } else {
-        ((sum_r / count) as u8,
-         (sum_g / count) as u8,
-         (sum_b / count) as u8)
+    // 5) átlagolás és visszaadás: a képernyő szélén levágott keret kevesebb pixelt ad
+    let count = (raw.len() / 4) as u64;
+    match (sum_r.checked_div(count), sum_g.checked_div(count), sum_b.checked_div(count)) {
+        (Some(r), Some(g), Some(b)) => (r as u8, g as u8, b as u8),
+        _ => (0, 0, 0),
     }
 }
 
@@ -112,60 +109,45 @@ pub fn is_color_within_tolerance(color: (u8, u8, u8), target: (u8, u8, u8), tol:
 }
 
 /// Moves the cursor to (x, y).
-pub fn set_cursor_pos(x: i32, y: i32) {
-    if let Err(e) = move_cursor(x, y) {
-        error!("[set_cursor_pos] Error: {}", e);
-    }
+pub fn set_cursor_pos(x: i32, y: i32) -> Result<(), InputError> {
+    move_cursor(x, y).inspect_err(|e| error!("[set_cursor_pos] Error: {}", e))?;
     sleep(Duration::from_millis(100));
+    Ok(())
 }
 
 /// Simulates a left mouse click.
-pub fn left_click() {
-    if let Err(e) = click_left() {
-        error!("left_click error: {}", e);
-    }
+pub fn left_click() -> Result<(), InputError> {
+    click_left().inspect_err(|e| error!("left_click error: {}", e))?;
     sleep(Duration::from_millis(100));
+    Ok(())
+}
+
+/// Moves to (x, y) and clicks there.
+pub fn click_at(x: i32, y: i32) -> Result<(), InputError> {
+    set_cursor_pos(x, y)?;
+    left_click()
 }
 
 /// Simulates a key press + release.
-pub fn press_key(keycode: u32) {
-    if let Err(e) = send_key(enigo::Key::Other(keycode)) {
-        error!("press_key error: {}", e);
-    }
+pub fn press_key(keycode: u32) -> Result<(), InputError> {
+    send_key(enigo::Key::Other(keycode)).inspect_err(|e| error!("press_key error: {}", e))?;
     sleep(Duration::from_millis(100));
+    Ok(())
 }
 
 /// Takes a screenshot of the primary monitor and saves it with a timestamp.
 pub fn make_screenshot() {
-    match Screen::all() {
-        Ok(screens) => {
-            if let Some(screen) = screens.first() {
-                match screen.capture() {
-                    Ok(buffer) => {
-                        let now = Local::now();
-                        let filename = format!("screenshot_{}.png", now.format("%Y-%m-%d_%H-%M"));
-                        let (w, h) = (buffer.width(), buffer.height());
-                        match ImageBuffer::<Rgba<u8>, _>::from_raw(w, h, buffer.into_raw()) {
-                            Some(img_buf) => {
-                                let img = DynamicImage::ImageRgba8(img_buf);
-                                if let Err(e) = img.save(&filename) {
-                                    error!("[make_screenshot] Save failed: {}", e);
-                                } else {
-                                    info!("[make_screenshot] Saved {}", filename);
-                                }
-                            }
-                            None => {
-                                error!("[make_screenshot] Buffer size mismatch {}×{}", w, h);
-                            }
-                        }
-                    }
-                    Err(e) => error!("[make_screenshot] Capture failed: {}", e),
-                }
+    match screen_source().capture() {
+        Ok(img) => {
+            let now = Local::now();
+            let filename = format!("screenshot_{}.png", now.format("%Y-%m-%d_%H-%M"));
+            if let Err(e) = img.save(&filename) {
+                error!("[make_screenshot] Save failed: {}", e);
             } else {
-                error!("[make_screenshot] No screens available");
+                info!("[make_screenshot] Saved {}", filename);
             }
         }
-        Err(e) => error!("[make_screenshot] Screen enumeration failed: {}", e),
+        Err(e) => error!("[make_screenshot] Capture failed: {}", e),
     }
 }
 
@@ -175,40 +157,33 @@ pub struct Cords {
     pub home_button: (i32, i32),
     pub play_button: (i32, i32),
     pub attack_button: (i32, i32),
+    /// "Keep" a mulligan képernyőn (a London-alulra-tétel "Done" gombja is itt van).
+    pub keep_button: (i32, i32),
+    /// "Mulligan" a mulligan képernyőn.
+    pub mulligan_button: (i32, i32),
 }
 
 impl Cords {
-    /// Initialize UI coordinates based on screen size.
+    /// Initialize UI coordinates based on screen size and the layout profile.
     pub fn new(screen_width: i32, screen_height: i32) -> Self {
+        let layout = layout();
+        let (w, h) = (screen_width.max(0) as u32, screen_height.max(0) as u32);
         Self {
-            home_button: (
-                (screen_width as f64 * 0.052188).ceil() as i32,
-                (screen_height as f64 * 0.03426).ceil() as i32,
-            ),
-            play_button: (
-                (screen_width as f64 * 0.895).ceil() as i32,
-                (screen_height as f64 * 0.938).ceil() as i32,
-            ),
-            attack_button: (
-                (screen_width as f64 * 0.8815).ceil() as i32,
-                (screen_height as f64 * 0.86666).ceil() as i32,
-            ),
+            home_button: layout.button("home", w, h),
+            play_button: layout.button("play", w, h),
+            attack_button: layout.button("attack", w, h),
+            keep_button: layout.button("keep", w, h),
+            mulligan_button: layout.button("mulligan", w, h),
         }
     }
 }
 
 
-/// Returns "red", "blue" or "black" based on the attack button color.
-pub fn check_button_color(cords: &Cords) -> &'static str {
+/// Colour of the attack button.
+pub fn check_button_color(cords: &Cords) -> ButtonColor {
     let (x, y) = cords.attack_button;
     let c = get_color(x, y);
     info!("[check_button_color] Pixel @({}, {}) = {:?}", x, y, c);
-    if c.r > 200 {
-        "red"
-    } else if c.b > 200 {
-        "blue"
-    } else {
-        "black"
-    }
+    ButtonColor::of(c)
 }
 
@@ -1,3 +1,4 @@
 // src/lib.rs
+#![allow(non_snake_case)]
 pub mod multiplatform;
 pub mod app;
@@ -1,11 +1,10 @@
 // src/main.rs
 
 use tracing::info;
-use tracing_subscriber;
 
 
 use MTGA_me::app::*;
-use MTGA_me::multiplatform::init;
+use MTGA_me::multiplatform::{init, screen_size};
 
 fn main() {
     // Init logging
@@ -16,11 +15,51 @@ fn main() {
         eprintln!("Failed to initialize multiplatform utilities: {}", e);
         return;
     }
+    if let Err(e) = screen_source::init_screen_source_from_env() {
+        eprintln!("Failed to select screen source: {}", e);
+        return;
+    }
+    if let Err(e) = ocr_engine::init_ocr_engines_from_env() {
+        eprintln!("Failed to set up OCR engines: {}", e);
+        return;
+    }
+    if let Err(e) = card_hash::init_card_index_from_env() {
+        eprintln!("Failed to load card hash index: {}", e);
+        return;
+    }
+    if let Err(e) = screen_state::init_screen_templates_from_env() {
+        eprintln!("Failed to load screen templates: {}", e);
+        return;
+    }
+    // Layout profile: MTGA_LAYOUT or the built-in one matching the screen's aspect ratio
+    if let Ok((w, h)) = screen_size()
+        && let Err(e) = layout::init_layout(w as u32, h as u32)
+    {
+        eprintln!("Failed to load layout profile: {}", e);
+        return;
+    }
 
     // CLI switch: extra arg for coordinate-mode
     let args: Vec<String> = std::env::args().collect();
-    if args.len() > 1 {
-        info!("RightClick to get coordinates. LeftClick to exit.");
+    if args.len() == 4 && args[1] == "build-card-index" {
+        // Offline: art crops (named after the card) → saved hash index
+        match card_hash::CardHashIndex::build_from_dir(&args[2]).and_then(|idx| idx.save(&args[3])) {
+            Ok(()) => info!("Card hash index written to {}", args[3]),
+            Err(e) => eprintln!("Failed to build card hash index: {}", e),
+        }
+    } else if (3..=4).contains(&args.len()) && args[1] == "calibrate-layout" {
+        // Point at the reference buttons, get a fitted profile for this screen
+        let base = args.get(3).map(std::path::Path::new);
+        if let Err(e) = calibration::calibrate_layout(std::path::Path::new(&args[2]), base) {
+            eprintln!("Calibration failed: {}", e);
+        }
+    } else if args.len() > 1 {
+        // Coordinate mode: right click (or Enter) reads coordinates and colours; `save <name>` stores them
+        let out = args.get(2).map(String::as_str).unwrap_or("calibrated.layout");
+        info!("Coordinate mode, saved points go to {}", out);
+        if let Err(e) = calibration::run_coordinate_mode(std::path::Path::new(out)) {
+            eprintln!("Coordinate mode failed: {}", e);
+        }
     } else {
         let mut app = App::new();
         app.start();
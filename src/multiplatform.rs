@@ -1,14 +1,15 @@
-//! Cross-platform input and screen utilities using message-driven `enigo` and `screenshots`.
-//! Input is dispatched to a dedicated thread to avoid Send/Sync issues on macOS.
+//! Cross-platform input and screen utilities using message-driven `enigo` and the active `ScreenSource`.
+//! Input goes through the selected `InputDriver`; the enigo one dispatches to a
+//! dedicated thread to avoid Send/Sync issues on macOS.
 
-use std::sync::{Mutex, mpsc::{Sender, Receiver, channel}};
+use std::sync::{Arc, Mutex, mpsc::{Sender, Receiver, channel}};
 use std::thread;
 use std::time::Duration;
-use once_cell::sync::Lazy;
 use tracing::{debug, info, error};
 
-use enigo::{Enigo, Settings, Button, Direction, Key, InputError, Coordinate, Mouse, Keyboard};
-use screenshots::Screen;
+use enigo::{Enigo, Settings, Button, Direction, Key, Coordinate, Mouse, Keyboard};
+use crate::app::input_driver::{input_driver, set_input_driver, DryRunDriver, InputDriver, InputError};
+use crate::app::screen_source::screen_source;
 
 /// Commands for the input thread.
 enum InputCommand {
@@ -16,131 +17,233 @@ enum InputCommand {
     ClickLeft,
     ClickRight,
     SendKey(Key),
+    Location(Sender<Result<(i32, i32), String>>),
 }
 
-/// Sender for input commands.
-static INPUT_SENDER: Lazy<Sender<InputCommand>> = Lazy::new(|| {
-    let (tx, rx): (Sender<InputCommand>, Receiver<InputCommand>) = channel();
-    thread::spawn(move || {
-        // Initialize Enigo on this dedicated thread
-        let mut enigo = Enigo::new(&Settings::default())
-            .expect("Failed to initialize Enigo input context");
-        while let Ok(cmd) = rx.recv() {
-            match cmd {
-                InputCommand::MoveMouse { x, y } => {
-                    debug!("[input-thread] MoveMouse to ({}, {})", x, y);
-                    if let Err(e) = enigo.move_mouse(x, y, Coordinate::Abs) {
-                        error!("[input-thread] MoveMouse failed: {}", e);
-                    }
-                }
-                InputCommand::ClickLeft => {
-                    info!("[input-thread] ClickLeft");
-                    if let Err(e) = enigo.button(Button::Left, Direction::Press) {
-                        error!("[input-thread] ClickLeft press failed: {}", e);
-                    }
-                    thread::sleep(Duration::from_millis(50));
-                    if let Err(e) = enigo.button(Button::Left, Direction::Release) {
-                        error!("[input-thread] ClickLeft release failed: {}", e);
+/// Real input through enigo, running on its own thread.
+pub struct EnigoDriver {
+    sender: Mutex<Sender<InputCommand>>,
+}
+
+impl Default for EnigoDriver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl EnigoDriver {
+    /// Starts the input thread and initializes Enigo on it.
+    pub fn new() -> Self {
+        let (tx, rx): (Sender<InputCommand>, Receiver<InputCommand>) = channel();
+        thread::spawn(move || {
+            // Initialize Enigo on this dedicated thread
+            let mut enigo = Enigo::new(&Settings::default())
+                .expect("Failed to initialize Enigo input context");
+            while let Ok(cmd) = rx.recv() {
+                match cmd {
+                    InputCommand::MoveMouse { x, y } => {
+                        debug!("[input-thread] MoveMouse to ({}, {})", x, y);
+                        if let Err(e) = enigo.move_mouse(x, y, Coordinate::Abs) {
+                            error!("[input-thread] MoveMouse failed: {}", e);
+                        }
                     }
-                }
-                InputCommand::ClickRight => {
-                    info!("[input-thread] ClickRight");
-                    if let Err(e) = enigo.button(Button::Right, Direction::Press) {
-                        error!("[input-thread] ClickRight press failed: {}", e);
+                    InputCommand::ClickLeft => {
+                        info!("[input-thread] ClickLeft");
+                        if let Err(e) = enigo.button(Button::Left, Direction::Press) {
+                            error!("[input-thread] ClickLeft press failed: {}", e);
+                        }
+                        thread::sleep(Duration::from_millis(50));
+                        if let Err(e) = enigo.button(Button::Left, Direction::Release) {
+                            error!("[input-thread] ClickLeft release failed: {}", e);
+                        }
                     }
-                    thread::sleep(Duration::from_millis(50));
-                    if let Err(e) = enigo.button(Button::Right, Direction::Release) {
-                        error!("[input-thread] ClickRight release failed: {}", e);
+                    InputCommand::ClickRight => {
+                        info!("[input-thread] ClickRight");
+                        if let Err(e) = enigo.button(Button::Right, Direction::Press) {
+                            error!("[input-thread] ClickRight press failed: {}", e);
+                        }
+                        thread::sleep(Duration::from_millis(50));
+                        if let Err(e) = enigo.button(Button::Right, Direction::Release) {
+                            error!("[input-thread] ClickRight release failed: {}", e);
+                        }
                     }
-                }
-                InputCommand::SendKey(key) => {
-                    info!("[input-thread] SendKey {:?}", key);
-                    if let Err(e) = enigo.key(key, Direction::Press) {
-                        error!("[input-thread] Key press failed: {}", e);
+                    InputCommand::Location(reply) => {
+                        let _ = reply.send(enigo.location().map_err(|e| e.to_string()));
                     }
-                    thread::sleep(Duration::from_millis(50));
-                    if let Err(e) = enigo.key(key, Direction::Release) {
-                        error!("[input-thread] Key release failed: {}", e);
+                    InputCommand::SendKey(key) => {
+                        info!("[input-thread] SendKey {:?}", key);
+                        if let Err(e) = enigo.key(key, Direction::Press) {
+                            error!("[input-thread] Key press failed: {}", e);
+                        }
+                        thread::sleep(Duration::from_millis(50));
+                        if let Err(e) = enigo.key(key, Direction::Release) {
+                            error!("[input-thread] Key release failed: {}", e);
+                        }
                     }
                 }
             }
-        }
-    });
-    tx
-});
+        });
+        Self { sender: Mutex::new(tx) }
+    }
 
-/// Tracks the last cursor position so clicks know where they occur.
-static CURSOR_POS: Lazy<Mutex<(i32, i32)>> = Lazy::new(|| Mutex::new((0, 0)));
+    fn send(&self, cmd: InputCommand) -> Result<(), InputError> {
+        self.sender
+            .lock()
+            .map_err(|e| InputError::Backend(format!("sender lock error: {}", e)))?
+            .send(cmd)
+            .map_err(|e| InputError::Backend(format!("send error: {}", e)))
+    }
+}
 
-/// Initialize the input backend (starts the input thread).
+impl InputDriver for EnigoDriver {
+    fn move_mouse(&self, x: i32, y: i32) -> Result<(), InputError> {
+        self.send(InputCommand::MoveMouse { x, y })
+    }
+    fn click_left(&self) -> Result<(), InputError> {
+        self.send(InputCommand::ClickLeft)
+    }
+    fn click_right(&self) -> Result<(), InputError> {
+        self.send(InputCommand::ClickRight)
+    }
+    fn send_key(&self, key: Key) -> Result<(), InputError> {
+        self.send(InputCommand::SendKey(key))
+    }
+    fn cursor_position(&self) -> Result<(i32, i32), InputError> {
+        let (tx, rx) = channel();
+        self.send(InputCommand::Location(tx))?;
+        rx.recv()
+            .map_err(|e| InputError::Backend(format!("input thread gone: {}", e)))?
+            .map_err(InputError::Backend)
+    }
+}
+
+/// Initialize the input backend. `MTGA_INPUT=dry-run` selects the logging driver,
+/// anything else (or nothing) the enigo one.
 pub fn init() -> Result<(), String> {
-    Lazy::force(&INPUT_SENDER);
-    info!("Multi-platform input thread initialized");
+    match std::env::var("MTGA_INPUT").as_deref() {
+        Ok("dry-run") => {
+            set_input_driver(Arc::new(DryRunDriver::new()));
+            info!("Dry-run input driver selected; no real input will be sent");
+        }
+        Ok(other) if other != "enigo" => {
+            return Err(format!("[init] Unknown MTGA_INPUT driver: {}", other));
+        }
+        _ => {
+            set_input_driver(Arc::new(EnigoDriver::new()));
+            info!("Multi-platform input thread initialized");
+        }
+    }
     Ok(())
 }
 
+/// The selected driver; enigo is started on first use if nothing was selected.
+fn driver() -> Arc<dyn InputDriver> {
+    if let Some(d) = input_driver() {
+        return d;
+    }
+    let d: Arc<dyn InputDriver> = Arc::new(EnigoDriver::new());
+    set_input_driver(d.clone());
+    d
+}
+
 /// Returns the (width, height) of the primary monitor in pixels.
 pub fn screen_size() -> Result<(i32, i32), String> {
-    let screens = Screen::all()
-        .map_err(|e| format!("[screen_size] Screenshot enumeration failed: {}", e))?;
-    let screen = screens
-        .first()
-        .ok_or_else(|| "[screen_size] No screens detected".to_string())?;
-    let di = &screen.display_info;
-    info!("Primary screen size: {}x{}", di.width, di.height);
-    Ok((di.width as i32, di.height as i32))
+    let (w, h) = screen_source()
+        .size()
+        .map_err(|e| format!("[screen_size] {}", e))?;
+    info!("Primary screen size: {}x{}", w, h);
+    Ok((w, h))
 }
 
 /// Read the RGB color of the pixel at (x, y).
 pub fn get_pixel(x: i32, y: i32) -> Result<(u8, u8, u8), String> {
-    let screens = Screen::all().map_err(|e| format!("[get_pixel] {}", e))?;
-    let screen = screens.first().ok_or_else(|| "[get_pixel] No screens".to_string())?;
-    let cap = screen.capture().map_err(|e| format!("[get_pixel] {}", e))?;
-    let (w, h) = (cap.width() as usize, cap.height() as usize);
-    if x < 0 || y < 0 || (x as usize) >= w || (y as usize) >= h {
-        return Err(format!("Coordinates ({},{}) outside {}×{}", x, y, w, h));
-    }
-    let buf = cap.into_raw();
-    let idx = ((y as usize) * w + (x as usize)) * 4;
-    let (r, g, b) = (buf[idx], buf[idx + 1], buf[idx + 2]);
+    let (r, g, b) = screen_source()
+        .pixel(x, y)
+        .map_err(|e| format!("[get_pixel] {}", e))?;
     debug!("[get_pixel] Pixel @({}, {}) = ({},{},{})", x, y, r, g, b);
     Ok((r, g, b))
 }
 
+/// Current cursor position in absolute coordinates.
+pub fn cursor_position() -> Result<(i32, i32), InputError> {
+    driver().cursor_position()
+}
+
 /// Moves the mouse cursor to absolute (x, y) coordinates.
-pub fn move_cursor(x: i32, y: i32) -> Result<(), String> {
-    {
-        let mut pos = CURSOR_POS
-            .lock()
-            .map_err(|e| format!("[move_cursor] CURSOR_POS lock error: {}", e))?;
-        *pos = (x, y);
-    }
-    INPUT_SENDER
-        .send(InputCommand::MoveMouse { x, y })
-        .map_err(|e| format!("[move_cursor] send error: {}", e))?;
-    Ok(())
+pub fn move_cursor(x: i32, y: i32) -> Result<(), InputError> {
+    driver().move_mouse(x, y)
 }
 
 /// Simulates a left mouse click at the last moved-to position.
-pub fn click_left() -> Result<(), String> {
-    INPUT_SENDER
-        .send(InputCommand::ClickLeft)
-        .map_err(|e| format!("[click_left] send error: {}", e))?;
-    Ok(())
+pub fn click_left() -> Result<(), InputError> {
+    driver().click_left()
 }
 
 /// Simulates a right mouse click at the last moved-to position.
-pub fn click_right() -> Result<(), String> {
-    INPUT_SENDER
-        .send(InputCommand::ClickRight)
-        .map_err(|e| format!("[click_right] send error: {}", e))?;
-    Ok(())
+pub fn click_right() -> Result<(), InputError> {
+    driver().click_right()
 }
 
 /// Sends a single key (press + release).
-pub fn send_key(key: Key) -> Result<(), String> {
-    INPUT_SENDER
-        .send(InputCommand::SendKey(key))
-        .map_err(|e| format!("[send_key] send error: {}", e))?;
-    Ok(())
+pub fn send_key(key: Key) -> Result<(), InputError> {
+    driver().send_key(key)
+}
+
+/// Pointer position and mouse button state at one poll.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PointerState {
+    pub x: i32,
+    pub y: i32,
+    pub left: bool,
+    pub right: bool,
+}
+
+/// Reads the real mouse buttons by polling the X server; enigo can only send clicks.
+/// The pointer is not grabbed, so the clicks still reach the window under it.
+#[cfg(target_os = "linux")]
+pub struct PointerPoller {
+    conn: x11rb::rust_connection::RustConnection,
+    root: x11rb::protocol::xproto::Window,
+}
+
+#[cfg(target_os = "linux")]
+impl PointerPoller {
+    /// Connects to the display in `$DISPLAY`.
+    pub fn connect() -> Result<Self, String> {
+        use x11rb::connection::Connection;
+        let (conn, screen) = x11rb::connect(None).map_err(|e| format!("[pointer] {}", e))?;
+        let root = conn.setup().roots[screen].root;
+        Ok(Self { conn, root })
+    }
+
+    pub fn poll(&self) -> Result<PointerState, String> {
+        use x11rb::protocol::xproto::{ConnectionExt, KeyButMask};
+        let reply = self
+            .conn
+            .query_pointer(self.root)
+            .map_err(|e| format!("[pointer] {}", e))?
+            .reply()
+            .map_err(|e| format!("[pointer] {}", e))?;
+        Ok(PointerState {
+            x: reply.root_x as i32,
+            y: reply.root_y as i32,
+            left: reply.mask.contains(KeyButMask::BUTTON1),
+            right: reply.mask.contains(KeyButMask::BUTTON3),
+        })
+    }
+}
+
+/// Button polling is only implemented for X11.
+#[cfg(not(target_os = "linux"))]
+pub struct PointerPoller;
+
+#[cfg(not(target_os = "linux"))]
+impl PointerPoller {
+    pub fn connect() -> Result<Self, String> {
+        Err("[pointer] mouse button capture is only supported on X11".into())
+    }
+
+    pub fn poll(&self) -> Result<PointerState, String> {
+        Err("[pointer] mouse button capture is only supported on X11".into())
+    }
 }
@@ -0,0 +1,249 @@
+// tests/activated_ability_test.rs
+use MTGA_me::app::bot::Bot;
+use MTGA_me::app::card_attribute::{Condition, CounterType, CreatureType, Effect, TargetFilter};
+use MTGA_me::app::card_library::{Card, CardType, Creature, ManaCost, build_card_library};
+use MTGA_me::app::creature_positions::{get_ability_button_position, get_own_creature_positions};
+use MTGA_me::app::game_state::{GameAction, GamePhase, GameState, Player, Strategy};
+use MTGA_me::app::gre::{ActivatedAbility, ActivationTiming, Gre, ManaPool, StackEntry};
+use MTGA_me::app::input_driver::{set_input_driver, DryRunDriver, InputAction};
+use std::sync::Arc;
+
+fn grow(cost: ManaCost) -> ActivatedAbility {
+    ActivatedAbility {
+        cost,
+        condition: Condition::Always,
+        effect: Effect::AddCounter {
+            counter: CounterType::PlusOnePlusOne,
+            amount: 1,
+            target: TargetFilter::SelfCard,
+        },
+        activated_this_turn: false,
+        loyalty_change: 0,
+        additional_costs: Vec::new(),
+        timing: ActivationTiming::Instant,
+        once_each_turn: false,
+    }
+}
+
+fn bear(abilities: Vec<ActivatedAbility>) -> Card {
+    let mut card = Card::new(
+        "Bear",
+        CardType::Creature(Creature {
+            power: 2,
+            toughness: 2,
+            summoning_sickness: false,
+            abilities: Vec::new(),
+            types: vec![CreatureType::Warrior],
+            ephemeral_power: 0,
+            ephemeral_toughness: 0,
+        }),
+        ManaCost::new(1, 0, 1, 0, 0, 0),
+    );
+    for ability in abilities {
+        card = card.with_activated(ability);
+    }
+    card
+}
+
+/// Egyszer a megadott akciót választja, utána passzol.
+struct Once(Option<GameAction>);
+
+impl Strategy for Once {
+    fn decide(&mut self, _state: &GameState) -> GameAction {
+        self.0.take().unwrap_or(GameAction::PassPriority)
+    }
+}
+
+fn clicks(driver: &DryRunDriver) -> Vec<(i32, i32)> {
+    driver
+        .actions()
+        .into_iter()
+        .filter_map(|(_, a)| match a {
+            InputAction::ClickLeft { x, y } => Some((x, y)),
+            _ => None,
+        })
+        .collect()
+}
+
+fn enter(gre: &mut Gre, card: Card) -> u64 {
+    let mut c = card;
+    gre.enter_battlefield(&mut c);
+    c.card_id
+}
+
+#[test]
+fn mana_ability_resolves_without_the_stack() {
+    let mut gre = Gre::default();
+    let elves = enter(&mut gre, build_card_library()["Llanowar Elves"].clone());
+    // Summoning sick: a {T} költség nem fizethető
+    assert!(gre.activatable_abilities(elves, Player::Us).is_empty());
+
+    gre.untap_step(Player::Us);
+    assert_eq!(gre.activatable_abilities(elves, Player::Us), vec![0]);
+    assert!(gre.activate_ability_at(elves, 0, Player::Us));
+    assert!(gre.stack.is_empty());
+    assert_eq!(gre.player(Player::Us).mana_pool.green, 1);
+    assert!(gre.battlefield_creatures[&elves].tapped);
+    assert!(!gre.activate_ability_at(elves, 0, Player::Us));
+
+    gre.untap_step(Player::Us);
+    assert!(gre.activate_ability_at(elves, 0, Player::Us));
+    assert_eq!(gre.player(Player::Us).mana_pool.green, 2);
+}
+
+#[test]
+fn mana_cost_is_paid_from_the_pool_on_activation() {
+    let mut gre = Gre::default();
+    let id = enter(&mut gre, bear(vec![grow(ManaCost::new(1, 0, 1, 0, 0, 0))]));
+    assert!(!gre.activate_ability_at(id, 0, Player::Us));
+
+    gre.add_mana(
+        Player::Us,
+        ManaPool {
+            colorless: 1,
+            green: 1,
+            ..ManaPool::default()
+        },
+    );
+    assert!(gre.activate_ability_at(id, 0, Player::Us));
+    assert!(gre.player(Player::Us).mana_pool.is_empty());
+    assert_eq!(gre.stack.len(), 1);
+    gre.resolve_stack();
+    assert_eq!(
+        gre.battlefield_creatures[&id].counter_count(&CounterType::PlusOnePlusOne),
+        1
+    );
+
+    // Más irányítja: nem aktiválhatjuk
+    let theirs = enter(
+        &mut gre,
+        bear(vec![grow(ManaCost::free())]).with_controller(Player::Opponent),
+    );
+    assert!(gre.activatable_abilities(theirs, Player::Us).is_empty());
+    assert_eq!(gre.activatable_abilities(theirs, Player::Opponent), vec![0]);
+}
+
+#[test]
+fn sorcery_timing_and_once_each_turn() {
+    let mut gre = Gre::default();
+    let sorcery = ActivatedAbility {
+        timing: ActivationTiming::Sorcery,
+        ..grow(ManaCost::free())
+    };
+    let once = ActivatedAbility {
+        once_each_turn: true,
+        effect: Effect::AddCounter {
+            counter: CounterType::PlusOnePlusOne,
+            amount: 2,
+            target: TargetFilter::SelfCard,
+        },
+        ..grow(ManaCost::free())
+    };
+    let id = enter(&mut gre, bear(vec![sorcery, once]));
+
+    gre.set_phase(GamePhase::Combat);
+    assert_eq!(gre.activatable_abilities(id, Player::Us), vec![1]);
+    assert!(gre.activate_ability_at(id, 1, Player::Us));
+    assert!(!gre.activate_ability_at(id, 1, Player::Us));
+    gre.resolve_stack();
+
+    gre.set_phase(GamePhase::PostCombatMain);
+    // Az ellenfél körében sorcery-speed sincs
+    gre.active_player = Player::Opponent;
+    assert!(gre.activatable_abilities(id, Player::Us).is_empty());
+    gre.active_player = Player::Us;
+    assert_eq!(gre.activatable_abilities(id, Player::Us), vec![0]);
+
+    gre.on_turn_end();
+    assert_eq!(gre.activatable_abilities(id, Player::Us), vec![0, 1]);
+}
+
+#[test]
+fn ability_actions_index_our_battlefield() {
+    let mut gre = Gre::default();
+    let mut state = GameState::new();
+    let plain = bear(Vec::new());
+    let id = enter(
+        &mut gre,
+        bear(vec![
+            grow(ManaCost::new(0, 1, 0, 0, 0, 0)),
+            grow(ManaCost::free()),
+        ]),
+    );
+    state.battlefield = vec![plain, gre.battlefield_creatures[&id].clone()];
+
+    let actions = state.ability_actions(&gre);
+    assert_eq!(actions.len(), 1);
+    assert!(matches!(
+        actions[0],
+        GameAction::ActivateAbility {
+            card_idx: 1,
+            ability_idx: 1
+        }
+    ));
+
+    // A mana költséget a kliens fizeti a `mana_available`-ből, nem a GRE poolból
+    state.mana_available = 1;
+    assert_eq!(state.ability_actions(&gre).len(), 2);
+}
+
+#[test]
+fn abilities_resolve_after_what_was_put_on_them() {
+    let mut gre = Gre::default();
+    let id = enter(&mut gre, bear(vec![grow(ManaCost::free())]));
+    assert!(gre.activate_ability_at(id, 0, Player::Us));
+    gre.push_to_stack(StackEntry::TriggeredAbility {
+        source: None,
+        effect: grow(ManaCost::free()).effect,
+        controller: Player::Opponent,
+    });
+    // A később rárakott trigger van felül, a korábbi képesség alatta marad
+    let top = gre.stack.peek().unwrap();
+    assert!(matches!(top.entry, StackEntry::TriggeredAbility { .. }));
+}
+
+#[test]
+fn bot_activates_abilities_chosen_by_the_strategy() {
+    let driver = Arc::new(DryRunDriver::new());
+    set_input_driver(driver.clone());
+    let mut bot = Bot::new();
+    let id = enter(&mut bot.gre, bear(vec![grow(ManaCost::new(1, 0, 1, 0, 0, 0))]));
+    let card = bot.gre.battlefield_creatures[&id].clone();
+    bot.battlefield_creatures.insert(card.name.clone(), card);
+    bot.land_number = 3;
+
+    // A manát a kliens fizeti: üres GRE poollal is aktivál, amíg a földekből futja
+    bot.act_on_strategy();
+    let grown = &bot.gre.battlefield_creatures[&id];
+    assert_eq!(grown.counter_count(&CounterType::PlusOnePlusOne), 1);
+    assert_eq!(bot.land_number, 1);
+    assert!(bot.gre.stack.is_empty());
+    assert!(bot.gre.player(Player::Us).mana_pool.is_empty());
+
+    // Két képesség: a kliens választót nyit, a kért képesség gombjára is kattintunk
+    let mut bot = Bot::new();
+    let abilities = vec![grow(ManaCost::new(1, 0, 1, 0, 0, 0)), grow(ManaCost::free())];
+    let id = enter(&mut bot.gre, bear(abilities));
+    let card = bot.gre.battlefield_creatures[&id].clone();
+    bot.battlefield_creatures.insert(card.name.clone(), card);
+    let (w, h) = (bot.screen_width as u32, bot.screen_height as u32);
+    let slot = get_own_creature_positions(1, w, h)[0].clone();
+
+    // Nem fizethető: a GRE ellenőrzése a kattintás előtt fut, így a kliensben sem történik semmi
+    let before = clicks(&driver).len();
+    bot.strategy = Box::new(Once(Some(GameAction::ActivateAbility { card_idx: 0, ability_idx: 0 })));
+    bot.act_on_strategy();
+    assert_eq!(clicks(&driver).len(), before);
+
+    bot.strategy = Box::new(Once(Some(GameAction::ActivateAbility { card_idx: 0, ability_idx: 1 })));
+    bot.act_on_strategy();
+    let new_clicks = clicks(&driver)[before..].to_vec();
+    assert_eq!(
+        new_clicks,
+        vec![
+            (((slot.click_x1 + slot.click_x2) / 2) as i32, ((slot.click_y1 + slot.click_y2) / 2) as i32),
+            get_ability_button_position(&slot, 1, 2, w, h),
+        ]
+    );
+    assert_eq!(bot.gre.battlefield_creatures[&id].counter_count(&CounterType::PlusOnePlusOne), 1);
+}
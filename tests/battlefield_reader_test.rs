@@ -0,0 +1,143 @@
+// tests/battlefield_reader_test.rs
+use image::{DynamicImage, Rgba, RgbaImage};
+use std::collections::HashMap;
+use MTGA_me::app::battlefield_reader::{
+    looks_tapped, parse_counter_badge, parse_power_toughness, pt_box_region, reconcile,
+    tapped_region, CreatureReading, MismatchKind,
+};
+use MTGA_me::app::card_attribute::{CounterType, CreatureType};
+use MTGA_me::app::card_library::{Card, CardType, Creature, ManaCost};
+use MTGA_me::app::creature_positions::CreaturePosition;
+use MTGA_me::app::game_state::Player;
+use MTGA_me::app::gre::Gre;
+
+fn bear() -> Card {
+    Card::new(
+        "Bear",
+        CardType::Creature(Creature {
+            power: 2,
+            toughness: 2,
+            summoning_sickness: false,
+            abilities: Vec::new(),
+            types: vec![CreatureType::Warrior],
+            ephemeral_power: 0,
+            ephemeral_toughness: 0,
+        }),
+        ManaCost::new(1, 0, 1, 0, 0, 0),
+    )
+}
+
+fn reading(pt: Option<(i32, i32)>, badge: Option<u32>, tapped: bool) -> CreatureReading {
+    CreatureReading {
+        index: 1,
+        name: "Bear".into(),
+        power_toughness: pt,
+        counter_badge: badge,
+        tapped,
+    }
+}
+
+/// Szürke háttér, közepén egy `w`×`h` világos "kártya".
+fn probe(w: u32, h: u32) -> DynamicImage {
+    let (pw, ph) = (60, 60);
+    DynamicImage::ImageRgba8(RgbaImage::from_fn(pw, ph, |x, y| {
+        let inside = x >= (pw - w) / 2 && x < (pw + w) / 2 && y >= (ph - h) / 2 && y < (ph + h) / 2;
+        if inside { Rgba([230, 220, 200, 255]) } else { Rgba([40, 40, 40, 255]) }
+    }))
+}
+
+#[test]
+fn parses_pt_box_and_badge() {
+    assert_eq!(parse_power_toughness(" 3 / 4 "), Some((3, 4)));
+    assert_eq!(parse_power_toughness("+1/+1 5/6"), Some((5, 6)));
+    assert_eq!(parse_power_toughness("7"), None);
+    assert_eq!(parse_counter_badge("x2"), Some(2));
+    assert_eq!(parse_counter_badge(""), None);
+}
+
+#[test]
+fn pt_box_is_bottom_right_of_card() {
+    let pos = CreaturePosition {
+        ocr_x1: 100, ocr_x2: 200, ocr_y1: 100, ocr_y2: 110,
+        click_x1: 100, click_y1: 100, click_x2: 200, click_y2: 240,
+    };
+    let (x1, y1, x2, y2) = pt_box_region(&pos);
+    assert!(x1 > 150 && x2 == 200 && y1 > 200 && y2 == 240);
+}
+
+#[test]
+fn tapped_card_moves_pt_box_to_bottom_left() {
+    let pos = CreaturePosition {
+        ocr_x1: 100, ocr_x2: 200, ocr_y1: 100, ocr_y2: 110,
+        click_x1: 100, click_y1: 100, click_x2: 200, click_y2: 240,
+    };
+    // Középpont (150, 170); jobbra forgatva a jobb alsó sarok a bal alsóba kerül
+    let (x1, y1, x2, y2) = tapped_region(pt_box_region(&pos), &pos);
+    assert!(x1 < x2 && y1 < y2);
+    assert!(x2 <= 150 && y1 >= 170);
+    assert_eq!((x1, y2), (80, 220));
+}
+
+#[test]
+fn rotated_card_reads_as_tapped() {
+    assert!(!looks_tapped(&probe(30, 42), 40));
+    assert!(looks_tapped(&probe(42, 30), 40));
+    assert!(!looks_tapped(&probe(0, 0), 40));
+}
+
+#[test]
+fn reconcile_flags_counters_buffs_and_damage() {
+    let mut gre = Gre::new(Player::Us);
+    let mut card = bear();
+    gre.enter_battlefield(&mut card);
+    let id = card.card_id;
+    gre.battlefield_creatures
+        .get_mut(&id)
+        .unwrap()
+        .add_counters(&CounterType::PlusOnePlusOne, 1);
+    let cards: HashMap<String, Card> = [("Bear".to_string(), card)].into();
+
+    // Amit a GRE is vár: 3/3, 1 counter
+    assert!(reconcile(&[reading(Some((3, 3)), Some(1), false)], &cards, &gre, Player::Us).is_empty());
+
+    // Nem látott counter és sebzés
+    let kinds: Vec<MismatchKind> =
+        reconcile(&[reading(Some((3, 1)), None, false)], &cards, &gre, Player::Us)
+            .into_iter()
+            .map(|m| m.kind)
+            .collect();
+    assert_eq!(
+        kinds,
+        vec![
+            MismatchKind::Damaged { expected: 3, seen: 1 },
+            MismatchKind::Counters { expected: 1, seen: 0 },
+        ]
+    );
+
+    // Ismeretlen lény a képernyőn
+    let mut unknown = reading(None, None, true);
+    unknown.name = "Nonexistent Dragon".into();
+    let m = reconcile(&[unknown], &cards, &gre, Player::Us);
+    assert_eq!(m[0].kind, MismatchKind::Unknown);
+}
+
+#[test]
+fn counter_badge_syncs_into_the_gre() {
+    let mut gre = Gre::new(Player::Us);
+    let mut card = bear();
+    gre.enter_battlefield(&mut card);
+    let id = card.card_id;
+
+    gre.sync_counter_badge(id, 2);
+    assert_eq!(gre.battlefield_creatures[&id].counter_count(&CounterType::PlusOnePlusOne), 2);
+    gre.sync_counter_badge(id, 0);
+    assert_eq!(gre.battlefield_creatures[&id].counter_count(&CounterType::PlusOnePlusOne), 0);
+
+    // Ha -1/-1 counter van rajta, azt igazítjuk; 0 toughness → temető
+    gre.battlefield_creatures
+        .get_mut(&id)
+        .unwrap()
+        .add_counters(&CounterType::MinusOneMinusOne, 1);
+    gre.sync_counter_badge(id, 2);
+    assert!(!gre.battlefield_creatures.contains_key(&id));
+}
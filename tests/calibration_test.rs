@@ -0,0 +1,101 @@
+// tests/calibration_test.rs
+use image::{DynamicImage, Rgba, RgbaImage};
+use MTGA_me::app::calibration::{Click, ClickTracker, CoordinateSession, SessionCommand};
+use MTGA_me::app::layout::LayoutProfile;
+use MTGA_me::app::ocr_engine::{set_ocr_engine, OcrEngine, OcrError, OcrRegion};
+use MTGA_me::app::screen_source::{set_screen_source, DirectoryScreenSource};
+use MTGA_me::multiplatform::PointerState;
+use std::sync::Arc;
+
+/// Reports the size of the image it was given, so the rectangle can be checked.
+struct SizeEngine;
+
+impl OcrEngine for SizeEngine {
+    fn recognize(&self, img: &DynamicImage) -> Result<String, OcrError> {
+        Ok(format!("{}x{}", img.width(), img.height()))
+    }
+}
+
+#[test]
+fn session_commands_parse() {
+    assert_eq!(SessionCommand::parse(""), Some(SessionCommand::Capture));
+    assert_eq!(SessionCommand::parse(" r "), Some(SessionCommand::Corner));
+    assert_eq!(
+        SessionCommand::parse("save play_button"),
+        Some(SessionCommand::Save("play_button".into()))
+    );
+    assert_eq!(SessionCommand::parse("save"), None);
+    assert_eq!(SessionCommand::parse("save two words"), None);
+    assert_eq!(SessionCommand::parse("q"), Some(SessionCommand::Quit));
+}
+
+#[test]
+fn session_reports_points_and_saves_rectangles() {
+    let dir = std::env::temp_dir().join(format!("mtga_calibration_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    let frame = RgbaImage::from_pixel(200, 100, Rgba([10, 20, 30, 255]));
+    DynamicImage::ImageRgba8(frame).save(dir.join("0.png")).unwrap();
+    set_screen_source(Arc::new(DirectoryScreenSource::new(&dir).unwrap()));
+    set_ocr_engine(OcrRegion::Generic, Arc::new(SizeEngine));
+
+    let base = LayoutProfile::for_screen(1920, 1080);
+    let canvas = base.canvas;
+    let mut session = CoordinateSession::new(base, (200, 100));
+
+    assert_eq!(session.handle(&SessionCommand::Save("x".into()), (0, 0)), "nothing captured yet");
+
+    let report = session.handle(&SessionCommand::Capture, (50, 25));
+    assert!(report.contains("abs (50, 25)"), "{}", report);
+    assert!(report.contains("norm (0.25000, 0.25000)"), "{}", report);
+    assert!(report.contains("pixel (10, 20, 30)"), "{}", report);
+    assert!(report.contains("(10, 20, 30)"), "{}", report);
+    // The sample box stays on the screen at the edge
+    let edge = session.handle(&SessionCommand::Capture, (199, 99));
+    assert!(edge.contains("avg 5×5 (10, 20, 30)"), "{}", edge);
+    session.handle(&SessionCommand::Capture, (50, 25));
+    assert_eq!(session.handle(&SessionCommand::Save("probe".into()), (0, 0)), "saved point.probe");
+    let probe = session.profile().get("point.probe").unwrap();
+    assert!((probe[0] - canvas.0 / 4.0).abs() < 1e-9);
+    assert!((probe[1] - canvas.1 / 4.0).abs() < 1e-9);
+
+    // Corners in either order give the same rectangle
+    session.handle(&SessionCommand::Corner, (60, 40));
+    let rect = session.handle(&SessionCommand::Corner, (20, 10));
+    assert!(rect.contains("rect (20, 10)–(60, 40)"), "{}", rect);
+    // The current pipeline upscales the 40×30 crop 2× before OCR
+    assert!(rect.contains("OCR \"80x60\""), "{}", rect);
+    session.handle(&SessionCommand::Save("label".into()), (0, 0));
+    let region = session.profile().get("region.label").unwrap();
+    assert_eq!(region.len(), 4);
+    assert!((region[2] - canvas.0 * 0.3).abs() < 1e-9);
+
+    // A right drag gives the same rectangle without the `r` corners
+    let dragged = session.rectangle((60, 40), (20, 10));
+    assert!(dragged.contains("rect (20, 10)–(60, 40)"), "{}", dragged);
+
+    assert_eq!(session.saved(), ["point.probe", "region.label"]);
+    let reparsed = LayoutProfile::parse(&session.profile().to_text()).unwrap();
+    assert_eq!(reparsed.get("region.label"), session.profile().get("region.label"));
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn pointer_polls_become_clicks() {
+    let state = |x, y, left, right| PointerState { x, y, left, right };
+    let mut tracker = ClickTracker::default();
+
+    // Right click: press and release in place
+    assert_eq!(tracker.update(&state(10, 10, false, true)), None);
+    assert_eq!(tracker.update(&state(11, 10, false, false)), Some(Click::Point(11, 10)));
+
+    // Right drag: the press position is kept until the release
+    assert_eq!(tracker.update(&state(20, 20, false, true)), None);
+    assert_eq!(tracker.update(&state(50, 40, false, true)), None);
+    assert_eq!(tracker.update(&state(80, 60, false, false)), Some(Click::Drag((20, 20), (80, 60))));
+
+    // Left click exits on release
+    assert_eq!(tracker.update(&state(5, 5, true, false)), None);
+    assert_eq!(tracker.update(&state(5, 5, false, false)), Some(Click::Exit));
+    assert_eq!(tracker.update(&state(5, 5, false, false)), None);
+}
@@ -0,0 +1,84 @@
+// tests/card_hash_test.rs
+use image::{DynamicImage, Rgba, RgbaImage};
+use MTGA_me::app::card_hash::{
+    identify_creature, set_card_index, CardHashIndex, PerceptualHash,
+};
+use MTGA_me::app::creature_positions::CreaturePosition;
+use MTGA_me::app::screen_source::{set_screen_source, DirectoryScreenSource};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("mtga_hash_{}_{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Egyszerű "art": vízszintes vagy függőleges színátmenet.
+fn art(width: u32, height: u32, horizontal: bool) -> DynamicImage {
+    DynamicImage::ImageRgba8(RgbaImage::from_fn(width, height, |x, y| {
+        let v = if horizontal { x * 255 / width } else { y * 255 / height } as u8;
+        Rgba([v, 255 - v, (x * y % 256) as u8, 255])
+    }))
+}
+
+#[test]
+fn hash_is_stable_under_rescaling() {
+    let a = art(60, 40, true);
+    let scaled = a.resize_exact(90, 60, image::imageops::FilterType::Triangle);
+    let other = art(60, 40, false);
+    let (ha, hs, ho) = (PerceptualHash::of(&a), PerceptualHash::of(&scaled), PerceptualHash::of(&other));
+    assert!(ha.distance(&hs) <= 4, "distance {}", ha.distance(&hs));
+    assert!(ha.distance(&ho) > 16);
+}
+
+#[test]
+fn index_build_save_load_and_match() {
+    let dir = temp_dir("build");
+    art(60, 40, true).save(dir.join("Shock.png")).unwrap();
+    art(60, 40, true).save(dir.join("Shock#2.png")).unwrap();
+    art(60, 40, false).save(dir.join("Monastery Swiftspear.png")).unwrap();
+
+    let index = CardHashIndex::build_from_dir(&dir).unwrap();
+    assert_eq!(index.len(), 3);
+    let file = dir.join("index.tsv");
+    index.save(&file).unwrap();
+    let loaded = CardHashIndex::load(&file).unwrap();
+
+    let m = loaded.identify(&art(60, 40, false)).unwrap();
+    assert_eq!(m.name, "Monastery Swiftspear");
+    assert_eq!(m.distance, 0);
+    // Ugyanannak a kártyának több variánsa nem teszi kétértelművé
+    assert_eq!(loaded.best_match(PerceptualHash::of(&art(60, 40, true))).unwrap().confidence, 1.0);
+
+    std::fs::write(&file, "zz\tBroken\n").unwrap();
+    assert!(CardHashIndex::load(&file).is_err());
+}
+
+#[test]
+fn creature_identified_from_screen_crop_without_ocr() {
+    let crop = art(30, 40, false);
+    let mut index = CardHashIndex::new();
+    index.insert("Llanowar Elves", PerceptualHash::of(&crop));
+    index.insert("Shock", PerceptualHash::of(&art(30, 40, true)));
+    set_card_index(Arc::new(index));
+
+    let dir = temp_dir("screen");
+    let mut frame = RgbaImage::from_pixel(100, 80, Rgba([0, 0, 0, 255]));
+    image::imageops::overlay(&mut frame, &crop.to_rgba8(), 20, 10);
+    DynamicImage::ImageRgba8(frame).save(dir.join("frame.png")).unwrap();
+    set_screen_source(Arc::new(DirectoryScreenSource::new(&dir).unwrap()));
+
+    let pos = CreaturePosition {
+        ocr_x1: 20,
+        ocr_x2: 50,
+        ocr_y1: 10,
+        ocr_y2: 15,
+        click_x1: 20,
+        click_y1: 10,
+        click_x2: 50,
+        click_y2: 50,
+    };
+    assert_eq!(identify_creature(pos, 1, false, 100, 80), "Llanowar Elves");
+}
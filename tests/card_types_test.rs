@@ -0,0 +1,70 @@
+// tests/card_types_test.rs
+use MTGA_me::app::card_attribute::{
+    ArtifactType, CreatureType, EnchantmentType, LandType, SpellFilter, Subtype, TargetFilter,
+};
+use MTGA_me::app::card_library::{
+    build_card_library, Card, CardType, CardTypeFlags, Creature, ManaCost, SuperTypeFlags,
+};
+
+fn bear() -> CardType {
+    CardType::Creature(Creature {
+        power: 2,
+        toughness: 2,
+        summoning_sickness: true,
+        abilities: Vec::new(),
+        types: vec![CreatureType::Robot],
+        ephemeral_power: 0,
+        ephemeral_toughness: 0,
+    })
+}
+
+#[test]
+fn sorcery_battle_and_kindred_set_flags() {
+    let s = Card::new("Sorcery", CardType::Sorcery, ManaCost::new(1, 1, 0, 0, 0, 0));
+    assert!(s.has_type(CardTypeFlags::SORCERY));
+    let b = Card::new("Battle", CardType::Battle, ManaCost::free());
+    assert!(b.has_type(CardTypeFlags::BATTLE));
+    let k = Card::new("Kindred", CardType::Kindred, ManaCost::free())
+        .with_added_type(CardTypeFlags::INSTANT);
+    assert!(k.has_type(CardTypeFlags::KINDRED | CardTypeFlags::INSTANT));
+}
+
+#[test]
+fn artifact_creature_matches_both_filters() {
+    let golem = Card::new("Golem", bear(), ManaCost::new(3, 0, 0, 0, 0, 0))
+        .with_added_type(CardTypeFlags::ARTIFACT);
+    assert!(TargetFilter::Artifact.matches_card(&golem));
+    assert!(TargetFilter::Creature.matches_card(&golem));
+    assert!(TargetFilter::CreatureType(CreatureType::Robot).matches_card(&golem));
+    assert!(!TargetFilter::Enchantment.matches_card(&golem));
+    assert!(SpellFilter::HasType(CardTypeFlags::ARTIFACT | CardTypeFlags::CREATURE).matches(&golem));
+    assert!(!SpellFilter::Noncreature.matches(&golem));
+}
+
+#[test]
+fn spell_filter_instant_or_sorcery() {
+    let sorcery = Card::new("Sorcery", CardType::Sorcery, ManaCost::free());
+    let instant = Card::new("Instant", CardType::Instant, ManaCost::free());
+    let aura = Card::new("Aura", CardType::Enchantment, ManaCost::free())
+        .with_subtype(Subtype::Enchantment(EnchantmentType::Aura));
+    assert!(SpellFilter::InstantOrSorcery.matches(&sorcery));
+    assert!(SpellFilter::InstantOrSorcery.matches(&instant));
+    assert!(!SpellFilter::InstantOrSorcery.matches(&aura));
+    assert!(SpellFilter::Subtype(Subtype::Enchantment(EnchantmentType::Aura)).matches(&aura));
+}
+
+#[test]
+fn library_supertypes_and_subtypes() {
+    let lib = build_card_library();
+    let forest = &lib["Forest"];
+    assert!(forest.has_supertype(SuperTypeFlags::BASIC));
+    assert!(forest.has_subtype(&Subtype::Land(LandType::Forest)));
+    assert!(!forest.has_subtype(&Subtype::Land(LandType::Mountain)));
+
+    let surrak = &lib["Surrak, Elusive Hunter"];
+    assert!(TargetFilter::Supertype(SuperTypeFlags::LEGENDARY).matches_card(surrak));
+    assert!(!TargetFilter::Supertype(SuperTypeFlags::LEGENDARY).matches_card(forest));
+
+    assert!(lib["Hunter's Talent"].has_subtype(&Subtype::Enchantment(EnchantmentType::Class)));
+    assert!(!lib["Hunter's Talent"].has_subtype(&Subtype::Artifact(ArtifactType::Equipment)));
+}
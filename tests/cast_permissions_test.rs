@@ -0,0 +1,214 @@
+// tests/cast_permissions_test.rs
+use image::{DynamicImage, Rgba, RgbaImage};
+use MTGA_me::app::bot::Bot;
+use MTGA_me::app::card_hash::{hand_card_art_region, set_card_index, CardHashIndex, PerceptualHash};
+use MTGA_me::app::card_attribute::{Duration, Effect, PlayerSelector};
+use MTGA_me::app::card_library::{Card, CardType, ManaCost};
+use MTGA_me::app::game_state::{
+    GameAction, GamePhase, GameState, Player, SimpleHeuristic, Strategy,
+};
+use MTGA_me::app::gre::{CastPermission, CastZone, Gre, ManaPool};
+use MTGA_me::app::input_driver::{set_input_driver, DryRunDriver};
+use MTGA_me::app::screen_source::{set_screen_source, DirectoryScreenSource};
+use std::sync::Arc;
+
+fn red(n: u32) -> ManaPool {
+    ManaPool {
+        red: n,
+        ..ManaPool::default()
+    }
+}
+
+fn mountain() -> Card {
+    Card::new("Mountain", CardType::Land, ManaCost::free())
+}
+
+fn sorcery(name: &str) -> Card {
+    Card::new(name, CardType::Sorcery, ManaCost::new(0, 1, 0, 0, 0, 0))
+}
+
+#[test]
+fn flashback_casts_from_the_graveyard_for_its_own_cost() {
+    let mut gre = Gre::default();
+    let spell = sorcery("Faithless Looting").with_flashback(ManaCost::new(2, 1, 0, 0, 0, 0));
+    gre.put_into_graveyard(spell);
+    let castable = gre.castable_from_zones(Player::Us);
+    assert_eq!(castable.len(), 1);
+    assert_eq!(castable[0].mana_cost.total(), 3);
+    let id = castable[0].card_id;
+
+    gre.set_phase(GamePhase::PreCombatMain);
+    gre.add_mana(Player::Us, red(1));
+    assert!(!gre.can_play_with_permission(id, Player::Us));
+    gre.add_mana(Player::Us, red(2));
+    assert!(gre.play_from_zone(id, Player::Us));
+    assert!(gre.player(Player::Us).mana_pool.is_empty());
+    assert!(gre.player(Player::Us).graveyard.is_empty());
+    assert!(gre.castable_from_zones(Player::Us).is_empty());
+
+    // Flashback nélküli lap a temetőből nem kijátszható
+    gre.put_into_graveyard(sorcery("Lava Spike"));
+    assert!(gre.castable_from_zones(Player::Us).is_empty());
+}
+
+#[test]
+fn sorcery_timing_applies_to_permitted_spells() {
+    let mut gre = Gre::default();
+    gre.set_library(Player::Us, vec![sorcery("Lava Spike")]);
+    gre.handle_effect(Effect::ExileThenPlayFromExile {
+        count: 1,
+        player: PlayerSelector::Controller,
+        duration: Duration::EndOfTurn,
+    });
+    let id = gre.player(Player::Us).exile[0].card_id;
+    gre.set_phase(GamePhase::Combat);
+    gre.add_mana(Player::Us, red(1));
+    assert!(!gre.can_play_with_permission(id, Player::Us));
+    assert!(!gre.play_from_zone(id, Player::Us));
+
+    gre.set_phase(GamePhase::PostCombatMain);
+    gre.add_mana(Player::Us, red(1));
+    assert!(gre.can_play_with_permission(id, Player::Us));
+    assert!(!gre.can_play_with_permission(id, Player::Opponent));
+
+    // EndOfTurn: a kör végén lejár
+    gre.on_turn_end();
+    assert!(gre.castable_from_zones(Player::Us).is_empty());
+    assert_eq!(gre.player(Player::Us).exile.len(), 1);
+}
+
+#[test]
+fn legal_actions_and_heuristic_prefer_expiring_zone_cards() {
+    let mut gre = Gre::default();
+    gre.set_library(Player::Us, vec![sorcery("Lava Spike"), mountain()]);
+    gre.handle_effect(Effect::ExileThenPlayFromExile {
+        count: 2,
+        player: PlayerSelector::Controller,
+        duration: Duration::NextTurnEnd,
+    });
+    gre.set_phase(GamePhase::PreCombatMain);
+
+    let mut state = GameState::new();
+    state.hand = vec![mountain()];
+    state.castable_from_zones = gre.castable_from_zones(Player::Us);
+    let actions = state.zone_actions(&gre);
+    assert_eq!(actions.len(), 1);
+    assert!(matches!(actions[0], GameAction::PlayLandFromZone(0)));
+    assert!(matches!(
+        SimpleHeuristic.decide(&state),
+        GameAction::PlayLandFromZone(0)
+    ));
+
+    let land_id = state.castable_from_zones[0].card_id;
+    assert!(gre.play_from_zone(land_id, Player::Us));
+    assert!(gre.battlefield_creatures.contains_key(&land_id));
+
+    gre.add_mana(Player::Us, red(1));
+    state.castable_from_zones = gre.castable_from_zones(Player::Us);
+    state.land_played_this_turn = true;
+    state.mana_available = 1;
+    assert!(matches!(
+        state.zone_actions(&gre)[..],
+        [GameAction::CastFromZone(0)]
+    ));
+    assert!(matches!(
+        SimpleHeuristic.decide(&state),
+        GameAction::CastFromZone(0)
+    ));
+}
+
+#[test]
+fn permissions_can_be_granted_directly() {
+    let mut gre = Gre::default();
+    gre.put_into_graveyard(mountain());
+    let id = gre.player(Player::Us).graveyard[0].card_id;
+    gre.grant_cast_permission(CastPermission::new(
+        id,
+        Player::Us,
+        CastZone::Graveyard,
+        &Duration::NextTurnEnd,
+    ));
+    gre.on_turn_end();
+    assert_eq!(gre.castable_from_zones(Player::Us).len(), 1);
+    gre.on_turn_end();
+    assert!(gre.castable_from_zones(Player::Us).is_empty());
+}
+
+#[test]
+fn bot_plays_exiled_cards_chosen_by_the_strategy() {
+    set_input_driver(Arc::new(DryRunDriver::new()));
+    let mut bot = Bot::new();
+    bot.gre.set_library(Player::Us, vec![sorcery("Lava Spike"), mountain()]);
+    bot.gre.handle_effect(Effect::ExileThenPlayFromExile {
+        count: 2,
+        player: PlayerSelector::Controller,
+        duration: Duration::NextTurnEnd,
+    });
+    bot.gre.set_phase(GamePhase::PreCombatMain);
+
+    // Előbb a föld, aztán a kliens által fizetett varázslat
+    bot.act_on_strategy();
+    assert!(bot.gre.player(Player::Us).exile.is_empty());
+    assert!(bot.land_played_this_turn);
+    assert_eq!(bot.gre.player(Player::Us).lands_played_this_turn, 1);
+    assert_eq!(bot.gre.player(Player::Us).spells_cast_this_turn, 1);
+    assert_eq!(bot.land_number, 0);
+    assert!(bot.gre.stack.is_empty());
+    assert!(bot.gre.player(Player::Us).mana_pool.is_empty());
+}
+
+/// Egyszerű "art": vízszintes vagy függőleges színátmenet.
+fn art(width: u32, height: u32, horizontal: bool) -> RgbaImage {
+    RgbaImage::from_fn(width, height, |x, y| {
+        let v = if horizontal { x * 255 / width } else { y * 255 / height } as u8;
+        Rgba([v, 255 - v, (x * y % 256) as u8, 255])
+    })
+}
+
+#[test]
+fn bot_identifies_exiled_cards_from_the_screen() {
+    set_input_driver(Arc::new(DryRunDriver::new()));
+    let mut bot = Bot::new();
+    let (w, h) = (1920, 1080);
+    bot.screen_width = w as i32;
+    bot.screen_height = h as i32;
+    for card in [sorcery("Lava Spike"), mountain()] {
+        bot.card_library.insert(card.name.clone(), card);
+    }
+    // Ismeretlen könyvtár: helyőrzők kerülnek száműzetésbe, ezek még nem kijátszhatók
+    bot.gre.handle_effect(Effect::ExileThenPlayFromExile {
+        count: 2,
+        player: PlayerSelector::Controller,
+        duration: Duration::NextTurnEnd,
+    });
+    bot.gre.set_phase(GamePhase::PreCombatMain);
+    assert!(bot.gre.castable_from_zones(Player::Us).is_empty());
+
+    // A kliens a kéz jobb szélén mutatja őket, az engedélyek sorrendjében; a lapok
+    // egymásra lógnak, ezért a hash index a képernyőn látható részt ismeri
+    let cards = [("Lava Spike", true), ("Mountain", false)];
+    let regions: Vec<_> = (0..cards.len())
+        .map(|slot| hand_card_art_region(slot, cards.len(), w, h).unwrap())
+        .collect();
+    let mut frame = RgbaImage::from_pixel(w, h, Rgba([0, 0, 0, 255]));
+    for (&(_, horizontal), &(x1, y1, x2, y2)) in cards.iter().zip(&regions) {
+        image::imageops::overlay(&mut frame, &art(x2 - x1, y2 - y1, horizontal), x1 as i64, y1 as i64);
+    }
+    let mut index = CardHashIndex::new();
+    for (&(name, _), &(x1, y1, x2, y2)) in cards.iter().zip(&regions) {
+        let shown = image::imageops::crop_imm(&frame, x1, y1, x2 - x1, y2 - y1).to_image();
+        index.insert(name, PerceptualHash::of(&DynamicImage::ImageRgba8(shown)));
+    }
+    set_card_index(Arc::new(index));
+    let dir = std::env::temp_dir().join(format!("mtga_zone_cards_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    DynamicImage::ImageRgba8(frame).save(dir.join("frame.png")).unwrap();
+    set_screen_source(Arc::new(DirectoryScreenSource::new(&dir).unwrap()));
+
+    bot.act_on_strategy();
+    assert!(bot.gre.player(Player::Us).exile.is_empty());
+    assert!(bot.land_played_this_turn);
+    assert_eq!(bot.gre.player(Player::Us).spells_cast_this_turn, 1);
+    assert!(bot.gre.player(Player::Us).mana_pool.is_empty());
+}
@@ -0,0 +1,54 @@
+// tests/common/mod.rs
+//! Közös tesztsegédek a GRE-tesztekhez: lények, battlefieldre léptetés, mana.
+#![allow(dead_code)]
+
+use MTGA_me::app::card_attribute::{CreatureType, KeywordAbility};
+use MTGA_me::app::card_library::{Card, CardType, Creature, ManaCost};
+use MTGA_me::app::game_state::Player;
+use MTGA_me::app::gre::{Gre, ManaPool};
+
+/// `power`/`toughness` Warrior, képességek nélkül, {1}{G} költséggel
+pub fn creature(name: &str, power: i32, toughness: i32, controller: Player) -> Card {
+    creature_with(name, power, toughness, vec![CreatureType::Warrior], Vec::new(), controller)
+}
+
+/// Lény a megadott típusokkal és képességekkel, {1}{G} költséggel
+pub fn creature_with(
+    name: &str,
+    power: i32,
+    toughness: i32,
+    types: Vec<CreatureType>,
+    abilities: Vec<KeywordAbility>,
+    controller: Player,
+) -> Card {
+    Card::new(
+        name,
+        CardType::Creature(Creature {
+            power,
+            toughness,
+            summoning_sickness: false,
+            abilities,
+            types,
+            ephemeral_power: 0,
+            ephemeral_toughness: 0,
+        }),
+        ManaCost::new(1, 0, 1, 0, 0, 0),
+    )
+    .with_controller(controller)
+}
+
+/// A lap a battlefieldre lép; a kiosztott id-t adja vissza
+pub fn enter(gre: &mut Gre, card: Card) -> u64 {
+    let mut c = card;
+    gre.enter_battlefield(&mut c);
+    c.card_id
+}
+
+pub fn mana(colorless: u32, red: u32, green: u32) -> ManaPool {
+    ManaPool {
+        colorless,
+        red,
+        green,
+        ..ManaPool::default()
+    }
+}
@@ -0,0 +1,201 @@
+// tests/condition_test.rs
+mod common;
+
+use MTGA_me::app::card_attribute::{
+    Comparison, Condition, CounterType, CreatureType, Effect, PlayerSelector, TargetFilter,
+};
+use MTGA_me::app::card_library::{ManaCost, build_card_library};
+use MTGA_me::app::game_state::{GameEvent, Player};
+use MTGA_me::app::gre::{ActivatedAbility, ActivationTiming, ConditionContext, Gre, StackEntry};
+use common::{creature, creature_with, enter};
+
+fn plus_counters(gre: &Gre, id: u64) -> u32 {
+    gre.battlefield_creatures[&id].counter_count(&CounterType::PlusOnePlusOne)
+}
+
+fn grow_first_time() -> ActivatedAbility {
+    ActivatedAbility {
+        cost: ManaCost::free(),
+        condition: Condition::FirstTimeThisTurn,
+        effect: Effect::Conditional {
+            condition: Condition::FirstTimeThisTurn,
+            effect_if_true: Box::new(Effect::AddCounter {
+                counter: CounterType::PlusOnePlusOne,
+                amount: 1,
+                target: TargetFilter::SelfCard,
+            }),
+            effect_if_false: None,
+        },
+        activated_this_turn: false,
+        loyalty_change: 0,
+        additional_costs: Vec::new(),
+        timing: ActivationTiming::Instant,
+        once_each_turn: false,
+    }
+}
+
+#[test]
+fn composite_conditions_read_the_game_state() {
+    let mut gre = Gre::default();
+    let ctx = ConditionContext::new(Player::Us);
+    let mouse = Condition::you_control(TargetFilter::CreatureType(CreatureType::Mouse));
+    assert!(!gre.evaluate_condition(&mouse, &ctx));
+
+    // Az ellenfél egere nem számít
+    enter(
+        &mut gre,
+        creature_with("Their Mouse", 1, 1, vec![CreatureType::Mouse], Vec::new(), Player::Opponent),
+    );
+    assert!(!gre.evaluate_condition(&mouse, &ctx));
+    enter(
+        &mut gre,
+        creature_with("Our Mouse", 1, 1, vec![CreatureType::Mouse], Vec::new(), Player::Us),
+    );
+    assert!(gre.evaluate_condition(&mouse, &ctx));
+
+    let two_spells = Condition::SpellsCastThisTurn {
+        player: PlayerSelector::Controller,
+        cmp: Comparison::AtLeast,
+        count: 2,
+    };
+    gre.player_mut(Player::Us).spells_cast_this_turn = 1;
+    assert!(!gre.evaluate_condition(&two_spells, &ctx));
+    assert!(gre.evaluate_condition(&mouse.clone().or(two_spells.clone()), &ctx));
+    gre.player_mut(Player::Us).spells_cast_this_turn = 2;
+    assert!(gre.evaluate_condition(&mouse.clone().and(two_spells.clone()), &ctx));
+    assert!(!gre.evaluate_condition(&two_spells.negate(), &ctx));
+
+    let low_life = Condition::LifeTotal {
+        player: PlayerSelector::Opponent,
+        cmp: Comparison::AtMost,
+        amount: 10,
+    };
+    assert!(!gre.evaluate_condition(&low_life, &ctx));
+    gre.player_mut(Player::Opponent).life = 7;
+    assert!(gre.evaluate_condition(&low_life, &ctx));
+}
+
+#[test]
+fn first_time_this_turn_agrees_on_activation_and_resolution() {
+    let mut gre = Gre::default();
+    let id = enter(
+        &mut gre,
+        creature_with("Grower", 1, 1, vec![CreatureType::Warrior], Vec::new(), Player::Us)
+            .with_activated(grow_first_time()),
+    );
+
+    assert!(gre.activate_ability_at(id, 0, Player::Us));
+    gre.resolve_stack();
+    assert_eq!(plus_counters(&gre, id), 1);
+    // Második aktiválás ugyanabban a körben: a feltétel mindkét helyen hamis
+    assert!(!gre.activate_ability_at(id, 0, Player::Us));
+
+    gre.on_turn_end();
+    assert!(gre.activate_ability_at(id, 0, Player::Us));
+    gre.resolve_stack();
+    assert_eq!(plus_counters(&gre, id), 2);
+}
+
+#[test]
+fn heartfire_hero_grows_only_on_the_first_targeting_each_turn() {
+    let mut gre = Gre::default();
+    let hero = enter(&mut gre, build_card_library()["Heartfire Hero"].clone());
+
+    gre.trigger_event(GameEvent::Targeted(hero), &mut [], Player::Us);
+    assert_eq!(gre.stack.len(), 1);
+    gre.resolve_stack();
+    assert_eq!(plus_counters(&gre, hero), 1);
+
+    gre.trigger_event(GameEvent::Targeted(hero), &mut [], Player::Us);
+    assert!(gre.stack.is_empty());
+    assert_eq!(plus_counters(&gre, hero), 1);
+
+    gre.on_turn_end();
+    gre.trigger_event(GameEvent::Targeted(hero), &mut [], Player::Us);
+    gre.resolve_stack();
+    assert_eq!(plus_counters(&gre, hero), 2);
+}
+
+#[test]
+fn first_time_this_turn_is_tracked_per_card() {
+    let mut gre = Gre::default();
+    let library = build_card_library();
+    let first = enter(&mut gre, library["Heartfire Hero"].clone());
+    let second = enter(&mut gre, library["Heartfire Hero"].clone());
+
+    gre.trigger_event(GameEvent::Targeted(first), &mut [], Player::Us);
+    gre.trigger_event(GameEvent::Targeted(second), &mut [], Player::Us);
+    gre.resolve_stack();
+    assert_eq!(plus_counters(&gre, first), 1);
+    assert_eq!(plus_counters(&gre, second), 1);
+}
+
+#[test]
+fn terrasymbiosis_draws_only_once_each_turn() {
+    let mut gre = Gre::default();
+    enter(&mut gre, build_card_library()["Terrasymbiosis"].clone());
+    let bear = enter(&mut gre, creature("Bear", 2, 2, Player::Us));
+    let wolf = enter(&mut gre, creature("Wolf", 2, 2, Player::Us));
+
+    gre.add_counters(bear, CounterType::PlusOnePlusOne, 2);
+    gre.resolve_stack();
+    assert_eq!(gre.player(Player::Us).hand_size, 2);
+
+    // Másik lényre, ugyanabban a körben: nincs újabb húzás
+    gre.add_counters(wolf, CounterType::PlusOnePlusOne, 1);
+    assert!(gre.stack.is_empty());
+    assert_eq!(gre.player(Player::Us).hand_size, 2);
+
+    gre.on_turn_end();
+    gre.add_counters(wolf, CounterType::PlusOnePlusOne, 3);
+    gre.resolve_stack();
+    assert_eq!(gre.player(Player::Us).hand_size, 5);
+}
+
+#[test]
+fn intervening_if_is_checked_on_trigger_and_resolution() {
+    let mut gre = Gre::default();
+    let ctx = ConditionContext::new(Player::Us);
+    let mouse = Condition::you_control(TargetFilter::CreatureType(CreatureType::Mouse));
+    let grow = |condition: Condition| Effect::Conditional {
+        condition,
+        effect_if_true: Box::new(Effect::AddCounter {
+            counter: CounterType::PlusOnePlusOne,
+            amount: 1,
+            target: TargetFilter::ControllerCreature,
+        }),
+        effect_if_false: None,
+    };
+    // Kiváltáskor hamis: nem kerül a stackre
+    assert!(
+        gre.check_intervening_if(grow(mouse.clone()), &ctx)
+            .is_none()
+    );
+
+    let id = enter(
+        &mut gre,
+        creature_with("Mouse", 1, 1, vec![CreatureType::Mouse], Vec::new(), Player::Us),
+    );
+    let effect = gre.check_intervening_if(grow(mouse), &ctx).unwrap();
+    gre.push_to_stack(StackEntry::TriggeredAbility {
+        source: None,
+        effect: effect.clone(),
+        controller: Player::Us,
+    });
+    gre.resolve_stack();
+    assert_eq!(plus_counters(&gre, id), 1);
+
+    // Feloldáskor újra ellenőrizzük: az egér közben elhagyta a battlefieldet
+    let other = enter(
+        &mut gre,
+        creature_with("Bear", 1, 1, vec![CreatureType::Warrior], Vec::new(), Player::Us),
+    );
+    gre.push_to_stack(StackEntry::TriggeredAbility {
+        source: None,
+        effect,
+        controller: Player::Us,
+    });
+    gre.battlefield_creatures.remove(&id);
+    gre.resolve_stack();
+    assert_eq!(plus_counters(&gre, other), 0);
+}
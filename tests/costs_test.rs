@@ -0,0 +1,195 @@
+// tests/costs_test.rs
+mod common;
+
+use MTGA_me::app::bot::Bot;
+use MTGA_me::app::card_attribute::{CounterType, TargetFilter};
+use MTGA_me::app::card_library::{
+    Card, CardType, CardTypeFlags, Creature, ManaCost, build_card_library,
+};
+use MTGA_me::app::game_state::Player;
+use MTGA_me::app::gre::{Cost, CostError, CostReduction, Decision, Gre, ScriptedDecisions};
+use common::{creature, enter, mana};
+
+#[test]
+fn burst_lightning_is_kicked_only_when_affordable() {
+    let burst = build_card_library()["Burst Lightning"].clone();
+    assert_eq!(burst.mana_cost.total(), 1);
+
+    let mut gre = Gre::default();
+    gre.add_mana(Player::Us, mana(4, 1, 0));
+    gre.cast_spell(burst.clone(), Player::Us, None).unwrap();
+    gre.resolve_stack();
+    assert_eq!(gre.player(Player::Opponent).life, 16);
+    assert!(gre.player(Player::Us).mana_pool.is_empty());
+    assert_eq!(gre.decision_log[0], Decision::PayCost(true));
+
+    // Egy manából nincs kicker, nincs róla döntés
+    let mut gre = Gre::default();
+    gre.add_mana(Player::Us, mana(0, 1, 0));
+    gre.cast_spell(burst.clone(), Player::Us, None).unwrap();
+    gre.resolve_stack();
+    assert_eq!(gre.player(Player::Opponent).life, 18);
+    assert!(!gre.decision_log.contains(&Decision::PayCost(true)));
+
+    let mut gre = Gre::default();
+    assert!(matches!(
+        gre.cast_spell(burst, Player::Us, None),
+        Err(CostError::NotEnoughMana { .. })
+    ));
+    assert!(gre.stack.is_empty());
+}
+
+#[test]
+fn declined_kicker_keeps_the_mana() {
+    let mut gre = Gre::default().with_decisions(ScriptedDecisions::new([Decision::PayCost(false)]));
+    gre.add_mana(Player::Us, mana(4, 1, 0));
+    gre.cast_spell(build_card_library()["Burst Lightning"].clone(), Player::Us, None)
+        .unwrap();
+    gre.resolve_stack();
+    assert_eq!(gre.player(Player::Opponent).life, 18);
+    assert_eq!(gre.player(Player::Us).mana_pool.total(), 4);
+}
+
+#[test]
+fn paid_offspring_creates_a_token_copy() {
+    let mut gre = Gre::default();
+    gre.add_mana(Player::Us, mana(2, 0, 1));
+    gre.cast_spell(build_card_library()["Pawpatch Recruit"].clone(), Player::Us, None)
+        .unwrap();
+    gre.resolve_stack();
+
+    let recruits: Vec<&Card> = gre
+        .battlefield_creatures
+        .values()
+        .filter(|c| c.name == "Pawpatch Recruit")
+        .collect();
+    assert_eq!(recruits.len(), 2);
+    let token = recruits
+        .iter()
+        .find(|c| c.has_type(CardTypeFlags::TOKEN))
+        .expect("offspring token");
+    assert!(matches!(
+        token.card_type,
+        CardType::Creature(Creature {
+            power: 1,
+            toughness: 1,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn scavenging_ooze_exiles_a_graveyard_card_as_its_cost() {
+    let mut gre = Gre::default();
+    let ooze_card = build_card_library()["Scavenging Ooze"].clone();
+    let ooze = enter(&mut gre, ooze_card);
+    let source = gre.battlefield_creatures[&ooze].clone();
+    let ability = source.activated_abilities[0].clone();
+    assert!(!gre.can_activate(&source, &ability, Player::Us));
+
+    gre.put_into_graveyard(creature("Bear", 2, 2, Player::Opponent));
+    gre.add_mana(Player::Us, mana(0, 0, 2));
+    assert!(gre.activate_ability(source.clone(), ability.clone(), Player::Us));
+    assert!(gre.player(Player::Opponent).graveyard.is_empty());
+    gre.resolve_stack();
+    assert_eq!(
+        gre.battlefield_creatures[&ooze].counter_count(&CounterType::PlusOnePlusOne),
+        1
+    );
+    assert_eq!(gre.player(Player::Us).life, 21);
+    assert!(!gre.can_activate(&source, &ability, Player::Us));
+}
+
+#[test]
+fn sacrifice_discard_and_life_are_paid_together() {
+    let ritual = Card::new("Costly Ritual", CardType::Sorcery, ManaCost::free())
+        .with_additional_cost(Cost::SacrificeCreature(TargetFilter::Creature))
+        .with_additional_cost(Cost::Discard(1))
+        .with_additional_cost(Cost::PayLife(2));
+
+    let mut gre = Gre::default();
+    gre.set_hand_size(Player::Us, 1);
+    assert_eq!(
+        gre.cast_spell(ritual.clone(), Player::Us, None),
+        Err(CostError::NothingToSacrifice)
+    );
+    assert_eq!(gre.player(Player::Us).hand_size, 1);
+
+    let bear = enter(&mut gre, creature("Bear", 2, 2, Player::Us));
+    let token = enter(
+        &mut gre,
+        creature("Spirit", 1, 1, Player::Us).with_added_type(CardTypeFlags::TOKEN),
+    );
+    gre.cast_spell(ritual, Player::Us, None).unwrap();
+    assert!(!gre.battlefield_creatures.contains_key(&token));
+    assert!(gre.battlefield_creatures.contains_key(&bear));
+    // A token nem kerül a temetőbe, az eldobott ismeretlen lap helyőrzőként igen
+    let graveyard = &gre.player(Player::Us).graveyard;
+    assert_eq!(graveyard.len(), 1);
+    assert!(graveyard[0].is_hidden());
+    assert_eq!(gre.player(Player::Us).hand_size, 0);
+    assert_eq!(gre.player(Player::Us).life, 18);
+}
+
+#[test]
+fn cost_reductions_only_touch_generic_mana_while_the_source_stays() {
+    let mut gre = Gre::default();
+    let source = enter(&mut gre, creature("Goblin Electromancer", 2, 2, Player::Us));
+    gre.add_cost_reduction(CostReduction {
+        source: Some(source),
+        controller: Player::Us,
+        filter: TargetFilter::Creature,
+        generic: 2,
+    });
+    let bear = creature("Bear", 2, 2, Player::Us);
+    assert_eq!(
+        gre.effective_cost(&bear, Player::Us),
+        ManaCost::new(0, 0, 1, 0, 0, 0)
+    );
+    assert_eq!(gre.effective_cost(&bear, Player::Opponent), bear.mana_cost);
+
+    gre.add_mana(Player::Us, mana(0, 0, 1));
+    gre.cast_spell(bear.clone(), Player::Us, None).unwrap();
+
+    gre.battlefield_creatures.remove(&source);
+    assert_eq!(gre.effective_cost(&bear, Player::Us), bear.mana_cost);
+}
+
+#[test]
+fn bot_casts_targeted_spells_through_cast_spell() {
+    let mut bot = Bot::new();
+    let mut hero = creature("Heartfire Hero", 1, 1, Player::Us);
+    bot.gre.enter_battlefield(&mut hero);
+    bot.battlefield_creatures.insert(hero.name.clone(), hero.clone());
+    bot.cards_texts.push("Felonious Rage".into());
+    bot.card_count = 1;
+
+    // A manát a kliens fizette: a GRE nem utasítja el üres mana poollal sem
+    bot.cast_felonious_rage_on_creature(0, "Heartfire Hero");
+    assert!(bot.cards_texts.is_empty());
+    assert_eq!(bot.gre.stack.len(), 1);
+    assert!(bot.gre.player(Player::Us).mana_pool.is_empty());
+
+    bot.gre.resolve_stack();
+    let hero = &bot.gre.battlefield_creatures[&hero.card_id];
+    assert_eq!(hero.get_current_power(&bot.gre), 3);
+}
+
+#[test]
+fn rejected_bot_cast_leaves_the_mana_pool_untouched() {
+    let mut bot = Bot::new();
+    let mut hero = creature("Heartfire Hero", 1, 1, Player::Us);
+    bot.gre.enter_battlefield(&mut hero);
+    bot.battlefield_creatures.insert(hero.name.clone(), hero.clone());
+    // Kifizethetetlen járulékos költség: üres kézből nem lehet három lapot eldobni
+    let rage = build_card_library()["Felonious Rage"]
+        .clone()
+        .with_additional_cost(Cost::Discard(3));
+    bot.card_library.insert(rage.name.clone(), rage);
+    bot.cards_texts.push("Felonious Rage".into());
+    bot.card_count = 1;
+
+    bot.cast_felonious_rage_on_creature(0, "Heartfire Hero");
+    assert!(bot.gre.stack.is_empty());
+    assert!(bot.gre.player(Player::Us).mana_pool.is_empty());
+}
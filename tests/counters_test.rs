@@ -0,0 +1,181 @@
+// tests/counters_test.rs
+use MTGA_me::app::card_attribute::{
+    Amount, CounterType, CreatureType, Effect, PlayerSelector, TargetFilter,
+};
+use MTGA_me::app::card_library::{build_card_library, Card, CardType, Creature, ManaCost};
+use MTGA_me::app::game_state::Player;
+use MTGA_me::app::gre::{Decision, Gre, ProliferateTarget, ScriptedDecisions};
+
+fn bear(controller: Player) -> Card {
+    Card::new(
+        "Bear",
+        CardType::Creature(Creature {
+            power: 2,
+            toughness: 2,
+            summoning_sickness: false,
+            abilities: Vec::new(),
+            types: vec![CreatureType::Warrior],
+            ephemeral_power: 0,
+            ephemeral_toughness: 0,
+        }),
+        ManaCost::new(1, 0, 1, 0, 0, 0),
+    )
+    .with_controller(controller)
+}
+
+fn enter(gre: &mut Gre, card: Card) -> u64 {
+    let mut c = card;
+    gre.enter_battlefield(&mut c);
+    c.card_id
+}
+
+fn base_pt(gre: &Gre, id: u64) -> (i32, i32) {
+    match &gre.battlefield_creatures[&id].card_type {
+        CardType::Creature(cr) => (cr.power, cr.toughness),
+        _ => panic!("not a creature"),
+    }
+}
+
+#[test]
+fn plus_and_minus_counters_annihilate() {
+    let mut gre = Gre::new(Player::Us);
+    let id = enter(&mut gre, bear(Player::Us));
+    gre.add_counters(id, CounterType::PlusOnePlusOne, 3);
+    assert_eq!(base_pt(&gre, id), (5, 5));
+
+    gre.add_counters(id, CounterType::MinusOneMinusOne, 2);
+    let card = &gre.battlefield_creatures[&id];
+    assert_eq!(card.counter_count(&CounterType::PlusOnePlusOne), 1);
+    assert_eq!(card.counter_count(&CounterType::MinusOneMinusOne), 0);
+    assert_eq!(base_pt(&gre, id), (3, 3));
+}
+
+#[test]
+fn minus_counters_kill_at_zero_toughness() {
+    let mut gre = Gre::new(Player::Us);
+    let id = enter(&mut gre, bear(Player::Opponent));
+    gre.handle_effect(Effect::AddCounter {
+        counter: CounterType::MinusOneMinusOne,
+        amount: 2,
+        target: TargetFilter::ExactCardID(id),
+    });
+    assert!(!gre.battlefield_creatures.contains_key(&id));
+}
+
+#[test]
+fn shield_counter_prevents_damage_once() {
+    let mut gre = Gre::new(Player::Us);
+    let id = enter(&mut gre, bear(Player::Us));
+    gre.add_counters(id, CounterType::Shield, 1);
+    let bolt = Effect::Damage {
+        amount: Amount::Fixed(3),
+        target: TargetFilter::ExactCardID(id),
+    };
+    gre.handle_effect(bolt.clone());
+    assert!(gre.battlefield_creatures.contains_key(&id));
+    assert_eq!(
+        gre.battlefield_creatures[&id].counter_count(&CounterType::Shield),
+        0
+    );
+    gre.handle_effect(bolt);
+    assert!(!gre.battlefield_creatures.contains_key(&id));
+}
+
+#[test]
+fn player_counters_are_tracked_and_spent() {
+    let mut gre = Gre::new(Player::Us);
+    gre.add_player_counters(Player::Opponent, CounterType::Poison, 3);
+    gre.add_player_counters(Player::Us, CounterType::Energy, 2);
+    // Nem játékos counter: nem kerül fel
+    gre.add_player_counters(Player::Us, CounterType::Shield, 1);
+
+    assert_eq!(gre.player_counter(Player::Opponent, &CounterType::Poison), 3);
+    assert_eq!(gre.player_counter(Player::Us, &CounterType::Shield), 0);
+    assert!(!gre.pay_player_counters(Player::Us, CounterType::Energy, 3));
+    assert!(gre.pay_player_counters(Player::Us, CounterType::Energy, 2));
+    assert_eq!(gre.player_counter(Player::Us, &CounterType::Energy), 0);
+}
+
+#[test]
+fn default_proliferate_picks_beneficial_targets() {
+    let mut gre = Gre::new(Player::Us);
+    let ours = enter(&mut gre, bear(Player::Us));
+    let theirs = enter(&mut gre, bear(Player::Opponent));
+    let mut vivien = build_card_library()["Vivien Reid"].clone();
+    gre.enter_battlefield(&mut vivien);
+    gre.add_counters(ours, CounterType::PlusOnePlusOne, 1);
+    gre.add_counters(theirs, CounterType::PlusOnePlusOne, 1);
+    gre.add_player_counters(Player::Opponent, CounterType::Poison, 1);
+    gre.add_player_counters(Player::Us, CounterType::Poison, 1);
+
+    gre.handle_effect(Effect::Proliferate {
+        player: PlayerSelector::Controller,
+    });
+
+    assert_eq!(base_pt(&gre, ours), (4, 4));
+    assert_eq!(base_pt(&gre, theirs), (3, 3));
+    assert_eq!(gre.battlefield_creatures[&vivien.card_id].loyalty(), Some(6));
+    assert_eq!(gre.player_counter(Player::Opponent, &CounterType::Poison), 2);
+    assert_eq!(gre.player_counter(Player::Us, &CounterType::Poison), 1);
+}
+
+#[test]
+fn proliferate_decision_overrides_default() {
+    let mut gre = Gre::new(Player::Us);
+    let a = enter(&mut gre, bear(Player::Us));
+    let b = enter(&mut gre, bear(Player::Us));
+    gre.add_counters(a, CounterType::Shield, 1);
+    gre.add_counters(b, CounterType::Shield, 1);
+    assert!(gre.proliferate_candidates().contains(&ProliferateTarget::Permanent(a)));
+    gre.decisions = Box::new(ScriptedDecisions::new([Decision::Proliferate(vec![
+        ProliferateTarget::Permanent(b),
+    ])]));
+    gre.execute_proliferate();
+    assert_eq!(gre.battlefield_creatures[&a].counter_count(&CounterType::Shield), 1);
+    assert_eq!(gre.battlefield_creatures[&b].counter_count(&CounterType::Shield), 2);
+}
+
+#[test]
+fn oil_and_lore_counters_are_added_and_proliferated() {
+    let mut gre = Gre::new(Player::Us);
+    let oily = enter(&mut gre, bear(Player::Us));
+    let saga = enter(&mut gre, bear(Player::Us));
+    gre.add_counters(oily, CounterType::Oil, 2);
+    gre.add_counters(saga, CounterType::Lore, 1);
+
+    gre.handle_effect(Effect::Proliferate {
+        player: PlayerSelector::Controller,
+    });
+
+    assert_eq!(gre.battlefield_creatures[&oily].counter_count(&CounterType::Oil), 3);
+    assert_eq!(gre.battlefield_creatures[&saga].counter_count(&CounterType::Lore), 2);
+    // A P/T-t nem módosítják
+    assert_eq!(base_pt(&gre, oily), (2, 2));
+}
+
+#[test]
+fn stun_counter_replaces_untap() {
+    let mut gre = Gre::new(Player::Us);
+    let id = enter(&mut gre, bear(Player::Us));
+    gre.add_counters(id, CounterType::Stun, 1);
+    gre.battlefield_creatures.get_mut(&id).unwrap().tapped = true;
+
+    gre.untap_step(Player::Us);
+    assert!(gre.battlefield_creatures[&id].tapped);
+    assert_eq!(gre.battlefield_creatures[&id].counter_count(&CounterType::Stun), 0);
+    gre.untap_step(Player::Us);
+    assert!(!gre.battlefield_creatures[&id].tapped);
+}
+
+#[test]
+fn finality_counter_exiles_instead_of_dying() {
+    let mut gre = Gre::new(Player::Us);
+    let id = enter(&mut gre, bear(Player::Us));
+    gre.add_counters(id, CounterType::Finality, 1);
+    gre.handle_effect(Effect::Destroy {
+        target: TargetFilter::ExactCardID(id),
+    });
+    assert!(!gre.battlefield_creatures.contains_key(&id));
+    assert!(gre.player(Player::Us).graveyard.is_empty());
+    assert_eq!(gre.player(Player::Us).exile.len(), 1);
+}
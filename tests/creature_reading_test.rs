@@ -3,11 +3,9 @@
 
 use std::env;
 use tracing::info;
-use tracing_subscriber;
 
 // a te crate-ed neve lehet más, de az alábbi szerint importáld:
 use MTGA_me::app::game_state_updater::{count_branch, detect_creature_count_for_side, load_side_creatures};
-use MTGA_me::app::ui::{get_average_color, is_color_within_tolerance};
 
 fn print_usage_and_exit() -> ! {
     eprintln!("Usage:");
@@ -20,12 +18,27 @@ fn print_usage_and_exit() -> ! {
 fn main() {
     // init mindenki által látott `info!` loghoz
     tracing_subscriber::fmt::init();
+    // MTGA_SCREEN_DIR=<png-k mappája> esetén mentett képernyőképeken fut
+    if let Err(e) = MTGA_me::app::screen_source::init_screen_source_from_env() {
+        eprintln!("screen source: {}", e);
+        std::process::exit(1);
+    }
 
     let mut args = env::args().skip(1);
-    let cmd = args.next().unwrap_or_else(|| print_usage_and_exit().to_string());
+    let cmd = args.next().unwrap_or_else(|| print_usage_and_exit());
 
-    // fix 2K felbontás
-    let (w, h) = (2560u32, 1440u32);
+    // A kiválasztott képernyőforrás mérete; a layout profil ehhez igazodik
+    let (w, h) = match MTGA_me::multiplatform::screen_size() {
+        Ok((w, h)) => (w as u32, h as u32),
+        Err(e) => {
+            eprintln!("screen size: {}", e);
+            std::process::exit(1);
+        }
+    };
+    if let Err(e) = MTGA_me::app::layout::init_layout(w, h) {
+        eprintln!("layout: {}", e);
+        std::process::exit(1);
+    }
 
     match cmd.as_str() {
         "count-branch" => {
@@ -42,7 +55,7 @@ fn main() {
             let step: i32        = args.next().and_then(|s| s.parse().ok()).unwrap_or_else(|| print_usage_and_exit());
             let max_count: usize = args.next().and_then(|s| s.parse().ok()).unwrap_or_else(|| print_usage_and_exit());
 
-            info!("→ running count_branch on fixed {}×{}", w, h);
+            info!("→ running count_branch on {}×{}", w, h);
             let result = count_branch(
                 y1, region_h, rect_w, x,
                 tol, (r,g,b),
@@ -58,7 +71,7 @@ fn main() {
                 "opp" => true,
                 _     => print_usage_and_exit(),
             };
-            info!("→ running detect_creature_count_for_side on fixed {}×{}", w, h);
+            info!("→ running detect_creature_count_for_side on {}×{}", w, h);
             let cnt = detect_creature_count_for_side(w, h, is_opp);
             println!("detect-creature-count({}) → {}", side, cnt);
         }
@@ -70,7 +83,7 @@ fn main() {
                 "opp" => true,
                 _     => print_usage_and_exit(),
             };
-            info!("→ running load_side_creatures on fixed {}×{}", w, h);
+            info!("→ running load_side_creatures on {}×{}", w, h);
             let map = load_side_creatures(w, h, is_opp);
             println!("loaded creatures on {} side: {} entries", side, map.len());
             for name in map.keys() {
@@ -0,0 +1,217 @@
+// tests/decision_test.rs
+mod common;
+
+use MTGA_me::app::card_attribute::{Amount, Effect, PlayerSelector, TargetFilter};
+use MTGA_me::app::card_library::{Card, CardType, Creature, ManaCost};
+use MTGA_me::app::game_state::Player;
+use MTGA_me::app::gre::{
+    Decision, DecisionProvider, Gre, HeuristicDecisions, ScriptedDecisions,
+    SearchDecisions, StackEntry, Target, MAX_SUBSET_SEARCH,
+};
+use common::{creature, enter, mana};
+
+fn resolve(gre: &mut Gre, effect: Effect, source: Option<Card>) {
+    gre.push_to_stack(StackEntry::TriggeredAbility {
+        source,
+        effect,
+        controller: Player::Us,
+    });
+    gre.resolve_stack();
+}
+
+fn shock_any_target() -> Effect {
+    Effect::Damage {
+        amount: Amount::Fixed(2),
+        target: TargetFilter::AnyTarget,
+    }
+}
+
+fn modes() -> Effect {
+    Effect::ChooseSome {
+        choose: 1,
+        options: vec![
+            Effect::GainLife {
+                amount: 1,
+                player: PlayerSelector::Controller,
+            },
+            Effect::GainLife {
+                amount: 2,
+                player: PlayerSelector::Controller,
+            },
+            Effect::GainLife {
+                amount: 3,
+                player: PlayerSelector::Controller,
+            },
+        ],
+    }
+}
+
+#[test]
+fn heuristic_damage_kills_the_best_creature_it_can() {
+    let mut gre = Gre::default();
+    let _big = enter(&mut gre, creature("Big", 5, 5, Player::Opponent));
+    let bear = enter(&mut gre, creature("Bear", 2, 2, Player::Opponent));
+    let _elf = enter(&mut gre, creature("Elf", 1, 1, Player::Opponent));
+    let _ours = enter(&mut gre, creature("Ours", 3, 1, Player::Us));
+
+    resolve(&mut gre, shock_any_target(), None);
+    assert!(!gre.battlefield_creatures.contains_key(&bear));
+    assert_eq!(
+        gre.decision_log,
+        vec![Decision::Target(Some(Target::Permanent(bear)))]
+    );
+
+    // Nincs megölhető lény: az ellenfél kapja
+    let mut gre = Gre::default();
+    enter(&mut gre, creature("Big", 5, 5, Player::Opponent));
+    resolve(&mut gre, shock_any_target(), None);
+    assert_eq!(gre.player(Player::Opponent).life, 18);
+}
+
+#[test]
+fn scripted_decisions_drive_modes_targets_and_costs() {
+    let mut gre = Gre::default().with_decisions(ScriptedDecisions::new([
+        Decision::Modes(vec![2]),
+        Decision::Target(Some(Target::Player(Player::Us))),
+        Decision::PayCost(false),
+    ]));
+    let start = gre.player(Player::Us).life;
+    resolve(&mut gre, modes(), None);
+    assert_eq!(gre.player(Player::Us).life, start + 3);
+
+    resolve(&mut gre, shock_any_target(), None);
+    assert_eq!(gre.player(Player::Us).life, start + 1);
+
+    // Az Offspring költséget kijátszáskor utasítjuk el: nincs token másolat
+    let mouse =
+        creature("Mouse", 1, 2, Player::Us).with_offspring(ManaCost::new(2, 0, 0, 0, 0, 0));
+    gre.add_mana(
+        Player::Us,
+        mana(3, 0, 1),
+    );
+    let before = gre.battlefield_creatures.len();
+    gre.cast_spell(mouse, Player::Us, None).unwrap();
+    gre.resolve_stack();
+    assert_eq!(gre.battlefield_creatures.len(), before + 1);
+    assert_eq!(gre.player(Player::Us).mana_pool.total(), 2);
+}
+
+#[test]
+fn invalid_answers_fall_back_to_the_heuristic() {
+    let mut gre = Gre::default().with_decisions(ScriptedDecisions::new([
+        Decision::Modes(vec![7]),
+        Decision::Target(Some(Target::Permanent(999))),
+    ]));
+    assert_eq!(
+        gre.choose_modes(&[vec![shock_any_target()], vec![modes()]], 1, 1),
+        vec![0]
+    );
+    let candidates = [Target::Player(Player::Opponent)];
+    assert_eq!(
+        gre.choose_target(&shock_any_target(), &candidates),
+        Some(Target::Player(Player::Opponent))
+    );
+}
+
+fn stats(card: Card) -> Creature {
+    match card.card_type {
+        CardType::Creature(cr) => cr,
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn decision_log_replays_a_game() {
+    let run = |gre: &mut Gre| {
+        resolve(gre, modes(), None);
+        resolve(gre, shock_any_target(), None);
+        let attacker = stats(creature("Attacker", 3, 3, Player::Us));
+        let blockers = [
+            stats(creature("A", 1, 4, Player::Opponent)),
+            stats(creature("B", 1, 1, Player::Opponent)),
+        ];
+        gre.damage_assignment_order(&attacker, &blockers)
+    };
+    let mut live = Gre::default().with_decisions(HeuristicDecisions);
+    assert_eq!(run(&mut live), vec![1, 0]);
+    assert_eq!(live.decision_log.len(), 3);
+
+    let mut replay =
+        Gre::default().with_decisions(ScriptedDecisions::new(live.decision_log.clone()));
+    run(&mut replay);
+    assert_eq!(replay.decision_log, live.decision_log);
+    assert_eq!(
+        replay.player(Player::Opponent).life,
+        live.player(Player::Opponent).life
+    );
+}
+
+#[test]
+fn search_enumerates_every_branch() {
+    let gre = Gre::default();
+    let options = [
+        vec![shock_any_target()],
+        vec![modes()],
+        vec![shock_any_target()],
+    ];
+    let mut path = Some(Vec::new());
+    let mut leaves = Vec::new();
+    while let Some(p) = path {
+        let mut search = SearchDecisions::new(p);
+        let chosen = search.choose_modes(&gre, &options, 1, 1);
+        let pay = search.pay_optional_cost(&gre, "Offspring", 2);
+        assert_eq!(search.arities(), &[3, 2]);
+        leaves.push((chosen, pay));
+        path = search.next_path();
+    }
+    assert_eq!(
+        leaves,
+        vec![
+            (vec![0], true),
+            (vec![0], false),
+            (vec![1], true),
+            (vec![1], false),
+            (vec![2], true),
+            (vec![2], false),
+        ]
+    );
+}
+
+#[test]
+fn scry_moves_the_chosen_cards_to_the_bottom() {
+    let card = |name: &str| Card::new(name, CardType::Instant, ManaCost::free());
+    // A lista vége a könyvtár teteje: C a legfelső
+    let mut gre = Gre::default()
+        .with_decisions(ScriptedDecisions::new([Decision::Scry(vec![true, false])]));
+    gre.set_library(Player::Us, vec![card("A"), card("B"), card("C")]);
+    resolve(
+        &mut gre,
+        Effect::Scry {
+            count: 2,
+            player: PlayerSelector::Controller,
+        },
+        None,
+    );
+    let names: Vec<&str> = gre
+        .player(Player::Us)
+        .library
+        .iter()
+        .map(|c| c.name.as_str())
+        .collect();
+    assert_eq!(names, vec!["B", "A", "C"]);
+    assert_eq!(gre.decision_log, vec![Decision::Scry(vec![true, false])]);
+}
+
+#[test]
+fn search_falls_back_to_the_heuristic_on_large_subsets() {
+    let gre = Gre::default();
+    let candidates: Vec<Target> = (1..=MAX_SUBSET_SEARCH as u64 + 20)
+        .map(Target::Permanent)
+        .collect();
+    let mut search = SearchDecisions::new(Vec::new());
+    assert_eq!(
+        search.choose_proliferate(&gre, &candidates),
+        HeuristicDecisions.choose_proliferate(&gre, &candidates)
+    );
+    assert!(search.arities().is_empty());
+}
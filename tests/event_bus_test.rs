@@ -0,0 +1,410 @@
+// tests/event_bus_test.rs
+mod common;
+
+use MTGA_me::app::card_attribute::{
+    Amount, CounterType, CreatureType, Duration, Effect, PlayerSelector, SpellFilter, TargetFilter,
+    Trigger, TriggeredEffectAttribute,
+};
+use MTGA_me::app::bot::Bot;
+use MTGA_me::app::card_library::{Card, CardType, Creature, ManaCost};
+use MTGA_me::app::combat_engine::{AttackTarget, CombatEngine, Combatant};
+use MTGA_me::app::game_state::{GameEvent, GamePhase, Player};
+use MTGA_me::app::gre::{Gre, StackEntry};
+use MTGA_me::app::state::{combat_damage_state::CombatDamageState, State};
+use common::{creature_with, enter};
+use std::collections::HashMap;
+
+/// `power`/`power` egér
+fn mouse(name: &str, power: i32, controller: Player) -> Card {
+    creature_with(name, power, power, vec![CreatureType::Mouse], Vec::new(), controller)
+}
+
+fn grow_self() -> Effect {
+    Effect::AddCounter {
+        counter: CounterType::PlusOnePlusOne,
+        amount: 1,
+        target: TargetFilter::SelfCard,
+    }
+}
+
+/// "Whenever <trigger>, put a +1/+1 counter on this creature."
+fn listener(name: &str, controller: Player, trigger: Trigger) -> Card {
+    mouse(name, 2, controller).with(
+        trigger.clone(),
+        TriggeredEffectAttribute {
+            trigger,
+            effect: grow_self(),
+        },
+    )
+}
+
+fn plus_counters(gre: &Gre, id: u64) -> u32 {
+    gre.battlefield_creatures[&id].counter_count(&CounterType::PlusOnePlusOne)
+}
+
+#[test]
+fn spell_cast_triggers_only_the_casters_permanents() {
+    let mut gre = Gre::default();
+    let on_cast = Trigger::OnSpellCast {
+        filter: SpellFilter::InstantOrSorcery,
+    };
+    let ours = enter(&mut gre, listener("Ours", Player::Us, on_cast.clone()));
+    let theirs = enter(&mut gre, listener("Theirs", Player::Opponent, on_cast));
+
+    let bolt = Card::new("Shock", CardType::Instant, ManaCost::free());
+    gre.cast_spell(bolt, Player::Us, None).unwrap();
+    // A trigger a varázslat fölé kerül
+    assert_eq!(gre.stack.len(), 2);
+    assert!(matches!(
+        gre.stack.peek().map(|e| &e.entry),
+        Some(StackEntry::TriggeredAbility { .. })
+    ));
+    gre.resolve_stack();
+    assert_eq!(plus_counters(&gre, ours), 1);
+    assert_eq!(plus_counters(&gre, theirs), 0);
+
+    let bear =
+        Card::new("Bear", CardType::Sorcery, ManaCost::free()).with_controller(Player::Opponent);
+    gre.cast_spell(bear, Player::Opponent, None).unwrap();
+    gre.resolve_stack();
+    assert_eq!(plus_counters(&gre, ours), 1);
+    assert_eq!(plus_counters(&gre, theirs), 1);
+}
+
+#[test]
+fn combat_and_damage_events_reach_their_subscribers() {
+    let mut gre = Gre::default();
+    let attacker = enter(
+        &mut gre,
+        listener(
+            "Raider",
+            Player::Us,
+            Trigger::OnAttack {
+                filter: TargetFilter::SelfCard,
+            },
+        ),
+    );
+    let captain = enter(
+        &mut gre,
+        listener(
+            "Captain",
+            Player::Us,
+            Trigger::OnAttackWithCreatureType {
+                creature_type: CreatureType::Mouse,
+            },
+        ),
+    );
+    let wall = enter(
+        &mut gre,
+        listener(
+            "Wall",
+            Player::Opponent,
+            Trigger::OnDealtDamage {
+                filter: TargetFilter::SelfCard,
+            },
+        ),
+    );
+
+    gre.trigger_event(GameEvent::AttackDeclared { attacker }, &mut [], Player::Us);
+    gre.resolve_stack();
+    assert_eq!(plus_counters(&gre, attacker), 1);
+    assert_eq!(plus_counters(&gre, captain), 1);
+    assert_eq!(plus_counters(&gre, wall), 0);
+
+    gre.push_to_stack(StackEntry::TriggeredAbility {
+        source: None,
+        effect: Effect::Damage {
+            amount: Amount::Fixed(1),
+            target: TargetFilter::ExactCardID(wall),
+        },
+        controller: Player::Us,
+    });
+    gre.resolve_stack();
+    assert_eq!(plus_counters(&gre, wall), 1);
+}
+
+#[test]
+fn declared_blocks_fire_on_block_triggers() {
+    let mut bot = Bot::new();
+    let wall = enter(
+        &mut bot.gre,
+        listener(
+            "Wall",
+            Player::Opponent,
+            Trigger::OnBlock {
+                filter: TargetFilter::SelfCard,
+            },
+        ),
+    );
+    let raider = enter(&mut bot.gre, mouse("Raider", 2, Player::Us));
+    bot.battlefield_opponent_creatures
+        .insert("Wall".into(), bot.gre.battlefield_creatures[&wall].clone());
+    bot.battlefield_creatures
+        .insert("Raider".into(), bot.gre.battlefield_creatures[&raider].clone());
+
+    bot.combat_blocks.insert(0, vec![0]);
+    bot.record_block_declaration();
+    assert_eq!(plus_counters(&bot.gre, wall), 1);
+    assert_eq!(plus_counters(&bot.gre, raider), 0);
+}
+
+#[test]
+fn only_attackers_that_got_through_report_hits() {
+    let bear = |power| Creature {
+        power,
+        toughness: 2,
+        summoning_sickness: false,
+        abilities: Vec::new(),
+        types: Vec::new(),
+        ephemeral_power: 0,
+        ephemeral_toughness: 0,
+    };
+    let mut blocks = HashMap::new();
+    blocks.insert(0, vec![0]);
+    let mut targets = HashMap::new();
+    targets.insert(2, AttackTarget::Planeswalker(42));
+
+    let outcome = CombatEngine::resolve_combat_with_targets(
+        &[0, 1, 2],
+        &[bear(2), bear(3), bear(1)],
+        &[bear(2)],
+        &blocks,
+        &targets,
+        &mut false,
+    );
+    assert_eq!(
+        outcome.hits,
+        vec![(1, AttackTarget::Player, 3), (2, AttackTarget::Planeswalker(42), 1)]
+    );
+}
+
+#[test]
+fn blocked_combat_records_damage_between_creatures() {
+    let bear = |power, toughness| Creature {
+        power,
+        toughness,
+        summoning_sickness: false,
+        abilities: Vec::new(),
+        types: Vec::new(),
+        ephemeral_power: 0,
+        ephemeral_toughness: 0,
+    };
+    let mut blocks = HashMap::new();
+    blocks.insert(0, vec![0, 1]);
+
+    let outcome = CombatEngine::resolve_combat_with_targets(
+        &[0],
+        &[bear(3, 3)],
+        &[bear(2, 2), bear(0, 4)],
+        &blocks,
+        &HashMap::new(),
+        &mut false,
+    );
+    assert!(outcome.hits.is_empty());
+    // A 0 erejű blokkoló nem sebez
+    assert_eq!(
+        outcome.creature_damage,
+        vec![
+            (Combatant::Attacker(0), Combatant::Blocker(0), 2),
+            (Combatant::Attacker(0), Combatant::Blocker(1), 1),
+            (Combatant::Blocker(0), Combatant::Attacker(0), 2),
+        ]
+    );
+}
+
+#[test]
+fn blocked_attackers_and_blockers_fire_damage_triggers() {
+    let mut bot = Bot::new();
+    let sturdy = |name: &str, controller, trigger: Trigger| {
+        creature_with(name, 1, 3, vec![CreatureType::Mouse], Vec::new(), controller).with(
+            trigger.clone(),
+            TriggeredEffectAttribute {
+                trigger,
+                effect: grow_self(),
+            },
+        )
+    };
+    let raider = enter(
+        &mut bot.gre,
+        sturdy(
+            "Raider",
+            Player::Us,
+            Trigger::OnCombatDamage {
+                filter: TargetFilter::SelfCard,
+            },
+        ),
+    );
+    let wall = enter(
+        &mut bot.gre,
+        sturdy(
+            "Wall",
+            Player::Opponent,
+            Trigger::OnDealtDamage {
+                filter: TargetFilter::SelfCard,
+            },
+        ),
+    );
+    bot.battlefield_creatures
+        .insert("Raider".into(), bot.gre.battlefield_creatures[&raider].clone());
+    bot.battlefield_opponent_creatures
+        .insert("Wall".into(), bot.gre.battlefield_creatures[&wall].clone());
+    bot.attacking.push("Raider".into());
+    bot.combat_attackers = vec![0];
+    bot.combat_blocks.insert(0, vec![0]);
+
+    CombatDamageState::new().update(&mut bot).unwrap();
+    // A blokkolt támadó sebzett (OnCombatDamage), a blokkolót sebzés érte (OnDealtDamage)
+    assert_eq!(plus_counters(&bot.gre, raider), 1);
+    assert_eq!(plus_counters(&bot.gre, wall), 1);
+}
+
+#[test]
+fn triggers_are_put_on_the_stack_in_apnap_order() {
+    let mut gre = Gre::default();
+    let at_combat = Trigger::AtPhase {
+        phase: GamePhase::BeginningCombat,
+        player: PlayerSelector::AnyPlayer,
+    };
+    enter(
+        &mut gre,
+        listener("Theirs", Player::Opponent, at_combat.clone()),
+    );
+    enter(&mut gre, listener("Ours", Player::Us, at_combat));
+
+    gre.trigger_event(
+        GameEvent::PhaseChange(GamePhase::BeginningCombat),
+        &mut [],
+        Player::Us,
+    );
+    assert_eq!(gre.stack.len(), 2);
+    // Az aktív játékos triggere került előbb a stackre, így a nem aktívé van felül
+    let top_controller = |gre: &Gre| match gre.stack.peek().map(|e| &e.entry) {
+        Some(StackEntry::TriggeredAbility { controller, .. }) => Some(*controller),
+        _ => None,
+    };
+    assert_eq!(top_controller(&gre), Some(Player::Opponent));
+    gre.resolve_top_of_stack();
+    assert_eq!(top_controller(&gre), Some(Player::Us));
+}
+
+#[test]
+fn apnap_order_holds_across_effect_kinds() {
+    let mut gre = Gre::default();
+    let at_combat = Trigger::AtPhase {
+        phase: GamePhase::BeginningCombat,
+        player: PlayerSelector::AnyPlayer,
+    };
+    enter(
+        &mut gre,
+        listener("Theirs", Player::Opponent, at_combat.clone()),
+    );
+    let pump = Effect::ModifyStats {
+        power_delta: 1,
+        toughness_delta: 1,
+        duration: Duration::EndOfTurn,
+        target: TargetFilter::SelfCard,
+    };
+    enter(
+        &mut gre,
+        mouse("Ours", 2, Player::Us).with(
+            at_combat.clone(),
+            TriggeredEffectAttribute {
+                trigger: at_combat,
+                effect: pump,
+            },
+        ),
+    );
+
+    gre.trigger_event(
+        GameEvent::PhaseChange(GamePhase::BeginningCombat),
+        &mut [],
+        Player::Us,
+    );
+    assert_eq!(gre.stack.len(), 2);
+    // A nem aktív játékos triggere a felső akkor is, ha az aktívé ModifyStats
+    match gre.stack.peek().map(|e| &e.entry) {
+        Some(StackEntry::TriggeredAbility {
+            controller, effect, ..
+        }) => {
+            assert_eq!(*controller, Player::Opponent);
+            assert!(matches!(effect, Effect::AddCounter { .. }));
+        }
+        other => panic!("unexpected top of stack: {:?}", other),
+    }
+}
+
+#[test]
+fn death_enter_and_cycle_are_zone_change_subscriptions() {
+    let mut gre = Gre::default();
+    let watcher = enter(
+        &mut gre,
+        listener(
+            "Watcher",
+            Player::Us,
+            Trigger::OnEnterBattlefield {
+                filter: TargetFilter::ControllerCreature,
+            },
+        ),
+    );
+    // "A creature you control" maga a figyelő is
+    gre.resolve_stack();
+    assert_eq!(plus_counters(&gre, watcher), 1);
+    // Az ellenfél lénye nem számít
+    enter(&mut gre, mouse("Their Mouse", 1, Player::Opponent));
+    gre.resolve_stack();
+    assert_eq!(plus_counters(&gre, watcher), 1);
+    let fodder = enter(&mut gre, mouse("Fodder", 1, Player::Us));
+    gre.resolve_stack();
+    assert_eq!(plus_counters(&gre, watcher), 2);
+
+    let mourner = enter(
+        &mut gre,
+        mouse("Mourner", 2, Player::Us).with(
+            Trigger::OnDeath {
+                filter: TargetFilter::ControllerCreature,
+            },
+            TriggeredEffectAttribute {
+                trigger: Trigger::OnDeath {
+                    filter: TargetFilter::ControllerCreature,
+                },
+                effect: Effect::DrawCards {
+                    count: 1,
+                    player: PlayerSelector::Controller,
+                },
+            },
+        ),
+    );
+    gre.resolve_stack();
+    gre.push_to_stack(StackEntry::TriggeredAbility {
+        source: None,
+        effect: Effect::Destroy {
+            target: TargetFilter::ExactCardID(fodder),
+        },
+        controller: Player::Us,
+    });
+    gre.resolve_stack();
+    assert!(gre.battlefield_creatures.contains_key(&mourner));
+    assert_eq!(gre.player(Player::Us).hand_size, 1);
+
+    let cycler = enter(
+        &mut gre,
+        listener(
+            "Cycler",
+            Player::Us,
+            Trigger::OnCycle {
+                filter: TargetFilter::AnyTarget,
+            },
+        ),
+    );
+    gre.resolve_stack();
+    let state = gre.player_mut(Player::Us);
+    state
+        .hand
+        .push(Card::new("Dust", CardType::Instant, ManaCost::free()));
+    state.hand_size += 1;
+    assert!(gre.cycle(Player::Us, 0));
+    gre.resolve_stack();
+    assert_eq!(plus_counters(&gre, cycler), 1);
+    assert_eq!(gre.player(Player::Us).graveyard.len(), 2);
+    assert_eq!(gre.player(Player::Us).hand_size, 2);
+}
@@ -237,9 +237,9 @@ fn test_push_to_stack_sets_correct_priority() {
         controller: Player::Us,
     };
     gre.push_to_stack(entry);
-    // Activated abilities should get priority 3
+    // Activated abilities share the priority of everything else on the stack
     let top = gre.stack.peek().unwrap();
-    assert_eq!(top.priority, 3);
+    assert_eq!(top.priority, 1);
 }
 
 #[test]
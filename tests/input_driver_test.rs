@@ -0,0 +1,68 @@
+// tests/input_driver_test.rs
+use enigo::Key;
+use MTGA_me::app::bot::Bot;
+use MTGA_me::app::error::AppError;
+use MTGA_me::app::input_driver::{
+    set_input_driver, AssertionDriver, DryRunDriver, InputAction, InputDriver,
+};
+use MTGA_me::app::ui::{click_at, press_key};
+use std::sync::Arc;
+
+#[test]
+fn dry_run_logs_clicks_with_cursor_position() {
+    let driver = DryRunDriver::new();
+    driver.move_mouse(100, 200).unwrap();
+    driver.click_left().unwrap();
+    driver.click_right().unwrap();
+    driver.send_key(Key::Other(0x20)).unwrap();
+
+    let actions: Vec<InputAction> = driver.actions().into_iter().map(|(_, a)| a).collect();
+    assert_eq!(
+        actions,
+        vec![
+            InputAction::MoveMouse { x: 100, y: 200 },
+            InputAction::ClickLeft { x: 100, y: 200 },
+            InputAction::ClickRight { x: 100, y: 200 },
+            InputAction::Key(Key::Other(0x20)),
+        ]
+    );
+    let times: Vec<_> = driver.actions().into_iter().map(|(t, _)| t).collect();
+    assert!(times.windows(2).all(|w| w[0] <= w[1]));
+}
+
+#[test]
+fn assertion_driver_rejects_unexpected_action() {
+    let driver = AssertionDriver::expecting([InputAction::MoveMouse { x: 1, y: 1 }]);
+    assert!(driver.move_mouse(1, 1).is_ok());
+    assert!(driver.click_left().is_err());
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| driver.assert_done()));
+    assert!(result.is_err());
+}
+
+#[test]
+fn ui_helpers_go_through_selected_driver() {
+    let driver = Arc::new(AssertionDriver::expecting([
+        InputAction::MoveMouse { x: 10, y: 20 },
+        InputAction::ClickLeft { x: 10, y: 20 },
+        InputAction::Key(Key::Other(0x5A)),
+    ]));
+    set_input_driver(driver.clone());
+
+    click_at(10, 20).unwrap();
+    press_key(0x5A).unwrap();
+    driver.assert_done();
+
+    // Egy nem várt akció hibát ad, ami AppError-rá alakítható
+    let err: AppError = press_key(0x20).unwrap_err().into();
+    assert!(matches!(err, AppError::Input(_)));
+}
+
+#[test]
+fn play_card_rejects_index_outside_the_hand() {
+    let mut bot = Bot::new();
+    bot.card_count = 2;
+    assert!(matches!(
+        Bot::play_card(&mut bot, 5),
+        Err(AppError::HandIndexOutOfRange { index: 5, hand_size: 2 })
+    ));
+}
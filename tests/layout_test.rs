@@ -0,0 +1,92 @@
+// tests/layout_test.rs
+use std::io::Cursor;
+use MTGA_me::app::calibration::calibrate_layout_with;
+use MTGA_me::app::cards_positions::{get_card_positions, get_mulligan_card_positions};
+use MTGA_me::app::creature_positions::get_opponent_creature_positions;
+use MTGA_me::app::layout::{LayoutError, LayoutProfile};
+use MTGA_me::app::ui::Cords;
+
+#[test]
+fn builtin_profiles_parse_and_match_aspect() {
+    let names: Vec<String> = LayoutProfile::builtin().into_iter().map(|p| p.name).collect();
+    assert_eq!(names, vec!["16:9", "16:10", "21:9"]);
+    assert_eq!(LayoutProfile::for_screen(2560, 1440).name, "16:9");
+    assert_eq!(LayoutProfile::for_screen(1920, 1200).name, "16:10");
+    assert_eq!(LayoutProfile::for_screen(3440, 1440).name, "21:9");
+}
+
+#[test]
+fn default_profile_reproduces_legacy_coordinates() {
+    // A layout fájlok előtti, kódba égetett értékek 2560×1440-en
+    let w = 2560u32;
+    let hand = get_card_positions(3, w);
+    assert_eq!(hand[0].hover_x, (277.547 / 677.292 * w as f64).ceil() as u32);
+    assert_eq!(hand[2].ocr_x2, (432.428 / 677.292 * w as f64).ceil() as u32);
+    assert!(get_card_positions(9, w).is_empty());
+
+    let opp = get_opponent_creature_positions(2, w, 1440);
+    assert_eq!(opp[1].click_x1, (345.801 / 677.292 * w as f64).ceil() as u32);
+    assert_eq!(opp[1].click_y2, (151.761 / 381.287 * 1440.0f64).ceil() as u32);
+
+    let mull = get_mulligan_card_positions(7, w, 1440);
+    assert_eq!(mull[3].click_x, 1280);
+
+    let cords = Cords::new(2560, 1440);
+    assert_eq!(cords.play_button, ((2560.0 * 0.895f64).ceil() as i32, (1440.0 * 0.938f64).ceil() as i32));
+}
+
+#[test]
+fn parse_errors_are_reported() {
+    assert!(matches!(LayoutProfile::parse("name = x\n"), Err(LayoutError::Missing(_))));
+    assert!(matches!(
+        LayoutProfile::parse("canvas = 1 1\nbutton.home = a b\n"),
+        Err(LayoutError::Parse { line: 2, .. })
+    ));
+    let mut text = include_str!("../layouts/16x9.layout").to_string();
+    text = text.replace("hand.2 = 308.755 371.089", "hand.2 = 308.755");
+    assert!(matches!(LayoutProfile::parse(&text), Err(LayoutError::Arity { .. })));
+}
+
+#[test]
+fn calibration_fits_scale_and_offset() {
+    let base = LayoutProfile::for_screen(16, 9);
+    // Ablakos mód: a 16:9 tartalom 1280×720-ban, (100, 50) eltolással
+    let (sx, sy) = (1280.0 / 677.292, 720.0 / 381.287);
+    let expect = |key: &str| {
+        let v = base.get(key).unwrap();
+        ((v[0] * sx + 100.0).round() as i32, (v[1] * sy + 50.0).round() as i32)
+    };
+    let mut positions = vec![
+        expect("button.home"),
+        expect("button.play"),
+        expect("button.mulligan"),
+        expect("button.keep"),
+    ]
+    .into_iter();
+    let mut out = Vec::new();
+    let profile = calibrate_layout_with(
+        &base,
+        "windowed",
+        (1920, 1080),
+        &mut Cursor::new("\n\n\n\n"),
+        &mut out,
+        || Ok(positions.next().unwrap()),
+    )
+    .unwrap();
+
+    assert_eq!(profile.canvas, (1920.0, 1080.0));
+    let hand = profile.hand_centers(1).unwrap()[0];
+    assert!((hand - (339.565 * sx + 100.0)).abs() < 1.0);
+    // A méretek csak skálázódnak, nem tolódnak
+    let ocr_x = profile.pair("hand.ocr_x");
+    assert!((ocr_x.0 - (-50.0 * sx)).abs() < 0.5);
+
+    // Mentés és visszaolvasás
+    let reparsed = LayoutProfile::parse(&profile.to_text()).unwrap();
+    assert_eq!(reparsed.name, "windowed");
+    assert!((reparsed.value("mulligan.click_y") - profile.value("mulligan.click_y")).abs() < 1e-3);
+
+    // Egyetlen pont nem elég
+    let err = calibrate_layout_with(&base, "x", (1920, 1080), &mut Cursor::new("\ns\ns\ns\n"), &mut Vec::new(), || Ok((1, 1)));
+    assert!(matches!(err, Err(LayoutError::Calibration(_))));
+}
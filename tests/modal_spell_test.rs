@@ -0,0 +1,137 @@
+// tests/modal_spell_test.rs
+mod common;
+
+use MTGA_me::app::card_attribute::{
+    Amount, CounterType, Effect, ModalSpell, PlayerSelector, SpellMode, TargetFilter,
+};
+use MTGA_me::app::card_library::{build_card_library, Card, CardType, ManaCost};
+use MTGA_me::app::game_state::Player;
+use MTGA_me::app::gre::{CostError, Decision, Gre, ScriptedDecisions, Target};
+use common::{creature, enter, mana};
+
+fn command(modal: fn(Vec<SpellMode>) -> ModalSpell) -> Card {
+    Card::new("Test Command", CardType::Instant, ManaCost::new(0, 2, 0, 0, 0, 0)).with_modes(modal(
+        vec![
+            SpellMode::new(
+                "You gain 2 life",
+                vec![Effect::GainLife {
+                    amount: 2,
+                    player: PlayerSelector::Controller,
+                }],
+            ),
+            SpellMode::new(
+                "3 damage to any target",
+                vec![Effect::Damage {
+                    amount: Amount::Fixed(3),
+                    target: TargetFilter::AnyTarget,
+                }],
+            ),
+            SpellMode::new(
+                "Destroy target artifact",
+                vec![Effect::Destroy {
+                    target: TargetFilter::Artifact,
+                }],
+            ),
+        ],
+    ))
+}
+
+#[test]
+fn archdruids_charm_picks_a_mode_and_its_targets_on_cast() {
+    let mut gre = Gre::default().with_decisions(ScriptedDecisions::new([Decision::Modes(vec![1])]));
+    let ours = enter(&mut gre, creature("Bear", 2, 2, Player::Us));
+    let theirs = enter(&mut gre, creature("Ogre", 3, 3, Player::Opponent));
+
+    let charm = build_card_library()["Archdruid's Charm"].clone();
+    gre.add_mana(Player::Us, mana(0, 3, 3));
+    gre.cast_spell(charm, Player::Us, None).unwrap();
+    // Az artifact/enchantment mód nem legális, így csak két mód közül választunk
+    assert_eq!(
+        gre.decision_log,
+        vec![
+            Decision::Modes(vec![1]),
+            Decision::Target(Some(Target::Permanent(ours))),
+            Decision::Target(Some(Target::Permanent(theirs))),
+        ]
+    );
+
+    gre.resolve_stack();
+    assert_eq!(
+        gre.battlefield_creatures[&ours].counter_count(&CounterType::PlusOnePlusOne),
+        1
+    );
+    assert!(!gre.battlefield_creatures.contains_key(&theirs));
+}
+
+#[test]
+fn one_or_more_takes_every_legal_mode() {
+    let mut gre = Gre::default();
+    gre.add_mana(Player::Us, mana(0, 3, 3));
+    gre.cast_spell(command(ModalSpell::one_or_more), Player::Us, None).unwrap();
+    gre.resolve_stack();
+    assert_eq!(gre.player(Player::Us).life, 22);
+    assert_eq!(gre.player(Player::Opponent).life, 17);
+    assert_eq!(gre.decision_log[0], Decision::Modes(vec![0, 1]));
+}
+
+#[test]
+fn choose_two_needs_two_legal_modes() {
+    let mut gre = Gre::default();
+    gre.add_mana(Player::Us, mana(0, 3, 3));
+    gre.cast_spell(command(|m| ModalSpell::choose(2, m)), Player::Us, None).unwrap();
+
+    let lone = Card::new("Lone Command", CardType::Instant, ManaCost::free()).with_modes(
+        ModalSpell::choose(
+            2,
+            vec![
+                SpellMode::new(
+                    "Destroy target artifact",
+                    vec![Effect::Destroy {
+                        target: TargetFilter::Artifact,
+                    }],
+                ),
+                SpellMode::new(
+                    "Draw a card",
+                    vec![Effect::DrawCards {
+                        count: 1,
+                        player: PlayerSelector::Controller,
+                    }],
+                ),
+            ],
+        ),
+    );
+    assert!(matches!(gre.cast_spell(lone, Player::Us, None), Err(CostError::NoLegalModes)));
+}
+
+#[test]
+fn spell_fizzles_when_every_target_is_gone() {
+    let mut gre = Gre::default().with_decisions(ScriptedDecisions::new([Decision::Modes(vec![1])]));
+    let ours = enter(&mut gre, creature("Bear", 2, 2, Player::Us));
+    let theirs = enter(&mut gre, creature("Ogre", 3, 3, Player::Opponent));
+    let charm = build_card_library()["Archdruid's Charm"].clone();
+    gre.add_mana(Player::Us, mana(0, 3, 3));
+    gre.cast_spell(charm, Player::Us, None).unwrap();
+
+    gre.battlefield_creatures.remove(&ours);
+    gre.battlefield_creatures.remove(&theirs);
+    gre.resolve_stack();
+    assert_eq!(gre.player(Player::Opponent).life, 20);
+}
+
+#[test]
+fn archdruids_charm_destroys_an_enchantment() {
+    let mut gre = Gre::default().with_decisions(ScriptedDecisions::new([Decision::Modes(vec![1])]));
+    let aura = enter(
+        &mut gre,
+        Card::new("Pacifism", CardType::Enchantment, ManaCost::free()).with_controller(Player::Opponent),
+    );
+    let charm = build_card_library()["Archdruid's Charm"].clone();
+    gre.add_mana(Player::Us, mana(0, 3, 3));
+    gre.cast_spell(charm, Player::Us, None).unwrap();
+    // Lény nélkül a keresés és a rombolás a két legális mód
+    assert_eq!(gre.decision_log[1], Decision::Target(Some(Target::Permanent(aura))));
+
+    gre.resolve_stack();
+    assert!(!gre.battlefield_creatures.contains_key(&aura));
+    assert_eq!(gre.player(Player::Opponent).graveyard[0].name, "Pacifism");
+}
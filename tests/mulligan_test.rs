@@ -0,0 +1,108 @@
+// tests/mulligan_test.rs
+use MTGA_me::app::card_library::{build_card_library, Card};
+use MTGA_me::app::mulligan::{MulliganDecision, MulliganEvaluator};
+
+fn hand(names: &[&str]) -> Vec<Card> {
+    let lib = build_card_library();
+    names
+        .iter()
+        .map(|n| lib.get(*n).unwrap_or_else(|| panic!("Hiányzó kártya: {}", n)).clone())
+        .collect()
+}
+
+#[test]
+fn three_lands_with_cheap_spells_is_kept() {
+    let eval = MulliganEvaluator::new();
+    let h = hand(&[
+        "Mountain",
+        "Mountain",
+        "Mountain",
+        "Burst Lightning",
+        "Burst Lightning",
+        "Lightning Strike",
+        "Lightning Strike",
+    ]);
+    let e = eval.evaluate(&h);
+    assert_eq!(e.land_count, 3);
+    assert_eq!(e.spell_count, 4);
+    assert_eq!(e.castable_one_drops, 4);
+    assert_eq!(e.off_color_spells, 0);
+    assert_eq!(eval.decide(&h, 7, 0), MulliganDecision::Keep);
+}
+
+#[test]
+fn one_lander_is_mulliganed() {
+    let eval = MulliganEvaluator::new();
+    let h = hand(&[
+        "Mountain",
+        "Burst Lightning",
+        "Burst Lightning",
+        "Lightning Strike",
+        "Lightning Strike",
+        "Lightning Strike",
+        "Burst Lightning",
+    ]);
+    assert_eq!(eval.decide(&h, 7, 0), MulliganDecision::Mulligan);
+}
+
+#[test]
+fn all_lands_is_mulliganed() {
+    let eval = MulliganEvaluator::new();
+    let h = hand(&["Mountain"; 7]);
+    assert_eq!(eval.decide(&h, 7, 0), MulliganDecision::Mulligan);
+}
+
+#[test]
+fn small_final_hand_is_always_kept() {
+    let eval = MulliganEvaluator::new();
+    let h = hand(&["Mountain"; 7]);
+    assert_eq!(eval.decide(&h, 7, 2), MulliganDecision::Keep);
+}
+
+#[test]
+fn unread_slots_do_not_shrink_the_hand() {
+    let eval = MulliganEvaluator::new();
+    // 7 lapból csak 5-öt ismertünk fel, egyik sem land
+    let h = hand(&[
+        "Lightning Strike",
+        "Lightning Strike",
+        "Lightning Strike",
+        "Lightning Strike",
+        "Lightning Strike",
+    ]);
+    // Egy mulligan után a végső kéz 6 lapos, nem 4: nincs kényszerű Keep
+    assert_eq!(eval.decide(&h, 7, 1), MulliganDecision::Mulligan);
+    // A land-tartomány a 7 lapos kézhez igazodik
+    let mut two_lands = hand(&["Mountain", "Mountain"]);
+    two_lands.extend(h.iter().take(3).cloned());
+    assert!(eval.evaluate_in_hand_of(&two_lands, 7).score > eval.evaluate_in_hand_of(&h, 7).score);
+}
+
+#[test]
+fn off_color_spells_are_counted() {
+    let eval = MulliganEvaluator::new();
+    let h = hand(&["Forest", "Forest", "Burst Lightning"]);
+    let e = eval.evaluate(&h);
+    assert_eq!(e.off_color_spells, 1);
+    assert_eq!(e.castable_one_drops, 0);
+}
+
+#[test]
+fn choose_bottom_removes_excess_lands() {
+    let eval = MulliganEvaluator::new();
+    let h = hand(&[
+        "Mountain",
+        "Mountain",
+        "Mountain",
+        "Mountain",
+        "Mountain",
+        "Burst Lightning",
+        "Lightning Strike",
+    ]);
+    let bottom = eval.choose_bottom(&h, 2);
+    assert_eq!(bottom.len(), 2);
+    assert!(bottom.windows(2).all(|w| w[0] < w[1]));
+    for idx in bottom {
+        assert_eq!(h[idx].name, "Mountain");
+    }
+}
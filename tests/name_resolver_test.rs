@@ -0,0 +1,78 @@
+// tests/name_resolver_test.rs
+use MTGA_me::app::bot::Bot;
+use MTGA_me::app::card_library::{build_card_library, Card, CardType, ManaCost};
+use MTGA_me::app::name_resolver::{
+    edit_distance, normalize_name, resolve_card, NameMatchConfig, NameResolver,
+};
+
+#[test]
+fn normalizes_and_measures_distance() {
+    assert_eq!(normalize_name("  Surrak,  Elusive|Hunter "), "surrak elusive hunter");
+    assert_eq!(edit_distance("swiftspear", "swiftspcar"), 1);
+    assert_eq!(edit_distance("", "abc"), 3);
+}
+
+#[test]
+fn misread_name_resolves_against_library() {
+    let library = build_card_library();
+    let card = resolve_card(&library, "Monastery Swiftspcar").expect("should resolve");
+    assert_eq!(card.name, "Monastery Swiftspear");
+}
+
+#[test]
+fn comma_names_match_by_tokens_and_short_name() {
+    let resolver = NameResolver::new(["Surrak, Elusive Hunter", "Tyvar, the Pummeler", "Shock"]);
+    assert_eq!(resolver.resolve("Surrak Elusive Huntcr").unwrap().name, "Surrak, Elusive Hunter");
+    assert_eq!(resolver.resolve("Tyvar").unwrap().name, "Tyvar, the Pummeler");
+    let m = resolver.resolve("Shock").unwrap();
+    assert_eq!(m.confidence, 1.0);
+}
+
+#[test]
+fn thresholds_reject_garbage_and_ambiguity() {
+    let resolver = NameResolver::new(["Shock", "Shore"]);
+    assert!(resolver.resolve("xq7#").is_none());
+    // "Shoce" is equally far from both
+    assert!(resolver.resolve("Shoce").is_none());
+
+    let strict = NameResolver::new(["Monastery Swiftspear"]).with_config(NameMatchConfig {
+        min_confidence: 0.99,
+        ..NameMatchConfig::default()
+    });
+    assert!(strict.resolve("Monastery Swiftspcar").is_none());
+    assert!(strict.best_match("Monastery Swiftspcar").unwrap().confidence > 0.9);
+}
+
+#[test]
+fn text_matches_is_stricter_for_short_names() {
+    let resolver = NameResolver::new(["Shock"]);
+    assert!(resolver.text_matches("Shock", "Shock"));
+    assert!(!resolver.text_matches("Shock", "Shore"));
+    assert!(resolver.text_matches("Monastery Swiftspear", "Monastery Swiftspcar"));
+}
+
+#[test]
+fn bot_matches_hand_text_with_its_resolver() {
+    let bot = Bot::new();
+    assert!(bot.text_contains("Heartfire Hero", "Heartfire Hero"));
+    assert!(bot.text_contains("Heartfire Hero", "Heartflre Hero"));
+    assert!(!bot.text_contains("Heartfire Hero", "Monstrous Rage"));
+}
+
+#[test]
+fn bot_resolves_hand_text_to_the_best_match() {
+    let mut bot = Bot::new();
+    // "Shocker" tartalmazza a "Shock" nevet is: a legjobb egyezés kell, nem az első elég jó
+    bot.card_library = ["Shock", "Shocker"]
+        .into_iter()
+        .map(|name| (name.to_string(), Card::new(name, CardType::Instant, ManaCost::free())))
+        .collect();
+    bot.name_resolver = NameResolver::from_library(&bot.card_library);
+    assert_eq!(bot.resolve_text("Shocker").map(|c| c.name.as_str()), Some("Shocker"));
+    assert_eq!(bot.resolve_text("Shockor").map(|c| c.name.as_str()), Some("Shocker"));
+    assert_eq!(bot.resolve_text("Shock").map(|c| c.name.as_str()), Some("Shock"));
+
+    bot.cards_texts = vec!["Shockor".into()];
+    bot.land_number = 1;
+    assert!(bot.can_cast_instant());
+}
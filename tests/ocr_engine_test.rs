@@ -0,0 +1,90 @@
+// tests/ocr_engine_test.rs
+use image::{DynamicImage, GrayImage, Luma};
+use MTGA_me::app::ocr_engine::{OcrEngine, OcrError, TemplateEngine, TemplateMode, TesseractEngine};
+
+/// Fekete "tinta" fehér háttéren; `rows` sorai '#' = tinta.
+fn glyph(rows: &[&str]) -> GrayImage {
+    let (w, h) = (rows[0].len() as u32, rows.len() as u32);
+    GrayImage::from_fn(w, h, |x, y| {
+        if rows[y as usize].as_bytes()[x as usize] == b'#' {
+            Luma([0])
+        } else {
+            Luma([255])
+        }
+    })
+}
+
+const ONE: [&str; 5] = ["..#..", ".##..", "..#..", "..#..", ".###."];
+const ZERO: [&str; 5] = [".###.", "#...#", "#...#", "#...#", ".###."];
+
+/// Glyph-ek egymás mellé, 2 px üres hellyel és margóval.
+fn line(glyphs: &[GrayImage]) -> DynamicImage {
+    let w: u32 = glyphs.iter().map(|g| g.width() + 2).sum::<u32>() + 2;
+    let mut out = GrayImage::from_pixel(w, 9, Luma([255]));
+    let mut x = 2;
+    for g in glyphs {
+        image::imageops::overlay(&mut out, g, x as i64, 2);
+        x += g.width() + 2;
+    }
+    DynamicImage::ImageLuma8(out)
+}
+
+#[test]
+fn template_glyphs_read_life_total() {
+    let engine = TemplateEngine::new(TemplateMode::Glyphs)
+        .with_template("1", &DynamicImage::ImageLuma8(glyph(&ONE)))
+        .with_template("0", &DynamicImage::ImageLuma8(glyph(&ZERO)));
+    let img = line(&[glyph(&ONE), glyph(&ZERO), glyph(&ZERO)]);
+    assert_eq!(engine.recognize(&img).unwrap(), "100");
+
+    // Felnagyítva is felismeri
+    let big = img.resize(img.width() * 3, img.height() * 3, image::imageops::FilterType::Nearest);
+    assert_eq!(engine.recognize(&big).unwrap(), "100");
+}
+
+#[test]
+fn template_labels_pick_best_or_nothing() {
+    let next = line(&[glyph(&ONE), glyph(&ZERO)]);
+    let pass = line(&[glyph(&ZERO), glyph(&ZERO), glyph(&ONE)]);
+    let engine = TemplateEngine::new(TemplateMode::Labels)
+        .with_template("Next", &next)
+        .with_template("Pass", &pass)
+        .with_min_score(0.95);
+    assert_eq!(engine.recognize(&pass).unwrap(), "Pass");
+    let blank = DynamicImage::ImageLuma8(GrayImage::from_pixel(20, 9, Luma([255])));
+    assert_eq!(engine.recognize(&blank).unwrap(), "");
+}
+
+#[test]
+fn missing_tesseract_binary_is_an_error() {
+    let engine = TesseractEngine::new().with_binary("/nonexistent/tesseract");
+    let img = DynamicImage::ImageLuma8(glyph(&ONE));
+    assert!(matches!(engine.recognize(&img), Err(OcrError::Spawn { .. })));
+}
+
+#[cfg(unix)]
+#[test]
+fn tesseract_reads_png_from_stdin() {
+    use std::os::unix::fs::PermissionsExt;
+    use MTGA_me::app::ocr_engine::TesseractInput;
+
+    let dir = std::env::temp_dir().join(format!("mtga_fake_tess_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let script = dir.join("tesseract");
+    // Az első argumentum "stdin" kell legyen, és PNG-nek kell érkeznie
+    std::fs::write(
+        &script,
+        "#!/bin/sh\n[ \"$1\" = stdin ] || exit 3\nhead -c 4 | grep -q PNG || exit 4\ncat >/dev/null\necho ' 20 '\n",
+    )
+    .unwrap();
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+    let img = DynamicImage::ImageLuma8(glyph(&ONE));
+    let engine = TesseractEngine::new().with_binary(&script);
+    assert_eq!(engine.recognize(&img).unwrap(), "20");
+
+    let file_engine = TesseractEngine::new()
+        .with_binary(&script)
+        .with_input(TesseractInput::TempFile(dir.clone()));
+    assert!(matches!(file_engine.recognize(&img), Err(OcrError::Failed(_))));
+}
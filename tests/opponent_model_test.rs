@@ -0,0 +1,74 @@
+// tests/opponent_model_test.rs
+use MTGA_me::app::bot::Bot;
+use MTGA_me::app::card_library::{build_card_library, Card};
+use MTGA_me::app::opponent_model::{OpponentModel, SeenZone};
+use std::collections::HashMap;
+
+fn battlefield(names: &[&str]) -> HashMap<String, Card> {
+    let lib = build_card_library();
+    names
+        .iter()
+        .enumerate()
+        .map(|(i, n)| (format!("{}#{}", n, i), lib.get(*n).unwrap().clone()))
+        .collect()
+}
+
+#[test]
+fn repeated_battlefield_reads_are_not_double_counted() {
+    let mut model = OpponentModel::new();
+    let bf = battlefield(&["Monastery Swiftspear"]);
+    model.observe_battlefield(&bf);
+    model.observe_battlefield(&bf);
+    assert_eq!(model.seen_count("Monastery Swiftspear"), 1);
+    assert_eq!(model.log.len(), 1);
+    assert_eq!(model.log[0].1, SeenZone::Battlefield);
+}
+
+#[test]
+fn red_cards_infer_red_archetype() {
+    let mut model = OpponentModel::new();
+    model.observe_battlefield(&battlefield(&["Monastery Swiftspear", "Heartfire Hero"]));
+    model.observe_spell("Lightning Strike");
+    let arch = model.likely_archetype().expect("archetype");
+    assert_eq!(arch.name, "Mono-Red Aggro");
+
+    let remaining = model.likely_remaining_cards();
+    assert_eq!(remaining.get("Lightning Strike"), Some(&3));
+    assert_eq!(remaining.get("Monastery Swiftspear"), Some(&3));
+}
+
+#[test]
+fn instant_interaction_respects_untapped_mana() {
+    let mut model = OpponentModel::new();
+    model.observe_battlefield(&battlefield(&["Heartfire Hero"]));
+    let lib = build_card_library();
+
+    let one = model.likely_instant_interaction(1, &lib);
+    assert!(one.iter().all(|c| c.mana_cost.total() <= 1));
+    assert!(one.iter().any(|c| c.name == "Lightning Strike"));
+    // A kicker opcionális: a Burst Lightning egy manából is kijátszható
+    assert!(one.iter().any(|c| c.name == "Burst Lightning"));
+
+    assert!(model.likely_instant_interaction(0, &lib).is_empty());
+}
+
+#[test]
+fn no_observations_means_no_archetype() {
+    let model = OpponentModel::new();
+    assert!(model.likely_archetype().is_none());
+    assert!(model.likely_remaining_cards().is_empty());
+}
+
+#[test]
+fn bot_records_the_opponents_spell_read_from_the_stack() {
+    let mut bot = Bot::new();
+    assert_eq!(
+        bot.observe_opponent_spell("Lightnlng Strlke").as_deref(),
+        Some("Lightning Strike")
+    );
+    assert_eq!(bot.opponent_model.seen_count("Lightning Strike"), 1);
+    assert_eq!(bot.opponent_model.log.last().map(|e| e.1), Some(SeenZone::Stack));
+    // Olvashatatlan szöveg nem kerül a modellbe
+    assert_eq!(bot.observe_opponent_spell("~~"), None);
+    assert_eq!(bot.opponent_model.log.len(), 1);
+}
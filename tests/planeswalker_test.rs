@@ -0,0 +1,154 @@
+// tests/planeswalker_test.rs
+use MTGA_me::app::card_attribute::{Amount, Effect, TargetFilter};
+use MTGA_me::app::bot::Bot;
+use MTGA_me::app::card_library::{build_card_library, CardType, Creature};
+use MTGA_me::app::combat_engine::{AttackTarget, CombatEngine};
+use MTGA_me::app::game_state::{GamePhase, Player};
+use MTGA_me::app::gre::Gre;
+use std::collections::HashMap;
+
+fn gre_with_vivien() -> (Gre, u64) {
+    let mut gre = Gre::new(Player::Us);
+    let mut vivien = build_card_library()["Vivien Reid"].clone();
+    gre.enter_battlefield(&mut vivien);
+    gre.set_phase(GamePhase::PreCombatMain);
+    (gre, vivien.card_id)
+}
+
+#[test]
+fn enters_with_printed_loyalty() {
+    let (gre, id) = gre_with_vivien();
+    assert_eq!(gre.battlefield_creatures[&id].loyalty(), Some(5));
+    assert_eq!(gre.planeswalkers().len(), 1);
+}
+
+#[test]
+fn loyalty_ability_paid_on_activation_once_per_turn() {
+    let (mut gre, id) = gre_with_vivien();
+    let source = gre.battlefield_creatures[&id].clone();
+    let plus = source.activated_abilities[0].clone();
+    let minus = source.activated_abilities[1].clone();
+
+    assert!(gre.activate_ability(source.clone(), plus.clone(), Player::Us));
+    assert_eq!(gre.battlefield_creatures[&id].loyalty(), Some(6));
+
+    // Ugyanabban a körben egyik képesség sem aktiválható újra
+    let source = gre.battlefield_creatures[&id].clone();
+    assert!(!gre.can_activate(&source, &source.activated_abilities[1], Player::Us));
+    assert!(!gre.activate_ability(source, minus, Player::Us));
+    gre.resolve_stack();
+
+    gre.on_turn_end();
+    let source = gre.battlefield_creatures[&id].clone();
+    assert!(gre.can_activate(&source, &source.activated_abilities[0], Player::Us));
+}
+
+#[test]
+fn loyalty_abilities_need_sorcery_speed() {
+    let (mut gre, id) = gre_with_vivien();
+    let source = gre.battlefield_creatures[&id].clone();
+    let plus = source.activated_abilities[0].clone();
+
+    gre.set_phase(GamePhase::Combat);
+    assert!(!gre.can_activate(&source, &plus, Player::Us));
+
+    gre.set_phase(GamePhase::PostCombatMain);
+    gre.active_player = Player::Opponent;
+    assert!(!gre.can_activate(&source, &plus, Player::Us));
+
+    gre.active_player = Player::Us;
+    assert!(gre.can_activate(&source, &plus, Player::Us));
+}
+
+#[test]
+fn minus_ability_needs_enough_loyalty() {
+    let (mut gre, id) = gre_with_vivien();
+    gre.damage_planeswalker(id, 3);
+    let source = gre.battlefield_creatures[&id].clone();
+    assert_eq!(source.loyalty(), Some(2));
+    assert!(!gre.can_activate(&source, &source.activated_abilities[1], Player::Us));
+}
+
+#[test]
+fn damage_effect_removes_loyalty_and_kills() {
+    let (mut gre, id) = gre_with_vivien();
+    gre.handle_effect(Effect::Damage {
+        amount: Amount::Fixed(2),
+        target: TargetFilter::ExactCardID(id),
+    });
+    assert_eq!(gre.battlefield_creatures[&id].loyalty(), Some(3));
+
+    gre.damage_planeswalker(id, 3);
+    assert!(!gre.battlefield_creatures.contains_key(&id));
+    assert_eq!(gre.player(Player::Us).graveyard[0].name, "Vivien Reid");
+}
+
+#[test]
+fn combat_damage_goes_to_attacked_planeswalker() {
+    let bear = |power| Creature {
+        power,
+        toughness: 2,
+        summoning_sickness: false,
+        abilities: Vec::new(),
+        types: Vec::new(),
+        ephemeral_power: 0,
+        ephemeral_toughness: 0,
+    };
+    let attackers = vec![bear(2), bear(3)];
+    let mut targets = HashMap::new();
+    targets.insert(1, AttackTarget::Planeswalker(42));
+    let mut prevent = false;
+
+    let outcome = CombatEngine::resolve_combat_with_targets(
+        &[0, 1],
+        &attackers,
+        &[],
+        &HashMap::new(),
+        &targets,
+        &mut prevent,
+    );
+    assert_eq!(outcome.player_damage, 2);
+    assert_eq!(outcome.planeswalker_damage.get(&42), Some(&3));
+    assert!(matches!(
+        build_card_library()["Ugin, Eye of the Storms"].card_type,
+        CardType::Planeswalker(ref pw) if pw.starting_loyalty == 7
+    ));
+}
+
+#[test]
+fn attack_declaration_records_attackers_and_targets() {
+    let mut bot = Bot::new();
+    let library = build_card_library();
+    // A nem-lény permanensek nem tolják el a támadók indexeit
+    for name in [
+        "Heartfire Hero",
+        "Vivien Reid",
+        "Emberheart Challenger",
+        "Mountain",
+        "Manifold Mouse",
+    ] {
+        bot.battlefield_creatures
+            .insert(name.to_string(), library[name].clone());
+    }
+    bot.attacking = vec!["Heartfire Hero".into(), "Manifold Mouse".into()];
+    bot.record_attack_declaration();
+
+    // A CombatEngine támadó oldala: csak a lények, bejárási sorrendben
+    let names: Vec<&String> = bot
+        .battlefield_creatures
+        .iter()
+        .filter(|(_, c)| matches!(c.card_type, CardType::Creature(_)))
+        .map(|(name, _)| name)
+        .collect();
+    assert_eq!(names.len(), 3);
+    let declared: Vec<&str> = bot
+        .combat_attackers
+        .iter()
+        .map(|&i| names[i].as_str())
+        .collect();
+    assert_eq!(declared.len(), 2);
+    assert!(declared.contains(&"Heartfire Hero") && declared.contains(&"Manifold Mouse"));
+    for i in &bot.combat_attackers {
+        assert_eq!(bot.combat_attack_targets.get(i), Some(&AttackTarget::Player));
+    }
+}
@@ -0,0 +1,147 @@
+// tests/player_state_test.rs
+use MTGA_me::app::card_attribute::{
+    Amount, Condition, CounterType, Duration, Effect, PlayerSelector, TargetFilter,
+};
+use MTGA_me::app::game_state::{GamePhase, Player};
+use MTGA_me::app::gre::player_state::STARTING_LIFE;
+use MTGA_me::app::gre::{Gre, StackEntry};
+
+fn resolve(gre: &mut Gre, effect: Effect, controller: Player) {
+    gre.push_to_stack(StackEntry::TriggeredAbility {
+        source: None,
+        effect,
+        controller,
+    });
+    gre.resolve_stack();
+}
+
+#[test]
+fn damage_and_life_gain_change_life_totals() {
+    let mut gre = Gre::default();
+    assert_eq!(gre.player(Player::Us).life, STARTING_LIFE);
+
+    // Az ellenfél villáma minket talál
+    resolve(
+        &mut gre,
+        Effect::Damage {
+            amount: Amount::Fixed(3),
+            target: TargetFilter::Player,
+        },
+        Player::Opponent,
+    );
+    assert_eq!(gre.player(Player::Us).life, 17);
+    assert!(gre.lost_life_this_turn(Player::Us));
+    assert!(!gre.lost_life_this_turn(Player::Opponent));
+
+    resolve(
+        &mut gre,
+        Effect::GainLife {
+            amount: 4,
+            player: PlayerSelector::Controller,
+        },
+        Player::Us,
+    );
+    assert_eq!(gre.player(Player::Us).life, 21);
+    assert_eq!(gre.player(Player::Us).life_gained_this_turn, 4);
+
+    gre.on_turn_end();
+    assert!(!gre.lost_life_this_turn(Player::Us));
+    assert_eq!(gre.player(Player::Us).life, 21);
+}
+
+#[test]
+fn prevented_life_gain_leaves_life_unchanged() {
+    let mut gre = Gre::default();
+    resolve(
+        &mut gre,
+        Effect::PreventLifeGain {
+            player: PlayerSelector::Opponent,
+            duration: Duration::EndOfTurn,
+        },
+        Player::Us,
+    );
+    assert!(!gre.gain_life(Player::Opponent, 5));
+    assert_eq!(gre.player(Player::Opponent).life, STARTING_LIFE);
+    assert!(gre.gain_life(Player::Us, 2));
+}
+
+#[test]
+fn opponent_lost_life_condition_follows_real_life_loss() {
+    let mut gre = Gre::default();
+    let conditional = || Effect::Conditional {
+        condition: Condition::OpponentLostLifeThisTurn,
+        effect_if_true: Box::new(Effect::DrawCards {
+            count: 1,
+            player: PlayerSelector::Controller,
+        }),
+        effect_if_false: None,
+    };
+
+    resolve(&mut gre, conditional(), Player::Us);
+    assert_eq!(gre.player(Player::Us).hand_size, 0);
+
+    // 0 sebzés nem életvesztés
+    gre.deal_damage_to_player(Player::Opponent, 0);
+    resolve(&mut gre, conditional(), Player::Us);
+    assert_eq!(gre.player(Player::Us).hand_size, 0);
+
+    gre.deal_damage_to_player(Player::Opponent, 2);
+    resolve(&mut gre, conditional(), Player::Us);
+    assert_eq!(gre.player(Player::Us).hand_size, 1);
+}
+
+#[test]
+fn draws_mana_and_turn_counts_are_per_player() {
+    let mut gre = Gre::default();
+    resolve(
+        &mut gre,
+        Effect::DrawCards {
+            count: 2,
+            player: PlayerSelector::Opponent,
+        },
+        Player::Us,
+    );
+    assert_eq!(gre.player(Player::Opponent).hand_size, 2);
+
+    resolve(
+        &mut gre,
+        Effect::AddMana {
+            colorless: 0,
+            red: 2,
+            blue: 0,
+            green: 0,
+            black: 0,
+            white: 0,
+        },
+        Player::Us,
+    );
+    assert_eq!(gre.player(Player::Us).mana_pool.red, 2);
+    gre.set_phase(GamePhase::Combat);
+    assert!(gre.player(Player::Us).mana_pool.is_empty());
+
+    gre.note_land_played(Player::Us);
+    gre.note_spell_cast(Player::Us);
+    gre.note_spell_cast(Player::Us);
+    assert_eq!(gre.player(Player::Us).lands_played_this_turn, 1);
+    assert_eq!(gre.player(Player::Us).spells_cast_this_turn, 2);
+    gre.on_turn_end();
+    assert_eq!(gre.player(Player::Us).spells_cast_this_turn, 0);
+}
+
+#[test]
+fn losing_by_life_or_poison() {
+    let mut gre = Gre::default();
+    gre.add_player_counters(Player::Opponent, CounterType::Poison, 9);
+    assert!(!gre.player(Player::Opponent).has_lost());
+    gre.add_player_counters(Player::Opponent, CounterType::Poison, 1);
+    assert_eq!(gre.player(Player::Opponent).poison(), 10);
+    assert!(gre.player(Player::Opponent).has_lost());
+
+    gre.deal_damage_to_player(Player::Us, 20);
+    assert!(gre.player(Player::Us).has_lost());
+
+    // Képernyőről olvasott élet felülírja, a sikertelen (0) olvasás nem
+    gre.sync_life_totals(0, 14);
+    assert_eq!(gre.player(Player::Us).life, 0);
+    assert_eq!(gre.player(Player::Opponent).life, 14);
+}
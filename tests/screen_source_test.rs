@@ -0,0 +1,88 @@
+// tests/screen_source_test.rs
+use image::{DynamicImage, Rgba, RgbaImage};
+use MTGA_me::app::screen_source::{
+    set_screen_source, DirectoryScreenSource, RecordedSession, ReplayMode, ScreenSource,
+    SessionRecorder,
+};
+use MTGA_me::app::ui::{get_average_color, get_color, Color};
+use MTGA_me::multiplatform::screen_size;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("mtga_screen_{}_{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn solid(width: u32, height: u32, rgb: [u8; 3]) -> DynamicImage {
+    DynamicImage::ImageRgba8(RgbaImage::from_pixel(
+        width,
+        height,
+        Rgba([rgb[0], rgb[1], rgb[2], 255]),
+    ))
+}
+
+#[test]
+fn directory_source_serves_sorted_frames() {
+    let dir = temp_dir("dir");
+    solid(8, 6, [255, 0, 0]).save(dir.join("a.png")).unwrap();
+    solid(8, 6, [0, 255, 0]).save(dir.join("b.png")).unwrap();
+    std::fs::write(dir.join("notes.txt"), "ignored").unwrap();
+
+    let src = DirectoryScreenSource::new(&dir).unwrap();
+    assert_eq!(src.frame_count(), 2);
+    assert_eq!(src.pixel(3, 3).unwrap(), (255, 0, 0));
+    assert!(src.advance());
+    assert_eq!(src.pixel(3, 3).unwrap(), (0, 255, 0));
+    assert!(!src.advance());
+    assert!(src.select("a.png"));
+    assert_eq!(src.size().unwrap(), (8, 6));
+    // Rectangles past the edge are clamped; an origin outside the frame is an error
+    assert_eq!(src.capture_area(6, 0, 4, 1).unwrap().width(), 2);
+    assert_eq!(src.capture_area(6, 5, u32::MAX, u32::MAX).unwrap().height(), 1);
+    assert!(src.capture_area(8, 0, 1, 1).is_err());
+    assert!(src.capture_area(-1, 0, 1, 1).is_err());
+}
+
+#[test]
+fn recorded_session_round_trip() {
+    let frames = temp_dir("frames");
+    solid(4, 4, [10, 20, 30]).save(frames.join("0.png")).unwrap();
+    solid(4, 4, [40, 50, 60]).save(frames.join("1.png")).unwrap();
+    let live = DirectoryScreenSource::new(&frames).unwrap();
+
+    let session = temp_dir("session");
+    let recorder = SessionRecorder::new(live, &session)
+        .unwrap()
+        .with_min_interval(Duration::ZERO);
+    recorder.capture().unwrap();
+    recorder.capture().unwrap();
+
+    let replay = RecordedSession::open(&session, ReplayMode::Manual).unwrap();
+    assert_eq!(replay.frame_count(), 2);
+    assert_eq!(replay.pixel(0, 0).unwrap(), (10, 20, 30));
+    assert_eq!(replay.frame_index_at(Duration::from_secs(3600)), 1);
+}
+
+#[test]
+fn perception_helpers_read_from_selected_source() {
+    let dir = temp_dir("global");
+    let mut frame = RgbaImage::from_pixel(20, 10, Rgba([0, 0, 0, 255]));
+    for x in 10..20 {
+        for y in 0..10 {
+            frame.put_pixel(x, y, Rgba([210, 175, 157, 255]));
+        }
+    }
+    DynamicImage::ImageRgba8(frame).save(dir.join("frame.png")).unwrap();
+    set_screen_source(Arc::new(DirectoryScreenSource::new(&dir).unwrap()));
+
+    assert_eq!(screen_size().unwrap(), (20, 10));
+    assert_eq!(get_color(15, 5), Color { r: 210, g: 175, b: 157 });
+    assert_eq!(get_average_color(10, 0, 10, 10), (210, 175, 157));
+    assert_eq!(get_average_color(5, 0, 10, 10), (105, 87, 78));
+    // A képkocka szélén túllógó terület: csak a ténylegesen befogott pixelek számítanak
+    assert_eq!(get_average_color(15, 0, 10, 10), (210, 175, 157));
+}
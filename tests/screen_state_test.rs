@@ -0,0 +1,153 @@
+// tests/screen_state_test.rs
+use image::{DynamicImage, Rgba, RgbaImage};
+use MTGA_me::app::error::AppError;
+use MTGA_me::app::layout::layout;
+use MTGA_me::app::ocr::crop_region;
+use MTGA_me::app::ocr_engine::{set_ocr_engine, OcrEngine, OcrError, OcrRegion};
+use MTGA_me::app::screen_source::{set_screen_source, DirectoryScreenSource};
+use MTGA_me::app::screen_state::{
+    classify_features, classify_main_button, wait_for, wait_for_screen, ButtonColor, GameOutcome,
+    ScreenClassifier, ScreenFeatures, ScreenState,
+};
+use MTGA_me::app::ui::Color;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+const W: u32 = 1600;
+const H: u32 = 900;
+
+/// Answers by the width of the image it gets: every probe region has a different width.
+struct WidthEngine(Mutex<HashMap<u32, String>>);
+
+impl OcrEngine for WidthEngine {
+    fn recognize(&self, img: &DynamicImage) -> Result<String, OcrError> {
+        Ok(self.0.lock().unwrap().get(&img.width()).cloned().unwrap_or_default())
+    }
+}
+
+fn width(region: &str) -> u32 {
+    let (x1, _, x2, _) = layout().region(region, W, H);
+    x2 - x1
+}
+
+/// Dark frame with a horizontal gradient (so templates have structure) and the attack
+/// button pixel in `button`.
+fn frame(button: [u8; 3]) -> DynamicImage {
+    let mut img = RgbaImage::from_fn(W, H, |x, _| Rgba([(x % 256) as u8 / 4, 20, 20, 255]));
+    let (bx, by) = layout().button("attack", W, H);
+    img.put_pixel(bx as u32, by as u32, Rgba([button[0], button[1], button[2], 255]));
+    DynamicImage::ImageRgba8(img)
+}
+
+fn stub_ocr(texts: &[(u32, &str)]) {
+    let map = texts.iter().map(|(w, t)| (*w, t.to_string())).collect();
+    let engine = Arc::new(WidthEngine(Mutex::new(map)));
+    set_ocr_engine(OcrRegion::ButtonLabel, engine.clone());
+    set_ocr_engine(OcrRegion::Generic, engine);
+}
+
+#[test]
+fn main_button_labels_map_to_screens() {
+    assert_eq!(classify_main_button("All Attack"), Some(ScreenState::DeclareAttackers { selected: 0 }));
+    assert_eq!(classify_main_button(" 2 Attackers "), Some(ScreenState::DeclareAttackers { selected: 2 }));
+    assert_eq!(classify_main_button("1 Attacker"), Some(ScreenState::DeclareAttackers { selected: 1 }));
+    assert_eq!(classify_main_button("No Blocks"), Some(ScreenState::DeclareBlockers { selected: 0 }));
+    assert_eq!(classify_main_button("3 Blockers"), Some(ScreenState::DeclareBlockers { selected: 3 }));
+    assert_eq!(classify_main_button("Opponent's Turn"), Some(ScreenState::OpponentTurn { prompt: false }));
+    assert_eq!(classify_main_button("Resolve"), Some(ScreenState::OpponentTurn { prompt: true }));
+    assert_eq!(classify_main_button("End Turn"), Some(ScreenState::MainPhase { end_turn: true }));
+    assert_eq!(classify_main_button("Next"), Some(ScreenState::MainPhase { end_turn: false }));
+    assert_eq!(classify_main_button("Cancel"), Some(ScreenState::WaitingForTarget));
+    assert_eq!(classify_main_button(""), None);
+}
+
+#[test]
+fn features_combine_by_strength() {
+    let over = ScreenFeatures {
+        main_text: "Next".into(),
+        banner_text: "DEFEAT".into(),
+        ..Default::default()
+    };
+    assert_eq!(classify_features(&over), ScreenState::GameOver(GameOutcome::Defeat));
+
+    let mulligan = ScreenFeatures {
+        mulligan_text: "Mulligan Keep".into(),
+        play_text: "Play".into(),
+        ..Default::default()
+    };
+    assert_eq!(classify_features(&mulligan), ScreenState::Mulligan);
+
+    let home = ScreenFeatures { play_text: "Play".into(), ..Default::default() };
+    assert_eq!(classify_features(&home), ScreenState::Home);
+    let queue = ScreenFeatures { play_text: "Cancel 012".into(), ..Default::default() };
+    assert_eq!(classify_features(&queue), ScreenState::Queue);
+
+    // Text wins over a template; a template is used when the text says nothing
+    let templated = ScreenFeatures {
+        template: Some(ScreenState::Home),
+        ..Default::default()
+    };
+    assert_eq!(classify_features(&templated), ScreenState::Home);
+    assert_eq!(classify_features(&ScreenFeatures::default()), ScreenState::Unknown);
+
+    assert_eq!(ButtonColor::of(Color { r: 230, g: 40, b: 40 }), ButtonColor::Red);
+    assert_eq!(ButtonColor::of(Color { r: 30, g: 40, b: 230 }), ButtonColor::Blue);
+    assert_eq!(ButtonColor::of(Color { r: 0, g: 0, b: 0 }), ButtonColor::Black);
+}
+
+#[test]
+fn classifier_reads_regions_of_one_screenshot() {
+    // Red button: white-invert pipeline (no upscale); other regions: standard pipeline (2×)
+    stub_ocr(&[
+        (width("main_button"), "2 Attackers"),
+        (width("main_button") * 2, "Next"),
+        (width("banner") * 2, ""),
+        (width("play_label") * 2, ""),
+        (width("mulligan_buttons") * 2, ""),
+    ]);
+    let classifier = ScreenClassifier::new();
+
+    let red = frame([240, 30, 30]);
+    let features = classifier.features(&red);
+    assert_eq!(features.button, ButtonColor::Red);
+    assert_eq!(features.main_text, "2 Attackers");
+    assert_eq!(classifier.classify(&red), ScreenState::DeclareAttackers { selected: 2 });
+    assert_eq!(classifier.classify(&frame([10, 10, 10])), ScreenState::MainPhase { end_turn: false });
+}
+
+#[test]
+fn templates_identify_screens_without_text() {
+    let screen = frame([10, 10, 10]);
+    let (x1, y1, x2, y2) = layout().region("banner", W, H);
+    let banner = crop_region(&screen, x1, y1, x2, y2).unwrap();
+    let classifier = ScreenClassifier::new()
+        .with_template("victory", &banner)
+        .with_template("no_such_screen", &banner);
+    assert_eq!(classifier.template_count(), 1);
+    assert_eq!(classifier.features(&screen).template, Some(ScreenState::GameOver(GameOutcome::Victory)));
+    assert_eq!(ScreenState::template_region("home"), Some("play_label"));
+    assert_eq!(ScreenState::template_region("bogus"), None);
+}
+
+#[test]
+fn waits_time_out_with_the_last_screen() {
+    let dir = std::env::temp_dir().join(format!("mtga_screen_state_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    frame([10, 10, 10]).save(dir.join("0.png")).unwrap();
+    set_screen_source(Arc::new(DirectoryScreenSource::new(&dir).unwrap()));
+
+    let mut polls = 0;
+    let found = wait_for("third poll", Duration::from_secs(5), Duration::from_millis(1), |_| {
+        polls += 1;
+        Ok((polls == 3).then_some(polls))
+    })
+    .unwrap();
+    assert_eq!(found, 3);
+
+    let err = wait_for_screen("victory", Duration::ZERO, Duration::from_millis(1), |s| s.is_game_over())
+        .unwrap_err();
+    assert!(matches!(err, AppError::ScreenTimeout { ref what, .. } if what == "victory"), "{}", err);
+    let _ = std::fs::remove_dir_all(&dir);
+}
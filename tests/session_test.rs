@@ -0,0 +1,137 @@
+// tests/session_test.rs
+use image::{DynamicImage, Rgba, RgbaImage};
+use MTGA_me::app::bot::Bot;
+use MTGA_me::app::error::AppError;
+use MTGA_me::app::input_driver::{set_input_driver, InputDriver, InputError};
+use MTGA_me::app::ocr_engine::{set_ocr_engine, OcrEngine, OcrError, OcrRegion};
+use MTGA_me::app::screen_source::{set_screen_source, DirectoryScreenSource};
+use MTGA_me::app::screen_state::{wait_for_screen, GameOutcome, ScreenState};
+use MTGA_me::app::session::{leave_results_screen, recover_after_error, tally, SessionLimits};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Every region reads "Victory": the banner decides.
+struct VictoryEngine;
+
+impl OcrEngine for VictoryEngine {
+    fn recognize(&self, _img: &DynamicImage) -> Result<String, OcrError> {
+        Ok("Victory".into())
+    }
+}
+
+/// The results screen until the first click, the home screen after it.
+#[derive(Default)]
+struct ResultsScreen {
+    clicks: AtomicU32,
+}
+
+impl OcrEngine for ResultsScreen {
+    fn recognize(&self, _img: &DynamicImage) -> Result<String, OcrError> {
+        Ok(if self.clicks.load(Ordering::SeqCst) == 0 { "Victory" } else { "Play" }.into())
+    }
+}
+
+impl InputDriver for ResultsScreen {
+    fn move_mouse(&self, _x: i32, _y: i32) -> Result<(), InputError> {
+        Ok(())
+    }
+    fn click_left(&self) -> Result<(), InputError> {
+        self.clicks.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+    fn click_right(&self) -> Result<(), InputError> {
+        Ok(())
+    }
+    fn send_key(&self, _key: enigo::Key) -> Result<(), InputError> {
+        Ok(())
+    }
+    fn cursor_position(&self) -> Result<(i32, i32), InputError> {
+        Ok((0, 0))
+    }
+}
+
+#[test]
+fn limits_count_games_and_run_time() {
+    let limits = SessionLimits {
+        max_games: 2,
+        max_duration: Duration::from_secs(600),
+    };
+    assert!(!limits.reached(1, Duration::from_secs(60)));
+    assert!(limits.reached(2, Duration::from_secs(60)));
+    assert!(limits.reached(0, Duration::from_secs(600)));
+}
+
+#[test]
+fn bot_records_results_and_resets_between_games() {
+    let mut bot = Bot::new();
+    bot.end_game_threshold = 2;
+    bot.time_game_threshold = Duration::from_secs(3600);
+    bot.opponent_turn_counter = 7;
+    bot.land_count = 4;
+    bot.cards_texts.push("Shock".into());
+
+    let result = bot.record_game_result(GameOutcome::Victory);
+    assert_eq!(result.turns, 7);
+    assert!(!bot.session_limit_reached());
+
+    bot.reset_for_new_game();
+    assert_eq!(bot.opponent_turn_counter, 0);
+    assert_eq!(bot.land_count, 0);
+    assert!(bot.cards_texts.is_empty());
+    assert_eq!(bot.end_game_counter, 1);
+
+    bot.record_game_result(GameOutcome::Defeat);
+    assert!(bot.session_limit_reached());
+    assert_eq!(tally(&bot.game_results), (1, 1));
+
+    // The session survives the reset
+    bot.reset_for_new_game();
+    assert_eq!(bot.end_game_threshold, 2);
+    assert_eq!(bot.game_results.len(), 2);
+}
+
+#[test]
+fn errored_games_count_towards_the_limit() {
+    let mut bot = Bot::new();
+    bot.end_game_threshold = 2;
+    bot.time_game_threshold = Duration::from_secs(3600);
+
+    bot.record_unfinished_game();
+    assert!(!bot.session_limit_reached());
+    bot.record_game_result(GameOutcome::Victory);
+    assert!(bot.session_limit_reached());
+    assert_eq!(bot.end_game_counter, 2);
+    assert_eq!(tally(&bot.game_results), (1, 0));
+}
+
+#[test]
+fn game_over_interrupts_waits() {
+    let dir = std::env::temp_dir().join(format!("mtga_session_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    DynamicImage::ImageRgba8(RgbaImage::from_pixel(1600, 900, Rgba([20, 20, 20, 255])))
+        .save(dir.join("0.png"))
+        .unwrap();
+    set_screen_source(Arc::new(DirectoryScreenSource::new(&dir).unwrap()));
+    set_ocr_engine(OcrRegion::Generic, Arc::new(VictoryEngine));
+    set_ocr_engine(OcrRegion::ButtonLabel, Arc::new(VictoryEngine));
+
+    let err = wait_for_screen("our turn", Duration::from_secs(5), Duration::from_millis(1), |s| {
+        matches!(s, ScreenState::MainPhase { .. })
+    })
+    .unwrap_err();
+    assert!(matches!(err, AppError::GameOver(GameOutcome::Victory)), "{}", err);
+
+    // A wait that accepts the game-over screen gets it
+    assert_eq!(recover_after_error(Duration::from_secs(5)).unwrap(), Some(GameOutcome::Victory));
+
+    // Leaving the results: the game-over screen is clicked through, not an error
+    let results = Arc::new(ResultsScreen::default());
+    set_ocr_engine(OcrRegion::Generic, results.clone());
+    set_ocr_engine(OcrRegion::ButtonLabel, results.clone());
+    set_input_driver(results.clone());
+    leave_results_screen((800, 450)).unwrap();
+    assert_eq!(results.clicks.load(Ordering::SeqCst), 1);
+    let _ = std::fs::remove_dir_all(&dir);
+}
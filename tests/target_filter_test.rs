@@ -0,0 +1,165 @@
+// tests/target_filter_test.rs
+mod common;
+
+use MTGA_me::app::card_attribute::{
+    Amount, Comparison, CounterType, CreatureType, Effect, FilterContext, KeywordAbility,
+    PlayerSelector, TargetFilter, Zone,
+};
+use MTGA_me::app::card_library::{Card, CardType, CardTypeFlags, ManaCost};
+use MTGA_me::app::game_state::Player;
+use MTGA_me::app::gre::{Gre, StackEntry, Target};
+use common::{creature_with, enter};
+
+/// `power`/`power` egér
+fn mouse(name: &str, power: i32, controller: Player) -> Card {
+    creature_with(name, power, power, vec![CreatureType::Mouse], Vec::new(), controller)
+}
+
+fn resolve(gre: &mut Gre, effect: Effect) {
+    gre.push_to_stack(StackEntry::TriggeredAbility {
+        source: None,
+        effect,
+        controller: Player::Us,
+    });
+    gre.resolve_stack();
+}
+
+fn big_creature_you_control() -> TargetFilter {
+    TargetFilter::Creature
+        .and(TargetFilter::ControlledBy(PlayerSelector::Controller))
+        .and(TargetFilter::Power(Comparison::AtLeast, 4))
+}
+
+fn nonland_permanent_opponent_controls() -> TargetFilter {
+    TargetFilter::Land
+        .negate()
+        .and(TargetFilter::ControlledBy(PlayerSelector::Opponent))
+}
+
+#[test]
+fn composite_filters_evaluate_against_the_controller() {
+    let ctx = FilterContext::new(Player::Us, None, Zone::Battlefield);
+    let filter = big_creature_you_control();
+    assert!(filter.matches(&mouse("Big", 4, Player::Us), &ctx));
+    assert!(!filter.matches(&mouse("Small", 3, Player::Us), &ctx));
+    assert!(!filter.matches(&mouse("Theirs", 5, Player::Opponent), &ctx));
+
+    let mut tapped = mouse("Tapped", 1, Player::Us);
+    tapped.tapped = true;
+    assert!(TargetFilter::Tapped.matches(&tapped, &ctx));
+    assert!(!TargetFilter::Token.matches(&tapped, &ctx));
+    assert!(
+        TargetFilter::CreatureType(CreatureType::Mouse)
+            .or(TargetFilter::HasKeyword(KeywordAbility::Flying))
+            .matches(&tapped, &ctx)
+    );
+    assert!(!TargetFilter::InZone(Zone::Graveyard).matches(&tapped, &ctx));
+}
+
+#[test]
+fn target_candidates_use_the_filter_engine() {
+    let mut gre = Gre::default();
+    let big = enter(&mut gre, mouse("Big", 5, Player::Us));
+    enter(&mut gre, mouse("Small", 2, Player::Us));
+    let theirs = enter(&mut gre, mouse("Theirs", 5, Player::Opponent));
+    let land = enter(
+        &mut gre,
+        Card::new("Mountain", CardType::Land, ManaCost::free()).with_controller(Player::Opponent),
+    );
+
+    assert_eq!(
+        gre.target_candidates(&big_creature_you_control(), Player::Us),
+        Some(vec![Target::Permanent(big)])
+    );
+    let nonland = gre.permanents_matching(&nonland_permanent_opponent_controls(), Player::Us);
+    assert_eq!(nonland, vec![theirs]);
+    assert!(!nonland.contains(&land));
+    // Az ellenfél szemszögéből a "you control" az ő lényeit jelenti
+    assert_eq!(
+        gre.permanents_matching(&big_creature_you_control(), Player::Opponent),
+        vec![theirs]
+    );
+}
+
+#[test]
+fn destroy_picks_only_a_matching_permanent() {
+    let mut gre = Gre::default();
+    let ours = enter(
+        &mut gre,
+        Card::new("Our Relic", CardType::Artifact, ManaCost::free()),
+    );
+    let theirs = enter(
+        &mut gre,
+        Card::new("Their Relic", CardType::Artifact, ManaCost::free())
+            .with_controller(Player::Opponent),
+    );
+    resolve(
+        &mut gre,
+        Effect::Destroy {
+            target: TargetFilter::Artifact
+                .and(TargetFilter::ControlledBy(PlayerSelector::Opponent)),
+        },
+    );
+    assert!(gre.battlefield_creatures.contains_key(&ours));
+    assert!(!gre.battlefield_creatures.contains_key(&theirs));
+    assert_eq!(gre.player(Player::Opponent).graveyard.len(), 1);
+}
+
+#[test]
+fn counters_and_damage_follow_filters() {
+    let mut gre = Gre::default();
+    let big = enter(&mut gre, mouse("Big", 4, Player::Us));
+    let small = enter(&mut gre, mouse("Small", 1, Player::Us));
+    let mut token = mouse("Token", 1, Player::Opponent);
+    token.type_flags |= CardTypeFlags::TOKEN;
+    let token = enter(&mut gre, token);
+
+    resolve(
+        &mut gre,
+        Effect::AddCounterAll {
+            counter: CounterType::PlusOnePlusOne,
+            amount: Amount::Fixed(1),
+            filter: TargetFilter::ControlledBy(PlayerSelector::Controller)
+                .and(TargetFilter::Power(Comparison::LessThan, 4)),
+        },
+    );
+    let plus = |gre: &Gre, id: u64| {
+        gre.battlefield_creatures[&id].counter_count(&CounterType::PlusOnePlusOne)
+    };
+    assert_eq!(plus(&gre, big), 0);
+    assert_eq!(plus(&gre, small), 1);
+    assert_eq!(plus(&gre, token), 0);
+
+    resolve(
+        &mut gre,
+        Effect::Damage {
+            amount: Amount::Fixed(1),
+            target: TargetFilter::Token,
+        },
+    );
+    assert!(!gre.battlefield_creatures.contains_key(&token));
+}
+
+#[test]
+fn zone_cards_matching_reads_graveyards() {
+    let mut gre = Gre::default();
+    gre.put_into_graveyard(mouse("Dead Mouse", 1, Player::Us));
+    gre.put_into_graveyard(Card::new("Spent", CardType::Instant, ManaCost::free()));
+    let found = gre.zone_cards_matching(
+        Player::Us,
+        Zone::Graveyard,
+        &TargetFilter::Creature.and(TargetFilter::InZone(Zone::Graveyard)),
+        Player::Us,
+    );
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].name, "Dead Mouse");
+    assert!(
+        gre.zone_cards_matching(
+            Player::Us,
+            Zone::Exile,
+            &TargetFilter::AnyTarget,
+            Player::Us
+        )
+        .is_empty()
+    );
+}
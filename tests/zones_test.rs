@@ -0,0 +1,223 @@
+// tests/zones_test.rs
+mod common;
+
+use MTGA_me::app::bot::Bot;
+use MTGA_me::app::card_attribute::{
+    CounterType, CreatureType, Duration, Effect, KeywordAbility, PlayerSelector, TargetFilter,
+};
+use MTGA_me::app::card_library::{Card, CardType, ManaCost};
+use MTGA_me::app::game_state::{GamePhase, Player};
+use MTGA_me::app::gre::{Gre, ManaPool};
+use common::{creature, creature_with, enter};
+
+fn shock() -> Card {
+    Card::new("Shock", CardType::Instant, ManaCost::new(0, 1, 0, 0, 0, 0))
+}
+
+fn abilities(gre: &Gre, id: u64) -> Vec<KeywordAbility> {
+    match &gre.battlefield_creatures[&id].card_type {
+        CardType::Creature(cr) => cr.abilities.clone(),
+        _ => Vec::new(),
+    }
+}
+
+#[test]
+fn draws_come_from_the_known_library() {
+    let mut gre = Gre::default();
+    gre.set_library(
+        Player::Us,
+        vec![
+            shock(),
+            Card::new("Mountain", CardType::Land, ManaCost::free()),
+        ],
+    );
+    gre.handle_effect(Effect::DrawCards {
+        count: 3,
+        player: PlayerSelector::Controller,
+    });
+    let us = gre.player(Player::Us);
+    assert_eq!(us.hand_size, 3);
+    assert_eq!(
+        us.hand.iter().map(|c| c.name.as_str()).collect::<Vec<_>>(),
+        vec!["Mountain", "Shock"]
+    );
+    assert!(us.library.is_empty());
+
+    // Előbb a harmadik, ismeretlen lap megy
+    gre.discard(Player::Us, 2);
+    let us = gre.player(Player::Us);
+    assert_eq!(us.hand_size, 1);
+    assert!(us.graveyard[0].is_hidden());
+    assert_eq!(us.graveyard[1].name, "Shock");
+}
+
+#[test]
+fn discard_drops_unknown_cards_first() {
+    let mut gre = Gre::default();
+    gre.set_library(Player::Us, vec![shock()]);
+    gre.draw_cards(Player::Us, 3);
+    assert_eq!(gre.player(Player::Us).hand.len(), 1);
+
+    gre.discard(Player::Us, 2);
+    let us = gre.player(Player::Us);
+    assert_eq!(us.hand_size, 1);
+    assert_eq!(us.hand[0].name, "Shock");
+    assert_eq!(us.graveyard.len(), 2);
+    assert!(us.graveyard.iter().all(|c| c.is_hidden()));
+
+    gre.discard(Player::Us, 5);
+    let us = gre.player(Player::Us);
+    assert_eq!(us.hand_size, 0);
+    assert_eq!(us.graveyard.last().unwrap().name, "Shock");
+}
+
+#[test]
+fn impulse_draw_from_an_unknown_library_exiles_placeholders() {
+    let mut gre = Gre::default();
+    gre.handle_effect(Effect::ExileThenPlayFromExile {
+        count: 2,
+        player: PlayerSelector::Controller,
+        duration: Duration::NextTurnEnd,
+    });
+    let exile = &gre.player(Player::Us).exile;
+    assert_eq!(exile.len(), 2);
+    assert!(exile.iter().all(|c| c.is_hidden() && c.card_id != 0));
+    // Fel nem ismert lapot nem játszunk ki
+    assert!(gre.castable_from_zones(Player::Us).is_empty());
+    assert!(!gre.play_from_zone(exile[0].card_id, Player::Us));
+}
+
+#[test]
+fn bot_hand_is_mirrored_into_the_gre() {
+    let mut bot = Bot::new();
+    bot.cards_texts = vec!["Felonious Rage".into(), "#@!".into(), "Mountain".into()];
+    bot.card_count = 3;
+    bot.remove_card_from_hand(2);
+
+    let us = bot.gre.player(Player::Us);
+    assert_eq!(us.hand_size, 2);
+    assert_eq!(
+        us.hand.iter().map(|c| c.name.as_str()).collect::<Vec<_>>(),
+        vec!["Felonious Rage"]
+    );
+}
+
+#[test]
+fn impulse_draw_can_be_played_until_the_permission_expires() {
+    let mut gre = Gre::default();
+    gre.set_library(
+        Player::Us,
+        vec![
+            Card::new("Mountain", CardType::Land, ManaCost::free()),
+            shock(),
+        ],
+    );
+    gre.handle_effect(Effect::ExileThenPlayFromExile {
+        count: 2,
+        player: PlayerSelector::Controller,
+        duration: Duration::NextTurnEnd,
+    });
+    assert_eq!(gre.player(Player::Us).exile.len(), 2);
+    assert_eq!(gre.castable_from_zones(Player::Us).len(), 2);
+    assert!(gre.castable_from_zones(Player::Opponent).is_empty());
+    let shock_id = gre.player(Player::Us).exile[0].card_id;
+    let land_id = gre.player(Player::Us).exile[1].card_id;
+
+    // Mana nélkül nem kijátszható, és a száműzetésben marad
+    assert!(!gre.play_from_zone(shock_id, Player::Us));
+    assert_eq!(gre.player(Player::Us).exile.len(), 2);
+
+    gre.on_turn_end();
+    gre.add_mana(
+        Player::Us,
+        ManaPool {
+            red: 1,
+            ..ManaPool::default()
+        },
+    );
+    assert!(gre.play_from_zone(shock_id, Player::Us));
+    assert_eq!(gre.stack.len(), 1);
+    assert_eq!(gre.player(Player::Us).exile.len(), 1);
+
+    // A föld csak sorcery-speed-ben
+    assert!(!gre.play_from_zone(land_id, Player::Us));
+    gre.on_turn_end();
+    assert!(gre.castable_from_zones(Player::Us).is_empty());
+    gre.set_phase(GamePhase::PreCombatMain);
+    assert!(!gre.play_from_zone(land_id, Player::Us));
+}
+
+#[test]
+fn exiled_land_is_played_as_the_land_drop() {
+    let mut gre = Gre::default();
+    gre.set_library(
+        Player::Us,
+        vec![Card::new("Mountain", CardType::Land, ManaCost::free())],
+    );
+    gre.handle_effect(Effect::ExileThenPlayFromExile {
+        count: 1,
+        player: PlayerSelector::Controller,
+        duration: Duration::EndOfTurn,
+    });
+    let land_id = gre.player(Player::Us).exile[0].card_id;
+    gre.set_phase(GamePhase::PreCombatMain);
+    assert!(gre.play_from_zone(land_id, Player::Us));
+    assert!(gre.battlefield_creatures.contains_key(&land_id));
+    assert_eq!(gre.player(Player::Us).lands_played_this_turn, 1);
+    assert!(gre.player(Player::Us).exile.is_empty());
+
+    // Az ExileTop csak száműz, engedély nélkül
+    gre.set_library(Player::Opponent, vec![shock()]);
+    gre.handle_effect(Effect::ExileTop {
+        count: 1,
+        player: PlayerSelector::Opponent,
+    });
+    assert_eq!(gre.player(Player::Opponent).exile.len(), 1);
+    assert!(gre.castable_from_zones(Player::Us).is_empty());
+}
+
+#[test]
+fn granted_ability_wears_off_at_end_of_turn() {
+    let mut gre = Gre::default();
+    let bear = enter(&mut gre, creature("Bear", 2, 2, Player::Us));
+    let trampler = enter(
+        &mut gre,
+        creature_with("Trampler", 2, 2, vec![CreatureType::Warrior], vec![KeywordAbility::Trample], Player::Us),
+    );
+    for id in [bear, trampler] {
+        gre.handle_effect(Effect::GrantAbility {
+            ability: KeywordAbility::Trample,
+            duration: Duration::EndOfTurn,
+            target: TargetFilter::ExactCardID(id),
+        });
+    }
+    assert_eq!(abilities(&gre, bear), vec![KeywordAbility::Trample]);
+
+    gre.dispatch_delayed(GamePhase::End);
+    assert!(abilities(&gre, bear).is_empty());
+    assert_eq!(abilities(&gre, trampler), vec![KeywordAbility::Trample]);
+}
+
+#[test]
+fn granted_ability_chooses_a_legal_target() {
+    let mut gre = Gre::default();
+    let theirs = enter(&mut gre, creature("Ogre", 2, 2, Player::Opponent));
+    let ours = enter(&mut gre, creature("Bear", 2, 2, Player::Us));
+    gre.handle_effect(Effect::GrantAbility {
+        ability: KeywordAbility::Menace,
+        duration: Duration::Permanent,
+        target: TargetFilter::ControllerCreature,
+    });
+    assert_eq!(abilities(&gre, ours), vec![KeywordAbility::Menace]);
+    assert!(abilities(&gre, theirs).is_empty());
+}
+
+#[test]
+fn draw_cards_counted_draws_one_per_counter() {
+    let mut gre = Gre::default();
+    let bear = enter(&mut gre, creature("Bear", 2, 2, Player::Us));
+    gre.add_counters(bear, CounterType::PlusOnePlusOne, 2);
+    let before = gre.player(Player::Us).hand_size;
+    gre.handle_effect(Effect::DrawCardsCounted);
+    assert_eq!(gre.player(Player::Us).hand_size, before + 2);
+}